@@ -0,0 +1,52 @@
+// Counts the allocations made through the system allocator and the bytes they hold, for the
+// benchmarks and tests which measure how much premises allocate
+// A binary using it has to make it the global allocator with
+// `#[global_allocator] static ALLOCATOR: CountingAllocator = CountingAllocator;`
+// The counts cover every thread, so a test using it should be the only one in its binary
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicIsize, AtomicUsize, Ordering};
+
+pub struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static LIVE_BYTES: AtomicIsize = AtomicIsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        LIVE_BYTES.fetch_add(layout.size() as isize, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.fetch_sub(layout.size() as isize, Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        LIVE_BYTES.fetch_add(
+            new_size as isize - layout.size() as isize,
+            Ordering::Relaxed,
+        );
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+// Returns how many allocations the function makes, along with what it returns
+pub fn count<T>(f: impl FnOnce() -> T) -> (usize, T) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let result = f();
+
+    (ALLOCATIONS.load(Ordering::Relaxed) - before, result)
+}
+
+// Returns how many more bytes are allocated after the function than before it, which are the
+// bytes held by what it returns if it frees everything else, along with what it returns
+pub fn held<T>(f: impl FnOnce() -> T) -> (isize, T) {
+    let before = LIVE_BYTES.load(Ordering::Relaxed);
+    let result = f();
+
+    (LIVE_BYTES.load(Ordering::Relaxed) - before, result)
+}
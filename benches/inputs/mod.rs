@@ -0,0 +1,404 @@
+// Generates the seeded inputs of the benchmark suite and runs the body of each benchmark, so the
+// suite and the smoke test in tests/benches.rs measure and check the same code
+// Random inputs come from the crate's SeededRng and Premise::arbitrary, while inputs whose shape
+// is the point of a benchmark, such as a chain of implications, are built directly
+
+use std::ops::ControlFlow;
+
+use deductions::entailment::Entailment;
+use deductions::generator::SeededRng;
+use deductions::normal_form::DEFAULT_MAX_CLAUSES;
+use deductions::observer::SolveObserver;
+use deductions::parallel::ParallelTable;
+use deductions::truth_table::{Formula, TruthTable};
+use deductions::{Deduction, EvaluationHistory, Premise, PremiseRef, ValueMap};
+
+// The seed every generated input starts from, so each run measures the same inputs
+pub const SEED: u64 = 0x5eed;
+
+// The premises the binary works through when it is run without arguments
+pub const EXAMPLE_PREMISES: [&str; 5] = ["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"];
+
+// Returns the proposition with the given number, past the letters the parser accepts, since
+// premises built in code may use any character
+fn proposition(i: usize) -> Premise {
+    Premise::atom(char::from_u32(0x100 + i as u32).unwrap())
+}
+
+// Returns the first letters of the alphabet
+fn letters(count: usize) -> Vec<char> {
+    ('a'..='z').take(count).collect()
+}
+
+// Generates a conjunction of random premises over the first propositions, nesting each up to the
+// depth, with as many premises as it takes to reach the given number of nodes
+// Joining many smaller premises keeps the nesting bounded however large the conjunction is
+pub fn generate_conjunction(
+    rng: &mut SeededRng,
+    nodes: usize,
+    depth: usize,
+    propositions: usize,
+) -> String {
+    let mut parts = Vec::new();
+    let mut total = 0;
+
+    while total < nodes {
+        let premise = Premise::arbitrary(depth, propositions, rng);
+        total += premise.node_count() + 1;
+        parts.push(format!("({})", premise));
+    }
+
+    parts.join(" ∧ ")
+}
+
+// Generates a file of random premises over the whole alphabet, one per line, each nested up to
+// the depth
+pub fn generate_file(rng: &mut SeededRng, premises: usize, depth: usize) -> String {
+    (0..premises)
+        .map(|_| Premise::arbitrary(depth, 26, rng).to_string() + "\n")
+        .collect()
+}
+
+// Generates a random 3-CNF formula of the given number of clauses over the first propositions,
+// such as "(a ∨ ¬c ∨ d) ∧ (¬b ∨ c ∨ e) ∧ ..."
+pub fn generate_3cnf(rng: &mut SeededRng, clauses: usize, propositions: usize) -> String {
+    let letters = letters(propositions);
+
+    (0..clauses)
+        .map(|_| {
+            let literals: Vec<String> = (0..3)
+                .map(|_| {
+                    let negation = if rng.below(2) == 0 { "!" } else { "" };
+                    format!("{}{}", negation, letters[rng.below(letters.len())])
+                })
+                .collect();
+
+            format!("({})", literals.join(" | "))
+        })
+        .collect::<Vec<String>>()
+        .join(" & ")
+}
+
+// Generates a formula over the first propositions which mentions every one of them, so its truth
+// table has a column for each
+pub fn generate_table_formula(rng: &mut SeededRng, propositions: usize) -> Premise {
+    let every = letters(propositions)
+        .iter()
+        .map(char::to_string)
+        .collect::<Vec<String>>()
+        .join(" ∨ ");
+
+    Premise::parse_str(&format!(
+        "{} ∧ ({})",
+        generate_conjunction(rng, 100, 6, propositions),
+        every
+    ))
+    .unwrap()
+}
+
+// Generates a chain of implications "x0", "x0 → x1", ..., ending at the given length, alongside
+// premises over other propositions which never change, such as "(y0 ∨ z0) ∧ (y0 → z0)"
+// When cyclic the chain ends with an implication back to "x0"
+// The premises are shuffled, so the order they are solved in is not the order of the chain
+pub fn generate_chain(
+    rng: &mut SeededRng,
+    length: usize,
+    unchanged: usize,
+    cyclic: bool,
+) -> Vec<Premise> {
+    let mut premises = vec![proposition(0)];
+    premises.extend((1..length).map(|i| proposition(i - 1).implies(proposition(i))));
+
+    if cyclic {
+        premises.push(proposition(length - 1).implies(proposition(0)));
+    }
+
+    premises.extend((0..unchanged).map(|i| {
+        let (y, z) = (length + 2 * i, length + 2 * i + 1);
+        (proposition(y) | proposition(z)) & proposition(y).implies(proposition(z))
+    }));
+
+    for i in (1..premises.len()).rev() {
+        premises.swap(i, rng.below(i + 1));
+    }
+
+    premises
+}
+
+// Builds a premise of the given number of groups nested inside each other, such as
+// "(a ∧ (b ∨ (c → ...)))", which should take time linear in the depth to parse
+pub fn generate_nested(depth: usize) -> String {
+    let mut premise = String::new();
+
+    for i in 0..depth {
+        premise.push_str(&format!(
+            "({} {} ",
+            (b'a' + (i % 26) as u8) as char,
+            ["∧", "∨", "→"][i % 3]
+        ));
+    }
+    premise.push('z');
+    premise.push_str(&")".repeat(depth));
+
+    premise
+}
+
+// Builds a premise of the given number of nested clauses over the propositions a to h, such as
+// "(a ∧ ¬b ∨ (c → (...)))", and gives the values of those propositions, such as "¬a" and "b"
+// Returns the premise with the values substituted, where every clause takes a few steps to
+// simplify, and the premise along with the values as premises to solve
+pub fn generate_clauses(clauses: usize) -> (Premise, Vec<Premise>) {
+    let propositions = letters(8);
+    let mut premise = String::from("a");
+
+    for i in 0..clauses {
+        let first = propositions[i % propositions.len()];
+        let second = propositions[(i * 3 + 1) % propositions.len()];
+
+        premise = match i % 3 {
+            0 => format!("({} & !{} | ({}))", first, second, premise),
+            1 => format!("({} > ({}) & {})", first, premise, second),
+            _ => format!("(!({}) | {} & {})", premise, first, second),
+        };
+    }
+
+    let premise = Premise::parse_str(&premise).unwrap();
+    let mut values = ValueMap::default();
+    let mut premises = Vec::new();
+
+    for (i, proposition) in propositions.into_iter().enumerate() {
+        let value = i % 3 != 0;
+        values.set_value(proposition, Some(value));
+        premises.push(match value {
+            true => Premise::atom(proposition),
+            false => !Premise::atom(proposition),
+        });
+    }
+
+    premises.push(premise.clone());
+
+    (premise.substituted(&values), premises)
+}
+
+// Builds a formula over the propositions a to h in which each of a few large subformulas appears
+// many times, as formulas converted to a normal form often do, such as
+// "(a ∨ ((a ∧ b) ∨ (c → d) ∨ ...)) ∧ (b ∨ (...)) ∧ ..."
+pub fn generate_repeated(repeats: usize) -> Premise {
+    let shared = [
+        "((a & b) | (c > d) | !(e & f) | (g > (h | a)))",
+        "((b | c) & (d > e) & !(f | g) | (h & a))",
+        "(!(a > c) | (e & (g | !b)) | (d & !h))",
+    ];
+
+    let formula = (0..repeats)
+        .map(|i| {
+            let proposition = (b'a' + (i % 8) as u8) as char;
+            format!("({} | {})", proposition, shared[i % shared.len()])
+        })
+        .collect::<Vec<String>>()
+        .join(" & ");
+
+    Premise::parse_str(&formula).unwrap()
+}
+
+// Builds a tautology over the first propositions by joining "x → x ∨ y" for each pair of
+// neighbouring propositions, which is true in every row but needs every proposition to show it
+pub fn generate_tautology(propositions: usize) -> Premise {
+    let formula = letters(propositions)
+        .windows(2)
+        .map(|pair| format!("({} > ({} | {}))", pair[0], pair[0], pair[1]))
+        .collect::<Vec<String>>()
+        .join(" & ");
+
+    Premise::parse_str(&formula).unwrap()
+}
+
+// Builds premises for a long solve whose history is measured: a premise which takes about two
+// steps per level of nesting to simplify once "a" is known, alongside premises over other
+// propositions which never change, such as "a", "a ∧ (a ∧ (...))", "(w ∨ x) ∧ (y → ¬z)", ...
+pub fn generate_history(depth: usize, unchanged: usize) -> Vec<Premise> {
+    let mut nested = String::from("a");
+    for _ in 0..depth {
+        nested = format!("a & ({})", nested);
+    }
+
+    let mut premises = vec![Premise::atom('a'), Premise::parse_str(&nested).unwrap()];
+    premises.extend((0..unchanged).map(|_| Premise::parse_str("(w | x) & (y > !z)").unwrap()));
+
+    premises
+}
+
+// The inputs of every benchmark which are generated from SEED, which is done once outside of the
+// measured code
+pub struct Inputs {
+    pub large_premise: String,
+    pub premise_file: String,
+    pub table_formula_15: Premise,
+    pub table_formula_16: Premise,
+    pub nested_formula: Premise,
+    pub random_3cnf: Premise,
+    pub copied_premises: Vec<Premise>,
+    pub chains: Vec<(&'static str, Vec<Premise>)>,
+}
+
+impl Inputs {
+    pub fn new() -> Self {
+        let mut rng = SeededRng::new(SEED);
+
+        Self {
+            large_premise: generate_conjunction(&mut rng, 10_000, 8, 26),
+            premise_file: generate_file(&mut rng, 10_000, 6),
+            table_formula_15: generate_table_formula(&mut rng, 15),
+            table_formula_16: generate_table_formula(&mut rng, 16),
+            nested_formula: Premise::arbitrary(5, 6, &mut rng),
+            random_3cnf: Premise::parse_str(&generate_3cnf(&mut rng, 50, 12)).unwrap(),
+            copied_premises: (0..200)
+                .map(|_| Premise::arbitrary(4, 26, &mut rng))
+                .collect(),
+            chains: vec![
+                ("100", generate_chain(&mut rng, 100, 0, false)),
+                ("1000", generate_chain(&mut rng, 1_000, 0, false)),
+                ("1000_cyclic", generate_chain(&mut rng, 1_000, 0, true)),
+                (
+                    "500_with_500_unchanged",
+                    generate_chain(&mut rng, 500, 500, false),
+                ),
+            ],
+        }
+    }
+}
+
+// Parses one large premise
+pub fn parse_large(premise: &str) -> Premise {
+    Premise::parse_str(premise).unwrap()
+}
+
+// Parses every line of a file of premises into owned Premises
+pub fn parse_file(file: &str) -> Vec<Premise> {
+    file.lines()
+        .map(|line| Premise::parse_str(line).unwrap())
+        .collect()
+}
+
+// Parses every line of a file of premises into PremiseRefs, which borrow from the file
+pub fn parse_file_borrowed(file: &str) -> Vec<PremiseRef<'_>> {
+    file.lines()
+        .map(|line| Premise::parse_borrowed(line).unwrap())
+        .collect()
+}
+
+// Solves the example argument from the start
+pub fn solve_example() -> EvaluationHistory {
+    Deduction::from_strs(EXAMPLE_PREMISES.to_vec())
+        .unwrap()
+        .solve()
+}
+
+// Solves the premises from the start, returning the solved Deduction and its history
+pub fn solve(premises: &[Premise]) -> (Deduction, EvaluationHistory) {
+    let mut deduction = Deduction::from_premises(premises.to_vec());
+    let history = deduction.solve();
+
+    (deduction, history)
+}
+
+// Simplifies the premise by calling evaluate_step until it returns None, which rebuilds the
+// nodes of the premise on every step
+pub fn simplify_by_steps(premise: &Premise) -> Premise {
+    let mut premise = premise.clone();
+    while premise.evaluate_step().is_some() {}
+
+    premise
+}
+
+// Simplifies the premise in one call, which looks up each rule in a table during a single scan
+// of an arena
+pub fn simplify(premise: &Premise) -> Premise {
+    let mut premise = premise.clone();
+    premise.simplify();
+
+    premise
+}
+
+// Builds the truth table of the formula
+pub fn truth_table(formula: &Premise) -> TruthTable {
+    TruthTable::new(formula, formula.propositions().len()).unwrap()
+}
+
+// Evaluates the formula under every assignment of its propositions one at a time, without the
+// sharing of work a truth table does
+pub fn evaluate_every_row(formula: &Premise) -> usize {
+    let propositions: Vec<char> = formula.propositions().into_iter().collect();
+    let mut satisfying = 0;
+
+    for assignment in 0..1u32 << propositions.len() {
+        let mut values = ValueMap::default();
+        for (i, proposition) in propositions.iter().enumerate() {
+            values.set_value(*proposition, Some(assignment & (1 << i) != 0));
+        }
+
+        satisfying += usize::from(formula.evaluate(&values) == Some(true));
+    }
+
+    satisfying
+}
+
+// Checks whether the formula entails "a ∨ b"
+pub fn check_entailment(formula: &Premise) -> bool {
+    let deduction = Deduction::from_premises(vec![formula.clone()]);
+    let goal = Premise::parse_str("a | b").unwrap();
+
+    matches!(deduction.check_entailment(&goal), Entailment::Proved)
+}
+
+// Checks every row of the formula's truth table across the given number of threads, returning
+// whether it is a tautology and how many rows satisfy it
+pub fn check_rows(formula: &Premise, threads: usize) -> (bool, usize) {
+    let table = ParallelTable::new(formula).with_threads(threads);
+
+    (table.is_tautology(), table.count_satisfying())
+}
+
+// Converts the formula to conjunctive normal form
+pub fn cnf(formula: &Premise) -> Premise {
+    formula.to_cnf(DEFAULT_MAX_CLAUSES).unwrap()
+}
+
+// Checks whether the formula can be satisfied
+// There is no DPLL solver yet, so this goes through the rows of its truth table until one
+// satisfies it
+pub fn satisfy(formula: &Premise) -> bool {
+    formula.is_satisfiable()
+}
+
+// Copies every premise on the stack at the start of each pass
+#[derive(Default)]
+pub struct CopyingObserver {
+    pub copies: Vec<Vec<Premise>>,
+}
+
+impl SolveObserver for CopyingObserver {
+    fn on_step_start(&mut self, _pass: usize, deduction: &Deduction) -> ControlFlow<()> {
+        self.copies.push(
+            deduction
+                .get_labeled_premises()
+                .into_iter()
+                .map(|(_, premise)| premise.clone())
+                .collect(),
+        );
+
+        ControlFlow::Continue(())
+    }
+}
+
+// Solves the premises along with "a", "b" and "n" while copying every premise at each pass,
+// which clones premises heavily
+pub fn solve_copying(premises: &[Premise]) -> CopyingObserver {
+    let mut premises = premises.to_vec();
+    premises.extend(['a', 'b', 'n'].map(Premise::atom));
+
+    let mut observer = CopyingObserver::default();
+    Deduction::from_premises(premises).solve_with_observer(&mut observer);
+
+    observer
+}
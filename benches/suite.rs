@@ -0,0 +1,192 @@
+// Measures the parser and solver on seeded inputs, as a baseline for comparing changes to them
+// Before the benchmarks run, the allocations made by the workloads which copy premises heavily
+// are counted and printed
+// Run with `cargo bench --bench suite`, or `cargo bench --bench suite -- <name>` for one group
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+#[path = "inputs/allocations.rs"]
+mod allocations;
+mod inputs;
+
+use allocations::CountingAllocator;
+use inputs::Inputs;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// Prints how many allocations parsing and copying premises make, and how many bytes the history
+// of a long solve holds, which stores the Deduction at the start of every pass
+fn report_allocations(inputs: &Inputs) {
+    let (owned, _) = allocations::count(|| inputs::parse_file(&inputs.premise_file));
+    let (borrowed, _) = allocations::count(|| inputs::parse_file_borrowed(&inputs.premise_file));
+    println!(
+        "parse_file: {} allocations owned, {} borrowed",
+        owned, borrowed
+    );
+
+    let (cloned, _) = allocations::count(|| inputs.copied_premises.clone());
+    let (copying, _) = allocations::count(|| inputs::solve_copying(&inputs.copied_premises));
+    println!(
+        "clone: {} allocations for 200 premises, {} solving while copying every pass",
+        cloned, copying
+    );
+
+    for (depth, unchanged) in [(100, 100), (250, 100), (250, 400)] {
+        let premises = inputs::generate_history(depth, unchanged);
+        let (bytes, (_, history)) = allocations::held(|| inputs::solve(&premises));
+
+        println!(
+            "history: {} premises, {} passes, {} bytes held",
+            premises.len(),
+            history.get_deductions().len(),
+            bytes
+        );
+    }
+}
+
+fn parse(c: &mut Criterion) {
+    let inputs = Inputs::new();
+    report_allocations(&inputs);
+
+    let mut group = c.benchmark_group("parse");
+    group.bench_function("large", |b| {
+        b.iter(|| inputs::parse_large(black_box(&inputs.large_premise)))
+    });
+    group.bench_function("file", |b| {
+        b.iter(|| inputs::parse_file(black_box(&inputs.premise_file)))
+    });
+    group.bench_function("file_borrowed", |b| {
+        b.iter(|| inputs::parse_file_borrowed(black_box(&inputs.premise_file)))
+    });
+
+    for depth in [250, 500, 1000, 2000] {
+        let nested = inputs::generate_nested(depth);
+        group.bench_with_input(BenchmarkId::new("nested", depth), &nested, |b, nested| {
+            b.iter(|| inputs::parse_large(nested))
+        });
+    }
+
+    group.finish();
+}
+
+fn solve(c: &mut Criterion) {
+    let inputs = Inputs::new();
+
+    let mut group = c.benchmark_group("solve");
+    group.bench_function("example", |b| b.iter(inputs::solve_example));
+
+    for (name, chain) in &inputs.chains {
+        group.bench_with_input(BenchmarkId::new("chain", name), chain, |b, chain| {
+            b.iter(|| inputs::solve(chain))
+        });
+    }
+
+    for (depth, unchanged) in [(100, 100), (250, 400)] {
+        let premises = inputs::generate_history(depth, unchanged);
+        let name = format!("{}_with_{}_unchanged", depth, unchanged);
+        group.bench_with_input(
+            BenchmarkId::new("history", name),
+            &premises,
+            |b, premises| b.iter(|| inputs::solve(premises)),
+        );
+    }
+
+    group.bench_function("copying_every_pass", |b| {
+        b.iter(|| inputs::solve_copying(black_box(&inputs.copied_premises)))
+    });
+    group.finish();
+}
+
+fn simplify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simplify");
+
+    for clauses in [50, 100, 200] {
+        let (substituted, premises) = inputs::generate_clauses(clauses);
+
+        group.bench_with_input(
+            BenchmarkId::new("by_steps", clauses),
+            &substituted,
+            |b, premise| b.iter(|| inputs::simplify_by_steps(premise)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("at_once", clauses),
+            &substituted,
+            |b, premise| b.iter(|| inputs::simplify(premise)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("solve", clauses),
+            &premises,
+            |b, premises| b.iter(|| inputs::solve(premises)),
+        );
+    }
+
+    group.finish();
+}
+
+fn enumerate(c: &mut Criterion) {
+    let inputs = Inputs::new();
+
+    let mut group = c.benchmark_group("enumerate");
+    group.bench_function("truth_table_15", |b| {
+        b.iter(|| inputs::truth_table(black_box(&inputs.table_formula_15)))
+    });
+    group.bench_function("truth_table_16", |b| {
+        b.iter(|| inputs::truth_table(black_box(&inputs.table_formula_16)))
+    });
+    group.bench_function("entailment", |b| {
+        b.iter(|| inputs::check_entailment(black_box(&inputs.table_formula_15)))
+    });
+
+    // Each subformula repeats many times, so the truth table shares work evaluating every row
+    // one at a time cannot
+    for repeats in [10, 40] {
+        let repeated = inputs::generate_repeated(repeats);
+        group.bench_with_input(
+            BenchmarkId::new("repeated_every_row", repeats),
+            &repeated,
+            |b, formula| b.iter(|| inputs::evaluate_every_row(formula)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("repeated_truth_table", repeats),
+            &repeated,
+            |b, formula| b.iter(|| inputs::truth_table(formula)),
+        );
+    }
+
+    let tautology = inputs::generate_tautology(22);
+    for threads in [1, 2, 4, 8] {
+        group.bench_with_input(
+            BenchmarkId::new("tautology_22_threads", threads),
+            &threads,
+            |b, threads| b.iter(|| inputs::check_rows(&tautology, *threads)),
+        );
+    }
+
+    group.finish();
+}
+
+fn normal_form(c: &mut Criterion) {
+    let inputs = Inputs::new();
+
+    let mut group = c.benchmark_group("normal_form");
+    group.bench_function("cnf_nested", |b| {
+        b.iter(|| inputs::cnf(black_box(&inputs.nested_formula)))
+    });
+    group.bench_function("satisfy_3cnf", |b| {
+        b.iter(|| inputs::satisfy(black_box(&inputs.random_3cnf)))
+    });
+    group.bench_function("clone_200", |b| {
+        b.iter(|| black_box(&inputs.copied_premises).clone())
+    });
+    group.finish();
+}
+
+criterion_group! {
+    name = suite;
+    config = Criterion::default().sample_size(20);
+    targets = parse, solve, simplify, enumerate, normal_form
+}
+criterion_main!(suite);
@@ -0,0 +1,109 @@
+// The bodies of the fuzz targets, which tests/fuzz.rs also runs over the inputs in fuzz/seeds so
+// they are checked without the fuzzer
+// Each body panics if it finds a bug, and returns early for inputs it does not handle
+
+use std::collections::BTreeSet;
+
+use deductions::generator::SeededRng;
+use deductions::observer::SolveOptions;
+use deductions::{Deduction, EvaluationHistory, Premise, ValueMap};
+
+// The longest input the parser is given, so a slow input is one which takes longer than it
+// should for its length rather than one which is merely long
+pub const MAX_INPUT_LENGTH: usize = 1024;
+
+// The most steps a generated argument may take to solve
+pub const MAX_STEPS: usize = 10_000;
+
+// Parses the bytes as a premise, which must fail with an error rather than panicking if they do
+// not form one, and must display as a string which parses back to the same premise if they do
+pub fn parse(data: &[u8]) {
+    if data.len() > MAX_INPUT_LENGTH {
+        return;
+    }
+
+    let input = String::from_utf8_lossy(data);
+    let Ok(premise) = Premise::parse_str(&input) else {
+        return;
+    };
+
+    // Every node is written with at least one character, so the premise grows no faster than
+    // the input
+    assert!(premise.node_count() <= input.chars().count());
+
+    let displayed = premise.to_string();
+    assert_eq!(
+        Premise::parse_str(&displayed).as_ref(),
+        Ok(&premise),
+        "{:?} displays as {:?}",
+        input,
+        displayed
+    );
+}
+
+// Solves an argument generated from the bytes, where the first 8 are the seed and the next 3 give
+// the number of premises, their depth and the number of propositions, checking that every value
+// the solver derives holds in every model of the premises found by trying every assignment
+pub fn solve(data: &[u8]) {
+    let mut bytes = [0; 11];
+    for (byte, value) in bytes.iter_mut().zip(data) {
+        *byte = *value;
+    }
+
+    let mut seed = [0; 8];
+    seed.copy_from_slice(&bytes[..8]);
+    let mut rng = SeededRng::new(u64::from_le_bytes(seed));
+
+    let count = 1 + bytes[8] as usize % 6;
+    let depth = bytes[9] as usize % 5;
+    let propositions = 1 + bytes[10] as usize % 5;
+
+    let premises: Vec<Premise> = (0..count)
+        .map(|_| Premise::arbitrary(depth, propositions, &mut rng))
+        .collect();
+
+    let mut deduction = Deduction::from_premises(premises.clone());
+    let options = SolveOptions {
+        max_steps: Some(MAX_STEPS),
+        timeout: None,
+        history: None,
+    };
+    deduction.solve_with_options(&options, &mut EvaluationHistory::new());
+
+    let mentioned: Vec<char> = premises
+        .iter()
+        .flat_map(Premise::propositions)
+        .collect::<BTreeSet<char>>()
+        .into_iter()
+        .collect();
+
+    for assignment in 0..1u32 << mentioned.len() {
+        let mut model = ValueMap::default();
+        for (i, proposition) in mentioned.iter().enumerate() {
+            model.set_value(*proposition, Some(assignment & (1 << i) != 0));
+        }
+
+        if !premises
+            .iter()
+            .all(|premise| premise.evaluate(&model) == Some(true))
+        {
+            continue;
+        }
+
+        for (proposition, value) in deduction.get_values().iter() {
+            if value.is_some() {
+                assert_eq!(
+                    value,
+                    model.get_value(proposition),
+                    "{} was derived for the premises {:?}, which the model {} also satisfies",
+                    proposition,
+                    premises
+                        .iter()
+                        .map(Premise::to_string)
+                        .collect::<Vec<String>>(),
+                    model
+                );
+            }
+        }
+    }
+}
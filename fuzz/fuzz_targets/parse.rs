@@ -0,0 +1,13 @@
+// Feeds arbitrary bytes to the fallible parser
+// Run with `cargo +nightly fuzz run parse fuzz/corpus/parse fuzz/seeds/parse -- -max_len=1024`
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Each target only uses its own body
+#[allow(dead_code)]
+#[path = "../bodies.rs"]
+mod bodies;
+
+fuzz_target!(|data: &[u8]| bodies::parse(data));
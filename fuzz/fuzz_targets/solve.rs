@@ -0,0 +1,13 @@
+// Solves random arguments built by the premise generator from the bytes
+// Run with `cargo +nightly fuzz run solve fuzz/corpus/solve fuzz/seeds/solve`
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Each target only uses its own body
+#[allow(dead_code)]
+#[path = "../bodies.rs"]
+mod bodies;
+
+fuzz_target!(|data: &[u8]| bodies::solve(data));
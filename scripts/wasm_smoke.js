@@ -0,0 +1,39 @@
+// Calls each export of the wasm feature from Node, checking the JSON it returns and that mistakes
+// in the input are thrown as errors
+// Build the package first with
+// `wasm-pack build --target nodejs --out-name deductions -- --features wasm`
+// and then run `node scripts/wasm_smoke.js`
+
+const assert = require("assert");
+const path = require("path");
+
+const wasm = require(path.join(__dirname, "..", "pkg", "deductions.js"));
+
+const solved = JSON.parse(
+  wasm.solve_argument(
+    JSON.stringify(["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"])
+  )
+);
+assert.strictEqual(solved.values.j, true);
+assert.strictEqual(solved.completed, true);
+assert.ok(solved.steps.length > 0);
+
+const proved = JSON.parse(
+  wasm.solve_argument(
+    JSON.stringify({ premises: ["p > q", "p"], conclusion: "q" })
+  )
+);
+assert.strictEqual(proved.entailment.result, "proved");
+assert.throws(() => wasm.solve_argument(JSON.stringify(["p >"])), /P1/);
+assert.throws(() => wasm.solve_argument("not json"));
+
+const valid = JSON.parse(wasm.check_formula("(m & !b) > j"));
+assert.strictEqual(valid.valid, true);
+assert.deepStrictEqual(valid.propositions, ["b", "j", "m"]);
+assert.strictEqual(JSON.parse(wasm.check_formula("p & $")).valid, false);
+
+const table = JSON.parse(wasm.truth_table("p > q"));
+assert.strictEqual(table.rows.length, 4);
+assert.throws(() => wasm.truth_table("p &"));
+
+console.log("Every export of the wasm package works");
@@ -0,0 +1,440 @@
+use std::cell::Cell;
+
+use crate::premises::{find_main_operator_of, EvaluationRule, Operator};
+use crate::rewrite::{OperandKind, RewriteTable};
+use crate::Premise;
+use crate::PremiseNode;
+
+// Stores every node of a premise in one vector and refers to them by index, with each subpremise
+// holding the indices of its nodes, so a rewrite only moves indices around instead of cloning the
+// subpremises it keeps
+// Nodes which are no longer used are left in the vector until the arena is converted back
+#[derive(Debug, Clone)]
+pub(crate) struct PremiseArena {
+    nodes: Vec<ArenaNode>,
+    root: Vec<usize>,
+}
+
+// Represents a node in the arena, where a Leaf is never a subpremise
+#[derive(Debug, Clone)]
+enum ArenaNode {
+    Leaf(PremiseNode),
+    Group(Vec<usize>),
+}
+
+// The work left to do while simplifying, kept on a stack instead of recursing so deeply nested
+// premises can be simplified
+#[derive(Debug)]
+enum Task {
+    // Simplifies the nodes, leaving them on the stack of results
+    Nodes(Vec<usize>),
+    // Joins the two results on top of the stack with the operator at the index
+    Operator(usize),
+    // Finishes the operand made of negations and the group at the index, whose simplified nodes
+    // are on top of the stack of results, given how many rules had been used before entering it
+    Group(Vec<usize>, usize, usize),
+}
+
+impl PremiseArena {
+    // Copies the nodes of the premise into a new arena
+    pub(crate) fn from_premise(premise: &Premise) -> Self {
+        let mut arena = Self {
+            nodes: Vec::with_capacity(premise.node_count()),
+            root: Vec::new(),
+        };
+        arena.root = arena.add_nodes(premise);
+
+        arena
+    }
+
+    // Builds a premise from the nodes which are still used, dropping the rest
+    pub(crate) fn to_premise(&self) -> Premise {
+        self.build_premise(&self.root)
+    }
+
+    // Simplifies the premise until nothing else can be evaluated, in a single scan which leaves
+    // each part as simplified as Premise::evaluate_step would before moving on
+    // Each rule is looked up in the RewriteTable by the shape of the nodes it would apply to and
+    // given to on_rewrite, in the same order as evaluate_step would use them
+    pub(crate) fn simplify(&mut self, on_rewrite: &mut impl FnMut(EvaluationRule)) {
+        let root = std::mem::take(&mut self.root);
+        self.root = self.simplify_nodes(root, RewriteTable::get(), on_rewrite);
+
+        // A premise which is entirely one subpremise does not need the parentheses
+        while let [i] = self.root.as_slice() {
+            let ArenaNode::Group(nodes) = &mut self.nodes[*i] else {
+                break;
+            };
+
+            self.root = std::mem::take(nodes);
+            on_rewrite(EvaluationRule::Parentheses);
+        }
+    }
+
+    // Adds the nodes of the premise, returning their indices
+    // The subpremises being added are kept on a stack along with the indices of their nodes so far,
+    // so deeply nested premises can be added
+    fn add_nodes(&mut self, premise: &Premise) -> Vec<usize> {
+        let mut stack = vec![(premise.get_nodes().iter(), Vec::new())];
+
+        loop {
+            let nodes = &mut stack.last_mut().unwrap().0;
+
+            let node = match nodes.next() {
+                Some(PremiseNode::Subpremise(subpremise)) => {
+                    stack.push((subpremise.get_nodes().iter(), Vec::new()));
+                    continue;
+                }
+                Some(node) => ArenaNode::Leaf(node.clone()),
+                None => {
+                    let indices = stack.pop().unwrap().1;
+
+                    if stack.is_empty() {
+                        return indices;
+                    }
+
+                    ArenaNode::Group(indices)
+                }
+            };
+
+            let i = self.push(node);
+            stack.last_mut().unwrap().1.push(i);
+        }
+    }
+
+    // Builds a premise from the nodes at the given indices, keeping the subpremises being built on a
+    // stack like add_nodes
+    fn build_premise(&self, indices: &[usize]) -> Premise {
+        let mut stack = vec![(indices.iter(), Vec::new())];
+
+        loop {
+            let indices = &mut stack.last_mut().unwrap().0;
+
+            let node = match indices.next().map(|&i| &self.nodes[i]) {
+                Some(ArenaNode::Group(group)) => {
+                    stack.push((group.iter(), Vec::new()));
+                    continue;
+                }
+                Some(ArenaNode::Leaf(node)) => node.clone(),
+                None => {
+                    let premise = Premise::new(stack.pop().unwrap().1);
+
+                    if stack.is_empty() {
+                        return premise;
+                    }
+
+                    PremiseNode::Subpremise(premise)
+                }
+            };
+
+            stack.last_mut().unwrap().1.push(node);
+        }
+    }
+
+    // Adds a node which is not a subpremise, returning its index
+    pub(crate) fn push_leaf(&mut self, node: PremiseNode) -> usize {
+        self.push(ArenaNode::Leaf(node))
+    }
+
+    // Adds a node, returning its index
+    fn push(&mut self, node: ArenaNode) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    // Returns the operator at the index, if the node is one
+    fn get_operator(&self, i: usize) -> Option<Operator> {
+        match self.nodes[i] {
+            ArenaNode::Leaf(PremiseNode::Operator(operator)) => Some(operator),
+            _ => None,
+        }
+    }
+
+    // Checks whether the node at the index is a negation
+    fn is_negation(&self, i: usize) -> bool {
+        matches!(self.nodes[i], ArenaNode::Leaf(PremiseNode::Negation))
+    }
+
+    // Returns the truth value of the nodes if they are exactly one truth value
+    pub(crate) fn get_truth_value(&self, indices: &[usize]) -> Option<bool> {
+        match indices {
+            [i] => match self.nodes[*i] {
+                ArenaNode::Leaf(PremiseNode::TruthValue(value)) => Some(value),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    // Checks whether the nodes form a single operand, like premises::is_single_operand
+    fn is_single_operand(&self, indices: &[usize]) -> bool {
+        let negations = indices.iter().take_while(|&&i| self.is_negation(i)).count();
+
+        indices.len() == negations + 1
+            && match &self.nodes[indices[negations]] {
+                ArenaNode::Leaf(node) => node.is_operand(),
+                ArenaNode::Group(_) => true,
+            }
+    }
+
+    // Wraps the nodes in a new subpremise unless they already form a single operand
+    pub(crate) fn wrap_operand(&mut self, indices: Vec<usize>) -> Vec<usize> {
+        match self.is_single_operand(&indices) {
+            true => indices,
+            false => vec![self.push(ArenaNode::Group(indices))],
+        }
+    }
+
+    // Returns the nodes of the group at the index
+    pub(crate) fn get_group(&self, i: usize) -> &[usize] {
+        match &self.nodes[i] {
+            ArenaNode::Group(nodes) => nodes,
+            ArenaNode::Leaf(_) => &[],
+        }
+    }
+
+    // Returns what the rewrite rules need to know about the nodes as an operand
+    fn get_operand_kind(&self, indices: &[usize]) -> OperandKind {
+        match indices {
+            [i] => match &self.nodes[*i] {
+                ArenaNode::Leaf(PremiseNode::TruthValue(true)) => OperandKind::True,
+                ArenaNode::Leaf(PremiseNode::TruthValue(false)) => OperandKind::False,
+                ArenaNode::Group(nodes) if self.is_single_operand(nodes) => OperandKind::Wrapped,
+                _ => OperandKind::Other,
+            },
+            _ => OperandKind::Other,
+        }
+    }
+
+    // Simplifies the nodes, going down to the operands on either side of each main operator and
+    // into each group before rewriting what they are a part of
+    // The left operand is always finished before the right one, so rules are used in the same
+    // order as evaluating the innermost, leftmost reducible part one step at a time
+    fn simplify_nodes(
+        &mut self,
+        indices: Vec<usize>,
+        table: &RewriteTable,
+        on_rewrite: &mut impl FnMut(EvaluationRule),
+    ) -> Vec<usize> {
+        let mut tasks = vec![Task::Nodes(indices)];
+        let mut results: Vec<Vec<usize>> = Vec::new();
+
+        let rewrites = Cell::new(0);
+        let on_rewrite = &mut |rule| {
+            rewrites.set(rewrites.get() + 1);
+            on_rewrite(rule);
+        };
+
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Nodes(mut nodes) => {
+                    let operators = nodes.iter().map(|&i| self.get_operator(i));
+
+                    if let Some(i) = find_main_operator_of(operators) {
+                        let right = nodes.split_off(i + 1);
+                        let operator = nodes.pop().unwrap();
+
+                        tasks.push(Task::Operator(operator));
+                        tasks.push(Task::Nodes(right));
+                        tasks.push(Task::Nodes(nodes));
+                        continue;
+                    }
+
+                    let negations = nodes.iter().take_while(|&&i| self.is_negation(i)).count();
+
+                    match nodes[negations..] {
+                        [operand] => match &mut self.nodes[operand] {
+                            ArenaNode::Group(group) => {
+                                let group = std::mem::take(group);
+                                tasks.push(Task::Group(nodes, operand, rewrites.get()));
+                                tasks.push(Task::Nodes(group));
+                            }
+                            ArenaNode::Leaf(_) => {
+                                results.push(self.rewrite_operand(nodes, table, on_rewrite))
+                            }
+                        },
+                        // Malformed input cannot be evaluated
+                        _ => results.push(nodes),
+                    }
+                }
+                Task::Operator(operator) => {
+                    let right = results.pop().unwrap();
+                    let left = results.pop().unwrap();
+
+                    results.push(self.rewrite_operator(left, operator, right, table, on_rewrite));
+                }
+                Task::Group(mut nodes, operand, before) => {
+                    let group = results.pop().unwrap();
+
+                    // A rule used inside a subpremise drops its parentheses along with it once
+                    // they only hold a single operand, as evaluate_step does
+                    if rewrites.get() > before && self.is_single_operand(&group) {
+                        nodes.pop();
+                        nodes.extend(group);
+                    } else {
+                        self.nodes[operand] = ArenaNode::Group(group);
+                    }
+
+                    results.push(self.rewrite_operand(nodes, table, on_rewrite));
+                }
+            }
+        }
+
+        results.pop().unwrap()
+    }
+
+    // Rewrites the simplified operands joined by the operator at the index if a rule matches them,
+    // and otherwise joins them back together
+    fn rewrite_operator(
+        &mut self,
+        mut left: Vec<usize>,
+        operator: usize,
+        right: Vec<usize>,
+        table: &RewriteTable,
+        on_rewrite: &mut impl FnMut(EvaluationRule),
+    ) -> Vec<usize> {
+        let Some(operator_value) = self.get_operator(operator) else {
+            unreachable!("the main operator is an operator")
+        };
+
+        let rule = table.binary(
+            self.get_operand_kind(&left),
+            operator_value,
+            self.get_operand_kind(&right),
+        );
+
+        match rule {
+            Some(rule) => {
+                on_rewrite(rule.rule);
+                let nodes = (rule.rewrite)(self, &left, operator_value, &right);
+
+                // Negating the left operand may leave a double negation
+                self.rewrite_operand(nodes, table, on_rewrite)
+            }
+            None => {
+                left.push(operator);
+                left.extend(right);
+                left
+            }
+        }
+    }
+
+    // Rewrites the nodes while they are a single operand after some negations which a rule
+    // matches, where any group in them has already been simplified
+    fn rewrite_operand(
+        &mut self,
+        mut nodes: Vec<usize>,
+        table: &RewriteTable,
+        on_rewrite: &mut impl FnMut(EvaluationRule),
+    ) -> Vec<usize> {
+        loop {
+            let negations = nodes.iter().take_while(|&&i| self.is_negation(i)).count();
+
+            let [operand] = nodes[negations..] else {
+                return nodes;
+            };
+            let Some(rule) = table.negated(negations, self.get_operand_kind(&[operand])) else {
+                return nodes;
+            };
+
+            on_rewrite(rule.rule);
+            nodes = (rule.rewrite)(self, &nodes[..negations], operand);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ValueMap;
+
+    // Simplifies the premise one evaluate_step at a time, as the arena is meant to, returning the
+    // rules used along the way
+    fn simplify_by_steps(premise: &Premise) -> (Premise, Vec<EvaluationRule>) {
+        let mut premise = premise.clone();
+        let mut rules = Vec::new();
+
+        while let Some(rule) = premise.evaluate_step() {
+            rules.push(rule);
+        }
+
+        (premise, rules)
+    }
+
+    // Generates a premise over the propositions a to d from the seed, nesting up to the depth
+    fn generate(seed: &mut u64, depth: usize) -> Premise {
+        *seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let choice = (*seed >> 33) % 10;
+
+        if depth == 0 || choice < 2 {
+            return Premise::atom(['a', 'b', 'c', 'd'][(*seed >> 40) as usize % 4]);
+        }
+
+        match choice {
+            2 => !generate(seed, depth - 1),
+            3 => !!generate(seed, depth - 1),
+            4 => {
+                // Redundant parentheses, which the constructors never add themselves
+                let parenthesized =
+                    |premise| Premise::from_nodes(vec![PremiseNode::Subpremise(premise)]).unwrap();
+
+                parenthesized(parenthesized(generate(seed, depth - 1)))
+            }
+            _ => Premise::combine(
+                [
+                    Operator::And,
+                    Operator::Or,
+                    Operator::Implies,
+                    Operator::Iff,
+                    Operator::Xor,
+                ][choice as usize - 5],
+                generate(seed, depth - 1),
+                generate(seed, depth - 1),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for premise in ["(m & !b) > j", "!(((p)))", "a > (b | !(c & d)) > e"] {
+            let premise = Premise::parse_str(premise).unwrap();
+            assert_eq!(PremiseArena::from_premise(&premise).to_premise(), premise);
+        }
+    }
+
+    #[test]
+    fn test_simplify_matches_steps() {
+        let mut seed = 1;
+
+        for _ in 0..1000 {
+            let premise = generate(&mut seed, 6);
+
+            // Try every assignment of a and b, leaving c and d unknown
+            for assignment in 0..4 {
+                let mut values = ValueMap::default();
+                values.set_value('a', Some(assignment & 1 != 0));
+                values.set_value('b', Some(assignment & 2 != 0));
+                values.set_value('c', None);
+                values.set_value('d', None);
+
+                let substituted = premise.substituted(&values);
+                let mut arena = PremiseArena::from_premise(&substituted);
+                let mut rules = Vec::new();
+                arena.simplify(&mut |rule| rules.push(rule));
+
+                // The table-driven scan uses the same rules in the same order as the rules
+                // evaluate_step implements one at a time
+                assert_eq!(
+                    (arena.to_premise(), rules),
+                    simplify_by_steps(&substituted),
+                    "Simplifying {} with a={}, b={}",
+                    substituted,
+                    assignment & 1 != 0,
+                    assignment & 2 != 0
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::io::{self, BufRead, Write};
+
+use deductions::notation::Notation;
+use deductions::proof::{InferenceRule, Proof, ProofStep};
+use deductions::Premise;
+
+const HELP: &str = "\
+Rules:
+  mp <line> <line>       Modus ponens: from p → q and p, derive q
+  mt <line> <line>       Modus tollens: from p → q and ¬q, derive ¬p
+  hs <line> <line>       Hypothetical syllogism: from p → q and q → r, derive p → r
+  ds <line> <line>       Disjunctive syllogism: from p ∨ q and ¬p, derive q
+  conj <line> <line>     Conjunction: from p and q, derive p ∧ q
+  simp <line> [formula]  Simplification: from p ∧ q, derive p, or q if it is given
+  add <line> <formula>   Addition: from p, derive a disjunction with p as one side
+  dn <line>              Double negation: from ¬¬p, derive p
+
+Commands:
+  hint                   Suggest the next step towards the goal
+  undo                   Remove the last derived line
+  show                   Show the proof so far
+  export [file]          Print the proof as two columns, or save it to a file
+  help                   Show this message
+  quit                   Leave the assistant";
+
+// Stores the proof being built in the assistant along with the goal it should reach
+#[derive(Debug)]
+pub struct Assistant {
+    proof: Proof,
+    goal: Premise,
+}
+
+// Runs the assistant until the input ends or the user quits, printing the premises and the goal
+// first and a prompt before each line
+pub fn run(
+    premises: Vec<Premise>,
+    goal: Premise,
+    input: impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<()> {
+    let mut assistant = Assistant::new(premises, goal);
+    let mut lines = input.lines();
+
+    assistant.introduce(output)?;
+
+    loop {
+        write!(output, ">> ")?;
+        output.flush()?;
+
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+
+        if !assistant.execute_line(&line, output)? {
+            break;
+        }
+    }
+
+    writeln!(output)
+}
+
+impl Assistant {
+    // Creates an assistant for proving the goal from the premises
+    pub fn new(premises: Vec<Premise>, goal: Premise) -> Self {
+        Self {
+            proof: Proof::new(premises),
+            goal,
+        }
+    }
+
+    // Prints the numbered premises and the goal along with how to apply a rule
+    fn introduce(&self, output: &mut impl Write) -> io::Result<()> {
+        write!(output, "{}", self.proof.render(Notation::Unicode))?;
+        writeln!(output, "Goal: {}", self.goal)?;
+
+        match self.proof.find_line(&self.goal) {
+            Some(line) => writeln!(output, "The goal is already line {}", line),
+            None => writeln!(
+                output,
+                "Type a rule and the lines to apply it to, such as 'mp 1 2', or 'help' for every rule"
+            ),
+        }
+    }
+
+    // Runs a single line of input, returning whether the assistant should keep going
+    pub fn execute_line(&mut self, line: &str, output: &mut impl Write) -> io::Result<bool> {
+        let line = line.trim();
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let rest = rest.trim();
+
+        match command {
+            "" => (),
+            "hint" => match (
+                self.proof.find_line(&self.goal),
+                self.proof.find_next_step(&self.goal),
+            ) {
+                (Some(line), _) => writeln!(output, "The goal is already proved on line {}", line)?,
+                (None, Some(step)) => writeln!(output, "Try '{}'", step)?,
+                (None, None) => writeln!(
+                    output,
+                    "No proof of the goal could be found from here with these rules"
+                )?,
+            },
+            "undo" => match self.proof.undo() {
+                Some(line) => writeln!(output, "Removed {}", line.get_formula())?,
+                None => writeln!(output, "There is no derived line to remove")?,
+            },
+            "show" => write!(output, "{}", self.proof.render(Notation::Unicode))?,
+            "export" => self.export(rest, output)?,
+            "help" => writeln!(output, "{}", HELP)?,
+            "quit" | "exit" => return Ok(false),
+            _ => match InferenceRule::parse(command) {
+                Some(rule) => self.apply(rule, rest, output)?,
+                None => writeln!(
+                    output,
+                    "Unknown rule or command '{}', type 'help' for a list of both",
+                    command
+                )?,
+            },
+        }
+
+        Ok(true)
+    }
+
+    // Applies the rule to the line numbers at the start of the arguments, taking the rest as the
+    // formula to derive, then prints the proof so far
+    fn apply(
+        &mut self,
+        rule: InferenceRule,
+        arguments: &str,
+        output: &mut impl Write,
+    ) -> io::Result<()> {
+        let mut words = arguments.split_whitespace().peekable();
+        let mut lines = Vec::new();
+
+        while let Some(line) = words.peek().and_then(|word| word.parse::<usize>().ok()) {
+            lines.push(line);
+            words.next();
+        }
+
+        let formula = words.collect::<Vec<&str>>().join(" ");
+        let formula = match formula.as_str() {
+            "" => None,
+            formula => match Premise::parse_str(formula) {
+                Ok(formula) => Some(formula),
+                Err(error) => return writeln!(output, "Error: {}", error),
+            },
+        };
+
+        let already_proved = self.proof.find_line(&self.goal).is_some();
+
+        let number = match self.proof.apply(&ProofStep {
+            rule,
+            lines,
+            formula,
+        }) {
+            Ok(number) => number,
+            Err(error) => return writeln!(output, "Error: {}", error),
+        };
+
+        write!(output, "{}", self.proof.render(Notation::Unicode))?;
+
+        if !already_proved && self.proof.find_line(&self.goal) == Some(number) {
+            writeln!(
+                output,
+                "Congratulations, line {} proves the goal {}! Type 'export' to print the proof",
+                number, self.goal
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Prints the proof as two columns, or saves it to the given file
+    fn export(&self, path: &str, output: &mut impl Write) -> io::Result<()> {
+        let proof = self.proof.render(Notation::Unicode);
+
+        if path.is_empty() {
+            return write!(output, "{}", proof);
+        }
+
+        match std::fs::write(path, proof) {
+            Ok(()) => writeln!(output, "Saved the proof to '{}'", path),
+            Err(error) => writeln!(output, "Error: Could not write '{}': {}", path, error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Runs the assistant on the given input, returning everything it printed
+    fn run_script(premises: &[&str], goal: &str, script: &str) -> String {
+        let premises = premises
+            .iter()
+            .map(|premise| Premise::parse_str(premise).unwrap())
+            .collect();
+        let mut output = Vec::new();
+        run(
+            premises,
+            Premise::parse_str(goal).unwrap(),
+            script.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_assist() {
+        let output = run_script(
+            &["p > q", "q > r", "p"],
+            "r",
+            "hint\nmp 1 3\nmp 3 2\nhint\nmp 2 4\nexport\nquit\nhint\n",
+        );
+
+        assert_eq!(
+            output,
+            "1. p → q  Premise\n\
+             2. q → r  Premise\n\
+             3. p      Premise\n\
+             Goal: r\n\
+             Type a rule and the lines to apply it to, such as 'mp 1 2', or 'help' for every rule\n\
+             >> Try 'mp 1 3'\n\
+             >> 1. p → q  Premise\n2. q → r  Premise\n3. p      Premise\n4. q      Modus ponens 1, 3\n\
+             >> Error: Modus ponens does not apply, as line 3 is p, which is not the antecedent q of line 2\n\
+             >> Try 'mp 2 4'\n\
+             >> 1. p → q  Premise\n2. q → r  Premise\n3. p      Premise\n4. q      Modus ponens 1, 3\n\
+             5. r      Modus ponens 2, 4\n\
+             Congratulations, line 5 proves the goal r! Type 'export' to print the proof\n\
+             >> 1. p → q  Premise\n2. q → r  Premise\n3. p      Premise\n4. q      Modus ponens 1, 3\n\
+             5. r      Modus ponens 2, 4\n\
+             >> \n"
+        );
+    }
+
+    #[test]
+    fn test_assist_errors() {
+        let output = run_script(
+            &["p & q"],
+            "q | r",
+            "undo\nsimp 1 r\nadd 1 p |\nfoo 1\nsimp 1\nundo\nsimp 1 q\nadd 2 q | r\nhint\n",
+        );
+
+        assert_eq!(
+            output,
+            "1. p ∧ q  Premise\n\
+             Goal: q ∨ r\n\
+             Type a rule and the lines to apply it to, such as 'mp 1 2', or 'help' for every rule\n\
+             >> There is no derived line to remove\n\
+             >> Error: Simplification does not apply, as r is not a side of line 1\n\
+             >> Error: Invalid premise structure\n\
+             >> Unknown rule or command 'foo', type 'help' for a list of both\n\
+             >> 1. p ∧ q  Premise\n2. p      Simplification 1\n\
+             >> Removed p\n\
+             >> 1. p ∧ q  Premise\n2. q      Simplification 1\n\
+             >> 1. p ∧ q  Premise\n2. q      Simplification 1\n3. q ∨ r  Addition 2\n\
+             Congratulations, line 3 proves the goal q ∨ r! Type 'export' to print the proof\n\
+             >> The goal is already proved on line 3\n\
+             >> \n"
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use crate::errors::ParseError;
+use crate::premises::{find_main_operator, Operator};
+use crate::Premise;
+use crate::PremiseNode;
+
+// Represents a premise as a tree in which every operator has exactly two operands, which is
+// easier to work with than the flat nodes of a Premise when writing recursive algorithms
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub enum BinaryExpr {
+    Atom(char),
+    Const(bool),
+    Not(Box<BinaryExpr>),
+    Binary(Operator, Box<BinaryExpr>, Box<BinaryExpr>),
+}
+
+impl BinaryExpr {
+    // Creates the negation of the expression
+    pub fn negation(operand: BinaryExpr) -> Self {
+        BinaryExpr::Not(Box::new(operand))
+    }
+
+    // Creates a binary operation on the two expressions
+    pub fn binary(operator: Operator, left: BinaryExpr, right: BinaryExpr) -> Self {
+        BinaryExpr::Binary(operator, Box::new(left), Box::new(right))
+    }
+
+    // Builds the tree of the given nodes, splitting them at their main operator so the precedence
+    // and associativity of the operators are kept
+    fn from_nodes(nodes: &[PremiseNode]) -> Result<Self, ParseError> {
+        if let Some(i) = find_main_operator(nodes) {
+            let PremiseNode::Operator(operator) = nodes[i] else {
+                unreachable!()
+            };
+
+            return Ok(BinaryExpr::binary(
+                operator,
+                BinaryExpr::from_nodes(&nodes[..i])?,
+                BinaryExpr::from_nodes(&nodes[i + 1..])?,
+            ));
+        }
+
+        match nodes {
+            [PremiseNode::Negation, operand @ ..] => {
+                Ok(BinaryExpr::negation(BinaryExpr::from_nodes(operand)?))
+            }
+            [PremiseNode::Proposition(proposition)] => Ok(BinaryExpr::Atom(*proposition)),
+            [PremiseNode::TruthValue(value)] => Ok(BinaryExpr::Const(*value)),
+            [PremiseNode::Subpremise(subpremise)] => BinaryExpr::from_nodes(subpremise.get_nodes()),
+            _ => Err(ParseError::InvalidStructure),
+        }
+    }
+
+    // Converts the tree to nodes, only adding the parentheses needed to keep its structure
+    fn to_nodes(&self) -> Vec<PremiseNode> {
+        match self {
+            BinaryExpr::Atom(proposition) => vec![PremiseNode::Proposition(*proposition)],
+            BinaryExpr::Const(value) => vec![PremiseNode::TruthValue(*value)],
+            BinaryExpr::Not(operand) => {
+                let mut nodes = vec![PremiseNode::Negation];
+
+                match operand.as_ref() {
+                    BinaryExpr::Binary(..) => {
+                        nodes.push(PremiseNode::Subpremise(Premise::new(operand.to_nodes())))
+                    }
+                    _ => nodes.extend(operand.to_nodes()),
+                }
+
+                nodes
+            }
+            BinaryExpr::Binary(operator, left, right) => {
+                let mut nodes = left.to_operand_nodes(*operator, false);
+                nodes.push(PremiseNode::Operator(*operator));
+                nodes.extend(right.to_operand_nodes(*operator, true));
+
+                nodes
+            }
+        }
+    }
+
+    // Converts the tree to nodes as an operand of the given operator, in parentheses if it is an
+    // operation which would otherwise be grouped differently when parsed
+    fn to_operand_nodes(&self, parent: Operator, is_right: bool) -> Vec<PremiseNode> {
+        let needs_parentheses = match self {
+            BinaryExpr::Binary(operator, ..) => {
+                operator.precedence() < parent.precedence()
+                    || (operator.precedence() == parent.precedence()
+                        && is_right != parent.is_right_associative())
+            }
+            _ => false,
+        };
+
+        match needs_parentheses {
+            true => vec![PremiseNode::Subpremise(Premise::new(self.to_nodes()))],
+            false => self.to_nodes(),
+        }
+    }
+}
+
+impl Premise {
+    // Converts the Premise to a binary tree, which drops any redundant parentheses
+    pub fn to_binary(&self) -> Result<BinaryExpr, ParseError> {
+        BinaryExpr::from_nodes(self.get_nodes())
+    }
+
+    // Creates a Premise from a binary tree, with only the parentheses needed to keep its structure
+    pub fn from_binary(expression: &BinaryExpr) -> Self {
+        Premise::new(expression.to_nodes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_binary() {
+        use BinaryExpr::{Atom, Const};
+
+        // ∧ binds tighter than ∨, and ∨ tighter than →
+        assert_eq!(
+            Premise::parse_str("a & b | c > !d").unwrap().to_binary(),
+            Ok(BinaryExpr::binary(
+                Operator::Implies,
+                BinaryExpr::binary(
+                    Operator::Or,
+                    BinaryExpr::binary(Operator::And, Atom('a'), Atom('b')),
+                    Atom('c')
+                ),
+                BinaryExpr::negation(Atom('d'))
+            ))
+        );
+
+        // → groups to the right, the other operators to the left
+        assert_eq!(
+            Premise::parse_str("a > b > c").unwrap().to_binary(),
+            Ok(BinaryExpr::binary(
+                Operator::Implies,
+                Atom('a'),
+                BinaryExpr::binary(Operator::Implies, Atom('b'), Atom('c'))
+            ))
+        );
+        assert_eq!(
+            Premise::parse_str("a & b & c").unwrap().to_binary(),
+            Ok(BinaryExpr::binary(
+                Operator::And,
+                BinaryExpr::binary(Operator::And, Atom('a'), Atom('b')),
+                Atom('c')
+            ))
+        );
+
+        let premise = Premise::new(vec![PremiseNode::Negation, PremiseNode::TruthValue(false)]);
+        assert_eq!(premise.to_binary(), Ok(BinaryExpr::negation(Const(false))));
+
+        let premise = Premise::new(vec![
+            PremiseNode::Proposition('a'),
+            PremiseNode::Operator(Operator::And),
+        ]);
+        assert_eq!(premise.to_binary(), Err(ParseError::InvalidStructure));
+    }
+
+    #[test]
+    fn test_from_binary() {
+        // Only the parentheses which change the grouping are kept
+        let cases = [
+            ("(m & !b) > j", "m ∧ ¬b → j"),
+            ("(f | s) > m", "f ∨ s → m"),
+            ("b > t", "b → t"),
+            ("f > !t", "f → ¬t"),
+            ("f", "f"),
+            ("!m > (j | (k > l))", "¬m → j ∨ (k → l)"),
+            ("!(b & c)", "¬(b ∧ c)"),
+            ("!!(p)", "¬¬p"),
+            ("(a > b) > c", "(a → b) → c"),
+            ("a > (b > c)", "a → b → c"),
+            ("(a & b) & c", "a ∧ b ∧ c"),
+            ("a & (b & c)", "a ∧ (b ∧ c)"),
+            ("(a | b) & c", "(a ∨ b) ∧ c"),
+            ("a | (b & c)", "a ∨ b ∧ c"),
+        ];
+
+        for (premise, expected) in cases {
+            let premise = Premise::parse_str(premise).unwrap();
+            let binary = premise.to_binary().unwrap();
+            let converted = Premise::from_binary(&binary);
+
+            assert_eq!(converted.to_string(), expected);
+            assert_eq!(converted.to_binary(), Ok(binary));
+            assert!(converted.is_equivalent(&premise));
+        }
+    }
+}
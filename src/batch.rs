@@ -0,0 +1,320 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Result};
+
+use serde_json::{json, Value};
+
+use crate::entailment::Entailment;
+use crate::loader::BatchBlock;
+use crate::notation::Notation;
+use crate::Deduction;
+use crate::Premise;
+use crate::ValueMap;
+
+// The outcome of checking one argument in a batch
+#[derive(Debug, Clone)]
+pub enum Verdict {
+    // The premises entail the conclusion
+    Valid,
+    // The premises are consistent but do not entail the conclusion, as in the counterexample
+    Invalid { counterexample: ValueMap },
+    // No assignment makes every premise true
+    Inconsistent,
+    // The argument could not be loaded or has no conclusion, along with the reason
+    Malformed(String),
+}
+
+// Stores the verdict for one argument in a batch, along with the line it starts on
+#[derive(Debug, Clone)]
+pub struct BatchResult {
+    line: usize,
+    // The line a malformed argument could not be parsed on, numbered from 1
+    error_line: Option<usize>,
+    conclusion: Option<Premise>,
+    values: Option<ValueMap>,
+    verdict: Verdict,
+}
+
+// Counts how many arguments in a batch had each verdict
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy)]
+pub struct Tally {
+    pub valid: usize,
+    pub invalid: usize,
+    pub inconsistent: usize,
+    pub malformed: usize,
+}
+
+impl Display for Verdict {
+    // Displays the verdict as a single lowercase word
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Verdict::Valid => write!(f, "valid"),
+            Verdict::Invalid { .. } => write!(f, "invalid"),
+            Verdict::Inconsistent => write!(f, "inconsistent"),
+            Verdict::Malformed(_) => write!(f, "malformed"),
+        }
+    }
+}
+
+impl Display for Tally {
+    // Displays the counts such as "14 valid, 3 invalid, 1 inconsistent", only mentioning
+    // malformed arguments if there were any
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "{} valid, {} invalid, {} inconsistent",
+            self.valid, self.invalid, self.inconsistent
+        )?;
+
+        if self.malformed > 0 {
+            write!(f, ", {} malformed", self.malformed)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Checks every argument in a batch, solving each one which could be loaded
+pub fn check_batch(blocks: Vec<BatchBlock>) -> Vec<BatchResult> {
+    blocks
+        .into_iter()
+        .map(|block| match block.deduction {
+            Ok(deduction) => check_argument(block.line, deduction),
+            Err(error) => BatchResult {
+                line: block.line,
+                error_line: error.get_line(),
+                conclusion: None,
+                values: None,
+                verdict: Verdict::Malformed(error.to_string()),
+            },
+        })
+        .collect()
+}
+
+// Checks whether a single argument is valid, then solves it to find the values of its propositions
+fn check_argument(line: usize, mut deduction: Deduction) -> BatchResult {
+    let conclusion = deduction.get_conclusion().cloned();
+    let verdict = find_verdict(&deduction);
+
+    deduction.solve();
+
+    BatchResult {
+        line,
+        error_line: None,
+        conclusion,
+        values: Some(deduction.get_values().clone()),
+        verdict,
+    }
+}
+
+// Checks whether the argument is valid, which it must be checked for before it is solved
+pub(crate) fn find_verdict(deduction: &Deduction) -> Verdict {
+    match deduction.get_conclusion() {
+        None => Verdict::Malformed(String::from("The argument has no conclusion")),
+        Some(_) if deduction.find_model().is_none() => Verdict::Inconsistent,
+        Some(conclusion) => match deduction.check_entailment(conclusion) {
+            Entailment::Proved => Verdict::Valid,
+            Entailment::NotProvable { counterexample }
+            | Entailment::Contradicted { counterexample } => Verdict::Invalid { counterexample },
+        },
+    }
+}
+
+// Counts the verdicts of every result
+pub fn tally(results: &[BatchResult]) -> Tally {
+    let mut tally = Tally::default();
+
+    for result in results {
+        match result.verdict {
+            Verdict::Valid => tally.valid += 1,
+            Verdict::Invalid { .. } => tally.invalid += 1,
+            Verdict::Inconsistent => tally.inconsistent += 1,
+            Verdict::Malformed(_) => tally.malformed += 1,
+        }
+    }
+
+    tally
+}
+
+// Renders one line per argument, numbered from 1 like render_report, with its verdict in
+// uppercase, such as "#2: INVALID"
+pub fn render_lines(results: &[BatchResult]) -> String {
+    results
+        .iter()
+        .enumerate()
+        .map(|(i, result)| {
+            format!(
+                "#{}: {}\n",
+                i + 1,
+                result.verdict.to_string().to_uppercase()
+            )
+        })
+        .collect()
+}
+
+// Renders a block per argument, numbered from 1, starting with a line giving its verdict and the
+// values which decided it, such as "#1: VALID (j=TRUE)" or "#2: PARSE ERROR line 6"
+// Malformed arguments are followed by the reason on an indented line, and the tally comes last
+pub fn render_report(results: &[BatchResult], notation: Notation) -> String {
+    let mut report = String::new();
+
+    for (i, result) in results.iter().enumerate() {
+        let verdict = match (&result.verdict, result.error_line) {
+            (Verdict::Valid, _) => match (&result.conclusion, &result.values) {
+                (Some(conclusion), Some(values)) => format!(
+                    "VALID ({})",
+                    render_values(values, &conclusion.propositions(), notation)
+                ),
+                _ => String::from("VALID"),
+            },
+            (Verdict::Invalid { counterexample }, _) => {
+                let propositions = counterexample.iter().map(|(p, _)| p).collect();
+                format!(
+                    "INVALID ({})",
+                    render_values(counterexample, &propositions, notation)
+                )
+            }
+            (Verdict::Inconsistent, _) => String::from("INCONSISTENT"),
+            (Verdict::Malformed(_), Some(line)) => format!("PARSE ERROR line {}", line),
+            (Verdict::Malformed(_), None) => String::from("MALFORMED"),
+        };
+
+        report.push_str(&format!("#{}: {}\n", i + 1, verdict));
+
+        if let Verdict::Malformed(reason) = &result.verdict {
+            report.push_str(&format!("    {}\n", reason));
+        }
+    }
+
+    report.push_str(&format!("\n{}\n", tally(results)));
+    report
+}
+
+// Renders the values of the given propositions in alphabetical order, such as "p=FALSE, q=TRUE"
+fn render_values(values: &ValueMap, propositions: &BTreeSet<char>, notation: Notation) -> String {
+    propositions
+        .iter()
+        .map(|&proposition| match values.get_value(proposition) {
+            Some(value) => format!("{}={}", proposition, notation.truth_value(value)),
+            None => format!("{}=UNKNOWN", proposition),
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// Converts the results to a JSON array with one object per argument
+pub fn to_json(results: &[BatchResult]) -> Value {
+    Value::Array(results.iter().map(BatchResult::to_json).collect())
+}
+
+impl BatchResult {
+    // Returns the line the argument starts on in the batch
+    pub fn get_line(&self) -> usize {
+        self.line
+    }
+
+    // Returns the verdict for the argument
+    pub fn get_verdict(&self) -> &Verdict {
+        &self.verdict
+    }
+
+    // Converts the result to a JSON object, with the counterexample for invalid arguments
+    // and the reason for malformed ones
+    pub fn to_json(&self) -> Value {
+        let mut json = json!({
+            "line": self.line,
+            "verdict": self.verdict.to_string(),
+            "conclusion": self.conclusion.as_ref().map(Premise::to_string),
+            "values": self.values.as_ref().map(ValueMap::to_json),
+        });
+
+        match &self.verdict {
+            Verdict::Invalid { counterexample } => {
+                json["counterexample"] = counterexample.to_json();
+            }
+            Verdict::Malformed(reason) => json["error"] = json!(reason),
+            _ => (),
+        }
+
+        json
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    // Checks every argument in the given batch text
+    fn check(input: &str) -> Vec<BatchResult> {
+        check_batch(Deduction::batch_from_reader(input.as_bytes(), Path::new("batch.txt")).unwrap())
+    }
+
+    #[test]
+    fn test_check_batch() {
+        let results = check("p > q\np\n∴ q\n\np > q\nq\n∴ p\n\np\n!p\n∴ q\n\np\n\nq &\n∴ q\n");
+
+        let verdicts: Vec<String> = results
+            .iter()
+            .map(|result| result.get_verdict().to_string())
+            .collect();
+        assert_eq!(
+            verdicts,
+            ["valid", "invalid", "inconsistent", "malformed", "malformed"]
+        );
+
+        match results[1].get_verdict() {
+            Verdict::Invalid { counterexample } => {
+                assert_eq!(counterexample.get_value('p'), Some(false));
+                assert_eq!(counterexample.get_value('q'), Some(true));
+            }
+            other => panic!("Expected Invalid, found {:?}", other),
+        }
+
+        assert_eq!(
+            tally(&results),
+            Tally {
+                valid: 1,
+                invalid: 1,
+                inconsistent: 1,
+                malformed: 2
+            }
+        );
+        assert_eq!(
+            tally(&results).to_string(),
+            "1 valid, 1 invalid, 1 inconsistent, 2 malformed"
+        );
+    }
+
+    #[test]
+    fn test_render_report() {
+        let results = check(
+            "# Modus ponens\np > q\np\n∴ q\n\n\
+             p > q\nq\n∴ p\n\n\
+             p\n!p\n∴ q\n\n\
+             p\nq &\n∴ q\n\n\
+             p\n",
+        );
+
+        assert_eq!(
+            render_report(&results, Notation::Unicode),
+            "#1: VALID (q=TRUE)\n\
+             #2: INVALID (p=FALSE, q=TRUE)\n\
+             #3: INCONSISTENT\n\
+             #4: PARSE ERROR line 15\n    \
+             'batch.txt', line 15: Invalid premise structure\n\
+             #5: MALFORMED\n    \
+             The argument has no conclusion\n\
+             \n\
+             1 valid, 1 invalid, 1 inconsistent, 2 malformed\n"
+        );
+    }
+
+    #[test]
+    fn test_render_lines() {
+        let results = check("p > q\np\n∴ q\n---\np | # q\n∴ p\n");
+
+        assert_eq!(render_lines(&results), "#1: VALID\n#2: MALFORMED\n");
+        assert_eq!(to_json(&results)[1]["verdict"], "malformed");
+        assert_eq!(to_json(&results)[0]["values"]["q"], true);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::errors::{BuildError, ParseError};
+use crate::Deduction;
+use crate::Premise;
+
+// Stores a premise given to the builder, which is only parsed once the Deduction is built
+#[derive(Debug, Clone)]
+enum Source {
+    Parsed(Premise),
+    Text(String),
+}
+
+impl Source {
+    // Parses the premise if it was given as a string
+    fn parse(self) -> std::result::Result<Premise, ParseError> {
+        match self {
+            Source::Parsed(premise) => Ok(premise),
+            Source::Text(text) => Premise::parse_str(&text),
+        }
+    }
+}
+
+// Collects the premises and conclusion of a Deduction, so premises given as strings and ones
+// already parsed can be mixed and every parse error is reported in one place
+#[derive(Debug, Default, Clone)]
+pub struct DeductionBuilder {
+    premises: Vec<Source>,
+    conclusion: Option<Source>,
+}
+
+impl Deduction {
+    // Creates a builder for a Deduction, which is the recommended way to create one in code
+    pub fn builder() -> DeductionBuilder {
+        DeductionBuilder::default()
+    }
+}
+
+impl DeductionBuilder {
+    // Adds an already-parsed premise
+    pub fn premise(mut self, premise: Premise) -> Self {
+        self.premises.push(Source::Parsed(premise));
+        self
+    }
+
+    // Adds a premise to be parsed when the Deduction is built
+    pub fn premise_str(mut self, premise: &str) -> Self {
+        self.premises.push(Source::Text(premise.to_string()));
+        self
+    }
+
+    // Sets an already-parsed conclusion, replacing any given before
+    pub fn conclusion(mut self, conclusion: Premise) -> Self {
+        self.conclusion = Some(Source::Parsed(conclusion));
+        self
+    }
+
+    // Sets a conclusion to be parsed when the Deduction is built, replacing any given before
+    pub fn conclusion_str(mut self, conclusion: &str) -> Self {
+        self.conclusion = Some(Source::Text(conclusion.to_string()));
+        self
+    }
+
+    // Parses every premise and the conclusion and creates the Deduction
+    // Returns the first parse error found, naming the premise or conclusion it was found in
+    pub fn build(self) -> std::result::Result<Deduction, BuildError> {
+        let premises = self
+            .premises
+            .into_iter()
+            .enumerate()
+            .map(|(i, premise)| {
+                premise.parse().map_err(|error| BuildError::Premise {
+                    number: i + 1,
+                    error,
+                })
+            })
+            .collect::<std::result::Result<Vec<Premise>, BuildError>>()?;
+
+        let mut deduction = Deduction::from_premises(premises);
+
+        if let Some(conclusion) = self.conclusion {
+            deduction.set_conclusion(conclusion.parse().map_err(BuildError::Conclusion)?);
+        }
+
+        Ok(deduction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        let mut built = Deduction::builder()
+            .premise_str("(m & !b) > j")
+            .premise_str("(f | s) > m")
+            .premise(Premise::parse_str("b > t").unwrap())
+            .premise_str("f > !t")
+            .premise_str("f")
+            .conclusion_str("j")
+            .build()
+            .unwrap();
+        let mut deduction =
+            Deduction::from_strs(vec!["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"])
+                .unwrap();
+
+        assert_eq!(
+            built.get_conclusion(),
+            Some(&Premise::parse_str("j").unwrap())
+        );
+        assert_eq!(
+            built.solve().get_steps().len(),
+            deduction.solve().get_steps().len()
+        );
+        assert_eq!(
+            built.get_values().to_string(),
+            deduction.get_values().to_string()
+        );
+        assert_eq!(built.evaluate_conclusion(), Some(true));
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let error = Deduction::builder()
+            .premise_str("p > q")
+            .premise_str("p & | q")
+            .build()
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Premise P2: Invalid premise structure");
+
+        let error = Deduction::builder()
+            .premise_str("p > q")
+            .conclusion_str("q #")
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            BuildError::Conclusion(ParseError::InvalidCharacter {
+                character: '#',
+                position: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::premises::{find_main_operator_of, Operator, PremiseNode};
+use crate::Premise;
+use crate::ValueMap;
+
+// Stores the values of the subpremises evaluated with it, so a subformula which appears many
+// times, or is evaluated again under the same values, is only evaluated once
+// Each distinct subpremise is given an id, found from its nodes with every subpremise inside them
+// replaced by its own id, so subpremises only share an id when they have the same structure
+// Each value is keyed by the id of the subpremise along with the values of the propositions it
+// mentions, so one cache can be reused across many ValueMaps
+#[derive(Debug, Default, Clone)]
+pub struct EvaluationCache {
+    ids: HashMap<Vec<CachedNode>, usize>,
+    // The propositions of each subpremise, in alphabetical order, by its id
+    propositions: Vec<Vec<char>>,
+    values: HashMap<(usize, Vec<Option<bool>>), Option<bool>>,
+    hits: usize,
+}
+
+// Represents a node of a subpremise in the cache, where a subpremise inside it is given by its id
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+enum CachedNode {
+    Proposition(char),
+    TruthValue(bool),
+    Operator(Operator),
+    Negation,
+    Subpremise(usize),
+}
+
+// Represents a node of a list being evaluated, where each operand is replaced by its value
+#[derive(Debug, Clone, Copy)]
+enum Operand {
+    Value(Option<bool>),
+    Operator(Operator),
+    Negation,
+}
+
+impl EvaluationCache {
+    // Creates an empty EvaluationCache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Returns how many values are stored
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    // Checks whether no values are stored
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    // Returns how many times a stored value was used instead of evaluating a subpremise again
+    pub fn get_hits(&self) -> usize {
+        self.hits
+    }
+
+    // Removes every stored value
+    pub fn clear(&mut self) {
+        self.ids.clear();
+        self.propositions.clear();
+        self.values.clear();
+        self.hits = 0;
+    }
+
+    // Returns the id of the subpremise with the given nodes, giving it the next one if it is new
+    // The propositions of a new subpremise are found from those of the subpremises inside it,
+    // which already have ids
+    fn intern(&mut self, nodes: Vec<CachedNode>) -> usize {
+        if let Some(id) = self.ids.get(&nodes) {
+            return *id;
+        }
+
+        let mut propositions = BTreeSet::new();
+        for node in &nodes {
+            match node {
+                CachedNode::Proposition(proposition) => {
+                    propositions.insert(*proposition);
+                }
+                CachedNode::Subpremise(id) => propositions.extend(&self.propositions[*id]),
+                _ => (),
+            }
+        }
+
+        let id = self.propositions.len();
+        self.propositions.push(propositions.into_iter().collect());
+        self.ids.insert(nodes, id);
+
+        id
+    }
+
+    // Returns the value of the subpremise with the given id under the given values, evaluating its
+    // operands only if it has not been evaluated under the same values of its propositions before
+    fn get_or_evaluate(
+        &mut self,
+        id: usize,
+        operands: &[Operand],
+        proposition_values: &ValueMap,
+    ) -> Option<bool> {
+        let relevant: Vec<Option<bool>> = self.propositions[id]
+            .iter()
+            .map(|proposition| proposition_values.get_value(*proposition))
+            .collect();
+
+        if let Some(value) = self.values.get(&(id, relevant.clone())) {
+            self.hits += 1;
+            return *value;
+        }
+
+        let value = evaluate_operands(operands);
+        self.values.insert((id, relevant), value);
+
+        value
+    }
+}
+
+impl Premise {
+    // Finds the truth value of the Premise like evaluate, reusing the values of any subpremises the
+    // cache has already evaluated under the same values of their propositions
+    // Subpremises are evaluated from the innermost out, keeping the ones being walked on a stack,
+    // so each node is only visited once and deeply nested premises do not overflow the stack
+    pub fn evaluate_with_cache(
+        &self,
+        proposition_values: &ValueMap,
+        cache: &mut EvaluationCache,
+    ) -> Option<bool> {
+        if !self.validate() {
+            return self.evaluate(proposition_values);
+        }
+
+        // Each entry is a list of nodes being walked, how many of them have been walked, and the
+        // nodes walked so far both as they are cached and with each operand replaced by its value
+        let mut stack = vec![(self.get_nodes().as_slice(), 0, Vec::new(), Vec::new())];
+
+        loop {
+            let (nodes, walked, cached, operands) = stack.last_mut().unwrap();
+
+            if let Some(node) = nodes.get(*walked) {
+                *walked += 1;
+
+                let (cached_node, operand) = match node {
+                    PremiseNode::Subpremise(subpremise) => {
+                        stack.push((subpremise.get_nodes().as_slice(), 0, Vec::new(), Vec::new()));
+                        continue;
+                    }
+                    PremiseNode::Proposition(proposition) => (
+                        CachedNode::Proposition(*proposition),
+                        Operand::Value(proposition_values.get_value(*proposition)),
+                    ),
+                    PremiseNode::TruthValue(value) => {
+                        (CachedNode::TruthValue(*value), Operand::Value(Some(*value)))
+                    }
+                    PremiseNode::Operator(operator) => (
+                        CachedNode::Operator(*operator),
+                        Operand::Operator(*operator),
+                    ),
+                    PremiseNode::Negation => (CachedNode::Negation, Operand::Negation),
+                };
+
+                cached.push(cached_node);
+                operands.push(operand);
+                continue;
+            }
+
+            let (_, _, cached, operands) = stack.pop().unwrap();
+
+            // The list at the bottom of the stack is the Premise itself, which is not cached
+            let Some((_, _, parent_cached, parent_operands)) = stack.last_mut() else {
+                return evaluate_operands(&operands);
+            };
+
+            let id = cache.intern(cached);
+            let value = cache.get_or_evaluate(id, &operands, proposition_values);
+
+            parent_cached.push(CachedNode::Subpremise(id));
+            parent_operands.push(Operand::Value(value));
+        }
+    }
+}
+
+// Finds the value of a valid list of nodes, given the value of each operand, by splitting it at its
+// main operator
+// Operators whose value is decided by one known operand do not need the other, as in simplify
+fn evaluate_operands(operands: &[Operand]) -> Option<bool> {
+    let main_operator = find_main_operator_of(operands.iter().map(|operand| match operand {
+        Operand::Operator(operator) => Some(*operator),
+        _ => None,
+    }));
+
+    if let Some(i) = main_operator {
+        let Operand::Operator(operator) = operands[i] else {
+            unreachable!()
+        };
+
+        return operator.apply_partial(
+            evaluate_operands(&operands[..i]),
+            evaluate_operands(&operands[i + 1..]),
+        );
+    }
+
+    match operands {
+        [Operand::Negation, operand @ ..] => evaluate_operands(operand).map(|value| !value),
+        [Operand::Value(value)] => *value,
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Generates a valid premise over the propositions a to d from the seed, nesting up to the depth
+    // and often repeating the same subformula on both sides of an operator
+    fn generate(seed: &mut u64, depth: usize) -> Premise {
+        *seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let choice = (*seed >> 33) % 8;
+
+        if depth == 0 || choice < 2 {
+            return match choice {
+                7 => Premise::from_nodes(vec![PremiseNode::TruthValue(seed.is_multiple_of(2))])
+                    .unwrap(),
+                _ => Premise::atom(['a', 'b', 'c', 'd'][(*seed >> 40) as usize % 4]),
+            };
+        }
+
+        match choice {
+            2 => !generate(seed, depth - 1),
+            3 => {
+                let repeated = generate(seed, depth - 1);
+                repeated.clone() & (repeated | generate(seed, depth - 1))
+            }
+            4 => generate(seed, depth - 1).implies(generate(seed, depth - 1)),
+            5 => generate(seed, depth - 1) | generate(seed, depth - 1),
+            _ => generate(seed, depth - 1) & generate(seed, depth - 1),
+        }
+    }
+
+    #[test]
+    fn test_matches_uncached() {
+        let mut seed = 7;
+        let mut cache = EvaluationCache::new();
+
+        for _ in 0..200 {
+            let premise = generate(&mut seed, 5);
+
+            // Every proposition may be TRUE, FALSE or unknown, and the cache is kept across all of
+            // them so stale values would be found
+            for assignment in 0..81 {
+                let mut values = ValueMap::default();
+                for (i, proposition) in ['a', 'b', 'c', 'd'].into_iter().enumerate() {
+                    let value = match assignment / 3usize.pow(i as u32) % 3 {
+                        0 => Some(true),
+                        1 => Some(false),
+                        _ => None,
+                    };
+                    values.set_value(proposition, value);
+                }
+
+                assert_eq!(
+                    premise.evaluate_with_cache(&values, &mut cache),
+                    premise.evaluate(&values),
+                    "{} under {}",
+                    premise,
+                    values.render_terse()
+                );
+            }
+        }
+
+        assert!(cache.get_hits() > 0);
+    }
+
+    #[test]
+    fn test_reuse() {
+        let premise = Premise::parse_str("(p & (q | r)) | (s > (p & (q | r)))").unwrap();
+        let mut cache = EvaluationCache::new();
+
+        let mut values = ValueMap::default();
+        values.set_value('p', Some(true));
+        values.set_value('q', Some(false));
+        values.set_value('r', Some(true));
+
+        // The second occurrences of "q ∨ r" and "p ∧ (q ∨ r)" are found in the cache
+        assert_eq!(premise.evaluate_with_cache(&values, &mut cache), Some(true));
+        assert_eq!(cache.get_hits(), 2);
+
+        // Only s changed, so both occurrences of the subformulas over p, q and r are found
+        values.set_value('s', Some(false));
+        assert_eq!(premise.evaluate_with_cache(&values, &mut cache), Some(true));
+        assert_eq!(cache.get_hits(), 6);
+
+        // Changing q changes the key of every subformula which mentions it
+        values.set_value('q', Some(false));
+        values.set_value('p', Some(false));
+        assert_eq!(premise.evaluate_with_cache(&values, &mut cache), Some(true));
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.get_hits(), 0);
+    }
+
+    #[test]
+    fn test_deeply_nested() {
+        // Builds "a ∧ (a ∧ (a ∧ ... (p ∨ q)))" with 50,000 levels around "p ∨ q"
+        let mut deep = Premise::parse_str("p | q").unwrap();
+        for _ in 0..50_000 {
+            deep = Premise::new(vec![
+                PremiseNode::Proposition('a'),
+                PremiseNode::Operator(Operator::And),
+                PremiseNode::Subpremise(deep),
+            ]);
+        }
+
+        // A small stack shows the evaluation does not recurse once per level, and each level
+        // only being visited once keeps it quick
+        std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(move || {
+                let mut cache = EvaluationCache::new();
+                let mut values = ValueMap::default();
+                values.set_value('a', Some(true));
+                values.set_value('p', Some(true));
+
+                assert_eq!(deep.evaluate_with_cache(&values, &mut cache), Some(true));
+                assert_eq!(cache.len(), 50_000);
+
+                // Every subpremise is found again under the same values
+                assert_eq!(deep.evaluate_with_cache(&values, &mut cache), Some(true));
+                assert_eq!(cache.get_hits(), 50_000);
+
+                values.set_value('a', Some(false));
+                assert_eq!(deep.evaluate_with_cache(&values, &mut cache), Some(false));
+                assert_eq!(deep.evaluate(&values), Some(false));
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+}
@@ -0,0 +1,2101 @@
+use std::fmt::{Display, Formatter, Result};
+use std::fs::File;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::assist;
+use crate::repl;
+use deductions::batch;
+use deductions::entailment::{Consistency, Entailment};
+use deductions::errors::{LoadError, ParseError};
+use deductions::events::JsonLinesObserver;
+use deductions::grading;
+use deductions::history::{EvaluationHistory, HistoryDetail, RenderDetail};
+use deductions::normal_form::{self, NormalForm, NormalFormError, DEFAULT_MAX_CLAUSES};
+use deductions::notation::Notation;
+use deductions::observer::{SolveLimit, SolveOptions, SolveResult};
+use deductions::truth_table::{RowFilter, TooManyVariables, TruthTable, DEFAULT_MAX_VARIABLES};
+use deductions::Deduction;
+use deductions::Premise;
+use deductions::ValueMap;
+
+// The argument solved when no input is given, which is also worked through in main.rs
+pub const EXAMPLE_PREMISES: [&str; 5] = ["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"];
+
+pub const USAGE: &str = "\
+Usage: deductions [COMMAND] [OPTIONS] [FILE]
+       deductions [COMMAND] [OPTIONS] <PREMISE> <PREMISE>... [--conclude <FORMULA>]
+       deductions table [OPTIONS] <FORMULA>
+       deductions batch [OPTIONS] <FILE>
+       deductions grade [OPTIONS] <FILE>
+       deductions check [OPTIONS] [FILE]
+       deductions refute [OPTIONS] [FILE]
+       deductions nf <--cnf|--dnf|--nnf> [OPTIONS] <FORMULA>
+       deductions equiv [OPTIONS] <FORMULA> <FORMULA>
+       deductions assist [OPTIONS] <FILE>
+
+Commands:
+  solve    Solve the argument and print the values found (the default)
+  prove    Check whether the premises entail the conclusion
+  table    Print the truth table of a single formula
+  batch    Check every argument in FILE, separated by blank lines or '---',
+           printing a verdict for each such as '#1: VALID (q=TRUE)'
+  grade    Check every row of a CSV FILE with a student id, premises separated
+           by ';', a conclusion and the expected verdict
+  check    Check whether the premises can all be TRUE, ignoring the conclusion
+  refute   Find values which make every premise TRUE and the conclusion FALSE
+  nf       Convert a single formula to a normal form
+  equiv    Check whether two formulas are equivalent
+  repl     Enter premises interactively
+  assist   Prove the conclusion step by step by applying rules of inference
+
+FILE is read one premise per line, with '-' reading from stdin, or as a JSON
+argument with its premises, conclusion and options if it ends in .json.
+Lines starting with '#' are comments, a '∴' or 'conclusion:' line gives the
+conclusion, and an 'assume: p=true' line gives p its value before solving.
+Two or more premises may be given on the command line instead of a FILE.
+The built-in example is used when neither is given, or with --demo.
+
+Options:
+  --conclude <FORMULA>           Use FORMULA as the conclusion of the argument
+  --demo                         Solve the built-in example argument
+  --stdin                        Read the argument from stdin, like '-'
+  --batch <FILE>                 Run the batch command on FILE
+  --prove <FORMULA>              Check whether the premises entail FORMULA
+                                 instead of the conclusion
+  --format <plain|unicode|ascii|latex|json|markdown>
+                                 How to write the output, where plain is unicode
+                                 in a terminal and ascii otherwise, and is the
+                                 default
+  --only <satisfying|falsifying> Only print the truth table rows where the
+                                 formula is TRUE or FALSE
+  --max-variables <N>            Refuse truth tables with more than N
+                                 propositions (12 by default)
+  --cnf, --dnf, --nnf            The normal form the nf command converts to
+  --clauses                      Print the clauses of a CNF or the terms of a
+                                 DNF as sets, one per line
+  --dimacs                       Print the clauses of a CNF in DIMACS format
+  --check                        Also check that the normal form is equivalent
+                                 to the formula
+  --structural                   Make the equiv command compare how the
+                                 formulas are built instead of their values,
+                                 ignoring redundant parentheses
+  --color <auto|always|never>    Whether to color the verdict
+  --max-steps <N>                Stop solving after N steps
+  --timeout <DURATION>           Stop solving after DURATION, such as 500ms,
+                                 5s or 2m
+  --steps[=full|diff|written]    Also print every pass of the solve, or with
+                                 diff only the premise changed in each step,
+                                 and with written also each premise as it
+                                 was typed
+  --trace                        Print one line per step
+  --results <FILE>               Also write the grade of every row to FILE as
+                                 a CSV
+  --events <FILE|->              Stream every event of the solve to FILE, or
+                                 to stdout with '-', as one JSON object per
+                                 line, printing the result to stderr instead
+  -v, --verbose                  Also print every pass of the solve and
+                                 statistics for it
+  -vv                            Also name the rule used in each evaluation and
+                                 list the values found in each pass
+  -q, --quiet                    Only print a single result line, such as
+                                 PROVED, NOT_PROVED or INCONSISTENT, or one
+                                 line per argument or row for the batch and
+                                 grade commands
+  -h, --help                     Print this message
+
+Exit codes:
+  0  The conclusion was proved, the argument was solved without one, the
+     premises are consistent, a counterexample was found, or the formulas
+     are equivalent
+  1  The conclusion was not proved, could not be refuted, or the formulas
+     are not equivalent
+  2  The command line or the input could not be read or parsed
+  3  The premises are inconsistent
+  4  A limit was hit, such as --max-steps, --timeout or --max-variables, or
+     a normal form would have too many clauses
+
+The batch command exits with 2 if any argument is malformed, otherwise 3 if
+any is inconsistent, otherwise 1 if any is invalid, otherwise 0. The grade
+command exits with 2 if any row is malformed, otherwise 4 if any has too
+many propositions, otherwise 1 if any verdict differs from the expected one,
+otherwise 0.";
+
+// The exit codes of the binary, which scripts and graders can rely on
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Exit {
+    Success = 0,
+    Failure = 1,
+    Usage = 2,
+    Inconsistent = 3,
+    ResourceLimit = 4,
+}
+
+// The mode the binary runs in
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Command {
+    Solve,
+    Prove,
+    Table,
+    Batch,
+    Grade,
+    Check,
+    Refute,
+    Nf,
+    Equiv,
+    Repl,
+    Assist,
+    Help,
+}
+
+// Where the argument is read from
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Input {
+    Example,
+    Stdin,
+    File(PathBuf),
+    Premises(Vec<Premise>),
+}
+
+// Where the events of a solve are streamed to by --events
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum EventOutput {
+    Stdout,
+    File(PathBuf),
+}
+
+// How the result of the nf command is written
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum NormalFormOutput {
+    Formula,
+    Clauses,
+    Dimacs,
+}
+
+// How the output is written
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum OutputFormat {
+    Unicode,
+    Ascii,
+    Latex,
+    Json,
+    Markdown,
+}
+
+// Whether the verdict is colored, with Auto only coloring when stdout is a terminal
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+// How much is printed besides the result
+#[derive(Debug, Eq, PartialEq, Clone, Copy, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+    VeryVerbose,
+}
+
+// Stores every option given on the command line
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct CliOptions {
+    pub command: Command,
+    pub input: Input,
+    pub format: Option<OutputFormat>,
+    pub color: ColorChoice,
+    pub verbosity: Verbosity,
+    pub solve_options: SolveOptions,
+    pub steps: Option<HistoryDetail>,
+    pub trace: bool,
+    pub events: Option<EventOutput>,
+    pub results: Option<PathBuf>,
+    pub goal: Option<Premise>,
+    pub conclusion: Option<Premise>,
+    pub formula: Option<Premise>,
+    pub second_formula: Option<Premise>,
+    pub row_filter: RowFilter,
+    pub max_variables: usize,
+    pub normal_form: Option<NormalForm>,
+    pub normal_form_output: NormalFormOutput,
+    pub check_equivalence: bool,
+    pub structural: bool,
+}
+
+// Represents a problem which stops the binary from producing a result
+#[derive(Debug)]
+pub enum CliError {
+    // The command line could not be understood, so the usage should be shown
+    Usage(String),
+    // The argument could not be loaded
+    Load(LoadError),
+    // The goal given with --prove could not be parsed
+    Goal(ParseError),
+    // The conclusion given with --conclude could not be parsed
+    Conclusion(ParseError),
+    // A premise given on the command line could not be parsed, with the number of the premise
+    Premise(usize, ParseError),
+    // A formula given to the table, nf or equiv command could not be parsed
+    Formula(ParseError),
+    // The formula has too many propositions for its truth table to be printed
+    TruthTable(TooManyVariables),
+    // The normal form of the formula would have too many clauses to be printed
+    NormalForm(NormalFormError),
+    // The command needs a conclusion but the argument has none
+    MissingConclusion,
+    // The output or the terminal could not be written to or read from
+    Io(io::Error),
+}
+
+impl Default for CliOptions {
+    fn default() -> Self {
+        Self {
+            command: Command::Solve,
+            input: Input::Example,
+            format: None,
+            color: ColorChoice::Auto,
+            verbosity: Verbosity::Normal,
+            solve_options: SolveOptions::default(),
+            steps: None,
+            trace: false,
+            events: None,
+            results: None,
+            goal: None,
+            conclusion: None,
+            formula: None,
+            second_formula: None,
+            row_filter: RowFilter::All,
+            max_variables: DEFAULT_MAX_VARIABLES,
+            normal_form: None,
+            normal_form_output: NormalFormOutput::Formula,
+            check_equivalence: false,
+            structural: false,
+        }
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}", message),
+            CliError::Load(error) => write!(f, "{}", error),
+            CliError::Goal(error) => write!(f, "Could not parse the goal: {}", error),
+            CliError::Conclusion(error) => write!(f, "Could not parse the conclusion: {}", error),
+            CliError::Premise(number, error) => {
+                write!(f, "Could not parse premise {}: {}", number, error)
+            }
+            CliError::Formula(error) => write!(f, "Could not parse the formula: {}", error),
+            CliError::TruthTable(error) => write!(f, "{}", error),
+            CliError::NormalForm(error) => write!(f, "{}", error),
+            CliError::MissingConclusion => {
+                write!(
+                    f,
+                    "The argument has no conclusion, add one with a '∴' line or --conclude"
+                )
+            }
+            CliError::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for CliError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CliError::Load(error) => Some(error),
+            CliError::Goal(error) => Some(error),
+            CliError::Conclusion(error) => Some(error),
+            CliError::Premise(_, error) => Some(error),
+            CliError::Formula(error) => Some(error),
+            CliError::TruthTable(error) => Some(error),
+            CliError::NormalForm(error) => Some(error),
+            CliError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<LoadError> for CliError {
+    fn from(error: LoadError) -> Self {
+        CliError::Load(error)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(error: io::Error) -> Self {
+        CliError::Io(error)
+    }
+}
+
+impl From<Exit> for ExitCode {
+    fn from(exit: Exit) -> Self {
+        ExitCode::from(exit as u8)
+    }
+}
+
+impl CliError {
+    // Returns the exit code for the error, which is 4 for limits and 2 for everything else
+    pub fn exit_code(&self) -> Exit {
+        match self {
+            CliError::TruthTable(_) | CliError::NormalForm(NormalFormError::TooManyClauses(_)) => {
+                Exit::ResourceLimit
+            }
+            _ => Exit::Usage,
+        }
+    }
+}
+
+impl Verbosity {
+    // Returns how much of the history of a solve is shown at this verbosity
+    pub fn render_detail(&self) -> RenderDetail {
+        match self {
+            Verbosity::Quiet => RenderDetail::Quiet,
+            Verbosity::Normal => RenderDetail::Premises,
+            Verbosity::Verbose => RenderDetail::Passes,
+            Verbosity::VeryVerbose => RenderDetail::Annotated,
+        }
+    }
+}
+
+impl CliOptions {
+    // Parses the command line arguments, not including the name of the binary
+    // The command may be left out, in which case the argument is solved
+    pub fn parse(
+        arguments: impl IntoIterator<Item = String>,
+    ) -> std::result::Result<Self, CliError> {
+        let mut options = Self::default();
+        let mut arguments = arguments.into_iter().peekable();
+        let mut positional = Vec::new();
+        let mut demo = false;
+
+        if let Some(command) = arguments.peek().and_then(|first| parse_command(first)) {
+            options.command = command;
+            arguments.next();
+        }
+
+        while let Some(argument) = arguments.next() {
+            // Flags may be given as "--flag value" or "--flag=value"
+            let (flag, inline_value) = match argument.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (argument.clone(), None),
+            };
+
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| arguments.next())
+                    .ok_or_else(|| CliError::Usage(format!("Missing value for '{}'", flag)))
+            };
+
+            match flag.as_str() {
+                "--format" => {
+                    options.format = match value()?.as_str() {
+                        "plain" => None,
+                        "unicode" => Some(OutputFormat::Unicode),
+                        "ascii" => Some(OutputFormat::Ascii),
+                        "latex" => Some(OutputFormat::Latex),
+                        "json" => Some(OutputFormat::Json),
+                        "markdown" => Some(OutputFormat::Markdown),
+                        other => return Err(invalid_value("--format", other)),
+                    }
+                }
+                "--color" => {
+                    options.color = match value()?.as_str() {
+                        "auto" => ColorChoice::Auto,
+                        "always" => ColorChoice::Always,
+                        "never" => ColorChoice::Never,
+                        other => return Err(invalid_value("--color", other)),
+                    }
+                }
+                "--only" => {
+                    options.row_filter = match value()?.as_str() {
+                        "satisfying" => RowFilter::Satisfying,
+                        "falsifying" => RowFilter::Falsifying,
+                        other => return Err(invalid_value("--only", other)),
+                    }
+                }
+                "--max-variables" => {
+                    let limit = value()?;
+                    options.max_variables = limit
+                        .parse()
+                        .map_err(|_| invalid_value("--max-variables", &limit))?;
+                }
+                "--max-steps" => {
+                    let steps = value()?;
+                    options.solve_options.max_steps = Some(
+                        steps
+                            .parse()
+                            .map_err(|_| invalid_value("--max-steps", &steps))?,
+                    );
+                }
+                "--timeout" => {
+                    let timeout = value()?;
+                    options.solve_options.timeout = Some(
+                        parse_duration(&timeout)
+                            .ok_or_else(|| invalid_value("--timeout", &timeout))?,
+                    );
+                }
+                "--results" => options.results = Some(PathBuf::from(value()?)),
+                "--events" => {
+                    options.events = match value()?.as_str() {
+                        "-" => Some(EventOutput::Stdout),
+                        path => Some(EventOutput::File(PathBuf::from(path))),
+                    }
+                }
+                "--prove" => {
+                    options.goal = Some(Premise::parse_str(&value()?).map_err(CliError::Goal)?);
+                }
+                "--conclude" => {
+                    options.conclusion =
+                        Some(Premise::parse_str(&value()?).map_err(CliError::Conclusion)?);
+                }
+                "--demo" => demo = true,
+                "--stdin" => positional.push(String::from("-")),
+                "--batch" => {
+                    options.command = Command::Batch;
+                    positional.push(value()?);
+                }
+                // The detail is only taken from "--steps=diff" or "--steps=written", so a file may
+                // follow the flag
+                "--steps" => {
+                    options.steps = match inline_value.as_deref() {
+                        None | Some("full") => Some(HistoryDetail::Full),
+                        Some("diff") => Some(HistoryDetail::Diff),
+                        Some("written") => Some(HistoryDetail::Written),
+                        Some(other) => return Err(invalid_value("--steps", other)),
+                    }
+                }
+                "--cnf" => options.normal_form = Some(NormalForm::Conjunctive),
+                "--dnf" => options.normal_form = Some(NormalForm::Disjunctive),
+                "--nnf" => options.normal_form = Some(NormalForm::Negation),
+                "--clauses" => options.normal_form_output = NormalFormOutput::Clauses,
+                "--dimacs" => options.normal_form_output = NormalFormOutput::Dimacs,
+                "--check" => options.check_equivalence = true,
+                "--structural" => options.structural = true,
+                "--trace" => options.trace = true,
+                // Giving -v twice is the same as -vv
+                "-v" | "--verbose" if options.verbosity >= Verbosity::Verbose => {
+                    options.verbosity = Verbosity::VeryVerbose
+                }
+                "-v" | "--verbose" => options.verbosity = Verbosity::Verbose,
+                "-vv" => options.verbosity = Verbosity::VeryVerbose,
+                "-q" | "--quiet" => options.verbosity = Verbosity::Quiet,
+                "-h" | "--help" => options.command = Command::Help,
+                "-" => positional.push(argument),
+                _ if flag.starts_with('-') => {
+                    return Err(CliError::Usage(format!("Unknown flag '{}'", argument)))
+                }
+                _ => positional.push(argument),
+            }
+        }
+
+        if options.results.is_some() && options.command != Command::Grade {
+            return Err(CliError::Usage(String::from(
+                "--results can only be used with the grade command",
+            )));
+        }
+
+        if options.conclusion.is_some()
+            && matches!(options.command, Command::Batch | Command::Grade)
+        {
+            return Err(CliError::Usage(String::from(
+                "--conclude cannot be used with the batch and grade commands",
+            )));
+        }
+
+        if options.events.is_some() && !matches!(options.command, Command::Solve | Command::Prove) {
+            return Err(CliError::Usage(String::from(
+                "--events can only be used with the solve and prove commands",
+            )));
+        }
+
+        // The table and nf commands take a formula instead of a file
+        if matches!(options.command, Command::Table | Command::Nf) {
+            return match positional.as_slice() {
+                [formula] => {
+                    options.formula = Some(Premise::parse_str(formula).map_err(CliError::Formula)?);
+                    Ok(options)
+                }
+                [] if options.command == Command::Nf => Err(CliError::Usage(String::from(
+                    "The nf command needs a formula",
+                ))),
+                [] => Err(CliError::Usage(String::from(
+                    "The table command needs a formula",
+                ))),
+                [_, extra, ..] => Err(CliError::Usage(format!("Unexpected argument '{}'", extra))),
+            };
+        }
+
+        // The equiv command takes the two formulas it compares
+        if options.command == Command::Equiv {
+            return match positional.as_slice() {
+                [first, second] => {
+                    options.formula = Some(Premise::parse_str(first).map_err(CliError::Formula)?);
+                    options.second_formula =
+                        Some(Premise::parse_str(second).map_err(CliError::Formula)?);
+                    Ok(options)
+                }
+                [_, _, extra, ..] => {
+                    Err(CliError::Usage(format!("Unexpected argument '{}'", extra)))
+                }
+                _ => Err(CliError::Usage(String::from(
+                    "The equiv command needs two formulas",
+                ))),
+            };
+        }
+
+        // Several arguments are the premises of the argument, except for the commands which read a
+        // file of arguments
+        match positional.as_slice() {
+            [] => (),
+            [extra, ..] if demo => {
+                return Err(CliError::Usage(format!("Unexpected argument '{}'", extra)))
+            }
+            [path] if path == "-" => options.input = Input::Stdin,
+            [path] => options.input = Input::File(PathBuf::from(path)),
+            [_, extra, ..] if matches!(options.command, Command::Batch | Command::Grade) => {
+                return Err(CliError::Usage(format!("Unexpected argument '{}'", extra)))
+            }
+            premises => {
+                options.input = Input::Premises(
+                    premises
+                        .iter()
+                        .enumerate()
+                        .map(|(i, premise)| {
+                            Premise::parse_str(premise)
+                                .map_err(|error| CliError::Premise(i + 1, error))
+                        })
+                        .collect::<std::result::Result<_, _>>()?,
+                );
+            }
+        }
+
+        if options.verbosity == Verbosity::Quiet && (options.steps.is_some() || options.trace) {
+            return Err(CliError::Usage(String::from(
+                "--quiet cannot be used with --steps or --trace",
+            )));
+        }
+
+        // Giving a goal to solve for makes it a proof
+        if options.goal.is_some() && options.command == Command::Solve {
+            options.command = Command::Prove;
+        }
+
+        Ok(options)
+    }
+}
+
+// Returns the command with the given name, if there is one
+fn parse_command(name: &str) -> Option<Command> {
+    match name {
+        "solve" => Some(Command::Solve),
+        "prove" => Some(Command::Prove),
+        "table" => Some(Command::Table),
+        "batch" => Some(Command::Batch),
+        "grade" => Some(Command::Grade),
+        "check" => Some(Command::Check),
+        "refute" => Some(Command::Refute),
+        "nf" => Some(Command::Nf),
+        "equiv" => Some(Command::Equiv),
+        "repl" => Some(Command::Repl),
+        "assist" => Some(Command::Assist),
+        "help" => Some(Command::Help),
+        _ => None,
+    }
+}
+
+// Parses a duration given as a number with an optional unit of ms, s, m or h, such as "1.5s",
+// where a number without a unit is in seconds
+fn parse_duration(duration: &str) -> Option<Duration> {
+    let split = duration
+        .find(|character: char| !character.is_ascii_digit() && character != '.')
+        .unwrap_or(duration.len());
+    let (amount, unit) = duration.split_at(split);
+
+    let amount: f64 = amount.parse().ok()?;
+    let seconds = match unit {
+        "ms" => amount / 1000.0,
+        "" | "s" => amount,
+        "m" => amount * 60.0,
+        "h" => amount * 3600.0,
+        _ => return None,
+    };
+
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+// Creates the usage error for a flag given a value it does not accept
+fn invalid_value(flag: &str, value: &str) -> CliError {
+    CliError::Usage(format!("Invalid value '{}' for '{}'", value, flag))
+}
+
+// Runs the command given by the options, printing the result to stdout
+pub fn run(options: &CliOptions) -> std::result::Result<Exit, CliError> {
+    let color = match options.color {
+        ColorChoice::Auto => io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+    };
+
+    if options.command == Command::Repl {
+        repl::run(io::stdin().lock(), &mut io::stdout().lock())?;
+        return Ok(Exit::Success);
+    }
+
+    if options.command == Command::Assist {
+        let deduction = load_deduction(options)?;
+        let goal = match &options.goal {
+            Some(goal) => goal.clone(),
+            None => deduction
+                .get_conclusion()
+                .ok_or(CliError::MissingConclusion)?
+                .clone(),
+        };
+
+        let premises = deduction
+            .get_labeled_premises()
+            .into_iter()
+            .map(|(_, premise)| premise.clone())
+            .collect();
+
+        assist::run(premises, goal, io::stdin().lock(), &mut io::stdout().lock())?;
+        return Ok(Exit::Success);
+    }
+
+    // Unicode symbols are only used by default when printing to a terminal, since whatever the
+    // output is piped into may not handle them
+    let mut options = options.clone();
+    if options.format.is_none() {
+        options.format = Some(match io::stdout().is_terminal() {
+            true => OutputFormat::Unicode,
+            false => OutputFormat::Ascii,
+        });
+    }
+
+    // The result goes to stderr when the events are streamed to stdout, so every line of stdout
+    // can be parsed as an event
+    let output = execute(&options, color)?;
+    match options.events {
+        Some(EventOutput::Stdout) => eprint!("{}", output.text),
+        _ => print!("{}", output.text),
+    }
+
+    Ok(output.exit_code)
+}
+
+// Stores what a command printed along with the exit code it finished with
+#[derive(Debug)]
+pub struct CommandOutput {
+    pub text: String,
+    pub exit_code: Exit,
+}
+
+// Runs every command except the REPL and the assistant, returning its output instead of printing it
+// Output is in Unicode if no format was chosen
+pub fn execute(options: &CliOptions, color: bool) -> std::result::Result<CommandOutput, CliError> {
+    if options.command == Command::Help {
+        return Ok(CommandOutput {
+            text: format!("{}\n", USAGE),
+            exit_code: Exit::Success,
+        });
+    }
+
+    let format = options.format.unwrap_or(OutputFormat::Unicode);
+
+    match options.command {
+        Command::Table => return execute_table(options, format),
+        Command::Batch => return execute_batch(options, format),
+        Command::Grade => return execute_grade(options, format),
+        Command::Check => return execute_check(options, format, color),
+        Command::Refute => return execute_refute(options, format),
+        Command::Nf => return execute_normal_form(options, format),
+        Command::Equiv => return execute_equivalence(options, format),
+        _ => (),
+    }
+
+    let notation = match format {
+        OutputFormat::Unicode => Some(Notation::Unicode),
+        OutputFormat::Ascii => Some(Notation::Ascii),
+        OutputFormat::Latex => Some(Notation::Latex),
+        // Markdown is written with the Unicode symbols
+        OutputFormat::Markdown => Some(Notation::Unicode),
+        OutputFormat::Json => None,
+    };
+
+    if options.trace && !matches!(format, OutputFormat::Unicode | OutputFormat::Ascii) {
+        return Err(CliError::Usage(String::from(
+            "--trace can only be used with the unicode and ascii formats",
+        )));
+    }
+
+    if options.trace && options.steps.is_some() {
+        return Err(CliError::Usage(String::from(
+            "--trace and --steps cannot be used together",
+        )));
+    }
+
+    let (mut deduction, argument_options) = load_argument(options)?;
+
+    // Options on the command line take precedence over those given in a JSON argument, and
+    // --trace over the history it asks for
+    let options = &CliOptions {
+        solve_options: SolveOptions {
+            max_steps: options
+                .solve_options
+                .max_steps
+                .or(argument_options.max_steps),
+            timeout: options.solve_options.timeout.or(argument_options.timeout),
+            history: None,
+        },
+        steps: match options.trace {
+            true => None,
+            false => options.steps.or(argument_options.history),
+        },
+        ..options.clone()
+    };
+
+    if let Some(goal) = &options.goal {
+        deduction.set_conclusion(goal.clone());
+    }
+
+    // Entailment and consistency are checked before solving, while every premise is still on the
+    // stack, as inconsistent premises entail any conclusion
+    let (entailment, mut consistent) = match (options.command, deduction.get_conclusion()) {
+        (Command::Prove, Some(conclusion)) => (
+            Some(deduction.check_entailment(conclusion)),
+            deduction.find_model().is_some(),
+        ),
+        (Command::Prove, None) => return Err(CliError::MissingConclusion),
+        _ => (None, true),
+    };
+
+    let mut history = EvaluationHistory::new();
+    let result = match &options.events {
+        Some(output) => {
+            let writer: Box<dyn Write> = match output {
+                EventOutput::Stdout => Box::new(io::stdout()),
+                EventOutput::File(path) => Box::new(File::create(path)?),
+            };
+
+            let mut events = JsonLinesObserver::new(writer);
+            let result = deduction
+                .solve_with_options(&options.solve_options, &mut (&mut history, &mut events));
+            if let Some(error) = events.take_error() {
+                return Err(CliError::Io(error));
+            }
+
+            // The verdict on the conclusion comes from checking entailment rather than the solve,
+            // so it follows the done event as its own, such as {"event":"verdict","result":"proved"}
+            // while done gives how the solve finished, such as "complete"
+            if let Some(entailment) = &entailment {
+                let mut verdict = entailment.to_json();
+                verdict["event"] = json!("verdict");
+                events.write_event(&verdict)?;
+            }
+
+            result
+        }
+        None => deduction.solve_with_options(&options.solve_options, &mut history),
+    };
+
+    // Solving may also show the premises to be inconsistent, without having to check every model
+    consistent &= !deduction.is_contradictory();
+
+    let outcome = SolveOutcome {
+        result,
+        entailment,
+        consistent,
+    };
+
+    let text = match notation {
+        Some(notation) if format == OutputFormat::Markdown => {
+            format_solve_markdown(options, notation, &deduction, &history, &outcome)
+        }
+        Some(notation) => format_solve(
+            options,
+            notation,
+            color && notation != Notation::Latex,
+            &deduction,
+            &history,
+            &outcome,
+        ),
+        None => format!(
+            "{:#}\n",
+            history.to_solve_json(
+                &deduction,
+                outcome.result,
+                outcome.consistent,
+                outcome.entailment.as_ref()
+            )
+        ),
+    };
+
+    // Solving only fails without a conclusion if it shows the premises to be inconsistent
+    let exit_code = match (options.command, outcome.entailment) {
+        _ if !outcome.consistent => Exit::Inconsistent,
+        _ if outcome.result != SolveResult::Complete => Exit::ResourceLimit,
+        (Command::Prove, Some(Entailment::Proved)) => Exit::Success,
+        (Command::Prove, _) => Exit::Failure,
+        _ => match (deduction.get_conclusion(), deduction.evaluate_conclusion()) {
+            (None, _) | (Some(_), Some(true)) => Exit::Success,
+            _ => Exit::Failure,
+        },
+    };
+
+    Ok(CommandOutput { text, exit_code })
+}
+
+// Stores what a solve or proof found, apart from the Deduction and its history
+struct SolveOutcome {
+    result: SolveResult,
+    // The result of checking the conclusion, only present for the prove command
+    entailment: Option<Entailment>,
+    consistent: bool,
+}
+
+// Formats the result of a solve or proof as text in the given notation
+fn format_solve(
+    options: &CliOptions,
+    notation: Notation,
+    color: bool,
+    deduction: &Deduction,
+    history: &EvaluationHistory,
+    outcome: &SolveOutcome,
+) -> String {
+    if options.verbosity == Verbosity::Quiet {
+        return format!("{}\n", format_quiet(deduction, outcome));
+    }
+
+    let latex = notation == Notation::Latex;
+    let mut text = String::new();
+
+    if options.trace {
+        for line in history.render_trace_lines(notation) {
+            text.push_str(&line);
+            text.push('\n');
+        }
+    } else {
+        // --steps chooses the history shown, otherwise the verbosity does
+        let rendered = match (options.steps, options.verbosity) {
+            (Some(detail), _) => render_history(history, detail, notation, color),
+            (None, Verbosity::Verbose) => {
+                render_history(history, HistoryDetail::Full, notation, color)
+            }
+            (None, verbosity) => history.render_at(verbosity.render_detail(), notation),
+        };
+
+        match latex {
+            true => text.push_str(&format!("\\[\n{}\\]\n\n", rendered)),
+            false => text.push_str(&format!("{}\n", rendered)),
+        }
+    }
+
+    if let SolveResult::LimitReached(limit) = outcome.result {
+        let values = deduction.get_values();
+        let steps = history.get_steps().len();
+
+        text.push_str(&paint("PARTIAL RESULT", "33", color));
+        text.push_str(&format!(
+            ": stopped by {} after {} step{}, with {} of {} values found\n\n",
+            limit_flag(limit),
+            steps,
+            if steps == 1 { "" } else { "s" },
+            values.iter().filter(|(_, value)| value.is_some()).count(),
+            values.iter().count()
+        ));
+    }
+
+    text.push_str(&format_values(deduction.get_values(), notation));
+
+    if options.verbosity >= Verbosity::Verbose {
+        match latex {
+            true => text.push_str(&format!(
+                "\n\\begin{{verbatim}}\n{}\\end{{verbatim}}\n",
+                history.stats()
+            )),
+            false => text.push_str(&format!("\n{}", history.stats())),
+        }
+    }
+
+    if !outcome.consistent {
+        text.push_str(&paint("The premises are inconsistent", "31", color));
+        text.push('\n');
+    }
+
+    match (&outcome.entailment, deduction.get_conclusion()) {
+        (Some(entailment), Some(conclusion)) => {
+            text.push_str(&format_entailment(conclusion, entailment, notation, color));
+        }
+        (None, Some(conclusion)) => {
+            let verdict = match deduction.evaluate_conclusion() {
+                Some(true) => paint("is TRUE", "32", color),
+                Some(false) => paint("is FALSE", "31", color),
+                None => paint("could not be determined", "33", color),
+            };
+
+            text.push_str(&format_conclusion_line(conclusion, &verdict, notation));
+        }
+        _ => (),
+    }
+
+    text
+}
+
+// Renders the history with the given detail, coloring the full history if the color feature is on
+#[cfg(feature = "color")]
+fn render_history(
+    history: &EvaluationHistory,
+    detail: HistoryDetail,
+    notation: Notation,
+    color: bool,
+) -> String {
+    use deductions::color::RenderStyle;
+
+    match (detail, color) {
+        (HistoryDetail::Full, true) => history.render_styled(RenderStyle::AnsiColor, notation),
+        _ => history.render_detail(detail, notation),
+    }
+}
+
+// Renders the history with the given detail, which is never colored without the color feature
+#[cfg(not(feature = "color"))]
+fn render_history(
+    history: &EvaluationHistory,
+    detail: HistoryDetail,
+    notation: Notation,
+    _color: bool,
+) -> String {
+    history.render_detail(detail, notation)
+}
+
+// Formats the result of a solve or proof as Markdown, with the whole history followed by the values
+// found and the verdict
+fn format_solve_markdown(
+    options: &CliOptions,
+    notation: Notation,
+    deduction: &Deduction,
+    history: &EvaluationHistory,
+    outcome: &SolveOutcome,
+) -> String {
+    if options.verbosity == Verbosity::Quiet {
+        return format!("{}\n", format_quiet(deduction, outcome));
+    }
+
+    let mut text = format!("{}\n**Values**\n\n", history.render_markdown(notation));
+
+    for line in deduction.get_values().render(notation).lines() {
+        text.push_str(&format!("- {}\n", line));
+    }
+
+    if !outcome.consistent {
+        text.push_str("\nThe premises are inconsistent\n");
+    }
+
+    match (&outcome.entailment, deduction.get_conclusion()) {
+        (Some(entailment), Some(conclusion)) => {
+            text.push('\n');
+            text.push_str(&format_entailment(conclusion, entailment, notation, false));
+        }
+        (None, Some(conclusion)) => {
+            let verdict = match deduction.evaluate_conclusion() {
+                Some(true) => "is TRUE",
+                Some(false) => "is FALSE",
+                None => "could not be determined",
+            };
+
+            text.push('\n');
+            text.push_str(&format_conclusion_line(conclusion, verdict, notation));
+        }
+        _ => (),
+    }
+
+    text
+}
+
+// Returns the flag which sets the given limit
+fn limit_flag(limit: SolveLimit) -> &'static str {
+    match limit {
+        SolveLimit::Steps => "--max-steps",
+        SolveLimit::Timeout => "--timeout",
+    }
+}
+
+// Returns the single line printed in quiet mode, which is the verdict of a proof, the value of
+// the conclusion, or the values found if there is no conclusion, such as "b=F, f=T, s=?"
+fn format_quiet(deduction: &Deduction, outcome: &SolveOutcome) -> String {
+    let line = match (&outcome.entailment, deduction.evaluate_conclusion()) {
+        _ if !outcome.consistent => "INCONSISTENT",
+        (Some(Entailment::Proved), _) => "PROVED",
+        (Some(_), _) => "NOT_PROVED",
+        _ if deduction.get_conclusion().is_none() => return deduction.get_values().render_terse(),
+        (None, Some(true)) => "TRUE",
+        (None, Some(false)) => "FALSE",
+        (None, None) => "UNKNOWN",
+    };
+
+    line.to_string()
+}
+
+// Prints the truth table of the formula given to the table command
+fn execute_table(
+    options: &CliOptions,
+    format: OutputFormat,
+) -> std::result::Result<CommandOutput, CliError> {
+    let formula = options
+        .formula
+        .as_ref()
+        .ok_or_else(|| CliError::Usage(String::from("The table command needs a formula")))?;
+
+    let table = TruthTable::new(formula, options.max_variables).map_err(CliError::TruthTable)?;
+
+    let text = match format {
+        OutputFormat::Unicode => table.render_text(options.row_filter, Notation::Unicode),
+        OutputFormat::Ascii => table.render_text(options.row_filter, Notation::Ascii),
+        OutputFormat::Latex => table.render_latex(options.row_filter),
+        OutputFormat::Json => format!("{:#}\n", table.to_json(options.row_filter)),
+        OutputFormat::Markdown => table.render_markdown(options.row_filter),
+    };
+
+    Ok(CommandOutput {
+        text,
+        exit_code: Exit::Success,
+    })
+}
+
+// Converts the formula given to the nf command to a normal form, printing it as a formula,
+// a list of clauses or in DIMACS format
+fn execute_normal_form(
+    options: &CliOptions,
+    format: OutputFormat,
+) -> std::result::Result<CommandOutput, CliError> {
+    let formula = options
+        .formula
+        .as_ref()
+        .ok_or_else(|| CliError::Usage(String::from("The nf command needs a formula")))?;
+
+    let form = options.normal_form.ok_or_else(|| {
+        CliError::Usage(String::from(
+            "The nf command needs one of --cnf, --dnf or --nnf",
+        ))
+    })?;
+
+    let notation = match format {
+        OutputFormat::Unicode | OutputFormat::Json => Notation::Unicode,
+        OutputFormat::Ascii => Notation::Ascii,
+        OutputFormat::Latex if options.normal_form_output == NormalFormOutput::Formula => {
+            Notation::Latex
+        }
+        OutputFormat::Latex => {
+            return Err(CliError::Usage(String::from(
+                "--clauses and --dimacs cannot be used with the latex format",
+            )))
+        }
+        OutputFormat::Markdown => {
+            return Err(CliError::Usage(String::from(
+                "The markdown format is only supported by the table command",
+            )))
+        }
+    };
+
+    let clauses = match (form, options.normal_form_output) {
+        (NormalForm::Negation, NormalFormOutput::Formula) => None,
+        (NormalForm::Negation, _) => {
+            return Err(CliError::Usage(String::from(
+                "--clauses and --dimacs need --cnf or --dnf",
+            )))
+        }
+        (NormalForm::Disjunctive, NormalFormOutput::Dimacs) => {
+            return Err(CliError::Usage(String::from("--dimacs needs --cnf")))
+        }
+        _ => Some(
+            formula
+                .to_clauses(form, DEFAULT_MAX_CLAUSES)
+                .map_err(CliError::NormalForm)?,
+        ),
+    };
+
+    let result = match &clauses {
+        Some(clauses) => Premise::from_clauses(form, clauses),
+        None => formula.to_nnf().map_err(CliError::NormalForm)?,
+    };
+
+    // The result should always be equivalent, so a difference means the conversion is wrong
+    let difference = match options.check_equivalence {
+        true => formula.find_difference(&result),
+        false => None,
+    };
+
+    let mut text = match (format, options.normal_form_output, &clauses) {
+        (OutputFormat::Json, _, _) => {
+            let mut json = json!({
+                "form": normal_form_name(form),
+                "formula": formula.to_string(),
+                "result": result.to_string(),
+            });
+
+            if let Some(clauses) = &clauses {
+                let clauses: Vec<Vec<String>> = clauses
+                    .iter()
+                    .map(|clause| {
+                        clause
+                            .iter()
+                            .map(|literal| literal.render(notation))
+                            .collect()
+                    })
+                    .collect();
+                json["clauses"] = json!(clauses);
+            }
+
+            if options.check_equivalence {
+                json["equivalent"] = json!(difference.is_none());
+            }
+
+            return Ok(CommandOutput {
+                text: format!("{:#}\n", json),
+                exit_code: normal_form_exit(&difference),
+            });
+        }
+        (_, NormalFormOutput::Clauses, Some(clauses)) => {
+            normal_form::render_clauses(clauses, notation)
+        }
+        (_, NormalFormOutput::Dimacs, Some(clauses)) => {
+            normal_form::render_dimacs(formula, clauses)
+        }
+        (OutputFormat::Latex, _, _) => format!("\\[ {} \\]\n", result.render(notation)),
+        _ => format!("{}\n", result.render(notation)),
+    };
+
+    // DIMACS only allows comments outside of the clauses
+    let prefix = match options.normal_form_output {
+        NormalFormOutput::Dimacs => "c ",
+        _ => "",
+    };
+
+    if options.check_equivalence {
+        match &difference {
+            None => text.push_str(&format!(
+                "{}Checked: the {} is equivalent to {}\n",
+                prefix,
+                form,
+                formula.render(notation)
+            )),
+            Some(difference) => text.push_str(&format!(
+                "{}The {} is not equivalent to {}, as they differ when {}\n",
+                prefix,
+                form,
+                formula.render(notation),
+                difference.render_terse()
+            )),
+        }
+    }
+
+    Ok(CommandOutput {
+        text,
+        exit_code: normal_form_exit(&difference),
+    })
+}
+
+// Returns the short name of a normal form, as used by its flag
+fn normal_form_name(form: NormalForm) -> &'static str {
+    match form {
+        NormalForm::Negation => "nnf",
+        NormalForm::Conjunctive => "cnf",
+        NormalForm::Disjunctive => "dnf",
+    }
+}
+
+// Returns the exit code of the nf command, which only fails if the check found a difference
+fn normal_form_exit(difference: &Option<ValueMap>) -> Exit {
+    match difference {
+        Some(_) => Exit::Failure,
+        None => Exit::Success,
+    }
+}
+
+// Compares the two formulas given to the equiv command, printing an assignment under which they
+// differ if they are not equivalent, or the first place they differ with --structural
+fn execute_equivalence(
+    options: &CliOptions,
+    format: OutputFormat,
+) -> std::result::Result<CommandOutput, CliError> {
+    let (Some(first), Some(second)) = (&options.formula, &options.second_formula) else {
+        return Err(CliError::Usage(String::from(
+            "The equiv command needs two formulas",
+        )));
+    };
+
+    let notation = match format {
+        OutputFormat::Unicode | OutputFormat::Json => Notation::Unicode,
+        OutputFormat::Ascii => Notation::Ascii,
+        OutputFormat::Latex | OutputFormat::Markdown => {
+            return Err(CliError::Usage(String::from(
+                "The equiv command only supports the unicode, ascii and json formats",
+            )))
+        }
+    };
+
+    let quiet = options.verbosity == Verbosity::Quiet;
+
+    if options.structural {
+        let difference = first.find_structural_difference(second);
+
+        let text = match (format, &difference) {
+            (OutputFormat::Json, _) => format!(
+                "{:#}\n",
+                json!({
+                    "structurally_equal": difference.is_none(),
+                    "difference": difference.as_ref().map(|difference| json!({
+                        "first": difference.first.to_string(),
+                        "second": difference.second.to_string(),
+                    })),
+                })
+            ),
+            (_, None) if quiet => String::from("STRUCTURALLY_EQUAL\n"),
+            (_, Some(_)) if quiet => String::from("STRUCTURALLY_DIFFERENT\n"),
+            (_, None) => String::from("structurally equal\n"),
+            (_, Some(difference)) => format!(
+                "structurally different\nThe first formula has {} where the second has {}\n",
+                difference.first.render(notation),
+                difference.second.render(notation)
+            ),
+        };
+
+        return Ok(CommandOutput {
+            text,
+            exit_code: match difference {
+                Some(_) => Exit::Failure,
+                None => Exit::Success,
+            },
+        });
+    }
+
+    let difference = first.find_difference(second);
+
+    let text = match (format, &difference) {
+        (OutputFormat::Json, _) => format!(
+            "{:#}\n",
+            json!({
+                "equivalent": difference.is_none(),
+                "difference": difference.as_ref().map(ValueMap::to_json),
+            })
+        ),
+        (_, None) if quiet => String::from("EQUIVALENT\n"),
+        (_, Some(_)) if quiet => String::from("NOT_EQUIVALENT\n"),
+        (_, None) => String::from("equivalent\n"),
+        (_, Some(difference)) => {
+            // Every proposition of both formulas is assigned, so both have a value
+            let value = |formula: &Premise| {
+                notation.truth_value(formula.evaluate(difference).unwrap_or(false))
+            };
+
+            format!(
+                "not equivalent\nWith {}, {} is {} but {} is {}\n",
+                difference.render_terse(),
+                first.render(notation),
+                value(first),
+                second.render(notation),
+                value(second)
+            )
+        }
+    };
+
+    Ok(CommandOutput {
+        text,
+        exit_code: match difference {
+            Some(_) => Exit::Failure,
+            None => Exit::Success,
+        },
+    })
+}
+
+// Checks every argument in the batch file, printing a verdict for each
+fn execute_batch(
+    options: &CliOptions,
+    format: OutputFormat,
+) -> std::result::Result<CommandOutput, CliError> {
+    let blocks = match &options.input {
+        Input::Example | Input::Premises(_) => {
+            return Err(CliError::Usage(String::from(
+                "The batch command needs a file",
+            )))
+        }
+        Input::Stdin => Deduction::batch_from_reader(io::stdin().lock(), Path::new("<stdin>"))?,
+        Input::File(path) => Deduction::batch_from_file(path)?,
+    };
+
+    let results = batch::check_batch(blocks);
+
+    let text = match format {
+        OutputFormat::Unicode | OutputFormat::Ascii if options.verbosity == Verbosity::Quiet => {
+            batch::render_lines(&results)
+        }
+        OutputFormat::Unicode => batch::render_report(&results, Notation::Unicode),
+        OutputFormat::Ascii => batch::render_report(&results, Notation::Ascii),
+        OutputFormat::Json => format!("{:#}\n", batch::to_json(&results)),
+        OutputFormat::Latex | OutputFormat::Markdown => {
+            return Err(CliError::Usage(String::from(
+                "The batch command only supports the unicode, ascii and json formats",
+            )))
+        }
+    };
+
+    let tally = batch::tally(&results);
+    let exit_code = if tally.malformed > 0 {
+        Exit::Usage
+    } else if tally.inconsistent > 0 {
+        Exit::Inconsistent
+    } else if tally.invalid > 0 {
+        Exit::Failure
+    } else {
+        Exit::Success
+    };
+
+    Ok(CommandOutput { text, exit_code })
+}
+
+// Grades every row of the CSV file, printing a grade for each and writing them to the results
+// file if one was given
+fn execute_grade(
+    options: &CliOptions,
+    format: OutputFormat,
+) -> std::result::Result<CommandOutput, CliError> {
+    let records = match &options.input {
+        Input::Example | Input::Premises(_) => {
+            return Err(CliError::Usage(String::from(
+                "The grade command needs a file",
+            )))
+        }
+        Input::Stdin => grading::grade_csv(io::stdin().lock(), options.max_variables)?,
+        Input::File(path) => File::open(path)
+            .and_then(|file| grading::grade_csv(file, options.max_variables))
+            .map_err(|error| LoadError::Io {
+                path: path.clone(),
+                error,
+            })?,
+    };
+
+    let text = match format {
+        OutputFormat::Unicode | OutputFormat::Ascii if options.verbosity == Verbosity::Quiet => {
+            grading::render_lines(&records)
+        }
+        OutputFormat::Unicode | OutputFormat::Ascii => grading::render_table(&records),
+        OutputFormat::Json => format!("{:#}\n", grading::to_json(&records)),
+        OutputFormat::Latex | OutputFormat::Markdown => {
+            return Err(CliError::Usage(String::from(
+                "The grade command only supports the unicode, ascii and json formats",
+            )))
+        }
+    };
+
+    if let Some(path) = &options.results {
+        grading::write_csv(&records, File::create(path)?)?;
+    }
+
+    let tally = grading::tally(&records);
+    let exit_code = if tally.malformed > 0 {
+        Exit::Usage
+    } else if tally.limited > 0 {
+        Exit::ResourceLimit
+    } else if tally.mismatched > 0 {
+        Exit::Failure
+    } else {
+        Exit::Success
+    };
+
+    Ok(CommandOutput { text, exit_code })
+}
+
+// Checks whether the premises are consistent, printing a model if they are and the premises
+// which conflict if they are not
+fn execute_check(
+    options: &CliOptions,
+    format: OutputFormat,
+    color: bool,
+) -> std::result::Result<CommandOutput, CliError> {
+    let notation = match format {
+        OutputFormat::Unicode => Notation::Unicode,
+        OutputFormat::Ascii => Notation::Ascii,
+        OutputFormat::Json => Notation::Unicode,
+        OutputFormat::Latex | OutputFormat::Markdown => {
+            return Err(CliError::Usage(String::from(
+                "The check command only supports the unicode, ascii and json formats",
+            )))
+        }
+    };
+
+    let consistency = load_deduction(options)?.check_consistency();
+
+    let exit_code = match consistency {
+        Consistency::Consistent { .. } => Exit::Success,
+        Consistency::Inconsistent { .. } => Exit::Inconsistent,
+    };
+
+    let text = match (&consistency, format) {
+        (_, OutputFormat::Json) => format!("{:#}\n", consistency.to_json()),
+        (Consistency::Consistent { .. }, _) if options.verbosity == Verbosity::Quiet => {
+            String::from("CONSISTENT\n")
+        }
+        (Consistency::Inconsistent { .. }, _) if options.verbosity == Verbosity::Quiet => {
+            String::from("INCONSISTENT\n")
+        }
+        (Consistency::Consistent { model }, _) => format!(
+            "{}, as every premise is TRUE when:\n{}",
+            paint("The premises are consistent", "32", color),
+            model.render(notation)
+        ),
+        (Consistency::Inconsistent { core }, _) => {
+            let mut text = format!(
+                "{}, as these cannot all be TRUE:\n",
+                paint("The premises are inconsistent", "31", color)
+            );
+
+            for premise in core {
+                match premise.line {
+                    Some(line) => text.push_str(&format!(
+                        "{} (line {}): {}\n",
+                        premise.label,
+                        line,
+                        premise.premise.render(notation)
+                    )),
+                    None => text.push_str(&format!(
+                        "{}: {}\n",
+                        premise.label,
+                        premise.premise.render(notation)
+                    )),
+                }
+            }
+
+            text
+        }
+    };
+
+    Ok(CommandOutput { text, exit_code })
+}
+
+// Looks for a counterexample to the argument, printing it along with a sentence explaining it
+// Finding one succeeds, so the command can be used to test that an argument is invalid
+fn execute_refute(
+    options: &CliOptions,
+    format: OutputFormat,
+) -> std::result::Result<CommandOutput, CliError> {
+    let notation = match format {
+        OutputFormat::Unicode | OutputFormat::Json => Notation::Unicode,
+        OutputFormat::Ascii => Notation::Ascii,
+        OutputFormat::Latex | OutputFormat::Markdown => {
+            return Err(CliError::Usage(String::from(
+                "The refute command only supports the unicode, ascii and json formats",
+            )))
+        }
+    };
+
+    let mut deduction = load_deduction(options)?;
+
+    if let Some(goal) = &options.goal {
+        deduction.set_conclusion(goal.clone());
+    }
+
+    let conclusion = deduction
+        .get_conclusion()
+        .ok_or(CliError::MissingConclusion)?
+        .clone();
+
+    // Inconsistent premises entail every conclusion, so there is nothing to refute
+    if deduction.find_model().is_none() {
+        let text = match format {
+            OutputFormat::Json => format!("{:#}\n", json!({ "consistent": false })),
+            _ if options.verbosity == Verbosity::Quiet => String::from("INCONSISTENT\n"),
+            _ => String::from(
+                "The premises are inconsistent, so every conclusion follows from them\n",
+            ),
+        };
+
+        return Ok(CommandOutput {
+            text,
+            exit_code: Exit::Inconsistent,
+        });
+    }
+
+    let counterexample = match deduction.check_entailment(&conclusion) {
+        Entailment::Proved => None,
+        Entailment::NotProvable { counterexample }
+        | Entailment::Contradicted { counterexample } => Some(counterexample),
+    };
+
+    let text = match (format, &counterexample) {
+        (OutputFormat::Json, _) => format!(
+            "{:#}\n",
+            json!({
+                "consistent": true,
+                "valid": counterexample.is_none(),
+                "counterexample": counterexample.as_ref().map(ValueMap::to_json),
+            })
+        ),
+        (_, None) if options.verbosity == Verbosity::Quiet => String::from("VALID\n"),
+        (_, Some(counterexample)) if options.verbosity == Verbosity::Quiet => {
+            format!("{}\n", counterexample.render_terse())
+        }
+        (_, None) => format!(
+            "{} {} follows from the premises, so there is no counterexample\n",
+            notation.therefore(),
+            conclusion.render(notation)
+        ),
+        (_, Some(counterexample)) => format!(
+            "Counterexample: {}\n{}\n",
+            counterexample.render_terse(),
+            describe_counterexample(counterexample, &conclusion, notation)
+        ),
+    };
+
+    let exit_code = match counterexample {
+        Some(_) => Exit::Success,
+        None => Exit::Failure,
+    };
+
+    Ok(CommandOutput { text, exit_code })
+}
+
+// Explains a counterexample in a sentence, such as
+// "With p FALSE and q TRUE, every premise is TRUE but the conclusion p is FALSE"
+fn describe_counterexample(
+    counterexample: &ValueMap,
+    conclusion: &Premise,
+    notation: Notation,
+) -> String {
+    let mut values: Vec<(char, Option<bool>)> = counterexample.iter().collect();
+    values.sort();
+
+    let mut values: Vec<String> = values
+        .into_iter()
+        .filter_map(|(proposition, value)| {
+            value.map(|value| format!("{} {}", proposition, notation.truth_value(value)))
+        })
+        .collect();
+
+    // The values are listed like "a, b and c"
+    let listed = match values.pop() {
+        Some(last) if values.is_empty() => last,
+        Some(last) => format!("{} and {}", values.join(", "), last),
+        None => String::new(),
+    };
+
+    format!(
+        "With {}, every premise is TRUE but the conclusion {} is FALSE",
+        listed,
+        conclusion.render(notation)
+    )
+}
+
+// Loads the argument from the given input
+fn load_deduction(options: &CliOptions) -> std::result::Result<Deduction, CliError> {
+    Ok(load_argument(options)?.0)
+}
+
+// Loads the argument from the input along with the options it gives, which only a JSON argument,
+// read from any file ending in .json, can give
+// A conclusion given with --conclude replaces the one in the argument
+fn load_argument(options: &CliOptions) -> std::result::Result<(Deduction, SolveOptions), CliError> {
+    let (mut deduction, argument_options) = match &options.input {
+        Input::File(path)
+            if path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("json")) =>
+        {
+            Deduction::from_json_file(path)?
+        }
+        input => (read_argument(input)?, SolveOptions::default()),
+    };
+
+    if let Some(conclusion) = &options.conclusion {
+        deduction.set_conclusion(conclusion.clone());
+    }
+
+    Ok((deduction, argument_options))
+}
+
+// Reads the argument from any input other than a JSON file
+fn read_argument(input: &Input) -> std::result::Result<Deduction, CliError> {
+    let deduction = match input {
+        Input::Example => EXAMPLE_PREMISES
+            .iter()
+            .fold(Deduction::builder(), |builder, premise| {
+                builder.premise_str(premise)
+            })
+            .build()
+            .unwrap_or_else(|error| {
+                panic!(
+                    "[INTERNAL ERROR] The example premises are invalid: {}",
+                    error
+                )
+            }),
+        Input::Stdin => Deduction::from_reader(io::stdin().lock(), Path::new("<stdin>"))?,
+        Input::File(path) => Deduction::from_file(path)?,
+        Input::Premises(premises) => Deduction::from_premises(premises.clone()),
+    };
+
+    Ok(deduction)
+}
+
+// Formats the values found, one per line, or as an array in LaTeX
+fn format_values(values: &ValueMap, notation: Notation) -> String {
+    let rendered = values.render(notation);
+
+    match notation {
+        Notation::Latex => format!(
+            "\\[\n\\begin{{array}}{{l}}\n{}\\end{{array}}\n\\]\n",
+            rendered.replace('\n', " \\\\\n")
+        ),
+        _ => rendered,
+    }
+}
+
+// Formats the line stating what is known about the conclusion, such as "∴ j is TRUE"
+fn format_conclusion_line(conclusion: &Premise, verdict: &str, notation: Notation) -> String {
+    match notation {
+        Notation::Latex => format!(
+            "\\[ {} {} \\quad \\text{{{}}} \\]\n",
+            notation.therefore(),
+            conclusion.render(notation),
+            verdict
+        ),
+        _ => format!(
+            "{} {} {}\n",
+            notation.therefore(),
+            conclusion.render(notation),
+            verdict
+        ),
+    }
+}
+
+// Formats whether the conclusion is entailed by the premises, followed by the counterexample
+// on its own line if it is not
+fn format_entailment(
+    conclusion: &Premise,
+    entailment: &Entailment,
+    notation: Notation,
+    color: bool,
+) -> String {
+    let (verdict, counterexample) = match entailment {
+        Entailment::Proved => {
+            return format_conclusion_line(conclusion, &paint("is proved", "32", color), notation)
+        }
+        Entailment::NotProvable { counterexample } => {
+            (paint("is not provable", "33", color), counterexample)
+        }
+        Entailment::Contradicted { counterexample } => (
+            paint("is not provable, the premises contradict it", "31", color),
+            counterexample,
+        ),
+    };
+
+    // The values are listed on one line, such as "p = TRUE, q = FALSE"
+    let rendered = counterexample.render(notation);
+    let assignment: Vec<&str> = rendered.lines().collect();
+
+    format!(
+        "{}Counterexample: {}\n",
+        format_conclusion_line(conclusion, &verdict, notation),
+        assignment.join(", ")
+    )
+}
+
+// Wraps the text in the ANSI escape code for the given color if color is enabled
+fn paint(text: &str, code: &str, color: bool) -> String {
+    if color {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Parses the arguments given as a single string split on spaces
+    fn parse(arguments: &str) -> std::result::Result<CliOptions, CliError> {
+        CliOptions::parse(arguments.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(parse("").unwrap(), CliOptions::default());
+
+        let options =
+            parse("prove --format=ascii --color never -q --max-steps 3 argument.txt").unwrap();
+        assert_eq!(
+            options,
+            CliOptions {
+                command: Command::Prove,
+                input: Input::File(PathBuf::from("argument.txt")),
+                format: Some(OutputFormat::Ascii),
+                color: ColorChoice::Never,
+                verbosity: Verbosity::Quiet,
+                solve_options: SolveOptions {
+                    max_steps: Some(3),
+                    timeout: None,
+                    history: None,
+                },
+                trace: false,
+                goal: None,
+                ..CliOptions::default()
+            }
+        );
+
+        // '-' reads from stdin, and the input may follow flags when the command is left out
+        assert_eq!(parse("-").unwrap().input, Input::Stdin);
+        assert_eq!(
+            parse("--trace solve.txt").unwrap().input,
+            Input::File(PathBuf::from("solve.txt"))
+        );
+
+        // A file may follow --steps without being taken as its detail
+        let options = parse("--steps solve.txt").unwrap();
+        assert_eq!(options.steps, Some(HistoryDetail::Full));
+        assert_eq!(options.input, Input::File(PathBuf::from("solve.txt")));
+        assert_eq!(
+            parse("--steps=diff").unwrap().steps,
+            Some(HistoryDetail::Diff)
+        );
+        assert_eq!(
+            parse("--steps=written").unwrap().steps,
+            Some(HistoryDetail::Written)
+        );
+
+        // Several arguments are premises, which may be given along with their conclusion
+        let options = parse("p>q p --conclude q").unwrap();
+        assert_eq!(
+            options.input,
+            Input::Premises(vec![
+                Premise::parse_str("p>q").unwrap(),
+                Premise::parse_str("p").unwrap()
+            ])
+        );
+        assert_eq!(options.conclusion, Some(Premise::parse_str("q").unwrap()));
+        assert_eq!(parse("--demo -q").unwrap().input, Input::Example);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for arguments in [
+            "--unknown",
+            "solve -x",
+            "--format html",
+            "--color",
+            "--max-steps many",
+            "--timeout soon",
+            "--timeout 5d",
+            "a.txt b.txt",
+            "p q --conclude",
+            "p q --conclude q&",
+            "--demo p q",
+            "batch a.txt b.txt",
+            "batch a.txt --conclude p",
+            "--prove",
+            "--prove p&",
+            "table",
+            "table p q",
+            "table p#q",
+            "table p --only some",
+            "-q --steps",
+            "--trace --quiet",
+            "equiv p",
+            "equiv p q r",
+            "equiv p q&",
+            "--events",
+            "table p --events -",
+            "--results out.csv",
+        ] {
+            let error = parse(arguments).unwrap_err();
+            assert!(
+                matches!(
+                    error,
+                    CliError::Usage(_)
+                        | CliError::Goal(_)
+                        | CliError::Formula(_)
+                        | CliError::Conclusion(_)
+                        | CliError::Premise(..)
+                ),
+                "{}",
+                arguments
+            );
+            assert_eq!(error.exit_code(), Exit::Usage);
+        }
+
+        assert_eq!(parse("--prove q").unwrap().command, Command::Prove);
+        assert_eq!(
+            parse("--events - a.txt").unwrap().events,
+            Some(EventOutput::Stdout)
+        );
+        assert_eq!(
+            parse("prove --events=events.ndjson").unwrap().events,
+            Some(EventOutput::File(PathBuf::from("events.ndjson")))
+        );
+        assert_eq!(
+            parse("--timeout 1.5s").unwrap().solve_options.timeout,
+            Some(Duration::from_millis(1500))
+        );
+        assert!(matches!(
+            parse("--steps=all").unwrap_err(),
+            CliError::Usage(_)
+        ));
+        assert_eq!(
+            parse("--bogus").unwrap_err().to_string(),
+            "Unknown flag '--bogus'"
+        );
+    }
+
+    #[test]
+    fn test_execute_solve() {
+        let output = execute(&parse("solve --format ascii").unwrap(), false).unwrap();
+
+        // Only the premises and values are printed unless the steps are asked for
+        assert_eq!(output.exit_code, Exit::Success);
+        assert!(output
+            .text
+            .starts_with("(m & !b) > j\n(f | s) > m\nb > t\nf > !t\nf\n\nb = FALSE\n"));
+        assert!(!output.text.contains("[SUBSTITUTE]"));
+        assert!(output.text.lines().any(|line| line == "j = TRUE"));
+
+        let output = execute(&parse("solve --format ascii --steps").unwrap(), false).unwrap();
+        assert!(output.text.starts_with("(m & !b) > j\n"));
+        assert!(output.text.lines().any(|line| line == "j = TRUE"));
+
+        let output = execute(&parse("--format ascii --steps=diff").unwrap(), false).unwrap();
+        assert!(output
+            .text
+            .starts_with("P2: (f | s) > m => (TRUE | s) > m [SUBSTITUTE]\n"));
+
+        let output = execute(&parse("--max-steps 2").unwrap(), false).unwrap();
+        assert_eq!(output.exit_code, Exit::ResourceLimit);
+        assert!(output.text.contains(
+            "\nPARTIAL RESULT: stopped by --max-steps after 2 steps, with 1 of 6 values found\n"
+        ));
+        assert!(output.text.lines().any(|line| line == "j = UNKNOWN"));
+
+        // Two premises give q different values
+        let output = execute(&parse("tests/fixtures/inconsistent.txt").unwrap(), false).unwrap();
+        assert_eq!(output.exit_code, Exit::Inconsistent);
+        assert!(output.text.contains("The premises are inconsistent\n"));
+
+        let output = execute(
+            &parse("prove -q tests/fixtures/inconsistent.txt").unwrap(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(output.exit_code, Exit::Inconsistent);
+    }
+
+    #[test]
+    fn test_execute_prove() {
+        // The built-in example has no conclusion to prove
+        let error = execute(&parse("prove").unwrap(), false).unwrap_err();
+        assert!(matches!(error, CliError::MissingConclusion));
+
+        let options = CliOptions {
+            command: Command::Prove,
+            input: Input::File(PathBuf::from("tests/fixtures/modus_ponens.txt")),
+            ..CliOptions::default()
+        };
+
+        let output = execute(&options, false).unwrap();
+        assert_eq!(output.exit_code, Exit::Success);
+        assert_eq!(
+            output.text,
+            "p → q\np\n∴ q\n\np = TRUE\nq = TRUE\n∴ q is proved\n"
+        );
+
+        let output = execute(&options, true).unwrap();
+        assert!(output.text.ends_with("∴ q \x1b[32mis proved\x1b[0m\n"));
+
+        // Quiet mode only prints the verdict, without any color
+        let quiet = CliOptions {
+            verbosity: Verbosity::Quiet,
+            ..options.clone()
+        };
+        assert_eq!(execute(&quiet, true).unwrap().text, "PROVED\n");
+
+        // A goal replaces the conclusion of the argument
+        let options = CliOptions {
+            goal: Some(Premise::parse_str("!p").unwrap()),
+            ..options
+        };
+
+        let output = execute(&options, false).unwrap();
+        assert_eq!(output.exit_code, Exit::Failure);
+        assert!(output.text.ends_with(
+            "∴ ¬p is not provable, the premises contradict it\nCounterexample: p = TRUE, q = TRUE\n"
+        ));
+
+        let quiet = CliOptions {
+            verbosity: Verbosity::Quiet,
+            ..options
+        };
+        assert_eq!(execute(&quiet, false).unwrap().text, "NOT_PROVED\n");
+    }
+
+    #[test]
+    fn test_execute_check() {
+        let output = execute(&parse("check").unwrap(), false).unwrap();
+        assert_eq!(output.exit_code, Exit::Success);
+        assert!(output.text.starts_with(
+            "The premises are consistent, as every premise is TRUE when:\nb = FALSE\n"
+        ));
+
+        let output = execute(
+            &parse("check tests/fixtures/inconsistent.txt --format ascii").unwrap(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(output.exit_code, Exit::Inconsistent);
+        assert_eq!(
+            output.text,
+            "The premises are inconsistent, as these cannot all be TRUE:\n\
+             P1 (line 2): p > q\n\
+             P2 (line 3): p\n\
+             P3 (line 4): !q\n"
+        );
+
+        let output = execute(&parse("check -q").unwrap(), false).unwrap();
+        assert_eq!(output.text, "CONSISTENT\n");
+    }
+
+    #[test]
+    fn test_execute_refute() {
+        let output = execute(&parse("refute --prove s").unwrap(), false).unwrap();
+        assert_eq!(output.exit_code, Exit::Success);
+        assert_eq!(
+            output.text,
+            "Counterexample: b=F, f=T, j=T, m=T, s=F, t=F\n\
+             With b FALSE, f TRUE, j TRUE, m TRUE, s FALSE and t FALSE, every premise is TRUE \
+             but the conclusion s is FALSE\n"
+        );
+
+        let output = execute(&parse("refute -q --prove j").unwrap(), false).unwrap();
+        assert_eq!(output.exit_code, Exit::Failure);
+        assert_eq!(output.text, "VALID\n");
+
+        let error = execute(&parse("refute").unwrap(), false).unwrap_err();
+        assert!(matches!(error, CliError::MissingConclusion));
+    }
+
+    #[test]
+    fn test_execute_normal_form() {
+        let output = execute(&parse("nf --dnf (a|b)&!c --check").unwrap(), false).unwrap();
+        assert_eq!(output.exit_code, Exit::Success);
+        assert_eq!(
+            output.text,
+            "(a ∧ ¬c) ∨ (b ∧ ¬c)\n\
+             Checked: the disjunctive normal form is equivalent to (a ∨ b) ∧ ¬c\n"
+        );
+
+        let output = execute(&parse("nf --cnf p>q --format json").unwrap(), false).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&output.text).unwrap();
+        assert_eq!(json["form"], "cnf");
+        assert_eq!(json["result"], "¬p ∨ q");
+        assert_eq!(json["clauses"], json!([["¬p", "q"]]));
+
+        for arguments in ["nf p", "nf --nnf p --clauses", "nf --dnf p --dimacs"] {
+            let error = execute(&parse(arguments).unwrap(), false).unwrap_err();
+            assert!(matches!(error, CliError::Usage(_)), "{}", arguments);
+        }
+
+        let error = execute(
+            &parse("nf --cnf (a&b)|(c&d)|(e&f)|(g&h)|(i&j)|(k&l)|(m&n)|(o&q)|(r&s)|(t&u)|(v&w)|(x&y)|(z&p)")
+                .unwrap(),
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(error.exit_code(), Exit::ResourceLimit);
+        assert_eq!(
+            error.to_string(),
+            "Converting the formula to conjunctive normal form would give more than 4096 clauses"
+        );
+    }
+
+    #[test]
+    fn test_execute_equivalence() {
+        let output = execute(&parse("equiv p>q !p|q").unwrap(), false).unwrap();
+        assert_eq!(output.exit_code, Exit::Success);
+        assert_eq!(output.text, "equivalent\n");
+
+        let output = execute(&parse("equiv p>q q>p --format ascii").unwrap(), false).unwrap();
+        assert_eq!(output.exit_code, Exit::Failure);
+        assert_eq!(
+            output.text,
+            "not equivalent\nWith p=T, q=F, p > q is FALSE but q > p is TRUE\n"
+        );
+
+        // Equivalent formulas can still be built differently
+        let output = execute(&parse("equiv p>q !p|q --structural -q").unwrap(), false).unwrap();
+        assert_eq!(output.exit_code, Exit::Failure);
+        assert_eq!(output.text, "STRUCTURALLY_DIFFERENT\n");
+
+        let output = execute(
+            &parse("equiv (p&q)&r p&(q&r) --structural --format json").unwrap(),
+            false,
+        )
+        .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&output.text).unwrap();
+        assert_eq!(
+            json,
+            json!({ "structurally_equal": true, "difference": null })
+        );
+    }
+
+    #[test]
+    fn test_execute_table() {
+        let output = execute(&parse("table p>q --format markdown").unwrap(), false).unwrap();
+        assert_eq!(output.exit_code, Exit::Success);
+        assert!(output.text.starts_with("| p | q | p → q |\n"));
+
+        let output = execute(
+            &parse("table p>q --only falsifying --format ascii").unwrap(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(output.text, "p  q  p > q\nT  F  F\n");
+
+        let error = execute(&parse("table p>(q&r) --max-variables 2").unwrap(), false).unwrap_err();
+        assert!(matches!(
+            error,
+            CliError::TruthTable(TooManyVariables { found: 3, limit: 2 })
+        ));
+
+        let error = execute(&parse("check --format markdown").unwrap(), false).unwrap_err();
+        assert!(matches!(error, CliError::Usage(_)));
+    }
+
+    #[test]
+    fn test_execute_formats() {
+        let output = execute(&parse("--format json").unwrap(), false).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&output.text).unwrap();
+
+        assert_eq!(json["premises"][0]["premise"], "(m ∧ ¬b) → j");
+        assert_eq!(json["steps"].as_array().unwrap().len(), 12);
+        assert_eq!(json["values"]["j"], true);
+        assert_eq!(json["completed"], true);
+
+        let output = execute(&parse("--format latex --prove j").unwrap(), false).unwrap();
+        assert!(output
+            .text
+            .ends_with("\\[ \\therefore j \\quad \\text{is proved} \\]\n"));
+
+        let output = execute(&parse("--format latex --steps").unwrap(), false).unwrap();
+        assert!(output
+            .text
+            .starts_with("\\[\n\\begin{array}{ll}\n(m \\land \\neg b) \\to j & \\\\\n"));
+
+        let error = execute(&parse("--format json --trace").unwrap(), false).unwrap_err();
+        assert!(matches!(error, CliError::Usage(_)));
+
+        let error = execute(&parse("--trace --steps").unwrap(), false).unwrap_err();
+        assert!(matches!(error, CliError::Usage(_)));
+    }
+}
@@ -0,0 +1,35 @@
+// Measures how long things take where the platform has a clock
+// WebAssembly in the browser has none, and Instant::now panics there, so on it a Stopwatch always
+// reads zero, which leaves durations at zero and timeouts never reached
+
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Stopwatch {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    start: Instant,
+}
+
+impl Stopwatch {
+    // Creates a Stopwatch which starts now
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+            start: Instant::now(),
+        }
+    }
+
+    // Returns how long it has been since the Stopwatch started
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    // Returns zero, as there is no clock to measure with
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    pub(crate) fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
@@ -0,0 +1,241 @@
+// Colored rendering of Deductions and their histories for terminals, behind the color feature
+// The plain renderers and Display impls are left alone, so this only adds ANSI escapes on request
+
+use std::io::IsTerminal;
+
+use crate::history::StepAction;
+use crate::notation::Notation;
+use crate::Deduction;
+use crate::EvaluationHistory;
+
+const BOLD: &str = "1";
+const DIM: &str = "2";
+const GREEN: &str = "32";
+const RED: &str = "31";
+const YELLOW: &str = "33";
+
+// How text should be styled when it is rendered
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum RenderStyle {
+    Plain,
+    AnsiColor,
+}
+
+impl RenderStyle {
+    // Returns AnsiColor only when stdout is a terminal and NO_COLOR is not set
+    pub fn detect() -> Self {
+        Self::from_environment(
+            std::io::stdout().is_terminal(),
+            std::env::var_os("NO_COLOR").is_some(),
+        )
+    }
+
+    // Picks the style given whether the output is a terminal and whether NO_COLOR is set
+    fn from_environment(terminal: bool, no_color: bool) -> Self {
+        match terminal && !no_color {
+            true => RenderStyle::AnsiColor,
+            false => RenderStyle::Plain,
+        }
+    }
+
+    // LaTeX is not meant for a terminal, so it is never colored
+    fn is_colored(&self, notation: Notation) -> bool {
+        *self == RenderStyle::AnsiColor && notation != Notation::Latex
+    }
+}
+
+impl EvaluationHistory {
+    // Renders the history like render, but in the given style
+    // In color, lines changed by a pass are bold and the rest are dimmed, with truth values in
+    // green or red
+    pub fn render_styled(&self, style: RenderStyle, notation: Notation) -> String {
+        if !style.is_colored(notation) {
+            return self.render(notation);
+        }
+
+        let mut rendered = String::new();
+
+        for (pass, deduction) in self.get_deductions().iter().enumerate() {
+            let pass_steps: Vec<StepAction> = self
+                .get_steps()
+                .iter()
+                .filter(|step| step.get_pass() == pass)
+                .map(|step| step.get_action())
+                .collect();
+
+            // Passes which only removed resolved premises are not shown
+            if pass > 0 {
+                if pass_steps.is_empty() {
+                    continue;
+                }
+
+                rendered.push_str("\n=>\n");
+            }
+
+            for (label, premise) in deduction.get_labeled_premises() {
+                let text = match premise.get_value_if_root_proposition() {
+                    Some((proposition, value)) => {
+                        format!("{} = {}", proposition, notation.truth_value(value))
+                    }
+                    None => premise.render(notation),
+                };
+
+                let action = self
+                    .get_steps()
+                    .iter()
+                    .find(|step| step.get_pass() == pass && step.get_label() == label)
+                    .map(|step| step.get_action());
+
+                let line = match action {
+                    Some(action) => format!(
+                        "{} {}",
+                        paint_line(&text, BOLD, notation),
+                        paint(&format!("[{}]", action), YELLOW)
+                    ),
+                    None => paint_line(&text, DIM, notation),
+                };
+
+                rendered.push_str(&line);
+                rendered.push('\n');
+            }
+
+            // The conclusion never changes, so it is only shown with the original premises
+            if let (0, Some(conclusion)) = (pass, deduction.get_conclusion()) {
+                let text = format!("{} {}", notation.therefore(), conclusion.render(notation));
+                rendered.push_str(&paint_line(&text, BOLD, notation));
+                rendered.push('\n');
+            }
+        }
+
+        rendered
+    }
+}
+
+impl Deduction {
+    // Renders the premises and conclusion like Display, but in the given style
+    // In color, truth values are shown in green or red
+    pub fn render_styled(&self, style: RenderStyle, notation: Notation) -> String {
+        let colored = style.is_colored(notation);
+        let mut rendered = String::new();
+
+        let mut lines: Vec<String> = self
+            .get_labeled_premises()
+            .into_iter()
+            .map(|(_, premise)| premise.render(notation))
+            .collect();
+
+        if let Some(conclusion) = self.get_conclusion() {
+            lines.push(format!(
+                "{} {}",
+                notation.therefore(),
+                conclusion.render(notation)
+            ));
+        }
+
+        for line in lines {
+            match colored {
+                true => rendered.push_str(&paint_truth_values(&line, notation)),
+                false => rendered.push_str(&line),
+            }
+
+            rendered.push('\n');
+        }
+
+        rendered
+    }
+}
+
+// Wraps the text in the given ANSI code, resetting everything after it
+fn paint(text: &str, code: &str) -> String {
+    format!("\x1b[{}m{}\x1b[0m", code, text)
+}
+
+// Wraps a whole line in the given ANSI code, with its truth values colored inside it
+fn paint_line(text: &str, code: &str, notation: Notation) -> String {
+    paint(&paint_truth_values(text, notation), code)
+}
+
+// Colors every truth value in the text, only resetting the foreground so that a bold or dimmed
+// line stays that way after each value
+fn paint_truth_values(text: &str, notation: Notation) -> String {
+    // Propositions are single lowercase letters, so the truth values cannot appear inside them
+    [(true, GREEN), (false, RED)]
+        .iter()
+        .fold(text.to_string(), |text, (value, code)| {
+            let symbol = notation.truth_value(*value);
+            text.replace(symbol, &format!("\x1b[{}m{}\x1b[39m", code, symbol))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solved_history() -> (Deduction, EvaluationHistory) {
+        let mut deduction = Deduction::from_strs(vec!["a", "a > b", "b & c > d", "!c"]).unwrap();
+        let history = deduction.solve();
+
+        (deduction, history)
+    }
+
+    #[test]
+    fn test_render_styled_history() {
+        let (_, history) = solved_history();
+
+        let plain = history.render_styled(RenderStyle::Plain, Notation::Unicode);
+        assert_eq!(plain, history.render(Notation::Unicode));
+        assert!(!plain.contains('\x1b'));
+
+        let colored = history.render_styled(RenderStyle::AnsiColor, Notation::Unicode);
+        assert!(colored.contains("\x1b[32mTRUE\x1b[39m"));
+        assert!(colored.contains("\x1b[31mFALSE\x1b[39m"));
+        assert!(colored.contains("\x1b[1m"));
+        assert!(colored.contains("\x1b[2m"));
+
+        // Removing the escapes leaves the same lines as the plain rendering
+        let stripped: String = colored
+            .split('\x1b')
+            .enumerate()
+            .map(|(i, part)| match i {
+                0 => part,
+                _ => part.split_once('m').map_or(part, |(_, rest)| rest),
+            })
+            .collect();
+        assert_eq!(stripped, plain);
+
+        // LaTeX is never colored
+        let latex = history.render_styled(RenderStyle::AnsiColor, Notation::Latex);
+        assert_eq!(latex, history.render(Notation::Latex));
+    }
+
+    #[test]
+    fn test_render_styled_deduction() {
+        let mut deduction = Deduction::from_strs(vec!["a", "!c", "a > b | c"]).unwrap();
+        deduction.update_actual_values();
+        let deduction = deduction.substituted_all();
+
+        let plain = deduction.render_styled(RenderStyle::Plain, Notation::Unicode);
+        assert_eq!(plain, deduction.to_string());
+        assert!(!plain.contains('\x1b'));
+
+        let colored = deduction.render_styled(RenderStyle::AnsiColor, Notation::Unicode);
+        assert!(colored.contains("\x1b[32mTRUE\x1b[39m"));
+        assert!(colored.contains("\x1b[31mFALSE\x1b[39m"));
+    }
+
+    #[test]
+    fn test_detect_style() {
+        assert_eq!(
+            RenderStyle::from_environment(true, false),
+            RenderStyle::AnsiColor
+        );
+        assert_eq!(
+            RenderStyle::from_environment(true, true),
+            RenderStyle::Plain
+        );
+        assert_eq!(
+            RenderStyle::from_environment(false, false),
+            RenderStyle::Plain
+        );
+    }
+}
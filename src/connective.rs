@@ -0,0 +1,514 @@
+use std::collections::BTreeSet;
+use std::fmt::{Debug, Display, Formatter, Result};
+use std::sync::Arc;
+
+use crate::errors::ParseError;
+use crate::notation::Notation;
+use crate::premises::Operator;
+use crate::ValueMap;
+
+// Describes a logical connective, so formulas can be written with connectives other than the
+// ones a Premise is made of, such as NAND or a ternary if-then-else
+// Connectives with one operand are written before it, those with two between them, and all others
+// like a function, such as "ite(p, q, r)"
+pub trait Connective: Debug + Send + Sync {
+    // Returns how many operands the connective takes
+    fn arity(&self) -> usize;
+
+    // Returns the value of the connective applied to its operands, of which there are always arity
+    fn apply(&self, operands: &[bool]) -> bool;
+
+    // Returns the symbol the connective is displayed with
+    fn symbol(&self) -> &str;
+
+    // Returns every token the connective may be written as when parsing, including its symbol
+    fn tokens(&self) -> Vec<&str> {
+        vec![self.symbol()]
+    }
+
+    // Returns how tightly a connective with two operands binds, where higher binds tighter
+    fn precedence(&self) -> u8 {
+        0
+    }
+
+    // Checks whether a chain of a connective with two operands groups from the right
+    fn is_right_associative(&self) -> bool {
+        false
+    }
+}
+
+// The negation of a single operand, written "¬" or "!"
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Not;
+
+// Stores the connectives a formula may be parsed with
+#[derive(Debug, Clone)]
+pub struct ConnectiveRegistry {
+    connectives: Vec<Arc<dyn Connective>>,
+}
+
+// Represents a formula built from the connectives of a registry, where every application holds
+// the connective it uses so it can be evaluated and displayed on its own
+#[derive(Debug, Clone)]
+pub enum ConnectiveExpr {
+    Atom(char),
+    Const(bool),
+    Apply(Arc<dyn Connective>, Vec<ConnectiveExpr>),
+}
+
+// Represents one token of a formula being parsed with a registry
+#[derive(Debug, Clone)]
+enum Token {
+    Atom(char),
+    Connective(Arc<dyn Connective>),
+    Open,
+    Close,
+    Comma,
+}
+
+impl Connective for Not {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn apply(&self, operands: &[bool]) -> bool {
+        !operands[0]
+    }
+
+    fn symbol(&self) -> &str {
+        Notation::Unicode.negation()
+    }
+
+    fn tokens(&self) -> Vec<&str> {
+        vec![Notation::Unicode.negation(), Notation::Ascii.negation()]
+    }
+}
+
+impl Connective for Operator {
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn apply(&self, operands: &[bool]) -> bool {
+        Operator::apply(*self, operands[0], operands[1])
+    }
+
+    fn symbol(&self) -> &str {
+        Notation::Unicode.operator(*self)
+    }
+
+    fn tokens(&self) -> Vec<&str> {
+        let mut tokens = vec![
+            Notation::Unicode.operator(*self),
+            Notation::Ascii.operator(*self),
+        ];
+
+        match self {
+            Operator::Implies => tokens.push("->"),
+            Operator::Iff => tokens.extend(["<->", "="]),
+            _ => (),
+        }
+
+        tokens
+    }
+
+    fn precedence(&self) -> u8 {
+        Operator::precedence(self)
+    }
+
+    fn is_right_associative(&self) -> bool {
+        Operator::is_right_associative(self)
+    }
+}
+
+impl Default for ConnectiveRegistry {
+    // Creates a registry of the connectives a Premise is made of, which parses the same formulas
+    fn default() -> Self {
+        Self::new()
+            .with(Not)
+            .with(Operator::And)
+            .with(Operator::Or)
+            .with(Operator::Implies)
+            .with(Operator::Iff)
+            .with(Operator::Xor)
+    }
+}
+
+impl ConnectiveRegistry {
+    // Creates a registry without any connectives
+    pub fn new() -> Self {
+        Self {
+            connectives: Vec::new(),
+        }
+    }
+
+    // Adds a connective to the registry
+    pub fn with(mut self, connective: impl Connective + 'static) -> Self {
+        self.connectives.push(Arc::new(connective));
+        self
+    }
+
+    // Parses a formula using only the connectives in the registry, so any other symbol is an
+    // invalid character
+    pub fn parse(&self, formula: &str) -> std::result::Result<ConnectiveExpr, ParseError> {
+        let tokens = self.tokenize(formula)?;
+        let mut position = 0;
+
+        let expression = parse_binary(&tokens, &mut position, 0)?;
+
+        match position == tokens.len() {
+            true => Ok(expression),
+            false => Err(ParseError::InvalidStructure),
+        }
+    }
+
+    // Splits the formula into tokens, matching the longest connective token at each position
+    fn tokenize(&self, formula: &str) -> std::result::Result<Vec<Token>, ParseError> {
+        let mut tokens = Vec::new();
+        let mut i = 0;
+
+        while let Some(c) = formula[i..].chars().next() {
+            let connective = self
+                .connectives
+                .iter()
+                .flat_map(|connective| {
+                    connective
+                        .tokens()
+                        .into_iter()
+                        .filter(|token| !token.is_empty() && formula[i..].starts_with(token))
+                        .map(|token| (token.len(), connective.clone()))
+                        .collect::<Vec<_>>()
+                })
+                .max_by_key(|(length, _)| *length);
+
+            if let Some((length, connective)) = connective {
+                tokens.push(Token::Connective(connective));
+                i += length;
+                continue;
+            }
+
+            match c {
+                ' ' => (),
+                '(' => tokens.push(Token::Open),
+                ')' => tokens.push(Token::Close),
+                ',' => tokens.push(Token::Comma),
+                'a'..='z' => tokens.push(Token::Atom(c)),
+                _ => {
+                    return Err(ParseError::InvalidCharacter {
+                        character: c,
+                        position: i,
+                    })
+                }
+            }
+
+            i += c.len_utf8();
+        }
+
+        Ok(tokens)
+    }
+}
+
+impl ConnectiveExpr {
+    // Finds the truth value of the formula, or None if it depends on a proposition whose value is
+    // unknown
+    pub fn evaluate(&self, proposition_values: &ValueMap) -> Option<bool> {
+        match self {
+            ConnectiveExpr::Atom(proposition) => proposition_values.get_value(*proposition),
+            ConnectiveExpr::Const(value) => Some(*value),
+            ConnectiveExpr::Apply(connective, operands) => {
+                let operands = operands
+                    .iter()
+                    .map(|operand| operand.evaluate(proposition_values))
+                    .collect::<Option<Vec<bool>>>()?;
+
+                Some(connective.apply(&operands))
+            }
+        }
+    }
+
+    // Returns every proposition in the formula, in alphabetical order
+    pub fn propositions(&self) -> BTreeSet<char> {
+        let mut propositions = BTreeSet::new();
+        self.add_propositions(&mut propositions);
+
+        propositions
+    }
+
+    // Adds every proposition in the formula to the set
+    fn add_propositions(&self, propositions: &mut BTreeSet<char>) {
+        match self {
+            ConnectiveExpr::Atom(proposition) => {
+                propositions.insert(*proposition);
+            }
+            ConnectiveExpr::Const(_) => (),
+            ConnectiveExpr::Apply(_, operands) => {
+                for operand in operands {
+                    operand.add_propositions(propositions);
+                }
+            }
+        }
+    }
+
+    // Returns the connective applied last if it is written between two operands
+    fn get_infix(&self) -> Option<&Arc<dyn Connective>> {
+        match self {
+            ConnectiveExpr::Apply(connective, _) if connective.arity() == 2 => Some(connective),
+            _ => None,
+        }
+    }
+
+    // Writes the formula as an operand of a connective with two operands, in parentheses if it
+    // would otherwise be grouped differently when parsed
+    fn fmt_operand(
+        &self,
+        f: &mut Formatter<'_>,
+        parent: &dyn Connective,
+        is_right: bool,
+    ) -> Result {
+        let needs_parentheses = self.get_infix().is_some_and(|connective| {
+            connective.precedence() < parent.precedence()
+                || (connective.precedence() == parent.precedence()
+                    && is_right != parent.is_right_associative())
+        });
+
+        match needs_parentheses {
+            true => write!(f, "({})", self),
+            false => write!(f, "{}", self),
+        }
+    }
+}
+
+impl Display for ConnectiveExpr {
+    // Displays the formula with the symbol of each connective and only the parentheses needed to
+    // keep its structure, such as "¬p ∧ q" or "ite(p, q, r)"
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ConnectiveExpr::Atom(proposition) => write!(f, "{}", proposition),
+            ConnectiveExpr::Const(value) => write!(f, "{}", Notation::Unicode.truth_value(*value)),
+            ConnectiveExpr::Apply(connective, operands) => match operands.as_slice() {
+                [operand] => match operand.get_infix() {
+                    Some(_) => write!(f, "{}({})", connective.symbol(), operand),
+                    None => write!(f, "{}{}", connective.symbol(), operand),
+                },
+                [left, right] => {
+                    left.fmt_operand(f, connective.as_ref(), false)?;
+                    write!(f, " {} ", connective.symbol())?;
+                    right.fmt_operand(f, connective.as_ref(), true)
+                }
+                operands => {
+                    let operands: Vec<String> =
+                        operands.iter().map(ConnectiveExpr::to_string).collect();
+
+                    write!(f, "{}({})", connective.symbol(), operands.join(", "))
+                }
+            },
+        }
+    }
+}
+
+// Parses a chain of connectives with two operands which bind at least as tightly as the given
+// precedence, grouping them by their precedence and associativity
+fn parse_binary(
+    tokens: &[Token],
+    position: &mut usize,
+    min_precedence: u8,
+) -> std::result::Result<ConnectiveExpr, ParseError> {
+    let mut left = parse_operand(tokens, position)?;
+
+    while let Some(Token::Connective(connective)) = tokens.get(*position) {
+        if connective.arity() != 2 || connective.precedence() < min_precedence {
+            break;
+        }
+
+        *position += 1;
+
+        // The right side of a left-associative connective may only hold ones which bind tighter
+        let next_precedence = match connective.is_right_associative() {
+            true => connective.precedence(),
+            false => connective.precedence().saturating_add(1),
+        };
+        let right = parse_binary(tokens, position, next_precedence)?;
+
+        left = ConnectiveExpr::Apply(connective.clone(), vec![left, right]);
+    }
+
+    Ok(left)
+}
+
+// Parses a single operand, which is a proposition, a formula in parentheses, a connective with
+// one operand followed by it, or any other connective followed by its operands in parentheses
+fn parse_operand(
+    tokens: &[Token],
+    position: &mut usize,
+) -> std::result::Result<ConnectiveExpr, ParseError> {
+    let token = tokens.get(*position).ok_or(ParseError::InvalidStructure)?;
+    *position += 1;
+
+    match token {
+        Token::Atom(proposition) => Ok(ConnectiveExpr::Atom(*proposition)),
+        Token::Open => {
+            let expression = parse_binary(tokens, position, 0)?;
+            expect_close(tokens, position)?;
+
+            Ok(expression)
+        }
+        Token::Connective(connective) if connective.arity() == 1 => {
+            let operand = parse_operand(tokens, position)?;
+
+            Ok(ConnectiveExpr::Apply(connective.clone(), vec![operand]))
+        }
+        Token::Connective(connective) if connective.arity() != 2 => {
+            let mut operands = Vec::new();
+
+            if connective.arity() > 0 {
+                let Some(Token::Open) = tokens.get(*position) else {
+                    return Err(ParseError::InvalidStructure);
+                };
+                *position += 1;
+
+                operands.push(parse_binary(tokens, position, 0)?);
+
+                while let Some(Token::Comma) = tokens.get(*position) {
+                    *position += 1;
+                    operands.push(parse_binary(tokens, position, 0)?);
+                }
+
+                expect_close(tokens, position)?;
+            }
+
+            match operands.len() == connective.arity() {
+                true => Ok(ConnectiveExpr::Apply(connective.clone(), operands)),
+                false => Err(ParseError::InvalidStructure),
+            }
+        }
+        _ => Err(ParseError::InvalidStructure),
+    }
+}
+
+// Skips the closing parenthesis which must come next
+fn expect_close(tokens: &[Token], position: &mut usize) -> std::result::Result<(), ParseError> {
+    match tokens.get(*position) {
+        Some(Token::Close) => {
+            *position += 1;
+            Ok(())
+        }
+        _ => Err(ParseError::InvalidStructure),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::truth_table::{RowFilter, TruthTable, DEFAULT_MAX_VARIABLES};
+
+    // TRUE when both operands have the same value
+    #[derive(Debug)]
+    struct Xnor;
+
+    impl Connective for Xnor {
+        fn arity(&self) -> usize {
+            2
+        }
+
+        fn apply(&self, operands: &[bool]) -> bool {
+            operands[0] == operands[1]
+        }
+
+        fn symbol(&self) -> &str {
+            "⊙"
+        }
+
+        fn tokens(&self) -> Vec<&str> {
+            vec!["⊙", "xnor"]
+        }
+    }
+
+    // If the first operand holds then the second, otherwise the third
+    #[derive(Debug)]
+    struct IfThenElse;
+
+    impl Connective for IfThenElse {
+        fn arity(&self) -> usize {
+            3
+        }
+
+        fn apply(&self, operands: &[bool]) -> bool {
+            match operands[0] {
+                true => operands[1],
+                false => operands[2],
+            }
+        }
+
+        fn symbol(&self) -> &str {
+            "ite"
+        }
+    }
+
+    #[test]
+    fn test_parse_default() {
+        let registry = ConnectiveRegistry::default();
+
+        // The default registry groups formulas like a Premise
+        for (formula, expected) in [
+            ("(m & !b) > j", "m ∧ ¬b → j"),
+            ("a > b > c", "a → b → c"),
+            ("(a > b) > c", "(a → b) → c"),
+            ("a | b & c", "a ∨ b ∧ c"),
+            ("!(a -> b)", "¬(a → b)"),
+        ] {
+            assert_eq!(registry.parse(formula).unwrap().to_string(), expected);
+        }
+
+        assert_eq!(
+            registry.parse("p # q").unwrap_err(),
+            ParseError::InvalidCharacter {
+                character: '#',
+                position: 2
+            }
+        );
+        assert_eq!(
+            registry.parse("p & ").unwrap_err(),
+            ParseError::InvalidStructure
+        );
+    }
+
+    #[test]
+    fn test_custom_connectives() {
+        let registry = ConnectiveRegistry::new()
+            .with(Not)
+            .with(Xnor)
+            .with(IfThenElse);
+
+        let formula = registry.parse("p xnor !q").unwrap();
+        assert_eq!(formula.to_string(), "p ⊙ ¬q");
+
+        let table = TruthTable::new(&formula, DEFAULT_MAX_VARIABLES).unwrap();
+        assert_eq!(
+            table.render_plain(RowFilter::All),
+            "p  q  p ⊙ ¬q\nT  T  F\nT  F  T\nF  T  T\nF  F  F\n"
+        );
+
+        let formula = registry.parse("ite(p, q xnor r, !r)").unwrap();
+        assert_eq!(formula.to_string(), "ite(p, q ⊙ r, ¬r)");
+
+        let mut values = ValueMap::default();
+        values.set_value('p', Some(false));
+        assert_eq!(formula.evaluate(&values), None);
+        values.set_value('q', Some(true));
+        values.set_value('r', Some(false));
+        assert_eq!(formula.evaluate(&values), Some(true));
+
+        // Symbols outside the registry are rejected, as is the wrong number of operands
+        assert_eq!(
+            registry.parse("p & q").unwrap_err(),
+            ParseError::InvalidCharacter {
+                character: '&',
+                position: 2
+            }
+        );
+        assert_eq!(
+            registry.parse("ite(p, q)").unwrap_err(),
+            ParseError::InvalidStructure
+        );
+    }
+}
@@ -1,145 +1,1105 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::ControlFlow;
+use std::sync::Arc;
 
+use crate::clock::Stopwatch;
+use crate::errors::{InconsistencyError, ParseError, RenameError};
+use crate::history::{EvaluationHistory, StepAction};
+use crate::observer::{LimitObserver, SolveObserver, SolveOptions, SolveResult};
+use crate::watch::WatchLists;
 use crate::Premise;
 use crate::PremiseNode;
 
 // Stores all the given or working premises on a stack
+#[derive(Debug, Default, Clone)]
 pub struct Deduction {
-    premise_stack: Vec<Premise>,
+    premise_stack: Vec<StackEntry>,
     proposition_values: ValueMap,
+    conclusion: Option<Premise>,
+    // The values given before solving, kept apart from those found so the problem can be
+    // written out again
+    assumptions: BTreeMap<char, bool>,
+    // The first proposition two premises were found to give different values
+    conflict: Option<char>,
+}
+
+// Stores a premise on the stack along with its position in the original list of premises,
+// so it can still be identified after other premises have been removed, and the line it was
+// read from if it was loaded from a file
+// A premise loaded from JSON may also have its own label in place of its number, and a comment
+// The premise is shared between clones of the Deduction, such as those stored in the history at
+// every pass, and is only copied when one of them changes it
+#[derive(Debug, Clone)]
+struct StackEntry {
+    number: usize,
+    line: Option<usize>,
+    label: Option<Arc<str>>,
+    comment: Option<Arc<str>>,
+    premise: Arc<Premise>,
 }
 
 // Stores all known root proposition values in the Deduction
-#[derive(Debug)]
+#[derive(Debug, Default, Clone)]
 pub struct ValueMap {
-    values: HashMap<char, Option<bool>>,
+    values: Values,
+}
+
+// Stores the values of a ValueMap in an array indexed by letter while every proposition is a
+// lowercase letter, as the parser reads them, so looking one up is an array read
+// Any other proposition, such as one made with Premise::atom, moves the values into a BTreeMap
+// Either way the values are kept in order, so iterating over them is the same on every run
+#[derive(Debug, Clone)]
+enum Values {
+    // Each slot is None if its letter is not in the ValueMap
+    Letters([Option<Option<bool>>; 26]),
+    Map(BTreeMap<char, Option<bool>>),
 }
 
 impl std::fmt::Display for Deduction {
     // Displays all the propositions in the Deduction
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for premise in &self.premise_stack {
-            writeln!(f, "{}", premise)?;
+        for entry in &self.premise_stack {
+            writeln!(f, "{}", entry.premise)?;
+        }
+
+        if let Some(conclusion) = &self.conclusion {
+            writeln!(f, "∴ {}", conclusion)?;
         }
 
         Ok(())
     }
 }
 
-impl Default for Deduction {
-    // Creates an empty Deduction
-    fn default() -> Self {
-        Self {
-            premise_stack: Vec::new(),
-            proposition_values: ValueMap::default(),
+impl PartialEq for ValueMap {
+    // ValueMaps are equal if they hold the same propositions with the same values, however each
+    // of them stores the values
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for ValueMap {}
+
+impl std::fmt::Display for ValueMap {
+    // Displays each proposition and its value on its own line, in alphabetical order
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut values: Vec<(char, Option<bool>)> = self.iter().collect();
+        values.sort();
+
+        for (proposition, value) in values {
+            match value {
+                Some(true) => writeln!(f, "{} = TRUE", proposition)?,
+                Some(false) => writeln!(f, "{} = FALSE", proposition)?,
+                None => writeln!(f, "{} = UNKNOWN", proposition)?,
+            }
         }
+
+        Ok(())
     }
 }
 
 impl Deduction {
     // Creates a new Deduction from the given fields
-    fn new(premise_stack: Vec<Premise>, proposition_values: ValueMap) -> Self {
+    fn new(premise_stack: Vec<StackEntry>, proposition_values: ValueMap) -> Self {
         Self {
             premise_stack,
             proposition_values,
+            conclusion: None,
+            assumptions: BTreeMap::new(),
+            conflict: None,
         }
     }
 
-    // Creates a Deduction from a vector of premises
-    pub fn from_strs(premises: Vec<&str>) -> Self {
-        let premise_stack: Vec<Premise> = premises.iter().map(|x| Premise::parse_str(x)).collect();
-        let proposition_values = ValueMap::from_premise_stack(&premise_stack);
+    // Creates a Deduction from a vector of premise strings, returning the error for the first
+    // premise which cannot be parsed
+    pub fn from_strs(premises: Vec<&str>) -> Result<Self, ParseError> {
+        Ok(Self::from_premises(
+            premises
+                .iter()
+                .map(|x| Premise::parse_str(x))
+                .collect::<Result<Vec<Premise>, ParseError>>()?,
+        ))
+    }
+
+    // Creates a Deduction from a vector of already-parsed premises
+    pub fn from_premises(premises: Vec<Premise>) -> Self {
+        let proposition_values = ValueMap::from_premise_stack(&premises);
+
+        let premise_stack = premises
+            .into_iter()
+            .enumerate()
+            .map(|(i, premise)| StackEntry {
+                number: i + 1,
+                line: None,
+                label: None,
+                comment: None,
+                premise: Arc::new(premise),
+            })
+            .collect();
 
         Self::new(premise_stack, proposition_values)
     }
 
-    // Checks if the Deduction is empty
-    fn is_empty(&self) -> bool {
+    // Checks whether every premise has been removed from the stack, which happens once solving has
+    // found a value from each of them
+    pub fn is_empty(&self) -> bool {
         self.premise_stack.is_empty()
     }
 
+    // Returns every premise left on the stack along with its label
+    pub fn get_labeled_premises(&self) -> Vec<(String, &Premise)> {
+        self.premise_stack
+            .iter()
+            .map(|entry| (entry.get_label(), entry.premise.as_ref()))
+            .collect()
+    }
+
+    // Returns the line the premise with the given label was read from, if it was loaded from a file
+    pub fn get_premise_line(&self, label: &str) -> Option<usize> {
+        self.premise_stack
+            .iter()
+            .find(|entry| entry.get_label() == label)
+            .and_then(|entry| entry.line)
+    }
+
+    // Records the line each premise was read from, in the order of the premises
+    pub(crate) fn set_premise_lines(&mut self, lines: Vec<usize>) {
+        for (entry, line) in self.premise_stack.iter_mut().zip(lines) {
+            entry.line = Some(line);
+        }
+    }
+
+    // Returns the comment given with the premise with the given label, if it was loaded from JSON
+    // with one
+    pub fn get_premise_comment(&self, label: &str) -> Option<&str> {
+        self.premise_stack
+            .iter()
+            .find(|entry| entry.get_label() == label)
+            .and_then(|entry| entry.comment.as_deref())
+    }
+
+    // Records the label and comment given with each premise, in the order of the premises, where
+    // a premise without a label keeps its number
+    // The labels should all differ from each other and from the numbers of the other premises
+    pub(crate) fn set_premise_notes(&mut self, notes: Vec<(Option<String>, Option<String>)>) {
+        for (entry, (label, comment)) in self.premise_stack.iter_mut().zip(notes) {
+            entry.label = label.map(Arc::from);
+            entry.comment = comment.map(Arc::from);
+        }
+    }
+
+    // Returns the number of premises left on the stack
+    pub fn get_num_premises(&self) -> usize {
+        self.premise_stack.len()
+    }
+
     // Returns the proposition values
     pub fn get_values(&self) -> &ValueMap {
         &self.proposition_values
     }
 
+    // Returns every proposition of the premises and the conclusion, in alphabetical order
+    pub fn get_propositions(&self) -> BTreeSet<char> {
+        self.proposition_values
+            .iter()
+            .map(|(proposition, _)| proposition)
+            .collect()
+    }
+
+    // Sets the conclusion which the premises are meant to support
+    // Its propositions are added to the ValueMap, as they may not appear in any premise
+    pub fn set_conclusion(&mut self, conclusion: Premise) {
+        self.proposition_values.add_propositions(&conclusion);
+        self.conclusion = Some(conclusion);
+    }
+
+    // Gives a proposition a value before solving, as an "assume: p=true" line in a file does
+    // The value is substituted into the premises like one found by solving, so a premise which
+    // gives the proposition the other value shows the premises to be inconsistent
+    pub fn assume_value(&mut self, proposition: char, value: bool) {
+        self.proposition_values.set_value(proposition, Some(value));
+        self.assumptions.insert(proposition, value);
+    }
+
+    // Returns the value given to each proposition before solving, in alphabetical order
+    pub fn get_assumptions(&self) -> &BTreeMap<char, bool> {
+        &self.assumptions
+    }
+
+    // Returns the conclusion, if one was given
+    pub fn get_conclusion(&self) -> Option<&Premise> {
+        self.conclusion.as_ref()
+    }
+
+    // Evaluates the conclusion using the values known so far
+    // Returns None if there is no conclusion or its value cannot be determined yet
+    pub fn evaluate_conclusion(&self) -> Option<bool> {
+        self.conclusion.as_ref()?.evaluate(&self.proposition_values)
+    }
+
+    // Checks whether the solve has shown the premises to be inconsistent, either because a premise
+    // was evaluated to FALSE or because two premises gave a proposition different values
+    pub fn is_contradictory(&self) -> bool {
+        self.ensure_consistent().is_err()
+    }
+
+    // Returns why the solve has shown the premises to be inconsistent like is_contradictory, so it
+    // can be used with ?
+    pub fn ensure_consistent(&self) -> Result<(), InconsistencyError> {
+        if let Some(proposition) = self.conflict {
+            return Err(InconsistencyError::ConflictingValues(proposition));
+        }
+
+        match self
+            .premise_stack
+            .iter()
+            .find(|entry| entry.premise.get_nodes() == &[PremiseNode::TruthValue(false)])
+        {
+            Some(entry) => Err(InconsistencyError::FalsePremise(entry.get_label())),
+            None => Ok(()),
+        }
+    }
+
     // Substitutes all root propositions with their actual truth values, if known
-    // * This should not be public but it is for testing purposes *
+    // This is the first half of a solve pass, without evaluating anything afterwards
     pub fn substitute_all(&mut self) {
-        for premise in &mut self.premise_stack {
-            premise.substitute(&self.proposition_values);
+        for entry in &mut self.premise_stack {
+            if entry.premise.can_substitute(&self.proposition_values) {
+                Arc::make_mut(&mut entry.premise).substitute(&self.proposition_values);
+            }
         }
     }
 
+    // Returns a copy of the Deduction with every premise substituted like substitute_all, leaving
+    // the Deduction itself unchanged so a change to the values can be tried out on the copy
+    pub fn substituted_all(&self) -> Deduction {
+        let mut deduction = self.clone();
+        deduction.substitute_all();
+
+        deduction
+    }
+
+    // Renames a root proposition in every premise, the conclusion and the ValueMap, such as to
+    // merge two propositions which stand for the same statement
+    // If the new name is already used its value is kept, unless the two values are known to differ
+    pub fn rename_proposition(&mut self, from: char, to: char) -> Result<(), RenameError> {
+        self.rename_proposition_with_observer(from, to, &mut ())
+    }
+
+    // Renames a root proposition like rename_proposition, reporting each premise it changed to the
+    // observer as a RENAME step
+    // The rename is made before the observer is told about it, so it cannot be stopped
+    pub fn rename_proposition_with_observer(
+        &mut self,
+        from: char,
+        to: char,
+        observer: &mut dyn SolveObserver,
+    ) -> Result<(), RenameError> {
+        let Some(from_value) = self.proposition_values.values.get(from) else {
+            return Err(RenameError::MissingProposition(from));
+        };
+
+        if from == to {
+            return Ok(());
+        }
+
+        let value = match self.proposition_values.values.get(to) {
+            Some(Some(to_value)) if from_value.is_some_and(|value| value != to_value) => {
+                return Err(RenameError::ConflictingValues { from, to })
+            }
+            Some(to_value) => from_value.or(to_value),
+            None => from_value,
+        };
+
+        self.proposition_values.values.remove(from);
+        self.proposition_values.set_value(to, value);
+
+        if let Some(conclusion) = &mut self.conclusion {
+            conclusion.rename_proposition(from, to);
+        }
+
+        let action = StepAction::Rename { from, to };
+
+        for entry in &mut self.premise_stack {
+            if !entry.premise.contains_proposition(from) {
+                continue;
+            }
+
+            let before = Arc::clone(&entry.premise);
+            Arc::make_mut(&mut entry.premise).rename_proposition(from, to);
+
+            if entry.premise != before {
+                let _ = observer.on_premise_changed(
+                    &entry.get_label(),
+                    &before,
+                    &entry.premise,
+                    action,
+                );
+            }
+        }
+
+        let _ = observer.on_proposition_renamed(from, to);
+
+        Ok(())
+    }
+
     // Finds and updates all of the actual truth values of the root propositions
     // Only finds values for propositions which have been collapsed to "p" or "!p"
-    // Removes the propositions whose values have been determined from the stack
-    // * This should not be public but it is for testing purposes *
-    // TODO: Write test cases for this!
+    // The premises whose values have been determined are removed from the stack on the next pass
     pub fn update_actual_values(&mut self) {
-        for premise in &mut self.premise_stack {
+        self.derive_values();
+    }
+
+    // Updates the values of the root propositions like update_actual_values, returning each
+    // proposition whose value changed along with its new value and the label of its premise
+    fn derive_values(&mut self) -> Vec<(char, bool, String)> {
+        let mut derived = Vec::new();
+
+        for entry in &mut self.premise_stack {
             if let Some((proposition_char, proposition_value)) =
-                premise.get_value_if_root_proposition()
+                entry.premise.get_value_if_root_proposition()
             {
+                let known_value = self.proposition_values.get_value(proposition_char);
+
+                // A value which disagrees with one already found means the premises are inconsistent
+                if known_value.is_some_and(|value| value != proposition_value) {
+                    self.conflict.get_or_insert(proposition_char);
+                }
+
+                if known_value != Some(proposition_value) {
+                    derived.push((proposition_char, proposition_value, entry.get_label()));
+                }
+
                 self.proposition_values
                     .set_value(proposition_char, Some(proposition_value));
             }
         }
+
+        derived
     }
-}
 
-impl Default for ValueMap {
-    // Creates an empty ValueMap
-    fn default() -> Self {
-        Self {
-            values: HashMap::new(),
+    // Repeatedly substitutes and evaluates the premises until nothing else can be determined,
+    // returning the history of every step taken
+    pub fn solve(&mut self) -> EvaluationHistory {
+        let mut history = EvaluationHistory::new();
+        self.solve_with_observer(&mut history);
+
+        history
+    }
+
+    // Solves the Deduction like solve, reporting every event to the given observer as it happens
+    // The observer can stop the solve early by returning ControlFlow::Break from any event
+    pub fn solve_with_observer(&mut self, observer: &mut dyn SolveObserver) -> SolveResult {
+        let stopwatch = Stopwatch::start();
+
+        let result = match self.run_passes(observer) {
+            ControlFlow::Continue(()) => SolveResult::Complete,
+            ControlFlow::Break(()) => SolveResult::Aborted,
+        };
+
+        observer.on_elapsed(stopwatch.elapsed());
+        observer.on_finished(&result);
+
+        result
+    }
+
+    // Solves the Deduction like solve_with_observer, stopping early if any of the limits in the
+    // options is reached
+    pub fn solve_with_options(
+        &mut self,
+        options: &SolveOptions,
+        observer: &mut dyn SolveObserver,
+    ) -> SolveResult {
+        let mut limits = LimitObserver::new(observer, options);
+
+        match self.solve_with_observer(&mut limits) {
+            SolveResult::Aborted => match limits.get_reached() {
+                Some(limit) => SolveResult::LimitReached(limit),
+                None => SolveResult::Aborted,
+            },
+            result => result,
         }
     }
+
+    // Runs passes over the stack until one of them changes nothing
+    // The premises which mention each proposition are watched throughout, so each pass only
+    // substitutes into the premises a known value is still in
+    fn run_passes(&mut self, observer: &mut dyn SolveObserver) -> ControlFlow<()> {
+        for (proposition, value, label) in self.derive_values() {
+            observer.on_value_derived(proposition, value, &label)?;
+        }
+
+        // Every value known before the first pass, including any assumed ones, has yet to be
+        // substituted
+        let mut changed: BTreeSet<char> = self
+            .proposition_values
+            .iter()
+            .filter(|(_, value)| value.is_some())
+            .map(|(proposition, _)| proposition)
+            .collect();
+
+        let mut watches = WatchLists::default();
+        for entry in &self.premise_stack {
+            watches.add(entry.number, &entry.premise);
+        }
+
+        let mut pass = 1;
+
+        loop {
+            observer.on_step_start(pass, self)?;
+
+            if !self.solve_pass(observer, &mut watches, &mut changed)? {
+                return ControlFlow::Continue(());
+            }
+
+            pass += 1;
+        }
+    }
+
+    // Performs one pass over the stack, where each premise is either substituted or evaluated once
+    // Only the premises mentioning a proposition in the changed set are substituted into, and the
+    // set is replaced with the propositions whose values this pass derived
+    // Returns whether anything in the Deduction changed
+    fn solve_pass(
+        &mut self,
+        observer: &mut dyn SolveObserver,
+        watches: &mut WatchLists,
+        changed_values: &mut BTreeSet<char>,
+    ) -> ControlFlow<(), bool> {
+        let stack_size = self.premise_stack.len();
+
+        // Premises which have been collapsed to a known root proposition or to TRUE hold no more information
+        self.premise_stack.retain(|entry| {
+            let kept = entry.premise.get_value_if_root_proposition().is_none()
+                && entry.premise.get_nodes() != &[PremiseNode::TruthValue(true)];
+
+            if !kept {
+                watches.remove(entry.number);
+            }
+
+            kept
+        });
+
+        let mut changed = self.premise_stack.len() != stack_size;
+        let affected = watches.affected(changed_values);
+
+        // Only the premises which change are replaced, so unchanged ones stay shared with any
+        // earlier copies of the Deduction
+        for entry in &mut self.premise_stack {
+            let before = Arc::clone(&entry.premise);
+
+            // A premise may still be watched for a proposition an evaluation step removed from it
+            let substitute =
+                affected.contains(&entry.number) && before.can_substitute(&self.proposition_values);
+
+            if affected.contains(&entry.number) && !substitute {
+                watches.remove_known(entry.number, &self.proposition_values);
+            }
+
+            let (after, action) = if substitute {
+                (
+                    before.substituted(&self.proposition_values),
+                    StepAction::Substitute,
+                )
+            } else if watches.is_settled(entry.number) {
+                continue;
+            } else if let Some((nodes, rule)) = before.next_step() {
+                (before.with_nodes(nodes), StepAction::Evaluate(rule))
+            } else {
+                watches.settle(entry.number);
+                continue;
+            };
+
+            // No step may make a premise more complex, or solving might never finish
+            debug_assert!(after.complexity() <= before.complexity());
+            debug_assert_eq!(
+                action == StepAction::Substitute,
+                before.can_substitute(&self.proposition_values)
+            );
+
+            // The observer is told before the change is made, so a solve it stops leaves the
+            // Deduction as the observer last saw it
+            observer.on_premise_changed(&entry.get_label(), &before, &after, action)?;
+
+            if substitute {
+                watches.update(entry.number, &before, &after);
+            }
+
+            entry.premise = Arc::new(after);
+            changed = true;
+        }
+
+        changed_values.clear();
+
+        for (proposition, value, label) in self.derive_values() {
+            observer.on_value_derived(proposition, value, &label)?;
+            changed_values.insert(proposition);
+        }
+
+        ControlFlow::Continue(changed)
+    }
 }
 
-impl ValueMap {
-    // Creates a new ValueMap from the given fields
-    fn new(values: HashMap<char, Option<bool>>) -> Self {
-        Self { values }
+impl StackEntry {
+    // Returns the label of the premise, which is its number from 1 in the original order unless
+    // it was given its own
+    fn get_label(&self) -> String {
+        match &self.label {
+            Some(label) => label.to_string(),
+            None => format!("P{}", self.number),
+        }
     }
+}
 
+impl ValueMap {
     // Finds all the root propositions in the given stack and initializes them to None
     // This is used to create a Deduction from a vector of propositions
-    fn from_premise_stack(premise_stack: &Vec<Premise>) -> Self {
-        let mut values = HashMap::new();
-
-        fn inner<'a>(
-            values: &mut HashMap<char, Option<bool>>,
-            premise: impl Iterator<Item = &'a PremiseNode>,
-        ) {
-            for node in premise {
-                match node {
-                    PremiseNode::Proposition(proposition_char) => {
-                        values.insert(*proposition_char, None);
-                    }
-                    PremiseNode::Subpremise(subpremise) => {
-                        inner(values, subpremise.get_nodes().iter());
-                    }
-                    _ => (),
-                }
-            }
-        }
+    // The values are kept in order, so the order the premises are in makes no difference
+    fn from_premise_stack(premise_stack: &[Premise]) -> Self {
+        let mut values = Self::default();
 
         for premise in premise_stack {
-            inner(&mut values, premise.get_nodes().iter());
+            values.add_propositions(premise);
         }
 
-        Self { values }
+        values
+    }
+
+    // Adds every root proposition in the premise which is not already in the ValueMap as unknown
+    pub(crate) fn add_propositions(&mut self, premise: &Premise) {
+        premise.visit(&mut |node| {
+            if let PremiseNode::Proposition(proposition_char) = node {
+                if !self.contains(*proposition_char) {
+                    self.values.insert(*proposition_char, None);
+                }
+            }
+        });
     }
 
     // Gets the value of a root proposition, if known
+    // A proposition which is not in the ValueMap at all is also unknown
     pub fn get_value(&self, proposition: char) -> Option<bool> {
-        *self.values.get(&proposition)
-        .expect(format!("[INTERNAL ERROR] Attempted to find the value of a proposition '{}' which does not exist in the ValueMap. Was it initialized correctly?", proposition).as_str())
+        self.values.get(proposition).flatten()
+    }
+
+    // Checks whether the root proposition is in the ValueMap, whether or not its value is known
+    pub fn contains(&self, proposition: char) -> bool {
+        self.values.get(proposition).is_some()
     }
 
     // Sets the value of a root proposition
     pub fn set_value(&mut self, proposition: char, value: Option<bool>) {
         self.values.insert(proposition, value);
     }
+
+    // Returns an iterator over every root proposition and its value, if known, in the order of
+    // the propositions
+    pub fn iter(&self) -> impl Iterator<Item = (char, Option<bool>)> + '_ {
+        let (letters, map) = match &self.values {
+            Values::Letters(letters) => (Some(letters), None),
+            Values::Map(map) => (None, Some(map)),
+        };
+
+        let letters = letters.into_iter().flat_map(|letters| {
+            ('a'..='z')
+                .zip(letters)
+                .filter_map(|(proposition, value)| Some((proposition, (*value)?)))
+        });
+        let map = map.into_iter().flat_map(|map| {
+            map.iter()
+                .map(|(proposition, value)| (*proposition, *value))
+        });
+
+        letters.chain(map)
+    }
+
+    // Creates an empty ValueMap which keeps its values in a BTreeMap from the start, so both ways
+    // of storing them can be compared
+    #[cfg(test)]
+    pub(crate) fn with_map() -> Self {
+        Self {
+            values: Values::Map(BTreeMap::new()),
+        }
+    }
+}
+
+impl Default for Values {
+    fn default() -> Self {
+        Values::Letters([None; 26])
+    }
+}
+
+impl Values {
+    // Returns Some with the value of the proposition if it is in the values, even if it is unknown
+    fn get(&self, proposition: char) -> Option<Option<bool>> {
+        match self {
+            Values::Letters(letters) => letters[letter_index(proposition)?],
+            Values::Map(map) => map.get(&proposition).copied(),
+        }
+    }
+
+    // Sets the value of the proposition, moving the values into a BTreeMap if it is not a
+    // lowercase letter
+    fn insert(&mut self, proposition: char, value: Option<bool>) {
+        match (&mut *self, letter_index(proposition)) {
+            (Values::Letters(letters), Some(i)) => letters[i] = Some(value),
+            (Values::Map(map), _) => {
+                map.insert(proposition, value);
+            }
+            (Values::Letters(letters), None) => {
+                let mut map: BTreeMap<char, Option<bool>> = ('a'..='z')
+                    .zip(letters.iter())
+                    .filter_map(|(letter, value)| Some((letter, (*value)?)))
+                    .collect();
+                map.insert(proposition, value);
+
+                *self = Values::Map(map);
+            }
+        }
+    }
+
+    // Removes the proposition from the values
+    fn remove(&mut self, proposition: char) {
+        match self {
+            Values::Letters(letters) => {
+                if let Some(i) = letter_index(proposition) {
+                    letters[i] = None;
+                }
+            }
+            Values::Map(map) => {
+                map.remove(&proposition);
+            }
+        }
+    }
+}
+
+// Returns the position of the proposition in Values::Letters, if it is a lowercase letter
+fn letter_index(proposition: char) -> Option<usize> {
+    proposition
+        .is_ascii_lowercase()
+        .then(|| (proposition as u8 - b'a') as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::premises::Operator;
+
+    // Copies every premise on the stack at the start of each pass
+    #[derive(Default)]
+    struct EagerObserver {
+        stacks: Vec<Vec<(String, Premise)>>,
+    }
+
+    impl SolveObserver for EagerObserver {
+        fn on_step_start(&mut self, _pass: usize, deduction: &Deduction) -> ControlFlow<()> {
+            self.stacks.push(
+                deduction
+                    .get_labeled_premises()
+                    .into_iter()
+                    .map(|(label, premise)| (label, premise.clone()))
+                    .collect(),
+            );
+
+            ControlFlow::Continue(())
+        }
+    }
+
+    // Records every event of a solve as a line of text
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: Vec<String>,
+    }
+
+    impl SolveObserver for RecordingObserver {
+        fn on_step_start(&mut self, pass: usize, _deduction: &Deduction) -> ControlFlow<()> {
+            self.events.push(format!("pass {}", pass));
+            ControlFlow::Continue(())
+        }
+
+        fn on_premise_changed(
+            &mut self,
+            label: &str,
+            before: &Premise,
+            after: &Premise,
+            action: StepAction,
+        ) -> ControlFlow<()> {
+            self.events
+                .push(format!("{}: {} => {} ({:?})", label, before, after, action));
+            ControlFlow::Continue(())
+        }
+
+        fn on_value_derived(
+            &mut self,
+            proposition: char,
+            value: bool,
+            source: &str,
+        ) -> ControlFlow<()> {
+            self.events
+                .push(format!("{} = {} from {}", proposition, value, source));
+            ControlFlow::Continue(())
+        }
+    }
+
+    // Solves the Deduction the way it was done before the premises were watched, checking every
+    // premise for a known proposition on every pass
+    fn solve_naively(deduction: &mut Deduction, observer: &mut RecordingObserver) {
+        for (proposition, value, label) in deduction.derive_values() {
+            let _ = observer.on_value_derived(proposition, value, &label);
+        }
+
+        for pass in 1.. {
+            let _ = observer.on_step_start(pass, deduction);
+            let stack_size = deduction.premise_stack.len();
+
+            deduction.premise_stack.retain(|entry| {
+                entry.premise.get_value_if_root_proposition().is_none()
+                    && entry.premise.get_nodes() != &[PremiseNode::TruthValue(true)]
+            });
+
+            let mut changed = deduction.premise_stack.len() != stack_size;
+
+            for entry in &mut deduction.premise_stack {
+                let before = Arc::clone(&entry.premise);
+
+                let action = if before.can_substitute(&deduction.proposition_values) {
+                    entry.premise = Arc::new(before.substituted(&deduction.proposition_values));
+                    StepAction::Substitute
+                } else if let Some((nodes, rule)) = before.next_step() {
+                    entry.premise = Arc::new(before.with_nodes(nodes));
+                    StepAction::Evaluate(rule)
+                } else {
+                    continue;
+                };
+
+                let _ = observer.on_premise_changed(
+                    &entry.get_label(),
+                    &before,
+                    &entry.premise,
+                    action,
+                );
+                changed = true;
+            }
+
+            for (proposition, value, label) in deduction.derive_values() {
+                let _ = observer.on_value_derived(proposition, value, &label);
+            }
+
+            if !changed {
+                return;
+            }
+        }
+    }
+
+    // Generates a premise over the propositions a to f from the seed
+    fn generate(seed: &mut u64, depth: usize) -> Premise {
+        *seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let choice = (*seed >> 33) % 7;
+
+        if depth == 0 || choice < 2 {
+            let proposition = Premise::atom((b'a' + (*seed >> 40) as u8 % 6) as char);
+
+            return match choice {
+                0 => !proposition,
+                _ => proposition,
+            };
+        }
+
+        let (left, right) = (generate(seed, depth - 1), generate(seed, depth - 1));
+
+        match choice {
+            2 => !(left & right),
+            3 => left.implies(right),
+            4 => left | right,
+            _ => left & right,
+        }
+    }
+
+    #[test]
+    fn test_from_strs() {
+        let deduction = Deduction::from_strs(vec!["p > q", "p"]).unwrap();
+        assert_eq!(deduction.get_labeled_premises().len(), 2);
+
+        assert_eq!(
+            Deduction::from_strs(vec!["p > q", "p # q", "(q"]).err(),
+            Some(ParseError::InvalidCharacter {
+                character: '#',
+                position: 2
+            })
+        );
+        assert_eq!(
+            Deduction::from_strs(vec!["p > q", "(q"]).err(),
+            Some(ParseError::UnbalancedParentheses { position: 0 })
+        );
+        assert_eq!(
+            Deduction::from_strs(vec!["p > q", " "]).err(),
+            Some(ParseError::EmptyPremise)
+        );
+    }
+
+    #[test]
+    fn test_watches_match_naive_solve() {
+        let mut seed = 11;
+
+        for _ in 0..300 {
+            // A few root propositions give the solve something to substitute
+            let mut premises: Vec<Premise> = (0..4).map(|_| generate(&mut seed, 4)).collect();
+            premises.extend((0..2).map(|_| generate(&mut seed, 0)));
+
+            let mut watched = Deduction::from_premises(premises.clone());
+            let mut watched_events = RecordingObserver::default();
+            watched.solve_with_observer(&mut watched_events);
+
+            let mut naive = Deduction::from_premises(premises);
+            let mut naive_events = RecordingObserver::default();
+            solve_naively(&mut naive, &mut naive_events);
+
+            assert_eq!(watched_events.events, naive_events.events);
+            assert_eq!(watched.to_string(), naive.to_string());
+            assert_eq!(
+                watched.get_values().to_string(),
+                naive.get_values().to_string()
+            );
+            assert_eq!(watched.is_contradictory(), naive.is_contradictory());
+        }
+    }
+
+    #[test]
+    fn test_snapshots_share_premises() {
+        let premises = vec![
+            "(m & !b) > j",
+            "(f | s) > m",
+            "b > t",
+            "f > !t",
+            "f",
+            "x | y",
+        ];
+
+        let mut eager = EagerObserver::default();
+        Deduction::from_strs(premises.clone())
+            .unwrap()
+            .solve_with_observer(&mut eager);
+        let history = Deduction::from_strs(premises).unwrap().solve();
+
+        // The shared snapshots hold the same premises as copying them at every pass
+        let snapshots: Vec<Vec<(String, Premise)>> = history
+            .get_deductions()
+            .iter()
+            .map(|deduction| {
+                deduction
+                    .get_labeled_premises()
+                    .into_iter()
+                    .map(|(label, premise)| (label, premise.clone()))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(snapshots, eager.stacks);
+
+        // "x ∨ y" never changes, so every snapshot shares the one copy of it
+        let unchanged: Vec<&Arc<Premise>> = history
+            .get_deductions()
+            .iter()
+            .map(|deduction| {
+                let entry = deduction
+                    .premise_stack
+                    .iter()
+                    .find(|entry| entry.number == 6);
+                &entry.unwrap().premise
+            })
+            .collect();
+        assert!(unchanged.len() > 2);
+        assert!(unchanged
+            .windows(2)
+            .all(|pair| Arc::ptr_eq(pair[0], pair[1])));
+
+        // "b → t" is replaced when t is substituted, so the snapshots before and after differ
+        let implication: Vec<&Arc<Premise>> = history
+            .get_deductions()
+            .iter()
+            .filter_map(|deduction| {
+                deduction
+                    .premise_stack
+                    .iter()
+                    .find(|entry| entry.number == 3)
+            })
+            .map(|entry| &entry.premise)
+            .collect();
+        assert!(implication
+            .windows(2)
+            .any(|pair| !Arc::ptr_eq(pair[0], pair[1])));
+    }
+
+    #[test]
+    fn test_snapshots_share_unchanged_premises() {
+        let proposition = |i: u32| Premise::atom(char::from_u32(0x100 + i).unwrap());
+
+        // A chain "x0", "x0 → x1", ..., "x9 → x10" changes a premise or two in each pass, while
+        // the other 89 premises, such as "y0 ∨ z0", never change
+        let mut premises = vec![proposition(0)];
+        premises.extend((1..=10).map(|i| proposition(i - 1).implies(proposition(i))));
+        premises.extend((0..89).map(|i| proposition(100 + 2 * i) | proposition(101 + 2 * i)));
+        assert_eq!(premises.len(), 100);
+
+        let mut deduction = Deduction::from_premises(premises);
+        let history = deduction.solve();
+        let snapshots = history.get_deductions();
+        assert!(snapshots.len() > 10);
+
+        // Each unchanged premise is one Arc held by every snapshot and the solved Deduction
+        for number in 12..=100 {
+            let copies: Vec<&Arc<Premise>> = snapshots
+                .iter()
+                .chain([&deduction])
+                .map(|snapshot| {
+                    let entry = snapshot
+                        .premise_stack
+                        .iter()
+                        .find(|entry| entry.number == number);
+                    &entry.unwrap().premise
+                })
+                .collect();
+
+            assert!(copies.windows(2).all(|pair| Arc::ptr_eq(pair[0], pair[1])));
+            assert_eq!(Arc::strong_count(copies[0]), copies.len());
+        }
+
+        // Only the premises of the chain were ever copied
+        let mut distinct: Vec<*const Premise> = snapshots
+            .iter()
+            .flat_map(|snapshot| snapshot.premise_stack.iter())
+            .map(|entry| Arc::as_ptr(&entry.premise))
+            .collect();
+        distinct.sort();
+        distinct.dedup();
+        assert!(distinct.len() < 100 + 3 * snapshots.len());
+    }
+
+    #[test]
+    fn test_value_map_is_deterministic() {
+        // The same argument with its premises in a different order, one holding a proposition
+        // which is not a lowercase letter
+        let build = |premises: Vec<&str>| {
+            let mut deduction = Deduction::from_premises(
+                premises
+                    .into_iter()
+                    .map(|premise| Premise::parse_str(premise).unwrap())
+                    .chain([Premise::atom('A').implies(Premise::atom('z'))])
+                    .collect(),
+            );
+            deduction.solve();
+            deduction
+        };
+
+        let first = build(vec!["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"]);
+        let second = build(vec!["f", "f > !t", "b > t", "(f | s) > m", "(m & !b) > j"]);
+
+        let first_json = first.get_values().to_json().to_string();
+        assert_eq!(first_json, second.get_values().to_json().to_string());
+        assert!(first_json.starts_with(r#"{"A":null,"b":false,"f":true,"j":true"#));
+        assert_eq!(
+            first.get_values().to_string(),
+            second.get_values().to_string()
+        );
+
+        let propositions: Vec<char> = first.get_values().iter().map(|(p, _)| p).collect();
+        assert_eq!(propositions, ['A', 'b', 'f', 'j', 'm', 's', 't', 'z']);
+    }
+
+    #[test]
+    fn test_value_map_backends() {
+        let mut seed: u64 = 11;
+        let mut next = |bound: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % bound
+        };
+
+        for round in 0..50 {
+            let (mut letters, mut map) = (ValueMap::default(), ValueMap::with_map());
+
+            for _ in 0..40 {
+                // Later rounds use propositions the array cannot hold, so the values move into a
+                // BTreeMap partway through
+                let proposition = match next(20) {
+                    0 if round >= 25 => ['A', 'é', '1'][next(3) as usize],
+                    _ => (b'a' + next(8) as u8) as char,
+                };
+                let value = [None, Some(true), Some(false)][next(3) as usize];
+
+                match next(3) {
+                    0 => {
+                        let premise = Premise::atom(proposition).implies(Premise::atom('x'));
+                        letters.add_propositions(&premise);
+                        map.add_propositions(&premise);
+                    }
+                    _ => {
+                        letters.set_value(proposition, value);
+                        map.set_value(proposition, value);
+                    }
+                }
+
+                for proposition in ['a', 'd', 'h', 'x', 'z', 'A', 'é', '1'] {
+                    assert_eq!(letters.get_value(proposition), map.get_value(proposition));
+                    assert_eq!(letters.contains(proposition), map.contains(proposition));
+                }
+
+                // Both iterate in the order of the propositions
+                let (first, second): (Vec<_>, Vec<_>) =
+                    (letters.iter().collect(), map.iter().collect());
+                assert_eq!(first, second);
+                assert!(first.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+                assert_eq!(letters.to_string(), map.to_string());
+                assert_eq!(letters.render_terse(), map.render_terse());
+                assert_eq!(letters.to_json(), map.to_json());
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_deeply_nested() {
+        // Builds "b ∨ (a ∧ (b ∨ (a ∧ ... (p ∨ q))))" with 50,000 levels around "p ∨ q"
+        let mut deep = Premise::parse_str("p | q").unwrap();
+        for level in 1..=50_000 {
+            let (proposition, operator) = match level % 2 {
+                1 => ('a', Operator::And),
+                _ => ('b', Operator::Or),
+            };
+
+            deep = Premise::new(vec![
+                PremiseNode::Proposition(proposition),
+                PremiseNode::Operator(operator),
+                PremiseNode::Subpremise(deep),
+            ]);
+        }
+
+        // A small stack shows neither solving nor displaying recurses once per level
+        std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(move || {
+                let mut deduction = Deduction::from_premises(vec![deep, Premise::atom('p')]);
+                deduction.solve();
+
+                // "p ∨ q" becomes TRUE and "a ∧ TRUE" becomes a, which leaves "b ∨ a" at the
+                // bottom
+                let premises = deduction.get_labeled_premises();
+                assert_eq!(premises.len(), 1);
+
+                let solved = premises[0].1;
+                assert_eq!(solved.depth(), 49_999);
+
+                let displayed = solved.to_string();
+                assert!(displayed.starts_with("b ∨ (a ∧ (b ∨ (a ∧ ("));
+                assert!(displayed.ends_with(&format!("(b ∨ a{}", ")".repeat(49_998))));
+
+                // The displayed premise parses back to the same one
+                assert!(&Premise::parse_str(&displayed).unwrap() == solved);
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
 }
@@ -0,0 +1,476 @@
+use std::collections::BTreeSet;
+
+use crate::errors::SatError;
+use crate::normal_form::DEFAULT_MAX_CLAUSES;
+use crate::sat::{ClauseSet, SatBackend, SatResult};
+use crate::truth_table::{Evaluator, Formula};
+use crate::Deduction;
+use crate::Premise;
+use crate::ValueMap;
+
+// The result of checking whether the premises of a Deduction entail a goal
+#[derive(Debug, Clone)]
+pub enum Entailment {
+    // The goal is true whenever every premise is true
+    Proved,
+    // The goal can be false while every premise is true, as in the counterexample,
+    // but it can also be true
+    NotProvable { counterexample: ValueMap },
+    // The goal is false whenever every premise is true, as in the counterexample
+    Contradicted { counterexample: ValueMap },
+}
+
+// The result of checking whether every premise of a Deduction can be true at once
+#[derive(Debug, Clone)]
+pub enum Consistency {
+    // The model makes every premise true
+    Consistent { model: ValueMap },
+    // The premises in the core cannot all be true, and no premise can be left out of the core
+    // without that changing
+    Inconsistent { core: Vec<CorePremise> },
+}
+
+// Stores a premise which is part of an inconsistent core, along with its label and the line
+// it was read from if it was loaded from a file
+#[derive(Debug, Clone)]
+pub struct CorePremise {
+    pub label: String,
+    pub line: Option<usize>,
+    pub premise: Premise,
+}
+
+impl Deduction {
+    // Checks whether the premises entail the goal by trying every assignment of the propositions
+    // whose values are not yet known, so it also finds goals the solver cannot derive step by step
+    // Inconsistent premises entail every goal
+    // A goal which is true under every assignment of its own propositions is proved without
+    // going through the premises
+    pub fn check_entailment(&self, goal: &Premise) -> Entailment {
+        if goal.is_tautology() {
+            return Entailment::Proved;
+        }
+
+        let negated_goal = goal.negated();
+        let premises = self.get_premises();
+
+        let counterexample =
+            self.find_model_of([premises.clone(), vec![&negated_goal]].concat(), Some(goal));
+
+        match counterexample {
+            None => Entailment::Proved,
+            Some(counterexample) => {
+                match self.find_model_of([premises, vec![goal]].concat(), None) {
+                    Some(_) => Entailment::NotProvable { counterexample },
+                    None => Entailment::Contradicted { counterexample },
+                }
+            }
+        }
+    }
+
+    // Finds an assignment of every proposition which makes all the premises true but the goal
+    // false, or None if the premises entail the goal
+    // Without a backend this tries every assignment like check_entailment, and with one the
+    // premises with the known values substituted, along with the negated goal, are converted to
+    // clauses for the backend to solve, failing if they would need more than DEFAULT_MAX_CLAUSES
+    pub fn find_counterexample(
+        &self,
+        goal: &Premise,
+        backend: Option<&dyn SatBackend>,
+    ) -> Result<Option<ValueMap>, SatError> {
+        let Some(backend) = backend else {
+            return Ok(match self.check_entailment(goal) {
+                Entailment::Proved => None,
+                Entailment::NotProvable { counterexample }
+                | Entailment::Contradicted { counterexample } => Some(counterexample),
+            });
+        };
+
+        let values = self.get_values();
+        let mut premises: Vec<Premise> = self
+            .get_premises()
+            .into_iter()
+            .map(|premise| premise.substituted(values))
+            .collect();
+        premises.push(goal.negated().substituted(values));
+
+        let premises: Vec<&Premise> = premises.iter().collect();
+        let clauses = ClauseSet::from_premises(&premises, DEFAULT_MAX_CLAUSES)?;
+
+        match backend.solve(&clauses) {
+            SatResult::Satisfiable(model) => Ok(Some(
+                self.complete_model(
+                    Some(goal),
+                    model
+                        .iter()
+                        .filter_map(|(proposition, value)| Some((proposition, value?))),
+                ),
+            )),
+            SatResult::Unsatisfiable => Ok(None),
+            SatResult::Failed(error) => Err(error),
+        }
+    }
+
+    // Checks whether the premises entail the goal, with the backend if one is given
+    pub fn is_valid(
+        &self,
+        goal: &Premise,
+        backend: Option<&dyn SatBackend>,
+    ) -> Result<bool, SatError> {
+        Ok(self.find_counterexample(goal, backend)?.is_none())
+    }
+
+    // Finds an assignment of every proposition which makes all the premises true,
+    // or None if the premises are inconsistent
+    pub fn find_model(&self) -> Option<ValueMap> {
+        self.find_model_of(self.get_premises(), None)
+    }
+
+    // Checks whether the premises are consistent, finding a model if they are and otherwise a core
+    // of premises which conflict, found by leaving out each premise in turn and keeping it only if
+    // the rest no longer conflict without it
+    // This should be done before solving, as the values found by solving inconsistent premises
+    // depend on the order the premises were used in
+    pub fn check_consistency(&self) -> Consistency {
+        if let Some(model) = self.find_model() {
+            return Consistency::Consistent { model };
+        }
+
+        let mut core = self.get_labeled_premises();
+        let mut i = 0;
+
+        while i < core.len() {
+            let mut without: Vec<&Premise> = core.iter().map(|(_, premise)| *premise).collect();
+            without.remove(i);
+
+            if self.find_model_of(without, None).is_some() {
+                i += 1;
+            } else {
+                core.remove(i);
+            }
+        }
+
+        Consistency::Inconsistent {
+            core: core
+                .into_iter()
+                .map(|(label, premise)| CorePremise {
+                    line: self.get_premise_line(&label),
+                    label,
+                    premise: premise.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    // Returns every premise left on the stack
+    fn get_premises(&self) -> Vec<&Premise> {
+        self.get_labeled_premises()
+            .into_iter()
+            .map(|(_, premise)| premise)
+            .collect()
+    }
+
+    // Finds the first assignment which agrees with the known values and makes all the given
+    // premises true, counting up from every unknown proposition being FALSE
+    // Only the unknown propositions the premises mention are assigned in turn, and every other
+    // proposition of the Deduction, along with those of the extra premise, is FALSE in the model,
+    // as it would be in the first model found by assigning them all
+    fn find_model_of(&self, premises: Vec<&Premise>, extra: Option<&Premise>) -> Option<ValueMap> {
+        let mut mentioned = BTreeSet::new();
+        for premise in &premises {
+            mentioned.append(&mut premise.propositions());
+        }
+
+        let known_values = self.get_values();
+        let (unknown, known): (Vec<char>, Vec<char>) = mentioned
+            .into_iter()
+            .partition(|proposition| known_values.get_value(*proposition).is_none());
+        let known: Vec<(char, bool)> = known
+            .into_iter()
+            .filter_map(|proposition| Some((proposition, known_values.get_value(proposition)?)))
+            .collect();
+
+        let assignment = find_assignment(&premises, &unknown, &known, |values| {
+            (0..premises.len()).all(|i| values(i) == Some(true))
+        })?;
+
+        Some(self.complete_model(extra, unknown.into_iter().zip(assignment)))
+    }
+
+    // Creates a model from the known values and the assignment, where every other proposition of
+    // the Deduction, along with those of the extra premise, is FALSE
+    fn complete_model(
+        &self,
+        extra: Option<&Premise>,
+        assignment: impl IntoIterator<Item = (char, bool)>,
+    ) -> ValueMap {
+        let mut model = self.get_values().clone();
+        if let Some(extra) = extra {
+            model.add_propositions(extra);
+        }
+
+        let unassigned: Vec<char> = model
+            .iter()
+            .filter(|(_, value)| value.is_none())
+            .map(|(proposition, _)| proposition)
+            .collect();
+        for proposition in unassigned {
+            model.set_value(proposition, Some(false));
+        }
+
+        for (proposition, value) in assignment {
+            model.set_value(proposition, Some(value));
+        }
+
+        model
+    }
+}
+
+impl Premise {
+    // Finds an assignment of the propositions of both premises under which they have different
+    // values, or None if they are equivalent
+    pub fn find_difference(&self, other: &Premise) -> Option<ValueMap> {
+        find_difference(self, other)
+    }
+
+    // Checks whether the premises have the same value under every assignment of their propositions
+    pub fn is_equivalent(&self, other: &Premise) -> bool {
+        self.find_difference(other).is_none()
+    }
+}
+
+// Finds an assignment of the propositions of both formulas under which they have different
+// values, stopping at the first one
+pub(crate) fn find_difference<F: Formula>(first: &F, second: &F) -> Option<ValueMap> {
+    let mut propositions = first.propositions();
+    propositions.append(&mut second.propositions());
+    let propositions: Vec<char> = propositions.into_iter().collect();
+
+    let assignment = find_assignment(&[first, second], &propositions, &[], |values| {
+        values(0) != values(1)
+    })?;
+
+    let mut values = ValueMap::default();
+    for (proposition, value) in propositions.into_iter().zip(assignment) {
+        values.set_value(proposition, Some(value));
+    }
+
+    Some(values)
+}
+
+// Returns the values of the first assignment of the unknown propositions for which the check
+// holds, trying them in order by counting up from every one being FALSE with the first one
+// changing fastest
+// The check is given a function which evaluates the formula at an index under the assignment, so
+// it only evaluates the formulas it needs, and the known propositions keep their values throughout
+fn find_assignment<F: Formula>(
+    formulas: &[&F],
+    unknown: &[char],
+    known: &[(char, bool)],
+    mut check: impl FnMut(&mut dyn FnMut(usize) -> Option<bool>) -> bool,
+) -> Option<Vec<bool>> {
+    let propositions: Vec<char> = unknown
+        .iter()
+        .copied()
+        .chain(known.iter().map(|(proposition, _)| *proposition))
+        .collect();
+    let mut evaluators: Vec<Evaluator> = formulas
+        .iter()
+        .map(|formula| formula.evaluator(&propositions))
+        .collect();
+
+    let mut values: Vec<bool> = unknown
+        .iter()
+        .map(|_| false)
+        .chain(known.iter().map(|(_, value)| *value))
+        .collect();
+
+    for assignment in 0..1u64 << unknown.len() {
+        for (i, value) in values[..unknown.len()].iter_mut().enumerate() {
+            *value = assignment & (1 << i) != 0;
+        }
+
+        if check(&mut |i| evaluators[i](&values)) {
+            return Some(values[..unknown.len()].to_vec());
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sat::Dpll;
+    use crate::truth_table::tests::Counting;
+
+    // Creates a Deduction from the main.rs example
+    fn example() -> Deduction {
+        Deduction::from_strs(vec!["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"]).unwrap()
+    }
+
+    #[test]
+    fn test_check_entailment() {
+        assert!(matches!(
+            example().check_entailment(&Premise::parse_str("j & m").unwrap()),
+            Entailment::Proved
+        ));
+
+        match example().check_entailment(&Premise::parse_str("s").unwrap()) {
+            Entailment::NotProvable { counterexample } => {
+                assert_eq!(counterexample.get_value('s'), Some(false));
+                assert_eq!(counterexample.get_value('f'), Some(true));
+            }
+            other => panic!("Expected NotProvable, found {:?}", other),
+        }
+
+        assert!(matches!(
+            example().check_entailment(&Premise::parse_str("b | t").unwrap()),
+            Entailment::Contradicted { .. }
+        ));
+
+        // The goal may use a proposition which is not in any premise
+        assert!(matches!(
+            example().check_entailment(&Premise::parse_str("z > z").unwrap()),
+            Entailment::Proved
+        ));
+    }
+
+    #[test]
+    fn test_check_entailment_by_cases() {
+        // The solver cannot derive r from these, but every model of the premises makes it true
+        let mut deduction = Deduction::from_strs(vec!["p | q", "p > r", "q > r"]).unwrap();
+        deduction.solve();
+
+        assert_eq!(deduction.get_values().get_value('r'), None);
+        assert!(matches!(
+            deduction.check_entailment(&Premise::parse_str("r").unwrap()),
+            Entailment::Proved
+        ));
+    }
+
+    #[test]
+    fn test_find_counterexample() {
+        let deduction = example();
+
+        for goal in ["j & m", "s", "b | t", "z > z", "!s | t"] {
+            let goal = Premise::parse_str(goal).unwrap();
+            let searched = deduction.find_counterexample(&goal, None).unwrap();
+            let solved = deduction.find_counterexample(&goal, Some(&Dpll)).unwrap();
+
+            assert_eq!(searched.is_some(), solved.is_some(), "{}", goal);
+            if let Some(counterexample) = solved {
+                for premise in deduction.get_premises() {
+                    assert_eq!(premise.evaluate(&counterexample), Some(true));
+                }
+                assert_eq!(goal.evaluate(&counterexample), Some(false));
+            }
+        }
+
+        // The known values are used, and inconsistent premises entail every goal
+        let mut deduction = Deduction::from_strs(vec!["p > q", "p"]).unwrap();
+        deduction.solve();
+        assert!(deduction
+            .is_valid(&Premise::parse_str("q").unwrap(), Some(&Dpll))
+            .unwrap());
+
+        let deduction = Deduction::from_strs(vec!["p", "!p"]).unwrap();
+        assert!(deduction
+            .is_valid(&Premise::parse_str("q").unwrap(), Some(&Dpll))
+            .unwrap());
+        assert!(deduction
+            .is_valid(&Premise::parse_str("q").unwrap(), None)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_check_consistency() {
+        assert!(matches!(
+            example().check_consistency(),
+            Consistency::Consistent { .. }
+        ));
+
+        // Only the first and third premises conflict
+        let deduction = Deduction::from_strs(vec!["p & q", "r > s", "!q", "r", "q | r"]).unwrap();
+
+        match deduction.check_consistency() {
+            Consistency::Inconsistent { core } => {
+                let labels: Vec<&str> = core.iter().map(|premise| premise.label.as_str()).collect();
+                assert_eq!(labels, ["P1", "P3"]);
+                assert_eq!(core[1].premise, Premise::parse_str("!q").unwrap());
+                assert_eq!(core[1].line, None);
+            }
+            other => panic!("Expected Inconsistent, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_find_difference() {
+        // De Morgan's law
+        assert!(Premise::parse_str("!(p & q)")
+            .unwrap()
+            .is_equivalent(&Premise::parse_str("!p | !q").unwrap()));
+        assert!(Premise::parse_str("p > q")
+            .unwrap()
+            .is_equivalent(&Premise::parse_str("!q > !p").unwrap()));
+
+        let difference = Premise::parse_str("p > q")
+            .unwrap()
+            .find_difference(&Premise::parse_str("q > p").unwrap())
+            .unwrap();
+        assert_ne!(difference.get_value('p'), difference.get_value('q'));
+
+        // A proposition only one side mentions still has to be assigned
+        assert!(!Premise::parse_str("p")
+            .unwrap()
+            .is_equivalent(&Premise::parse_str("p & r").unwrap()));
+    }
+
+    #[test]
+    fn test_find_difference_stops_early() {
+        // The formulas first differ when only d is TRUE, which is the ninth assignment tried
+        let (first, second) = (Counting::new("a | b | c"), Counting::new("a | b | c | d"));
+        let difference = find_difference(&first, &second).unwrap();
+        assert_eq!(difference.get_value('d'), Some(true));
+        assert_eq!(difference.get_value('a'), Some(false));
+        assert_eq!((first.get_evaluations(), second.get_evaluations()), (9, 9));
+
+        // Equivalent formulas are still evaluated under every assignment
+        let (first, second) = (Counting::new("a > b"), Counting::new("!b > !a"));
+        assert!(find_difference(&first, &second).is_none());
+        assert_eq!((first.get_evaluations(), second.get_evaluations()), (4, 4));
+    }
+
+    #[test]
+    fn test_check_entailment_shortcuts() {
+        // A tautological goal is proved even by inconsistent premises, without enumerating them
+        let deduction =
+            Deduction::from_strs(vec!["p & !p", "a | b | c | d | e | f | g | h"]).unwrap();
+        assert!(matches!(
+            deduction.check_entailment(&Premise::parse_str("q | !q").unwrap()),
+            Entailment::Proved
+        ));
+
+        // Propositions only the goal mentions are FALSE in the counterexample unless the search
+        // assigns them otherwise, as when every proposition was enumerated
+        match example().check_entailment(&Premise::parse_str("s | z").unwrap()) {
+            Entailment::NotProvable { counterexample } => {
+                assert_eq!(counterexample.get_value('s'), Some(false));
+                assert_eq!(counterexample.get_value('z'), Some(false));
+                assert_eq!(counterexample.get_value('j'), Some(true));
+            }
+            other => panic!("Expected NotProvable, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_find_model() {
+        let model = example().find_model().unwrap();
+        assert_eq!(model.get_value('j'), Some(true));
+        assert_eq!(model.get_value('t'), Some(false));
+
+        assert!(Deduction::from_strs(vec!["p > q", "p", "!q"])
+            .unwrap()
+            .find_model()
+            .is_none());
+    }
+}
@@ -0,0 +1,784 @@
+use std::fmt::{Display, Formatter, Result};
+use std::io;
+use std::path::PathBuf;
+
+use crate::normal_form::{NormalFormError, TooManyClauses};
+use crate::observer::SolveLimit;
+use crate::proof::InferenceRule;
+use crate::sexpr::SexprError;
+use crate::truth_table::TooManyVariables;
+
+// Represents a problem found while parsing a premise string
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ParseError {
+    // A character which is not part of the premise syntax, along with its byte offset in the string
+    InvalidCharacter { character: char, position: usize },
+    // The operators and operands do not form a valid premise, such as "a ∧ ∨ b"
+    InvalidStructure,
+    // A parenthesis is never closed, or is closed without being opened, along with its byte offset
+    UnbalancedParentheses { position: usize },
+    // The string holds nothing but whitespace
+    EmptyPremise,
+}
+
+impl Display for ParseError {
+    // Displays the error as a message for the user
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ParseError::InvalidCharacter {
+                character,
+                position,
+            } => write!(
+                f,
+                "Invalid character in premise: '{}' at position {}",
+                character, position
+            ),
+            ParseError::InvalidStructure => write!(f, "Invalid premise structure"),
+            ParseError::UnbalancedParentheses { position } => {
+                write!(
+                    f,
+                    "Unbalanced parenthesis in premise at position {}",
+                    position
+                )
+            }
+            ParseError::EmptyPremise => write!(f, "Empty premise"),
+        }
+    }
+}
+
+impl ParseError {
+    // Returns the byte offset in the string of the character which caused the error, if there is
+    // one, so it can be pointed out to the user
+    pub fn get_position(&self) -> Option<usize> {
+        match self {
+            ParseError::InvalidCharacter { position, .. }
+            | ParseError::UnbalancedParentheses { position } => Some(*position),
+            ParseError::InvalidStructure | ParseError::EmptyPremise => None,
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Represents why a list of nodes does not form a valid premise
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum StructureError {
+    // There are no nodes
+    Empty,
+    // The node at the index cannot follow the one before it, such as an operator after another
+    UnexpectedNode {
+        index: usize,
+    },
+    // The nodes end with an operator or negation, which has no operand after it
+    MissingOperand,
+    // The subpremise at the index is not valid itself
+    InvalidSubpremise {
+        index: usize,
+        error: Box<StructureError>,
+    },
+}
+
+impl Display for StructureError {
+    // Displays the error as a message for the user
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            StructureError::Empty => write!(f, "The premise has no nodes"),
+            StructureError::UnexpectedNode { index } => {
+                write!(f, "Unexpected node at index {} of the premise", index)
+            }
+            StructureError::MissingOperand => {
+                write!(f, "The premise ends without an operand")
+            }
+            StructureError::InvalidSubpremise { index, error } => {
+                write!(f, "Invalid subpremise at index {}: {}", index, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StructureError {}
+
+// Represents why the value of a premise could not be found
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum EvalError {
+    // The premise uses a proposition which has no entry in the values at all
+    MissingProposition(char),
+    // The value depends on propositions whose values are unknown, in alphabetical order
+    Undetermined { unknown: Vec<char> },
+    // The premise does not form a valid formula, so it never reduces to a truth value
+    InvalidStructure,
+}
+
+impl Display for EvalError {
+    // Displays the error as a message for the user
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            EvalError::MissingProposition(proposition) => write!(
+                f,
+                "The proposition '{}' is not in the values the premise was evaluated with",
+                proposition
+            ),
+            EvalError::Undetermined { unknown } => {
+                let unknown: Vec<String> = unknown.iter().map(char::to_string).collect();
+
+                write!(
+                    f,
+                    "The value cannot be determined without knowing {}",
+                    unknown.join(", ")
+                )
+            }
+            EvalError::InvalidStructure => {
+                write!(f, "Invalid premise structure, so it cannot be evaluated")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+// Represents why a rule could not be applied
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum RuleError {
+    // The line number is not part of the proof
+    MissingLine(usize),
+    // The rule was given the wrong number of lines
+    WrongLineCount { rule: InferenceRule, found: usize },
+    // The rule needs to be told which formula to derive
+    MissingFormula(InferenceRule),
+    // The lines do not have the shape the rule needs, along with an explanation such as
+    // "line 2 is q, which is not the antecedent p of line 1"
+    DoesNotApply { rule: InferenceRule, reason: String },
+}
+
+impl Display for RuleError {
+    // Displays the error as a message for the user
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            RuleError::MissingLine(line) => write!(f, "There is no line {}", line),
+            RuleError::WrongLineCount { rule, found } => write!(
+                f,
+                "{} is applied to {} line{}, but {} {} given",
+                rule,
+                rule.get_num_lines(),
+                if rule.get_num_lines() == 1 { "" } else { "s" },
+                found,
+                if *found == 1 { "was" } else { "were" }
+            ),
+            RuleError::MissingFormula(rule) => write!(
+                f,
+                "{} needs the formula to derive, such as '{} 1 p | q'",
+                rule,
+                rule.get_short_name()
+            ),
+            RuleError::DoesNotApply { rule, reason } => {
+                write!(f, "{} does not apply, as {}", rule, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+// Represents a problem found while loading a Deduction from a file
+#[derive(Debug)]
+pub enum LoadError {
+    // The file could not be opened or read
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    // A line of the file could not be parsed as a premise, numbered from 1
+    Parse {
+        path: PathBuf,
+        line: usize,
+        error: ParseError,
+    },
+    // A second conclusion was found, numbered from 1
+    DuplicateConclusion {
+        path: PathBuf,
+        line: usize,
+    },
+    // An "assume:" line does not give a proposition and a value, numbered from 1
+    InvalidAssumption {
+        path: PathBuf,
+        line: usize,
+        assumption: String,
+    },
+    // An "assume:" line gives a proposition the opposite of the value it was already assumed to
+    // have, numbered from 1
+    ConflictingAssumption {
+        path: PathBuf,
+        line: usize,
+        proposition: char,
+    },
+    // The input ended without any premises
+    Empty {
+        path: PathBuf,
+    },
+    // The input is not valid JSON
+    Json {
+        path: PathBuf,
+        error: serde_json::Error,
+    },
+    // A field of a JSON argument is missing, unknown or has a value which is not allowed, where
+    // the field is named by its path, such as "premises[2].label"
+    Schema {
+        path: PathBuf,
+        field: String,
+        message: String,
+    },
+    // A formula in a field of a JSON argument could not be parsed
+    FieldParse {
+        path: PathBuf,
+        field: String,
+        error: ParseError,
+    },
+}
+
+impl LoadError {
+    // Returns the line of the input the error was found on, numbered from 1, if there is one
+    pub fn get_line(&self) -> Option<usize> {
+        match self {
+            LoadError::Parse { line, .. }
+            | LoadError::DuplicateConclusion { line, .. }
+            | LoadError::InvalidAssumption { line, .. }
+            | LoadError::ConflictingAssumption { line, .. } => Some(*line),
+            _ => None,
+        }
+    }
+}
+
+impl Display for LoadError {
+    // Displays the error as a message for the user, naming the file and line
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            LoadError::Io { path, error } => {
+                write!(f, "Could not read '{}': {}", path.display(), error)
+            }
+            LoadError::Parse { path, line, error } => {
+                write!(f, "'{}', line {}: {}", path.display(), line, error)
+            }
+            LoadError::DuplicateConclusion { path, line } => write!(
+                f,
+                "'{}', line {}: Only one conclusion may be given",
+                path.display(),
+                line
+            ),
+            LoadError::InvalidAssumption {
+                path,
+                line,
+                assumption,
+            } => write!(
+                f,
+                "'{}', line {}: Invalid assumption '{}', expected a proposition and its value such as 'p=true'",
+                path.display(),
+                line,
+                assumption
+            ),
+            LoadError::ConflictingAssumption {
+                path,
+                line,
+                proposition,
+            } => write!(
+                f,
+                "'{}', line {}: {} is already assumed to have the other value",
+                path.display(),
+                line,
+                proposition
+            ),
+            LoadError::Empty { path } => write!(f, "'{}' contains no premises", path.display()),
+            LoadError::Json { path, error } => {
+                write!(f, "'{}' is not valid JSON: {}", path.display(), error)
+            }
+            LoadError::Schema {
+                path,
+                field,
+                message,
+            } => write!(f, "'{}', {}: {}", path.display(), field, message),
+            LoadError::FieldParse { path, field, error } => {
+                write!(f, "'{}', {}: {}", path.display(), field, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io { error, .. } => Some(error),
+            LoadError::Parse { error, .. } => Some(error),
+            LoadError::Json { error, .. } => Some(error),
+            LoadError::FieldParse { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+// Represents why a DeductionBuilder could not build a Deduction
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum BuildError {
+    // A premise could not be parsed, numbered from 1 in the order it was added
+    Premise { number: usize, error: ParseError },
+    // The conclusion could not be parsed
+    Conclusion(ParseError),
+}
+
+impl Display for BuildError {
+    // Displays the error as a message for the user, naming the premise or conclusion
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            BuildError::Premise { number, error } => write!(f, "Premise P{}: {}", number, error),
+            BuildError::Conclusion(error) => write!(f, "Conclusion: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::Premise { error, .. } => Some(error),
+            BuildError::Conclusion(error) => Some(error),
+        }
+    }
+}
+
+// Represents why a proposition could not be renamed
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum RenameError {
+    // The proposition to rename is not in the Deduction
+    MissingProposition(char),
+    // Both propositions are in the Deduction and their values are known to differ
+    ConflictingValues { from: char, to: char },
+}
+
+impl Display for RenameError {
+    // Displays the error as a message for the user
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            RenameError::MissingProposition(proposition) => write!(
+                f,
+                "The proposition '{}' is not in the Deduction",
+                proposition
+            ),
+            RenameError::ConflictingValues { from, to } => write!(
+                f,
+                "'{}' cannot be renamed to '{}', as their values differ",
+                from, to
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RenameError {}
+
+// Represents why a solve did not run to completion
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum SolveError {
+    // An observer stopped the solve early
+    Aborted,
+    // One of the limits in the SolveOptions stopped the solve early
+    LimitReached(SolveLimit),
+}
+
+impl Display for SolveError {
+    // Displays the error as a message for the user
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            SolveError::Aborted => write!(f, "The solve was stopped early by an observer"),
+            SolveError::LimitReached(SolveLimit::Steps) => {
+                write!(f, "The solve was stopped after reaching its step limit")
+            }
+            SolveError::LimitReached(SolveLimit::Timeout) => {
+                write!(f, "The solve was stopped after running out of time")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+// Represents why solving showed the premises to be inconsistent
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum InconsistencyError {
+    // Two premises give the proposition different values
+    ConflictingValues(char),
+    // The premise with the label was evaluated to FALSE
+    FalsePremise(String),
+}
+
+impl Display for InconsistencyError {
+    // Displays the error as a message for the user, naming the proposition or premise
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            InconsistencyError::ConflictingValues(proposition) => write!(
+                f,
+                "The premises are inconsistent, as they give {} both TRUE and FALSE",
+                proposition
+            ),
+            InconsistencyError::FalsePremise(label) => write!(
+                f,
+                "The premises are inconsistent, as {} was evaluated to FALSE",
+                label
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InconsistencyError {}
+
+// Represents why a SatBackend could not decide whether a set of clauses is satisfiable
+#[derive(Debug)]
+pub enum SatError {
+    // A premise could not be converted to clauses
+    NormalForm(NormalFormError),
+    // The DIMACS file could not be written, or the solver could not be run
+    Io { path: PathBuf, error: io::Error },
+    // The solver's output said neither SAT nor UNSAT
+    NoAnswer,
+    // A line of the solver's output could not be read, numbered from 1
+    MalformedOutput { line: usize, text: String },
+    // The solver said SAT without giving a model
+    MissingModel,
+    // The model the solver gave does not make every clause true
+    InvalidModel,
+    // A clause uses the proposition, which is not one of the propositions of the set
+    UnknownProposition(char),
+}
+
+impl Display for SatError {
+    // Displays the error as a message for the user
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            SatError::NormalForm(error) => write!(f, "{}", error),
+            SatError::Io { path, error } => {
+                write!(
+                    f,
+                    "Could not run the SAT solver '{}': {}",
+                    path.display(),
+                    error
+                )
+            }
+            SatError::NoAnswer => write!(f, "The SAT solver did not answer SAT or UNSAT"),
+            SatError::MalformedOutput { line, text } => write!(
+                f,
+                "Could not read line {} of the SAT solver's output: '{}'",
+                line, text
+            ),
+            SatError::MissingModel => write!(f, "The SAT solver answered SAT without a model"),
+            SatError::InvalidModel => {
+                write!(
+                    f,
+                    "The model given by the SAT solver does not satisfy the clauses"
+                )
+            }
+            SatError::UnknownProposition(proposition) => write!(
+                f,
+                "The clauses use {}, which is not one of their propositions",
+                proposition
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SatError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SatError::NormalForm(error) => Some(error),
+            SatError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<NormalFormError> for SatError {
+    fn from(error: NormalFormError) -> Self {
+        SatError::NormalForm(error)
+    }
+}
+
+// Represents any error the crate can return, so an application can use a single error type
+// The message and source are those of the error it wraps
+#[derive(Debug)]
+pub enum DeductionError {
+    Parse(ParseError),
+    Structure(StructureError),
+    Eval(EvalError),
+    Rule(RuleError),
+    Load(LoadError),
+    Build(BuildError),
+    Solve(SolveError),
+    Inconsistency(InconsistencyError),
+    Rename(RenameError),
+    Sexpr(SexprError),
+    TruthTable(TooManyVariables),
+    NormalForm(NormalFormError),
+    Sat(SatError),
+}
+
+impl Display for DeductionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            DeductionError::Parse(error) => write!(f, "{}", error),
+            DeductionError::Structure(error) => write!(f, "{}", error),
+            DeductionError::Eval(error) => write!(f, "{}", error),
+            DeductionError::Rule(error) => write!(f, "{}", error),
+            DeductionError::Load(error) => write!(f, "{}", error),
+            DeductionError::Build(error) => write!(f, "{}", error),
+            DeductionError::Solve(error) => write!(f, "{}", error),
+            DeductionError::Inconsistency(error) => write!(f, "{}", error),
+            DeductionError::Rename(error) => write!(f, "{}", error),
+            DeductionError::Sexpr(error) => write!(f, "{}", error),
+            DeductionError::TruthTable(error) => write!(f, "{}", error),
+            DeductionError::NormalForm(error) => write!(f, "{}", error),
+            DeductionError::Sat(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for DeductionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DeductionError::Load(error) => error.source(),
+            DeductionError::Build(error) => error.source(),
+            DeductionError::Sat(error) => error.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for DeductionError {
+    fn from(error: ParseError) -> Self {
+        DeductionError::Parse(error)
+    }
+}
+
+impl From<StructureError> for DeductionError {
+    fn from(error: StructureError) -> Self {
+        DeductionError::Structure(error)
+    }
+}
+
+impl From<EvalError> for DeductionError {
+    fn from(error: EvalError) -> Self {
+        DeductionError::Eval(error)
+    }
+}
+
+impl From<RuleError> for DeductionError {
+    fn from(error: RuleError) -> Self {
+        DeductionError::Rule(error)
+    }
+}
+
+impl From<LoadError> for DeductionError {
+    fn from(error: LoadError) -> Self {
+        DeductionError::Load(error)
+    }
+}
+
+impl From<BuildError> for DeductionError {
+    fn from(error: BuildError) -> Self {
+        DeductionError::Build(error)
+    }
+}
+
+impl From<SolveError> for DeductionError {
+    fn from(error: SolveError) -> Self {
+        DeductionError::Solve(error)
+    }
+}
+
+impl From<RenameError> for DeductionError {
+    fn from(error: RenameError) -> Self {
+        DeductionError::Rename(error)
+    }
+}
+
+impl From<SexprError> for DeductionError {
+    fn from(error: SexprError) -> Self {
+        DeductionError::Sexpr(error)
+    }
+}
+
+impl From<TooManyVariables> for DeductionError {
+    fn from(error: TooManyVariables) -> Self {
+        DeductionError::TruthTable(error)
+    }
+}
+
+impl From<NormalFormError> for DeductionError {
+    fn from(error: NormalFormError) -> Self {
+        DeductionError::NormalForm(error)
+    }
+}
+
+impl From<InconsistencyError> for DeductionError {
+    fn from(error: InconsistencyError) -> Self {
+        DeductionError::Inconsistency(error)
+    }
+}
+
+impl From<SatError> for DeductionError {
+    fn from(error: SatError) -> Self {
+        DeductionError::Sat(error)
+    }
+}
+
+impl From<TooManyClauses> for DeductionError {
+    fn from(error: TooManyClauses) -> Self {
+        DeductionError::NormalForm(NormalFormError::TooManyClauses(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observer::{SolveOptions, SolveResult};
+    use crate::proof::{Proof, ProofStep};
+    use crate::Deduction;
+    use crate::Premise;
+    use crate::ValueMap;
+    use std::error::Error;
+    use std::path::Path;
+
+    // Loads an argument and solves it within the step limit, using ? with every error
+    fn solve_within(
+        input: &str,
+        max_steps: usize,
+    ) -> std::result::Result<ValueMap, DeductionError> {
+        let mut deduction = Deduction::from_reader(input.as_bytes(), Path::new("argument.txt"))?;
+        let options = SolveOptions {
+            max_steps: Some(max_steps),
+            timeout: None,
+            history: None,
+        };
+
+        deduction
+            .solve_with_options(&options, &mut crate::EvaluationHistory::new())
+            .into_result()?;
+
+        Ok(deduction.get_values().clone())
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Premise::parse_str("p & #").unwrap_err().to_string(),
+            "Invalid character in premise: '#' at position 4"
+        );
+        assert_eq!(
+            Premise::parse_str("p & (q").unwrap_err().to_string(),
+            "Unbalanced parenthesis in premise at position 4"
+        );
+        assert_eq!(
+            Premise::parse_str(" ").unwrap_err().to_string(),
+            "Empty premise"
+        );
+        assert_eq!(
+            Premise::parse_str("p & (q").unwrap_err().get_position(),
+            Some(4)
+        );
+        assert_eq!(Premise::parse_str("p &").unwrap_err().get_position(), None);
+
+        let mut values = ValueMap::default();
+        values.add_propositions(&Premise::parse_str("p | q").unwrap());
+        assert_eq!(
+            Premise::parse_str("p | q")
+                .unwrap()
+                .try_evaluate(&values)
+                .unwrap_err()
+                .to_string(),
+            "The value cannot be determined without knowing p, q"
+        );
+        assert_eq!(
+            Premise::parse_str("p & r").unwrap().try_evaluate(&values),
+            Err(EvalError::MissingProposition('r'))
+        );
+
+        values.set_value('q', Some(true));
+        assert_eq!(
+            Premise::parse_str("p | q").unwrap().try_evaluate(&values),
+            Ok(true)
+        );
+
+        let error = Proof::new(vec![Premise::parse_str("p > q").unwrap()])
+            .apply(&ProofStep {
+                rule: InferenceRule::ModusPonens,
+                lines: vec![1, 2],
+                formula: None,
+            })
+            .unwrap_err();
+        assert_eq!(
+            DeductionError::from(error).to_string(),
+            "There is no line 2"
+        );
+
+        assert_eq!(
+            solve_within("p > q\np\n", 1).unwrap_err().to_string(),
+            "The solve was stopped after reaching its step limit"
+        );
+        assert!(solve_within("p > q\np\n", 10).is_ok());
+    }
+
+    #[test]
+    fn test_source() {
+        let error = solve_within("p > q\np & | q\n", 10).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "'argument.txt', line 2: Invalid premise structure"
+        );
+        assert!(matches!(
+            error
+                .source()
+                .and_then(|source| source.downcast_ref::<ParseError>()),
+            Some(ParseError::InvalidStructure)
+        ));
+
+        let error = SolveResult::Aborted.into_result().unwrap_err();
+        assert!(DeductionError::from(error).source().is_none());
+    }
+
+    // Loads and solves a file, requiring the premises to be consistent
+    fn solve_file(path: &Path) -> std::result::Result<ValueMap, crate::Error> {
+        let mut deduction = Deduction::from_file(path)?;
+        deduction.solve();
+        deduction.ensure_consistent()?;
+
+        Ok(deduction.get_values().clone())
+    }
+
+    #[test]
+    fn test_error_chain() {
+        let error = solve_file(Path::new("tests/fixtures/invalid.txt")).unwrap_err();
+
+        // The message and every source below it, as an error reporter would print them
+        let mut chain = vec![error.to_string()];
+        let mut source = error.source();
+        while let Some(error) = source {
+            chain.push(error.to_string());
+            source = error.source();
+        }
+
+        assert_eq!(
+            chain.join(": "),
+            "'tests/fixtures/invalid.txt', line 2: Invalid character in premise: '#' at position 4: \
+             Invalid character in premise: '#' at position 4"
+        );
+
+        assert_eq!(
+            solve_file(Path::new("tests/fixtures/inconsistent.txt"))
+                .unwrap_err()
+                .to_string(),
+            "The premises are inconsistent, as P1 was evaluated to FALSE"
+        );
+        assert!(solve_file(Path::new("tests/fixtures/modus_ponens.txt")).is_ok());
+
+        let mut deduction = Deduction::from_strs(vec!["p", "q", "!p"]).unwrap();
+        deduction.solve();
+        assert_eq!(
+            deduction.ensure_consistent(),
+            Err(InconsistencyError::ConflictingValues('p'))
+        );
+    }
+}
@@ -0,0 +1,247 @@
+// Streams the events of a solve as newline-delimited JSON, one object per line, so other tools
+// can follow a solve as it happens instead of waiting for the history
+
+use std::io::{self, Write};
+use std::ops::ControlFlow;
+
+use serde_json::{json, Value};
+
+use crate::history::StepAction;
+use crate::observer::{SolveLimit, SolveObserver, SolveResult};
+use crate::Deduction;
+use crate::Premise;
+
+// Writes every event of a solve to the writer as a line of JSON, flushing after each one, such as
+// {"event":"value","prop":"t","value":false,"premise":"P4"}
+// If the writer fails, the solve is stopped and the error is kept for take_error
+pub struct JsonLinesObserver<W: Write> {
+    writer: W,
+    steps: usize,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> JsonLinesObserver<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            steps: 0,
+            error: None,
+        }
+    }
+
+    // Returns the number of step events written so far
+    pub fn get_steps(&self) -> usize {
+        self.steps
+    }
+
+    // Returns the first error the writer gave, if any, leaving none in its place
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+
+    // Returns the writer, for reading back what was written to it
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    // Writes the event on its own line and flushes it, for events the solve does not report,
+    // such as the verdict on a conclusion
+    pub fn write_event(&mut self, event: &Value) -> io::Result<()> {
+        writeln!(self.writer, "{}", event)?;
+        self.writer.flush()
+    }
+
+    // Writes the event unless the writer has already failed, stopping the solve if it fails now
+    fn emit(&mut self, event: Value) -> ControlFlow<()> {
+        if self.error.is_some() {
+            return ControlFlow::Break(());
+        }
+
+        match self.write_event(&event) {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(error) => {
+                self.error = Some(error);
+                ControlFlow::Break(())
+            }
+        }
+    }
+}
+
+impl<W: Write> SolveObserver for JsonLinesObserver<W> {
+    fn on_step_start(&mut self, pass: usize, _deduction: &Deduction) -> ControlFlow<()> {
+        self.emit(json!({ "event": "pass", "n": pass }))
+    }
+
+    // The steps are numbered from 1 like the trace lines, and the rule is null unless the
+    // premise was evaluated
+    fn on_premise_changed(
+        &mut self,
+        label: &str,
+        before: &Premise,
+        after: &Premise,
+        action: StepAction,
+    ) -> ControlFlow<()> {
+        self.steps += 1;
+
+        let rule = match action {
+            StepAction::Substitute | StepAction::Rename { .. } => None,
+            StepAction::Evaluate(rule) => Some(rule.to_string()),
+        };
+
+        self.emit(json!({
+            "event": "step",
+            "n": self.steps,
+            "premise": label,
+            "action": action.to_string().to_lowercase(),
+            "rule": rule,
+            "from": before.to_string(),
+            "to": after.to_string(),
+        }))
+    }
+
+    fn on_proposition_renamed(&mut self, from: char, to: char) -> ControlFlow<()> {
+        self.emit(json!({ "event": "rename", "from": from, "to": to }))
+    }
+
+    fn on_value_derived(
+        &mut self,
+        proposition: char,
+        value: bool,
+        source: &str,
+    ) -> ControlFlow<()> {
+        self.emit(json!({
+            "event": "value",
+            "prop": proposition,
+            "value": value,
+            "premise": source,
+        }))
+    }
+
+    // The result names the limit reached, in the same way as the limit in the solve JSON
+    // It says how the solve finished rather than whether a conclusion was proved, which the CLI
+    // writes afterwards as a verdict event
+    fn on_finished(&mut self, result: &SolveResult) {
+        let result = match result {
+            SolveResult::Complete => "complete",
+            SolveResult::Aborted => "aborted",
+            SolveResult::LimitReached(SolveLimit::Steps) => "max-steps",
+            SolveResult::LimitReached(SolveLimit::Timeout) => "timeout",
+        };
+
+        let _ = self.emit(json!({ "event": "done", "result": result, "steps": self.steps }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observer::SolveOptions;
+
+    // Parses every line the observer wrote, each of which must be a JSON object
+    fn parse_lines(observer: JsonLinesObserver<Vec<u8>>) -> Vec<Value> {
+        let output = String::from_utf8(observer.into_inner()).unwrap();
+        assert!(output.ends_with('\n'));
+
+        output
+            .lines()
+            .map(|line| {
+                let event: Value = serde_json::from_str(line).unwrap();
+                assert!(event["event"].is_string(), "{} has no event", line);
+                event
+            })
+            .collect()
+    }
+
+    // Fails every write, as a closed pipe would
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_events() {
+        let mut observer = JsonLinesObserver::new(Vec::new());
+        let result = Deduction::from_strs(vec!["p > q", "p"])
+            .unwrap()
+            .solve_with_observer(&mut observer);
+        assert_eq!(result, SolveResult::Complete);
+        assert_eq!(observer.get_steps(), 2);
+
+        let events = parse_lines(observer);
+        let names: Vec<&str> = events
+            .iter()
+            .map(|event| event["event"].as_str().unwrap())
+            .collect();
+        assert_eq!(names.first(), Some(&"value"));
+        assert_eq!(names.last(), Some(&"done"));
+
+        assert!(events.contains(&json!({
+            "event": "step",
+            "n": 1,
+            "premise": "P1",
+            "action": "substitute",
+            "rule": null,
+            "from": "p → q",
+            "to": "TRUE → q",
+        })));
+        assert!(events.contains(&json!({
+            "event": "step",
+            "n": 2,
+            "premise": "P1",
+            "action": "evaluate",
+            "rule": "implication",
+            "from": "TRUE → q",
+            "to": "q",
+        })));
+        assert!(events.contains(&json!({
+            "event": "value",
+            "prop": "q",
+            "value": true,
+            "premise": "P1",
+        })));
+        assert_eq!(
+            events.last(),
+            Some(&json!({ "event": "done", "result": "complete", "steps": 2 }))
+        );
+    }
+
+    #[test]
+    fn test_events_with_limit() {
+        let mut observer = JsonLinesObserver::new(Vec::new());
+        let options = SolveOptions {
+            max_steps: Some(1),
+            ..SolveOptions::default()
+        };
+        Deduction::from_strs(vec!["p > q", "p"])
+            .unwrap()
+            .solve_with_options(&options, &mut observer);
+
+        assert_eq!(
+            parse_lines(observer).last(),
+            Some(&json!({ "event": "done", "result": "max-steps", "steps": 1 }))
+        );
+    }
+
+    #[test]
+    fn test_write_error_stops_solve() {
+        let mut observer = JsonLinesObserver::new(FailingWriter);
+        let result = Deduction::from_strs(vec!["p > q", "p"])
+            .unwrap()
+            .solve_with_observer(&mut observer);
+
+        assert_eq!(result, SolveResult::Aborted);
+        assert_eq!(observer.get_steps(), 0);
+        assert_eq!(
+            observer.take_error().map(|error| error.kind()),
+            Some(io::ErrorKind::BrokenPipe)
+        );
+        assert!(observer.take_error().is_none());
+    }
+}
@@ -0,0 +1,308 @@
+// Exports the solver through a C interface, for embedding it in other languages such as Python
+// through ctypes
+// A Deduction is handed out as an opaque pointer which must be given back to deduction_free, and
+// every string returned must be given back to deduction_string_free
+// Functions which fail return an error code, or a null pointer, and leave a message for
+// deduction_last_error on the calling thread
+// Build with `cargo build --release --features capi`, which gives the cdylib to load
+// The safety requirements of each function are in the "# Safety" part of the comment above it,
+// which clippy cannot see as it only reads doc comments
+#![allow(clippy::missing_safety_doc)]
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::path::Path;
+use std::ptr;
+
+use crate::history::EvaluationHistory;
+use crate::observer::{SolveOptions, SolveResult};
+use crate::Deduction;
+
+// The codes returned by deduction_evaluate and deduction_last_error_code
+pub const DEDUCTION_OK: c_int = 0;
+pub const DEDUCTION_NULL_POINTER: c_int = -1;
+pub const DEDUCTION_INVALID_UTF8: c_int = -2;
+pub const DEDUCTION_PARSE_ERROR: c_int = -3;
+pub const DEDUCTION_STEP_LIMIT: c_int = -4;
+
+// The most steps a solve may take, so a large argument cannot hang the calling program
+pub const MAX_STEPS: usize = 100_000;
+
+thread_local! {
+    // The code and message of the last error on this thread, which deduction_last_error returns
+    static LAST_ERROR: RefCell<Option<(c_int, CString)>> = const { RefCell::new(None) };
+}
+
+// Creates a Deduction from an array of count lines, each a null-terminated UTF-8 string in the
+// format read by Deduction::from_file, so lines may also be comments, a "∴" conclusion or an
+// "assume: p=true" line
+// Returns a null pointer if any line is null, is not UTF-8 or cannot be parsed
+//
+// # Safety
+// lines must point to count pointers, each of which is null or a null-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn deduction_new_from_lines(
+    lines: *const *const c_char,
+    count: usize,
+) -> *mut Deduction {
+    if lines.is_null() {
+        set_error(DEDUCTION_NULL_POINTER, "The array of lines is null");
+        return ptr::null_mut();
+    }
+
+    let mut strings = Vec::with_capacity(count);
+    for i in 0..count {
+        // SAFETY: the caller promises lines holds count pointers
+        let line = unsafe { *lines.add(i) };
+        if line.is_null() {
+            set_error(DEDUCTION_NULL_POINTER, &format!("Line {} is null", i + 1));
+            return ptr::null_mut();
+        }
+
+        // SAFETY: the caller promises each pointer is a null-terminated string
+        match unsafe { CStr::from_ptr(line) }.to_str() {
+            Ok(line) => strings.push((i + 1, line)),
+            Err(_) => {
+                set_error(
+                    DEDUCTION_INVALID_UTF8,
+                    &format!("Line {} is not valid UTF-8", i + 1),
+                );
+                return ptr::null_mut();
+            }
+        }
+    }
+
+    match Deduction::parse_numbered_lines(strings.into_iter(), Path::new("<lines>")) {
+        Ok(deduction) => {
+            clear_error();
+            Box::into_raw(Box::new(deduction))
+        }
+        Err(error) => {
+            set_error(DEDUCTION_PARSE_ERROR, &error.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+// Solves the Deduction, returning DEDUCTION_OK once nothing else can be found, or
+// DEDUCTION_STEP_LIMIT if the solve took more than MAX_STEPS steps
+//
+// # Safety
+// deduction must be null or a pointer given by deduction_new_from_lines which has not been freed
+#[no_mangle]
+pub unsafe extern "C" fn deduction_evaluate(deduction: *mut Deduction) -> c_int {
+    // SAFETY: the caller promises the pointer is null or a live Deduction
+    let Some(deduction) = (unsafe { deduction.as_mut() }) else {
+        return set_error(DEDUCTION_NULL_POINTER, "The Deduction is null");
+    };
+
+    let options = SolveOptions {
+        max_steps: Some(MAX_STEPS),
+        timeout: None,
+        history: None,
+    };
+
+    match deduction.solve_with_options(&options, &mut EvaluationHistory::new()) {
+        SolveResult::LimitReached(_) => set_error(
+            DEDUCTION_STEP_LIMIT,
+            &format!("The solve was stopped after {} steps", MAX_STEPS),
+        ),
+        _ => {
+            clear_error();
+            DEDUCTION_OK
+        }
+    }
+}
+
+// Returns 1 if the proposition is known to be TRUE, 0 if it is known to be FALSE, and -1 if its
+// value is not known, the proposition is not a letter or the Deduction is null
+//
+// # Safety
+// deduction must be null or a pointer given by deduction_new_from_lines which has not been freed
+#[no_mangle]
+pub unsafe extern "C" fn deduction_get_value(
+    deduction: *const Deduction,
+    proposition: c_char,
+) -> c_int {
+    // SAFETY: the caller promises the pointer is null or a live Deduction
+    let Some(deduction) = (unsafe { deduction.as_ref() }) else {
+        set_error(DEDUCTION_NULL_POINTER, "The Deduction is null");
+        return -1;
+    };
+
+    match deduction.get_values().get_value(proposition as u8 as char) {
+        Some(true) => 1,
+        Some(false) => 0,
+        None => -1,
+    }
+}
+
+// Returns the premises left on the stack and the conclusion as a string, one per line, which must
+// be given back to deduction_string_free, or a null pointer if the Deduction is null
+//
+// # Safety
+// deduction must be null or a pointer given by deduction_new_from_lines which has not been freed
+#[no_mangle]
+pub unsafe extern "C" fn deduction_render(deduction: *const Deduction) -> *mut c_char {
+    // SAFETY: the caller promises the pointer is null or a live Deduction
+    let Some(deduction) = (unsafe { deduction.as_ref() }) else {
+        set_error(DEDUCTION_NULL_POINTER, "The Deduction is null");
+        return ptr::null_mut();
+    };
+
+    // A premise is never displayed with a null character, as the parser does not accept one
+    CString::new(deduction.to_string())
+        .expect("A rendered Deduction should not contain a null character")
+        .into_raw()
+}
+
+// Frees a Deduction, doing nothing if it is null
+//
+// # Safety
+// deduction must be null or a pointer given by deduction_new_from_lines which has not been freed
+#[no_mangle]
+pub unsafe extern "C" fn deduction_free(deduction: *mut Deduction) {
+    if !deduction.is_null() {
+        // SAFETY: the caller promises the pointer came from Box::into_raw and is freed only once
+        drop(unsafe { Box::from_raw(deduction) });
+    }
+}
+
+// Frees a string returned by deduction_render, doing nothing if it is null
+//
+// # Safety
+// string must be null or a pointer given by deduction_render which has not been freed
+#[no_mangle]
+pub unsafe extern "C" fn deduction_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        // SAFETY: the caller promises the pointer came from CString::into_raw and is freed only
+        // once
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+// Returns the message of the last error on this thread, or a null pointer if the last call
+// succeeded
+// The string belongs to the library and stays valid until the next call on this thread, so it
+// must not be freed
+#[no_mangle]
+pub extern "C" fn deduction_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| match &*error.borrow() {
+        Some((_, message)) => message.as_ptr(),
+        None => ptr::null(),
+    })
+}
+
+// Returns the code of the last error on this thread, or DEDUCTION_OK if the last call succeeded
+#[no_mangle]
+pub extern "C" fn deduction_last_error_code() -> c_int {
+    LAST_ERROR.with(|error| match &*error.borrow() {
+        Some((code, _)) => *code,
+        None => DEDUCTION_OK,
+    })
+}
+
+// Records the error for deduction_last_error, returning its code
+fn set_error(code: c_int, message: &str) -> c_int {
+    let message = CString::new(message.replace('\0', " "))
+        .expect("Null characters should have been replaced");
+    LAST_ERROR.with(|error| *error.borrow_mut() = Some((code, message)));
+
+    code
+}
+
+// Forgets the last error after a call succeeds
+fn clear_error() {
+    LAST_ERROR.with(|error| *error.borrow_mut() = None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Creates a Deduction through the C interface from the lines, as a caller would
+    fn new_from_lines(lines: &[&[u8]]) -> *mut Deduction {
+        let strings: Vec<CString> = lines
+            .iter()
+            .map(|line| CString::new(*line).unwrap())
+            .collect();
+        let pointers: Vec<*const c_char> = strings.iter().map(|line| line.as_ptr()).collect();
+
+        unsafe { deduction_new_from_lines(pointers.as_ptr(), pointers.len()) }
+    }
+
+    // Returns the message of the last error as a Rust string
+    fn last_error() -> Option<String> {
+        let message = deduction_last_error();
+
+        (!message.is_null()).then(|| {
+            unsafe { CStr::from_ptr(message) }
+                .to_str()
+                .unwrap()
+                .to_string()
+        })
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let deduction = new_from_lines(&[
+            b"# The example argument",
+            b"(m & !b) > j",
+            b"(f | s) > m",
+            b"b > t",
+            b"f > !t",
+            b"f",
+            "∴ j".as_bytes(),
+        ]);
+        assert!(!deduction.is_null());
+        assert_eq!(last_error(), None);
+
+        unsafe {
+            assert_eq!(deduction_get_value(deduction, b'j' as c_char), -1);
+            assert_eq!(deduction_evaluate(deduction), DEDUCTION_OK);
+            assert_eq!(deduction_get_value(deduction, b'j' as c_char), 1);
+            assert_eq!(deduction_get_value(deduction, b'b' as c_char), 0);
+            assert_eq!(deduction_get_value(deduction, b'z' as c_char), -1);
+
+            let rendered = deduction_render(deduction);
+            assert_eq!(CStr::from_ptr(rendered).to_str(), Ok("∴ j\n"));
+
+            deduction_string_free(rendered);
+            deduction_free(deduction);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let deduction = new_from_lines(&[b"p > q", b"p & #"]);
+        assert!(deduction.is_null());
+        assert_eq!(deduction_last_error_code(), DEDUCTION_PARSE_ERROR);
+        assert_eq!(
+            last_error().as_deref(),
+            Some("'<lines>', line 2: Invalid character in premise: '#' at position 4")
+        );
+
+        let deduction = new_from_lines(&[b"p", b"q > \xff"]);
+        assert!(deduction.is_null());
+        assert_eq!(deduction_last_error_code(), DEDUCTION_INVALID_UTF8);
+        assert_eq!(last_error().as_deref(), Some("Line 2 is not valid UTF-8"));
+
+        let lines = [c"p".as_ptr(), ptr::null()];
+        assert!(unsafe { deduction_new_from_lines(lines.as_ptr(), 2) }.is_null());
+        assert_eq!(last_error().as_deref(), Some("Line 2 is null"));
+
+        unsafe {
+            assert_eq!(deduction_evaluate(ptr::null_mut()), DEDUCTION_NULL_POINTER);
+            assert_eq!(deduction_get_value(ptr::null(), b'p' as c_char), -1);
+            assert!(deduction_render(ptr::null()).is_null());
+            deduction_free(ptr::null_mut());
+            deduction_string_free(ptr::null_mut());
+        }
+
+        // A call which succeeds clears the error
+        let deduction = new_from_lines(&[b"p"]);
+        assert_eq!(deduction_last_error_code(), DEDUCTION_OK);
+        assert_eq!(last_error(), None);
+        unsafe { deduction_free(deduction) };
+    }
+}
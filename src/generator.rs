@@ -0,0 +1,386 @@
+// Generates random well-formed premises from a seed, for property tests and random arguments
+// The same seed always gives the same premises, and a premise a property fails for can be shrunk
+// to a smaller one it still fails for
+
+use std::fmt::{Display, Formatter, Result};
+
+use crate::ast::BinaryExpr;
+use crate::premises::Operator;
+use crate::Premise;
+
+// The most times check_property replaces a failing premise with a smaller one
+pub const MAX_SHRINKS: usize = 1000;
+
+// A small random number generator which gives the same numbers from the same seed on every
+// platform, using the same constants as Knuth's MMIX
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    state: u64,
+}
+
+// Describes the premises check_property generates for each case
+#[derive(Debug, Clone, Copy)]
+pub struct PropertyConfig {
+    pub seed: u64,
+    pub cases: usize,
+    // The number of premises given to the property in each case
+    pub premises: usize,
+    pub depth: usize,
+    pub propositions: usize,
+}
+
+// Represents a case a property failed for, along with the smallest premises found by shrinking
+// them which it still fails for
+#[derive(Debug, Clone)]
+pub struct PropertyFailure {
+    // The number of the case, counting from 0
+    pub case: usize,
+    pub original: Vec<Premise>,
+    pub shrunk: Vec<Premise>,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+
+        self.state
+    }
+
+    // Returns a number from 0 up to but not including the limit, which must not be 0
+    pub fn below(&mut self, limit: usize) -> usize {
+        // The high bits of an LCG are the most random
+        (self.next_u64() >> 33) as usize % limit
+    }
+}
+
+impl Default for PropertyConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            cases: 256,
+            premises: 1,
+            depth: 4,
+            propositions: 4,
+        }
+    }
+}
+
+impl Display for PropertyFailure {
+    // Displays the failure as a message for the user, such as
+    // "Case 3 failed for (a ∧ b) → ¬c, which shrinks to a → ¬a"
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let join = |premises: &[Premise]| {
+            premises
+                .iter()
+                .map(Premise::to_string)
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        write!(
+            f,
+            "Case {} failed for {}, which shrinks to {}",
+            self.case,
+            join(&self.original),
+            join(&self.shrunk)
+        )
+    }
+}
+
+impl Premise {
+    // Generates a random premise over the first letters of the alphabet, from 1 to 26 of them,
+    // nesting negations and operators up to the depth
+    // Compound operands are parenthesized as by combine, so every premise can be parsed back
+    // from how it is displayed
+    pub fn arbitrary(depth: usize, propositions: usize, rng: &mut SeededRng) -> Premise {
+        let choice = rng.below(7);
+
+        if depth == 0 || choice == 0 {
+            return Premise::atom(letter(rng.below(propositions.clamp(1, 26))));
+        }
+
+        match choice {
+            1 => !Premise::arbitrary(depth - 1, propositions, rng),
+            _ => {
+                let operator = [
+                    Operator::And,
+                    Operator::Or,
+                    Operator::Implies,
+                    Operator::Iff,
+                    Operator::Xor,
+                ][choice - 2];
+                let left = Premise::arbitrary(depth - 1, propositions, rng);
+                let right = Premise::arbitrary(depth - 1, propositions, rng);
+
+                Premise::combine(operator, left, right)
+            }
+        }
+    }
+
+    // Returns smaller premises to try in place of the Premise when a property fails for it,
+    // simplest first: each operand on its own, then the Premise with one operand shrunk, and for
+    // a proposition other than a, the proposition a
+    // A Premise which does not form a valid formula has no smaller premises
+    pub fn shrink(&self) -> Vec<Premise> {
+        match self.to_binary() {
+            Ok(expression) => shrink_expression(&expression)
+                .iter()
+                .map(combined)
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+// Checks the property for premises generated from the config, returning the first case it fails
+// for with its premises shrunk as far as they can be while it still fails
+pub fn check_property(
+    config: &PropertyConfig,
+    mut property: impl FnMut(&[Premise]) -> bool,
+) -> std::result::Result<(), PropertyFailure> {
+    let mut rng = SeededRng::new(config.seed);
+
+    for case in 0..config.cases {
+        let premises: Vec<Premise> = (0..config.premises)
+            .map(|_| Premise::arbitrary(config.depth, config.propositions, &mut rng))
+            .collect();
+
+        if !property(&premises) {
+            return Err(PropertyFailure {
+                case,
+                shrunk: shrink_failure(&premises, &mut property),
+                original: premises,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// Repeatedly replaces a premise with the first smaller one the property still fails for, until
+// none of them can be shrunk any further
+fn shrink_failure(
+    premises: &[Premise],
+    property: &mut impl FnMut(&[Premise]) -> bool,
+) -> Vec<Premise> {
+    let mut shrunk = premises.to_vec();
+
+    'shrinks: for _ in 0..MAX_SHRINKS {
+        for i in 0..shrunk.len() {
+            for candidate in shrunk[i].shrink() {
+                let mut premises = shrunk.clone();
+                premises[i] = candidate;
+
+                if !property(&premises) {
+                    shrunk = premises;
+                    continue 'shrinks;
+                }
+            }
+        }
+
+        break;
+    }
+
+    shrunk
+}
+
+// Returns the smaller expressions to try in place of the expression, as described in shrink
+fn shrink_expression(expression: &BinaryExpr) -> Vec<BinaryExpr> {
+    match expression {
+        BinaryExpr::Atom(proposition) if *proposition != 'a' => vec![BinaryExpr::Atom('a')],
+        BinaryExpr::Atom(_) | BinaryExpr::Const(_) => Vec::new(),
+        BinaryExpr::Not(operand) => [
+            vec![operand.as_ref().clone()],
+            shrink_expression(operand)
+                .into_iter()
+                .map(BinaryExpr::negation)
+                .collect(),
+        ]
+        .concat(),
+        BinaryExpr::Binary(operator, left, right) => [
+            vec![left.as_ref().clone(), right.as_ref().clone()],
+            shrink_expression(left)
+                .into_iter()
+                .map(|left| BinaryExpr::binary(*operator, left, right.as_ref().clone()))
+                .collect(),
+            shrink_expression(right)
+                .into_iter()
+                .map(|right| BinaryExpr::binary(*operator, left.as_ref().clone(), right))
+                .collect(),
+        ]
+        .concat(),
+    }
+}
+
+// Builds the premise of the expression the way arbitrary does, parenthesizing every compound
+// operand
+fn combined(expression: &BinaryExpr) -> Premise {
+    match expression {
+        BinaryExpr::Atom(proposition) => Premise::atom(*proposition),
+        BinaryExpr::Const(value) => Premise::from_binary(&BinaryExpr::Const(*value)),
+        BinaryExpr::Not(operand) => !combined(operand),
+        BinaryExpr::Binary(operator, left, right) => {
+            Premise::combine(*operator, combined(left), combined(right))
+        }
+    }
+}
+
+// Returns the letter at the index of the alphabet, starting from a
+fn letter(index: usize) -> char {
+    (b'a' + index as u8) as char
+}
+
+#[cfg(feature = "proptest")]
+mod strategies {
+    use proptest::prelude::*;
+
+    use crate::premises::Operator;
+    use crate::Premise;
+
+    // Describes the premises generated by Premise's Arbitrary implementation
+    #[derive(Debug, Clone, Copy)]
+    pub struct PremiseParameters {
+        pub depth: usize,
+        pub propositions: usize,
+    }
+
+    impl Default for PremiseParameters {
+        fn default() -> Self {
+            Self {
+                depth: 4,
+                propositions: 4,
+            }
+        }
+    }
+
+    impl Arbitrary for Operator {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Operator>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            prop_oneof![
+                Just(Operator::And),
+                Just(Operator::Or),
+                Just(Operator::Implies),
+                Just(Operator::Iff),
+                Just(Operator::Xor)
+            ]
+            .boxed()
+        }
+    }
+
+    impl Arbitrary for Premise {
+        type Parameters = PremiseParameters;
+        type Strategy = BoxedStrategy<Premise>;
+
+        // Generates premises like Premise::arbitrary, which proptest shrinks towards a proposition
+        fn arbitrary_with(parameters: PremiseParameters) -> Self::Strategy {
+            let propositions = parameters.propositions.clamp(1, 26);
+            let leaf = (0..propositions)
+                .prop_map(|index| Premise::atom(super::letter(index)))
+                .boxed();
+
+            leaf.prop_recursive(parameters.depth as u32, 256, 2, |inner| {
+                prop_oneof![
+                    inner.clone().prop_map(|premise| !premise),
+                    (any::<Operator>(), inner.clone(), inner).prop_map(
+                        |(operator, left, right)| Premise::combine(operator, left, right)
+                    ),
+                ]
+            })
+            .boxed()
+        }
+    }
+}
+
+#[cfg(feature = "proptest")]
+pub use strategies::PremiseParameters;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arbitrary() {
+        let generate = |seed| {
+            let mut rng = SeededRng::new(seed);
+            (0..100)
+                .map(|_| Premise::arbitrary(5, 3, &mut rng))
+                .collect::<Vec<Premise>>()
+        };
+
+        let premises = generate(7);
+        assert_eq!(premises, generate(7));
+        assert_ne!(premises, generate(8));
+
+        for premise in &premises {
+            assert!(Premise::from_nodes(premise.get_nodes().to_vec()).is_ok());
+            assert!(premise
+                .propositions()
+                .iter()
+                .all(|p| ('a'..='c').contains(p)));
+        }
+
+        // Every premise has at least one proposition, even when none are asked for
+        let mut rng = SeededRng::new(0);
+        assert_eq!(Premise::arbitrary(0, 0, &mut rng), Premise::atom('a'));
+    }
+
+    #[test]
+    fn test_shrink() {
+        let shrunk: Vec<String> = Premise::parse_str("!b & c")
+            .unwrap()
+            .shrink()
+            .iter()
+            .map(Premise::to_string)
+            .collect();
+        assert_eq!(shrunk, ["¬b", "c", "b ∧ c", "¬a ∧ c", "¬b ∧ a"]);
+
+        assert!(Premise::atom('a').shrink().is_empty());
+        assert!(Premise::parse_lenient("p &").shrink().is_empty());
+    }
+
+    #[test]
+    fn test_check_property() {
+        let config = PropertyConfig {
+            seed: 1,
+            depth: 6,
+            ..PropertyConfig::default()
+        };
+        assert!(check_property(&config, |premises| premises[0].node_count() > 0).is_ok());
+
+        // The smallest premise with an implication in it
+        let failure =
+            check_property(&config, |premises| !premises[0].to_string().contains('→')).unwrap_err();
+        assert_eq!(failure.shrunk, [Premise::parse_str("a > a").unwrap()]);
+        assert!(failure.original[0].node_count() >= 3);
+        assert!(failure.to_string().ends_with("which shrinks to a → a"));
+
+        // Only the premise the property depends on is shrunk all the way
+        let config = PropertyConfig {
+            premises: 2,
+            ..config
+        };
+        let failure = check_property(&config, |premises| premises[1].node_count() < 5).unwrap_err();
+        assert_eq!(failure.shrunk[0], Premise::atom('a'));
+        assert!(failure.shrunk[1]
+            .shrink()
+            .iter()
+            .all(|premise| premise.node_count() < 5));
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #[test]
+        fn test_arbitrary_strategy(premise: Premise) {
+            proptest::prop_assert_eq!(Premise::parse_str(&premise.to_string()).unwrap(), premise);
+        }
+    }
+}
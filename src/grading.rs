@@ -0,0 +1,561 @@
+// Grades a class's answers from a CSV export, where each row has a student id, the premises
+// separated by semicolons, the conclusion and the verdict the student gave, such as
+// s1024,"p > q; p",q,valid
+// A first row whose verdict is not one of valid, invalid or inconsistent is taken as a header
+
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Result};
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::batch::{self, Verdict};
+use crate::clock::Stopwatch;
+use crate::Deduction;
+use crate::Premise;
+
+// The verdicts a row may expect, as they are displayed by Verdict
+pub const VERDICTS: [&str; 3] = ["valid", "invalid", "inconsistent"];
+
+// The header of the CSV written by write_csv
+pub const RESULTS_HEADER: &str = "row,student,expected,verdict,grade,time_ms,error";
+
+// How a row compares to the verdict found for its argument
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Grade {
+    // The argument has the expected verdict
+    Correct,
+    // The argument has a different verdict from the one expected
+    Mismatch,
+    // The argument has more propositions than may be checked, so it was not graded
+    LimitReached,
+    // The row could not be read, along with the reason
+    Malformed(String),
+}
+
+// Stores the grade for one row, along with the row number it was read from, counting the header
+#[derive(Debug, Clone)]
+pub struct GradeRecord {
+    row: usize,
+    student: Option<String>,
+    expected: Option<String>,
+    verdict: Option<Verdict>,
+    grade: Grade,
+    elapsed: Duration,
+}
+
+// Counts how many rows had each grade
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy)]
+pub struct GradeTally {
+    pub correct: usize,
+    pub mismatched: usize,
+    pub limited: usize,
+    pub malformed: usize,
+}
+
+impl Display for Grade {
+    // Displays the grade as a single lowercase word
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Grade::Correct => write!(f, "correct"),
+            Grade::Mismatch => write!(f, "mismatch"),
+            Grade::LimitReached => write!(f, "limit"),
+            Grade::Malformed(_) => write!(f, "malformed"),
+        }
+    }
+}
+
+impl Display for GradeTally {
+    // Displays the counts such as "14 correct, 3 mismatched", only mentioning rows which were
+    // too large to check or malformed if there were any
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "{} correct, {} mismatched",
+            self.correct, self.mismatched
+        )?;
+
+        if self.limited > 0 {
+            write!(f, ", {} over the limit", self.limited)?;
+        }
+        if self.malformed > 0 {
+            write!(f, ", {} malformed", self.malformed)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl GradeRecord {
+    // Returns the number of the row, counting from 1 at the first line of the CSV
+    pub fn get_row(&self) -> usize {
+        self.row
+    }
+
+    // Returns the student id, unless the row had no fields
+    pub fn get_student(&self) -> Option<&str> {
+        self.student.as_deref()
+    }
+
+    // Returns the verdict the row expected, if it gave a valid one
+    pub fn get_expected(&self) -> Option<&str> {
+        self.expected.as_deref()
+    }
+
+    // Returns the verdict found for the argument, if it was checked
+    pub fn get_verdict(&self) -> Option<&Verdict> {
+        self.verdict.as_ref()
+    }
+
+    pub fn get_grade(&self) -> &Grade {
+        &self.grade
+    }
+
+    // Returns how long the row took to read and check
+    pub fn get_elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    // Converts the record to a JSON object, with the reason for malformed rows
+    pub fn to_json(&self) -> Value {
+        let mut json = json!({
+            "row": self.row,
+            "student": self.student,
+            "expected": self.expected,
+            "verdict": self.verdict.as_ref().map(Verdict::to_string),
+            "grade": self.grade.to_string(),
+            "time_ms": self.elapsed.as_secs_f64() * 1000.0,
+        });
+
+        if let Grade::Malformed(reason) = &self.grade {
+            json["error"] = json!(reason);
+        }
+
+        json
+    }
+
+    // Returns the reason a row was not graded, or nothing if it was
+    fn get_error(&self) -> String {
+        match &self.grade {
+            Grade::Malformed(reason) => reason.clone(),
+            Grade::LimitReached => String::from("Too many propositions to check"),
+            Grade::Correct | Grade::Mismatch => String::new(),
+        }
+    }
+}
+
+// Grades every row of the CSV, giving arguments with more than max_variables propositions the
+// grade LimitReached instead of checking them
+// A row which cannot be read is graded as malformed without stopping the rest from being graded,
+// so only failing to read the CSV itself is an error
+pub fn grade_csv(mut reader: impl Read, max_variables: usize) -> io::Result<Vec<GradeRecord>> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+
+    let mut records = Vec::new();
+
+    for (i, (row, fields)) in read_rows(&text).into_iter().enumerate() {
+        if i == 0 && fields.as_ref().is_ok_and(|fields| is_header(fields)) {
+            continue;
+        }
+
+        let stopwatch = Stopwatch::start();
+        let mut record = grade_row(row, fields, max_variables);
+        record.elapsed = stopwatch.elapsed();
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+// Counts the grades of every record
+pub fn tally(records: &[GradeRecord]) -> GradeTally {
+    let mut tally = GradeTally::default();
+
+    for record in records {
+        match record.grade {
+            Grade::Correct => tally.correct += 1,
+            Grade::Mismatch => tally.mismatched += 1,
+            Grade::LimitReached => tally.limited += 1,
+            Grade::Malformed(_) => tally.malformed += 1,
+        }
+    }
+
+    tally
+}
+
+// Renders the records as a table with one row per record, followed by the tally
+// Rows which were not graded are shown with the reason at the end
+pub fn render_table(records: &[GradeRecord]) -> String {
+    let mut rows = vec![[
+        String::from("Row"),
+        String::from("Student"),
+        String::from("Expected"),
+        String::from("Verdict"),
+        String::from("Grade"),
+        String::from("Time"),
+        String::new(),
+    ]];
+
+    for record in records {
+        rows.push([
+            record.row.to_string(),
+            record.student.clone().unwrap_or_default(),
+            record.expected.clone().unwrap_or_default(),
+            record
+                .verdict
+                .as_ref()
+                .map(Verdict::to_string)
+                .unwrap_or_default(),
+            record.grade.to_string(),
+            format!("{:.3}ms", record.elapsed.as_secs_f64() * 1000.0),
+            record.get_error(),
+        ]);
+    }
+
+    // Every column but the last is padded to the width of its longest cell
+    let widths: Vec<usize> = (0..6)
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut table = String::new();
+
+    for row in &rows {
+        let mut line = String::new();
+        for (cell, width) in row.iter().zip(&widths) {
+            line.push_str(&format!("{:<width$}  ", cell, width = width));
+        }
+        line.push_str(&row[6]);
+
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+
+    table.push_str(&format!("\n{}\n", tally(records)));
+    table
+}
+
+// Renders one line per record with its row and grade, such as "8 mismatch"
+pub fn render_lines(records: &[GradeRecord]) -> String {
+    records
+        .iter()
+        .map(|record| format!("{} {}\n", record.row, record.grade))
+        .collect()
+}
+
+// Converts the records to a JSON array with one object per record
+pub fn to_json(records: &[GradeRecord]) -> Value {
+    Value::Array(records.iter().map(GradeRecord::to_json).collect())
+}
+
+// Writes the records as a CSV with the columns in RESULTS_HEADER, quoting any field which needs it
+pub fn write_csv(records: &[GradeRecord], mut writer: impl Write) -> io::Result<()> {
+    writeln!(writer, "{}", RESULTS_HEADER)?;
+
+    for record in records {
+        let fields = [
+            record.row.to_string(),
+            record.student.clone().unwrap_or_default(),
+            record.expected.clone().unwrap_or_default(),
+            record
+                .verdict
+                .as_ref()
+                .map(Verdict::to_string)
+                .unwrap_or_default(),
+            record.grade.to_string(),
+            format!("{:.3}", record.elapsed.as_secs_f64() * 1000.0),
+            record.get_error(),
+        ];
+
+        let fields: Vec<String> = fields.iter().map(|field| quote(field)).collect();
+        writeln!(writer, "{}", fields.join(","))?;
+    }
+
+    writer.flush()
+}
+
+// Grades a single row, which must have a student id, the premises, the conclusion and the
+// expected verdict
+fn grade_row(
+    row: usize,
+    fields: std::result::Result<Vec<String>, String>,
+    max_variables: usize,
+) -> GradeRecord {
+    let mut record = GradeRecord {
+        row,
+        student: None,
+        expected: None,
+        verdict: None,
+        grade: Grade::Correct,
+        elapsed: Duration::ZERO,
+    };
+
+    let fields = match fields {
+        Ok(fields) => fields,
+        Err(reason) => {
+            record.grade = Grade::Malformed(reason);
+            return record;
+        }
+    };
+
+    record.student = fields.first().map(|student| student.trim().to_string());
+
+    let [_, premises, conclusion, expected] = fields.as_slice() else {
+        record.grade = Grade::Malformed(format!("Expected 4 fields but found {}", fields.len()));
+        return record;
+    };
+
+    record.expected = parse_expected(expected);
+    if record.expected.is_none() {
+        record.grade = Grade::Malformed(format!(
+            "Unknown verdict '{}', expected one of {}",
+            expected.trim(),
+            VERDICTS.join(", ")
+        ));
+        return record;
+    }
+
+    let (premises, conclusion) = match parse_argument(premises, conclusion) {
+        Ok(argument) => argument,
+        Err(reason) => {
+            record.grade = Grade::Malformed(reason);
+            return record;
+        }
+    };
+
+    // Checking tries every assignment, so the number of propositions is limited
+    let propositions = premises
+        .iter()
+        .chain([&conclusion])
+        .flat_map(Premise::propositions)
+        .collect::<BTreeSet<char>>()
+        .len();
+    if propositions > max_variables {
+        record.grade = Grade::LimitReached;
+        return record;
+    }
+
+    let mut deduction = Deduction::from_premises(premises);
+    deduction.set_conclusion(conclusion);
+
+    let verdict = batch::find_verdict(&deduction);
+    record.grade = match record.expected.as_deref() == Some(verdict.to_string().as_str()) {
+        true => Grade::Correct,
+        false => Grade::Mismatch,
+    };
+    record.verdict = Some(verdict);
+
+    record
+}
+
+// Parses the premises separated by semicolons and the conclusion
+fn parse_argument(
+    premises: &str,
+    conclusion: &str,
+) -> std::result::Result<(Vec<Premise>, Premise), String> {
+    let premises = premises
+        .split(';')
+        .map(str::trim)
+        .filter(|premise| !premise.is_empty())
+        .enumerate()
+        .map(|(i, premise)| {
+            Premise::parse_str(premise).map_err(|error| format!("Premise {}: {}", i + 1, error))
+        })
+        .collect::<std::result::Result<Vec<Premise>, String>>()?;
+
+    let conclusion =
+        Premise::parse_str(conclusion.trim()).map_err(|error| format!("Conclusion: {}", error))?;
+
+    Ok((premises, conclusion))
+}
+
+// Checks whether the fields are a header, which is any row of the right length whose last field is
+// not a verdict
+fn is_header(fields: &[String]) -> bool {
+    fields.len() == 4 && parse_expected(&fields[3]).is_none()
+}
+
+// Returns the verdict in the form it is displayed in, if it is one of VERDICTS
+fn parse_expected(verdict: &str) -> Option<String> {
+    let verdict = verdict.trim().to_lowercase();
+
+    VERDICTS.contains(&verdict.as_str()).then_some(verdict)
+}
+
+// Splits the text into rows of fields, along with the line each row starts on, skipping blank
+// lines
+// Fields may be quoted to hold commas, line breaks or quotes, which are written twice, and a row
+// with a quoted field that is never closed is an error
+fn read_rows(text: &str) -> Vec<(usize, std::result::Result<Vec<String>, String>)> {
+    let mut rows = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+
+    while chars.peek().is_some() {
+        let start = line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+
+        loop {
+            match chars.next() {
+                None => break,
+                Some('"') if quoted => match chars.peek() {
+                    Some('"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    _ => quoted = false,
+                },
+                Some('"') if field.is_empty() => quoted = true,
+                Some(',') if !quoted => fields.push(std::mem::take(&mut field)),
+                Some('\n') => {
+                    line += 1;
+                    match quoted {
+                        true => field.push('\n'),
+                        false => break,
+                    }
+                }
+                Some('\r') if !quoted => (),
+                Some(c) => field.push(c),
+            }
+        }
+
+        fields.push(field);
+
+        if quoted {
+            rows.push((start, Err(String::from("Unterminated quoted field"))));
+        } else if fields.len() > 1 || !fields[0].trim().is_empty() {
+            rows.push((start, Ok(fields)));
+        }
+    }
+
+    rows
+}
+
+// Quotes the field if it holds a comma, a quote or a line break, doubling any quotes in it
+fn quote(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::truth_table::DEFAULT_MAX_VARIABLES;
+
+    fn s(text: &str) -> String {
+        text.to_string()
+    }
+
+    // Grades the CSV text with the default limit
+    fn grade(input: &str) -> Vec<GradeRecord> {
+        grade_csv(input.as_bytes(), DEFAULT_MAX_VARIABLES).unwrap()
+    }
+
+    #[test]
+    fn test_read_rows() {
+        let rows = read_rows("a,\"b, c\",\"say \"\"hi\"\"\"\r\n\n\"two\nlines\",x\n\"open");
+
+        assert_eq!(
+            rows,
+            [
+                (1, Ok(vec![s("a"), s("b, c"), s("say \"hi\"")])),
+                (3, Ok(vec![s("two\nlines"), s("x")])),
+                (5, Err(s("Unterminated quoted field"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grade_csv() {
+        let records = grade(
+            "student,premises,conclusion,expected\n\
+             s1,p > q; p,q,valid\n\
+             s2,p > q; q,p,Valid\n\
+             s3,p; !p,q,inconsistent\n\
+             s4,p > q,\n\
+             s5,p &,q,valid\n\
+             s6,p,q,maybe\n",
+        );
+
+        let grades: Vec<String> = records
+            .iter()
+            .map(|record| record.get_grade().to_string())
+            .collect();
+        assert_eq!(
+            grades,
+            [
+                "correct",
+                "mismatch",
+                "correct",
+                "malformed",
+                "malformed",
+                "malformed"
+            ]
+        );
+
+        // Rows are numbered from the header
+        assert_eq!(records[0].get_row(), 2);
+        assert_eq!(records[1].get_expected(), Some("valid"));
+        assert_eq!(records[1].get_verdict().unwrap().to_string(), "invalid");
+        assert_eq!(records[3].get_student(), Some("s4"));
+        assert_eq!(
+            records[3].get_grade(),
+            &Grade::Malformed(s("Expected 4 fields but found 3"))
+        );
+        assert!(records[4].get_error().starts_with("Premise 1: "));
+        assert!(records[5]
+            .get_error()
+            .starts_with("Unknown verdict 'maybe'"));
+
+        assert_eq!(
+            tally(&records).to_string(),
+            "2 correct, 1 mismatched, 3 malformed"
+        );
+        assert!(render_lines(&records).starts_with("2 correct\n3 mismatch\n"));
+    }
+
+    #[test]
+    fn test_limit() {
+        let records = grade_csv("s1,a > b; b > c,c,invalid\n".as_bytes(), 2).unwrap();
+
+        // Without a header the first row is graded
+        assert_eq!(records[0].get_row(), 1);
+        assert_eq!(records[0].get_grade(), &Grade::LimitReached);
+        assert!(records[0].get_verdict().is_none());
+        assert_eq!(
+            tally(&records).to_string(),
+            "0 correct, 0 mismatched, 1 over the limit"
+        );
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let records = grade("s1,p > q; p,q,valid\n\"s,2\",p,q,\"oops\"\n");
+
+        let mut output = Vec::new();
+        write_csv(&records, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let rows = read_rows(&output);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].1, Ok(RESULTS_HEADER.split(',').map(s).collect()));
+
+        let second = rows[2].1.as_ref().unwrap();
+        assert_eq!(
+            second[..5],
+            [s("2"), s("s,2"), s(""), s(""), s("malformed")]
+        );
+        assert!(second[5].parse::<f64>().is_ok());
+        assert!(second[6].starts_with("Unknown verdict 'oops'"));
+    }
+}
@@ -1,8 +1,102 @@
+use std::ops::ControlFlow;
+use std::time::Duration;
+
+use crate::notation::Notation;
+use crate::observer::SolveObserver;
+use crate::premises::EvaluationRule;
+use crate::stats::SolveStats;
 use crate::Deduction;
+use crate::Premise;
+use crate::ValueMap;
 
 // Stores the history of the evaluation of the propositions in order to show work later
 pub struct EvaluationHistory {
     old_deduction_stacks: Vec<Deduction>,
+    steps: Vec<HistoryStep>,
+    value_changes: Vec<ValueChange>,
+    duration: Duration,
+}
+
+// Represents a root proposition's value changing during the solve
+// The step is the number of the step which produced the premise the value was read from,
+// or 0 if the premise was given that way, and the source is the label of that premise
+#[derive(Debug, Clone)]
+struct ValueChange {
+    step: usize,
+    proposition: char,
+    value: Option<bool>,
+    source: String,
+}
+
+// Represents a single change made to one premise while solving
+#[derive(Debug, Clone)]
+pub struct HistoryStep {
+    pass: usize,
+    action: StepAction,
+    label: String,
+    before: Premise,
+    after: Premise,
+}
+
+// Selects how much of the history is rendered
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum HistoryDetail {
+    // The whole premise stack after every pass, as it is displayed
+    Full,
+    // Only the premise changed in each step, before and after the change
+    Diff,
+    // Like Diff, but also showing each premise as it was written, so a line can be matched
+    // with what was typed
+    Written,
+}
+
+// Selects how much of a solve is rendered, from none of the work to every detail of it
+#[derive(Debug, Eq, PartialEq, Clone, Copy, PartialOrd, Ord)]
+pub enum RenderDetail {
+    // Nothing, leaving only the values found
+    Quiet,
+    // The original premises and conclusion
+    Premises,
+    // The premise stack after every pass, with each changed premise annotated with its action
+    Passes,
+    // Like Passes, also naming the rule used in each evaluation and listing the values found
+    // in each pass
+    Annotated,
+}
+
+// Represents the kind of change made to a premise in a step
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum StepAction {
+    Substitute,
+    Evaluate(EvaluationRule),
+    // A proposition was renamed throughout the Deduction
+    Rename { from: char, to: char },
+}
+
+impl std::fmt::Display for StepAction {
+    // Displays the action as it is annotated in the history
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StepAction::Substitute => write!(f, "SUBSTITUTE"),
+            StepAction::Evaluate(_) => write!(f, "EVALUATE"),
+            StepAction::Rename { .. } => write!(f, "RENAME"),
+        }
+    }
+}
+
+impl std::fmt::Display for EvaluationHistory {
+    // Displays the premise stack after every pass which changed a premise, separated by "=>",
+    // with each changed premise annotated with the action taken and each resolved premise shown
+    // with its value, such as "t = FALSE [EVALUATE]"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(Notation::Unicode))
+    }
+}
+
+impl Default for EvaluationHistory {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl EvaluationHistory {
@@ -10,11 +104,858 @@ impl EvaluationHistory {
     pub fn new() -> Self {
         Self {
             old_deduction_stacks: Vec::new(),
+            steps: Vec::new(),
+            value_changes: Vec::new(),
+            duration: Duration::ZERO,
         }
     }
 
     // Adds a Deduction to the history
-    fn push(&mut self, deduction: Deduction) {
+    pub fn push(&mut self, deduction: Deduction) {
         self.old_deduction_stacks.push(deduction);
     }
+
+    // Sets how long the solve took
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
+    // Returns how long the solve took
+    pub fn get_duration(&self) -> Duration {
+        self.duration
+    }
+
+    // Summarizes the solve with counts of the steps taken and the size of the premise stack
+    pub fn stats(&self) -> SolveStats {
+        let mut stats = SolveStats {
+            passes: self.old_deduction_stacks.len(),
+            steps: self.steps.len(),
+            duration: self.duration,
+            ..Default::default()
+        };
+
+        for step in &self.steps {
+            match step.action {
+                StepAction::Substitute => stats.substitutions += 1,
+                StepAction::Evaluate(rule) => *stats.rewrites.entry(rule).or_insert(0) += 1,
+                StepAction::Rename { .. } => (),
+            }
+        }
+
+        let stack_sizes: Vec<usize> = self
+            .old_deduction_stacks
+            .iter()
+            .map(|deduction| deduction.get_num_premises())
+            .collect();
+
+        stats.peak_stack_size = stack_sizes.iter().copied().max().unwrap_or(0);
+
+        let complexities: Vec<u32> = self
+            .old_deduction_stacks
+            .iter()
+            .map(|deduction| {
+                deduction
+                    .get_labeled_premises()
+                    .iter()
+                    .map(|(_, premise)| premise.complexity())
+                    .sum()
+            })
+            .collect();
+
+        stats.initial_complexity = complexities.first().copied().unwrap_or(0);
+        stats.final_complexity = complexities.last().copied().unwrap_or(0);
+        stats.premises_removed = match (stack_sizes.first(), stack_sizes.last()) {
+            (Some(first), Some(last)) => first - last,
+            _ => 0,
+        };
+
+        stats
+    }
+
+    // Returns the Deductions stored at the end of each pass, starting with the initial one
+    pub fn get_deductions(&self) -> &Vec<Deduction> {
+        &self.old_deduction_stacks
+    }
+
+    // Returns all recorded steps in the order they were taken
+    pub fn get_steps(&self) -> &Vec<HistoryStep> {
+        &self.steps
+    }
+
+    // Returns the number of the step after which the value of the given proposition was first known,
+    // where 0 means it was known from the given premises, or None if it was never determined
+    pub fn step_where_determined(&self, proposition: char) -> Option<usize> {
+        self.value_changes
+            .iter()
+            .find(|change| change.proposition == proposition && change.value.is_some())
+            .map(|change| change.step)
+    }
+
+    // Returns the values of all root propositions as they were known after the given step
+    // The values are rebuilt from the recorded changes rather than stored for every step
+    pub fn values_at(&self, step: usize) -> ValueMap {
+        let mut values = ValueMap::default();
+
+        // Every proposition in the Deduction starts out unknown
+        if let Some(initial) = self.old_deduction_stacks.first() {
+            for (proposition, _) in initial.get_values().iter() {
+                values.set_value(proposition, None);
+            }
+        }
+
+        for change in self
+            .value_changes
+            .iter()
+            .filter(|change| change.step <= step)
+        {
+            values.set_value(change.proposition, change.value);
+        }
+
+        values
+    }
+
+    // Returns the label of the premise the value of the given proposition was first read from,
+    // or None if it was never determined
+    pub fn source_of(&self, proposition: char) -> Option<&str> {
+        self.value_changes
+            .iter()
+            .find(|change| change.proposition == proposition && change.value.is_some())
+            .map(|change| change.source.as_str())
+    }
+
+    // Explains how the value of the given proposition was found, with one line for the premise
+    // it was read from followed by the explanations of the values that premise needed, indented
+    // below it, such as:
+    // m = TRUE from P2: (f ∨ s) → m
+    //   f = TRUE from P5: f
+    // Returns None if the value was never determined
+    pub fn explain(&self, proposition: char) -> Option<String> {
+        let mut explanation = String::new();
+        self.explain_into(proposition, 0, &mut Vec::new(), &mut explanation)?;
+
+        Some(explanation)
+    }
+
+    // Adds the explanation of a single value at the given depth, skipping any proposition already
+    // being explained further up the chain
+    fn explain_into(
+        &self,
+        proposition: char,
+        depth: usize,
+        chain: &mut Vec<char>,
+        explanation: &mut String,
+    ) -> Option<()> {
+        let change = self
+            .value_changes
+            .iter()
+            .find(|change| change.proposition == proposition && change.value.is_some())?;
+
+        let (_, premise) = self
+            .old_deduction_stacks
+            .first()?
+            .get_labeled_premises()
+            .into_iter()
+            .find(|(label, _)| *label == change.source)?;
+
+        explanation.push_str(&format!(
+            "{}{} = {} from {}: {}\n",
+            "  ".repeat(depth),
+            proposition,
+            Notation::Unicode.truth_value(change.value?),
+            change.source,
+            premise
+        ));
+
+        // Only the values known by the time this one was found can have been used to find it
+        let mut used = ValueMap::default();
+        used.add_propositions(premise);
+
+        let mut used: Vec<char> = used
+            .iter()
+            .map(|(used, _)| used)
+            .filter(|used| *used != proposition && !chain.contains(used))
+            .filter(|used| {
+                self.step_where_determined(*used)
+                    .is_some_and(|step| step <= change.step)
+            })
+            .collect();
+        used.sort();
+
+        chain.push(proposition);
+        for used in used {
+            self.explain_into(used, depth + 1, chain, explanation);
+        }
+        chain.pop();
+
+        Some(())
+    }
+
+    // Returns every change to the value of the given proposition along with the step it happened after
+    pub fn timeline(&self, proposition: char) -> Vec<(usize, Option<bool>)> {
+        self.value_changes
+            .iter()
+            .filter(|change| change.proposition == proposition)
+            .map(|change| (change.step, change.value))
+            .collect()
+    }
+
+    // Returns each step as a single line in a terse, machine-friendly format, such as
+    // step=3 action=SUBSTITUTE premise=P2 from="(f ∨ s) → m" to="(TRUE ∨ s) → m"
+    pub fn trace_lines(&self) -> impl Iterator<Item = String> + '_ {
+        self.render_trace_lines(Notation::Unicode)
+    }
+
+    // Returns the lines of trace_lines with the premises rendered in the given notation
+    pub fn render_trace_lines(&self, notation: Notation) -> impl Iterator<Item = String> + '_ {
+        self.steps.iter().enumerate().map(move |(i, step)| {
+            format!(
+                "step={} action={} premise={} from={} to={}",
+                i + 1,
+                step.action,
+                step.get_label(),
+                quote_trace_field(&step.before.render(notation)),
+                quote_trace_field(&step.after.render(notation)),
+            )
+        })
+    }
+
+    // Renders the history as it is displayed, using the symbols of the given notation
+    // LaTeX is rendered as an array with the actions in a second column and a rule between passes
+    pub fn render(&self, notation: Notation) -> String {
+        self.render_at(RenderDetail::Passes, notation)
+    }
+
+    // Renders as much of the history as the given detail asks for, in the same way as render
+    pub fn render_at(&self, detail: RenderDetail, notation: Notation) -> String {
+        let latex = notation == Notation::Latex;
+        let annotated = detail == RenderDetail::Annotated;
+        let mut rendered = String::new();
+
+        if detail == RenderDetail::Quiet {
+            return rendered;
+        }
+
+        if latex {
+            rendered.push_str("\\begin{array}{ll}\n");
+        }
+
+        for (pass, deduction) in self.old_deduction_stacks.iter().enumerate() {
+            let pass_steps: Vec<&HistoryStep> =
+                self.steps.iter().filter(|step| step.pass == pass).collect();
+
+            // Passes which only removed resolved premises are not shown
+            if pass > 0 {
+                if pass_steps.is_empty() || detail == RenderDetail::Premises {
+                    continue;
+                }
+
+                rendered.push_str(if latex { "\\hline\n" } else { "\n=>\n" });
+            }
+
+            // The premises alone are shown as they were given, without the values read from them
+            for (label, premise) in deduction.get_labeled_premises() {
+                let text = match premise.get_value_if_root_proposition() {
+                    Some((proposition, value)) if detail != RenderDetail::Premises => {
+                        format!("{} = {}", proposition, notation.truth_value(value))
+                    }
+                    _ => premise.render(notation),
+                };
+
+                let action = pass_steps
+                    .iter()
+                    .find(|step| step.label == label)
+                    .map(|step| match (step.action, annotated) {
+                        (StepAction::Evaluate(rule), true) => format!("EVALUATE: {}", rule),
+                        (action, _) => action.to_string(),
+                    });
+
+                push_history_line(&mut rendered, latex, &text, action.as_deref());
+            }
+
+            // The conclusion never changes, so it is only shown with the original premises
+            if let (0, Some(conclusion)) = (pass, deduction.get_conclusion()) {
+                let text = format!("{} {}", notation.therefore(), conclusion.render(notation));
+                push_history_line(&mut rendered, latex, &text, None);
+            }
+
+            if let (true, Some(before)) = (annotated, pass.checked_sub(1)) {
+                let found = render_found_values(
+                    self.old_deduction_stacks[before].get_values(),
+                    deduction.get_values(),
+                    notation,
+                );
+
+                if !found.is_empty() {
+                    let text = match latex {
+                        true => format!("\\text{{values:}} {}", found),
+                        false => format!("values: {}", found),
+                    };
+                    push_history_line(&mut rendered, latex, &text, None);
+                }
+            }
+        }
+
+        if latex {
+            rendered.push_str("\\end{array}\n");
+        }
+
+        rendered
+    }
+}
+
+impl EvaluationHistory {
+    // Renders the history with the given amount of detail, using the symbols of the given notation
+    pub fn render_detail(&self, detail: HistoryDetail, notation: Notation) -> String {
+        match detail {
+            HistoryDetail::Full => self.render(notation),
+            HistoryDetail::Diff => self.render_diff(notation),
+            HistoryDetail::Written => self.render_steps(notation, true),
+        }
+    }
+
+    // Renders one line per step with the premise before and after it, such as
+    // P2: (f ∨ s) → m => (TRUE ∨ s) → m [SUBSTITUTE]
+    // Passes are separated by a blank line, or a rule in LaTeX
+    pub fn render_diff(&self, notation: Notation) -> String {
+        self.render_steps(notation, false)
+    }
+
+    // Renders one line per step like render_diff, also showing the premise after each step as it
+    // was written if as_written is set
+    fn render_steps(&self, notation: Notation, as_written: bool) -> String {
+        let latex = notation == Notation::Latex;
+        let mut rendered = String::new();
+
+        if latex {
+            rendered.push_str("\\begin{array}{ll}\n");
+        }
+
+        for (i, step) in self.steps.iter().enumerate() {
+            if i > 0 && self.steps[i - 1].pass != step.pass {
+                rendered.push_str(if latex { "\\hline\n" } else { "\n" });
+            }
+
+            let after = match as_written {
+                true => step.after.render_as_written(notation),
+                false => step.after.render(notation),
+            };
+
+            let text = match latex {
+                true => format!(
+                    "\\text{{{}:}} {} \\Rightarrow {}",
+                    step.label,
+                    step.before.render(notation),
+                    after
+                ),
+                false => format!(
+                    "{}: {} => {}",
+                    step.label,
+                    step.before.render(notation),
+                    after
+                ),
+            };
+
+            push_history_line(&mut rendered, latex, &text, Some(&step.action.to_string()));
+        }
+
+        if latex {
+            rendered.push_str("\\end{array}\n");
+        }
+
+        rendered
+    }
+
+    // Renders the history as Markdown, listing the original premises and conclusion followed by a
+    // table of the steps in each pass, using the symbols of the given notation
+    pub fn render_markdown(&self, notation: Notation) -> String {
+        let mut rendered = String::from("**Premises**\n\n");
+
+        if let Some(deduction) = self.old_deduction_stacks.first() {
+            for (label, premise) in deduction.get_labeled_premises() {
+                rendered.push_str(&format!("- {}: `{}`\n", label, premise.render(notation)));
+            }
+
+            if let Some(conclusion) = deduction.get_conclusion() {
+                rendered.push_str(&format!(
+                    "\n**Conclusion:** `{}`\n",
+                    conclusion.render(notation)
+                ));
+            }
+        }
+
+        for (i, step) in self.steps.iter().enumerate() {
+            if i == 0 || self.steps[i - 1].pass != step.pass {
+                rendered.push_str(&format!(
+                    "\n**Pass {}**\n\n| Premise | Before | After | Action |\n|---|---|---|---|\n",
+                    step.pass
+                ));
+            }
+
+            rendered.push_str(&format!(
+                "| {} | `{}` | `{}` | {} |\n",
+                step.label,
+                markdown_cell(&step.before.render(notation)),
+                markdown_cell(&step.after.render(notation)),
+                step.action
+            ));
+        }
+
+        rendered
+    }
+
+    // Renders every pass of the solve as two columns, with the premise stack before the pass on the
+    // left and after it on the right, so each line fits within the given width
+    pub fn render_side_by_side(&self, width: usize) -> String {
+        (1..self.old_deduction_stacks.len())
+            .map(|pass| self.render_pass_side_by_side(pass, width))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    // Renders a single pass as two columns, marking each changed row with a '*' in the gutter
+    // Premises too long for their column are wrapped onto the following rows
+    pub fn render_pass_side_by_side(&self, pass: usize, width: usize) -> String {
+        let (Some(before), Some(after)) = (
+            pass.checked_sub(1)
+                .and_then(|before| self.old_deduction_stacks.get(before)),
+            self.old_deduction_stacks.get(pass),
+        ) else {
+            return String::new();
+        };
+
+        // Leave room for the gutter and the separator between the columns
+        let column_width = (width.saturating_sub(5) / 2).max(1);
+
+        let after_premises = after.get_labeled_premises();
+        let mut rendered = format!("Pass {}\n", pass);
+
+        // Premises keep their order on the stack, so every premise after the pass was also there before it
+        for (label, premise) in before.get_labeled_premises() {
+            let after_premise = after_premises
+                .iter()
+                .find(|(after_label, _)| *after_label == label)
+                .map(|(_, after_premise)| *after_premise);
+
+            let is_changed = after_premise != Some(premise);
+
+            let left = wrap_text(&format!("{} {}", label, premise), column_width);
+            let right = match after_premise {
+                Some(after_premise) => {
+                    wrap_text(&format!("{} {}", label, after_premise), column_width)
+                }
+                None => Vec::new(),
+            };
+
+            for row in 0..left.len().max(right.len()) {
+                let gutter = if is_changed && row == 0 { '*' } else { ' ' };
+                let left_text = left.get(row).map_or("", String::as_str);
+                let right_text = right.get(row).map_or("", String::as_str);
+
+                let line = format!(
+                    "{} {:<width$} | {}",
+                    gutter,
+                    left_text,
+                    right_text,
+                    width = column_width
+                );
+
+                rendered.push_str(line.trim_end());
+                rendered.push('\n');
+            }
+        }
+
+        rendered
+    }
+}
+
+impl SolveObserver for EvaluationHistory {
+    // Records how long the solve took, however it was started
+    fn on_elapsed(&mut self, elapsed: Duration) {
+        self.duration = elapsed;
+    }
+
+    // Stores the Deduction as it is at the start of each pass
+    fn on_step_start(&mut self, _pass: usize, deduction: &Deduction) -> ControlFlow<()> {
+        self.push(deduction.clone());
+        ControlFlow::Continue(())
+    }
+
+    // Records a change made to a premise during the current pass
+    fn on_premise_changed(
+        &mut self,
+        label: &str,
+        before: &Premise,
+        after: &Premise,
+        action: StepAction,
+    ) -> ControlFlow<()> {
+        self.steps.push(HistoryStep {
+            pass: self.old_deduction_stacks.len(),
+            action,
+            label: label.to_string(),
+            before: before.clone(),
+            after: after.clone(),
+        });
+
+        ControlFlow::Continue(())
+    }
+
+    // Renames the proposition in every recorded value change, so the values found before the
+    // rename are still known by the new name
+    fn on_proposition_renamed(&mut self, from: char, to: char) -> ControlFlow<()> {
+        for change in &mut self.value_changes {
+            if change.proposition == from {
+                change.proposition = to;
+            }
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    // Records the new value along with the step which produced the premise it came from
+    fn on_value_derived(
+        &mut self,
+        proposition: char,
+        value: bool,
+        source: &str,
+    ) -> ControlFlow<()> {
+        let step = self
+            .steps
+            .iter()
+            .rposition(|step| step.label == source)
+            .map_or(0, |i| i + 1);
+
+        self.value_changes.push(ValueChange {
+            step,
+            proposition,
+            value: Some(value),
+            source: source.to_string(),
+        });
+
+        ControlFlow::Continue(())
+    }
+}
+
+impl HistoryStep {
+    // Returns the pass the step was taken in
+    pub fn get_pass(&self) -> usize {
+        self.pass
+    }
+
+    // Returns the kind of change made in the step
+    pub fn get_action(&self) -> StepAction {
+        self.action
+    }
+
+    // Returns the label of the changed premise
+    pub fn get_label(&self) -> &str {
+        &self.label
+    }
+
+    // Returns the premise before the step
+    pub fn get_before(&self) -> &Premise {
+        &self.before
+    }
+
+    // Returns the premise after the step
+    pub fn get_after(&self) -> &Premise {
+        &self.after
+    }
+}
+
+// Adds one line of the rendered history, annotated with the action taken on it if any
+// In LaTeX the annotation goes in the array's second column
+fn push_history_line(rendered: &mut String, latex: bool, text: &str, action: Option<&str>) {
+    let line = match (latex, action) {
+        (false, Some(action)) => format!("{} [{}]\n", text, action),
+        (false, None) => format!("{}\n", text),
+        (true, Some(action)) => format!("{} & \\text{{[{}]}} \\\\\n", text, action),
+        (true, None) => format!("{} & \\\\\n", text),
+    };
+
+    rendered.push_str(&line);
+}
+
+// Renders the values which became known between two sets of values, such as "f = TRUE, t = FALSE"
+fn render_found_values(before: &ValueMap, after: &ValueMap, notation: Notation) -> String {
+    let mut found: Vec<(char, bool)> = after
+        .iter()
+        .filter_map(
+            |(proposition, value)| match (before.get_value(proposition), value) {
+                (None, Some(value)) => Some((proposition, value)),
+                _ => None,
+            },
+        )
+        .collect();
+    found.sort();
+
+    found
+        .into_iter()
+        .map(|(proposition, value)| format!("{} = {}", proposition, notation.truth_value(value)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// Escapes the pipes in the text so it can be placed in a Markdown table cell
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+// Splits the text into lines of at most the given number of characters, breaking at spaces
+// where possible and splitting any word which is too long on its own
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split(' ') {
+        let word: Vec<char> = word.chars().collect();
+
+        for piece in word.chunks(width) {
+            let line_length = line.chars().count();
+
+            if line_length > 0 && line_length + 1 + piece.len() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+
+            if !line.is_empty() {
+                line.push(' ');
+            }
+
+            line.extend(piece);
+        }
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+// Wraps a field of a trace line in double quotes, escaping any backslashes or double quotes inside it
+fn quote_trace_field(field: &str) -> String {
+    format!("\"{}\"", field.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_lines() {
+        let mut deduction = Deduction::from_strs(vec!["(f | s) > m", "f"]).unwrap();
+        let history = deduction.solve();
+
+        let lines: Vec<String> = history.trace_lines().collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "step=1 action=SUBSTITUTE premise=P1 from=\"(f ∨ s) → m\" to=\"(TRUE ∨ s) → m\"",
+                "step=2 action=EVALUATE premise=P1 from=\"(TRUE ∨ s) → m\" to=\"TRUE → m\"",
+                "step=3 action=EVALUATE premise=P1 from=\"TRUE → m\" to=\"m\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_diff() {
+        let mut deduction = Deduction::from_strs(vec!["(f | s) > m", "f", "m > q"]).unwrap();
+        let history = deduction.solve();
+
+        assert_eq!(
+            history.render_detail(HistoryDetail::Diff, Notation::Ascii),
+            "\
+P1: (f | s) > m => (TRUE | s) > m [SUBSTITUTE]
+
+P1: (TRUE | s) > m => TRUE > m [EVALUATE]
+
+P1: TRUE > m => m [EVALUATE]
+
+P3: m > q => TRUE > q [SUBSTITUTE]
+
+P3: TRUE > q => q [EVALUATE]
+"
+        );
+
+        assert_eq!(
+            history.render_detail(HistoryDetail::Full, Notation::Unicode),
+            history.to_string()
+        );
+
+        let written = history.render_detail(HistoryDetail::Written, Notation::Unicode);
+        assert!(written.starts_with(
+            "P1: (f ∨ s) → m => (TRUE ∨ s) → m (as written: (f | s) > m) [SUBSTITUTE]\n"
+        ));
+        assert!(written.ends_with("P3: TRUE → q => q (as written: m > q) [EVALUATE]\n"));
+        assert!(history
+            .render_diff(Notation::Latex)
+            .contains("\\text{P1:} (f \\lor s) \\to m \\Rightarrow (\\top \\lor s) \\to m & \\text{[SUBSTITUTE]} \\\\\n"));
+    }
+
+    #[test]
+    fn test_render_at() {
+        let mut deduction = Deduction::from_strs(vec!["(f | s) > m", "f"]).unwrap();
+        let history = deduction.solve();
+
+        assert_eq!(history.render_at(RenderDetail::Quiet, Notation::Ascii), "");
+        assert_eq!(
+            history.render_at(RenderDetail::Premises, Notation::Ascii),
+            "(f | s) > m\nf\n"
+        );
+        assert_eq!(
+            history.render_at(RenderDetail::Passes, Notation::Unicode),
+            history.to_string()
+        );
+
+        let annotated = history.render_at(RenderDetail::Annotated, Notation::Ascii);
+        assert!(annotated.contains("(TRUE | s) > m [SUBSTITUTE]\n"));
+        assert!(annotated.contains("TRUE > m [EVALUATE: disjunction]\n"));
+        assert!(annotated.ends_with("m = TRUE [EVALUATE: implication]\nvalues: m = TRUE\n"));
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let mut deduction = Deduction::from_strs(vec!["(f | s) > m", "f"]).unwrap();
+        let history = deduction.solve();
+
+        assert_eq!(
+            history.render_markdown(Notation::Ascii),
+            "\
+**Premises**
+
+- P1: `(f | s) > m`
+- P2: `f`
+
+**Pass 1**
+
+| Premise | Before | After | Action |
+|---|---|---|---|
+| P1 | `(f \\| s) > m` | `(TRUE \\| s) > m` | SUBSTITUTE |
+
+**Pass 2**
+
+| Premise | Before | After | Action |
+|---|---|---|---|
+| P1 | `(TRUE \\| s) > m` | `TRUE > m` | EVALUATE |
+
+**Pass 3**
+
+| Premise | Before | After | Action |
+|---|---|---|---|
+| P1 | `TRUE > m` | `m` | EVALUATE |
+"
+        );
+    }
+
+    #[test]
+    fn test_step_where_determined() {
+        let mut deduction =
+            Deduction::from_strs(vec!["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"])
+                .unwrap();
+        let history = deduction.solve();
+
+        // The values are determined in the same order as the walkthrough in main.rs
+        assert_eq!(history.step_where_determined('f'), Some(0));
+        assert_eq!(history.step_where_determined('t'), Some(4));
+        assert_eq!(history.step_where_determined('m'), Some(5));
+        assert_eq!(history.step_where_determined('b'), Some(8));
+        assert_eq!(history.step_where_determined('j'), Some(12));
+        assert_eq!(history.step_where_determined('s'), None);
+
+        assert_eq!(history.timeline('b'), vec![(8, Some(false))]);
+        assert_eq!(history.timeline('s'), vec![]);
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut deduction =
+            Deduction::from_strs(vec!["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"])
+                .unwrap();
+        let history = deduction.solve();
+
+        assert_eq!(history.source_of('j'), Some("P1"));
+        assert_eq!(history.source_of('f'), Some("P5"));
+        assert_eq!(history.source_of('s'), None);
+
+        assert_eq!(
+            history.explain('j').unwrap(),
+            "\
+j = TRUE from P1: (m ∧ ¬b) → j
+  b = FALSE from P3: b → t
+    t = FALSE from P4: f → ¬t
+      f = TRUE from P5: f
+  m = TRUE from P2: (f ∨ s) → m
+    f = TRUE from P5: f
+"
+        );
+        assert_eq!(history.explain('s'), None);
+    }
+
+    #[test]
+    fn test_values_at() {
+        let mut deduction =
+            Deduction::from_strs(vec!["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"])
+                .unwrap();
+        let history = deduction.solve();
+
+        let values = history.values_at(0);
+        assert_eq!(values.get_value('f'), Some(true));
+        assert_eq!(values.get_value('t'), None);
+
+        let values = history.values_at(5);
+        assert_eq!(values.get_value('f'), Some(true));
+        assert_eq!(values.get_value('t'), Some(false));
+        assert_eq!(values.get_value('m'), Some(true));
+        assert_eq!(values.get_value('b'), None);
+        assert_eq!(values.get_value('j'), None);
+
+        let values = history.values_at(history.get_steps().len());
+        assert_eq!(values.to_string(), deduction.get_values().to_string());
+    }
+
+    #[test]
+    fn test_render_side_by_side() {
+        let mut deduction =
+            Deduction::from_strs(vec!["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"])
+                .unwrap();
+        let history = deduction.solve();
+
+        let expected = "\
+Pass 1
+  P1 (m ∧ ¬b) → j             | P1 (m ∧ ¬b) → j
+* P2 (f ∨ s) → m              | P2 (TRUE ∨ s) → m
+  P3 b → t                    | P3 b → t
+* P4 f → ¬t                   | P4 TRUE → ¬t
+* P5 f                        |
+";
+
+        assert_eq!(history.render_pass_side_by_side(1, 60), expected);
+
+        let expected = "\
+Pass 1
+  P1 (m ∧ ¬b)  | P1 (m ∧ ¬b)
+  → j          | → j
+* P2 (f ∨ s) → | P2 (TRUE ∨
+  m            | s) → m
+  P3 b → t     | P3 b → t
+* P4 f → ¬t    | P4 TRUE → ¬t
+* P5 f         |
+";
+
+        assert_eq!(history.render_pass_side_by_side(1, 29), expected);
+
+        for line in history.render_side_by_side(29).lines() {
+            assert!(line.chars().count() <= 29);
+        }
+    }
+
+    #[test]
+    fn test_quote_trace_field() {
+        assert_eq!(quote_trace_field("p → q"), "\"p → q\"");
+        assert_eq!(
+            quote_trace_field("\"rain\" → wet"),
+            "\"\\\"rain\\\" → wet\""
+        );
+        assert_eq!(quote_trace_field("a \\ b"), "\"a \\\\ b\"");
+    }
 }
@@ -0,0 +1,385 @@
+use serde_json::{json, Map, Value};
+
+use crate::entailment::{Consistency, Entailment};
+use crate::history::{EvaluationHistory, HistoryDetail, HistoryStep, StepAction};
+use crate::observer::{SolveLimit, SolveOptions, SolveResult};
+use crate::stats::SolveStats;
+use crate::truth_table::{Formula, RowFilter, TruthTable};
+use crate::Deduction;
+use crate::ValueMap;
+
+impl ValueMap {
+    // Converts the values to a JSON object from each proposition to its value, or null if unknown
+    pub fn to_json(&self) -> Value {
+        Value::Object(
+            self.iter()
+                .map(|(proposition, value)| (proposition.to_string(), json!(value)))
+                .collect(),
+        )
+    }
+}
+
+impl Deduction {
+    // Converts the premises left on the stack, the conclusion and the options to a JSON argument
+    // in the format read by from_json, giving every premise its label and any comment
+    // Options which are not set are left out, as is the strategy, which can only be "passes"
+    pub fn to_json(&self, options: &SolveOptions) -> Value {
+        let premises: Vec<Value> = self
+            .get_labeled_premises()
+            .into_iter()
+            .map(|(label, premise)| {
+                let mut json = json!({ "formula": premise.to_string(), "label": label });
+                if let Some(comment) = self.get_premise_comment(&label) {
+                    json["comment"] = json!(comment);
+                }
+
+                json
+            })
+            .collect();
+
+        let mut json = json!({ "premises": premises });
+
+        if let Some(conclusion) = self.get_conclusion() {
+            json["conclusion"] = json!(conclusion.to_string());
+        }
+
+        if !self.get_assumptions().is_empty() {
+            json["assumptions"] = self.assumptions_to_json();
+        }
+
+        let mut solve_options = Map::new();
+        if let Some(max_steps) = options.max_steps {
+            solve_options.insert(String::from("max_steps"), json!(max_steps));
+        }
+        if let Some(timeout) = options.timeout {
+            solve_options.insert(
+                String::from("timeout_ms"),
+                json!(timeout.as_millis() as u64),
+            );
+        }
+        if let Some(history) = options.history {
+            let history = match history {
+                HistoryDetail::Full => "full",
+                HistoryDetail::Diff => "diff",
+                HistoryDetail::Written => "written",
+            };
+            solve_options.insert(String::from("history"), json!(history));
+        }
+
+        if !solve_options.is_empty() {
+            json["options"] = Value::Object(solve_options);
+        }
+
+        json
+    }
+
+    // Converts the problem to a JSON argument in the shorter format read by from_json, such as
+    // {"premises": ["(m ∧ b) → j", "f"], "conclusion": "j", "assumptions": {"f": true}}
+    // Only the premises left on the stack are written, so this describes the problem rather than
+    // its solution if called before solving, and labels, comments and options are left out
+    pub fn problem_to_json(&self) -> Value {
+        let premises: Vec<String> = self
+            .get_labeled_premises()
+            .into_iter()
+            .map(|(_, premise)| premise.to_string())
+            .collect();
+
+        let mut json = json!({ "premises": premises });
+
+        if let Some(conclusion) = self.get_conclusion() {
+            json["conclusion"] = json!(conclusion.to_string());
+        }
+
+        if !self.get_assumptions().is_empty() {
+            json["assumptions"] = self.assumptions_to_json();
+        }
+
+        json
+    }
+
+    // Converts the assumptions to a JSON object from each proposition to its value
+    fn assumptions_to_json(&self) -> Value {
+        Value::Object(
+            self.get_assumptions()
+                .iter()
+                .map(|(proposition, value)| (proposition.to_string(), json!(value)))
+                .collect(),
+        )
+    }
+}
+
+// Returns a single word summing up a solve, which is the verdict of a proof if there was one,
+// otherwise the value of the conclusion, or whether the solve finished if there is no conclusion
+fn solve_outcome(
+    deduction: &Deduction,
+    result: SolveResult,
+    consistent: bool,
+    entailment: Option<&Entailment>,
+) -> &'static str {
+    match (entailment, deduction.get_conclusion()) {
+        _ if !consistent => "inconsistent",
+        (Some(Entailment::Proved), _) => "proved",
+        (Some(_), _) => "not_proved",
+        (None, Some(_)) => match deduction.evaluate_conclusion() {
+            Some(true) => "true",
+            Some(false) => "false",
+            None => "unknown",
+        },
+        (None, None) if result == SolveResult::Complete => "solved",
+        (None, None) => "partial",
+    }
+}
+
+impl EvaluationHistory {
+    // Converts the history of a solve to the JSON object the solve command prints, adding the
+    // values found, how the solve ended and whether the premises were found to be consistent,
+    // along with the verdict on the conclusion and whether the premises entail it if known
+    pub fn to_solve_json(
+        &self,
+        deduction: &Deduction,
+        result: SolveResult,
+        consistent: bool,
+        entailment: Option<&Entailment>,
+    ) -> Value {
+        let mut json = self.to_json();
+        json["values"] = deduction.get_values().to_json();
+        json["completed"] = json!(result == SolveResult::Complete);
+
+        if let SolveResult::LimitReached(limit) = result {
+            json["limit"] = json!(match limit {
+                SolveLimit::Steps => "max-steps",
+                SolveLimit::Timeout => "timeout",
+            });
+        }
+        json["consistent"] = json!(consistent);
+
+        if deduction.get_conclusion().is_some() {
+            json["verdict"] = json!(deduction.evaluate_conclusion());
+        }
+
+        if let Some(entailment) = entailment {
+            json["entailment"] = entailment.to_json();
+        }
+
+        json["outcome"] = json!(solve_outcome(deduction, result, consistent, entailment));
+        json["stats"] = self.stats().to_json();
+
+        json
+    }
+
+    // Converts the history to a JSON object holding the original premises with their labels,
+    // the conclusion if there is one, and every step taken
+    pub fn to_json(&self) -> Value {
+        let (premises, conclusion) = match self.get_deductions().first() {
+            Some(deduction) => (
+                deduction
+                    .get_labeled_premises()
+                    .into_iter()
+                    .map(|(label, premise)| {
+                        json!({ "label": label, "premise": premise.to_string() })
+                    })
+                    .collect(),
+                deduction.get_conclusion().map(|conclusion| conclusion.to_string()),
+            ),
+            None => (Vec::new(), None),
+        };
+
+        let steps: Vec<Value> = self
+            .get_steps()
+            .iter()
+            .enumerate()
+            .map(|(i, step)| step.to_json(i + 1))
+            .collect();
+
+        json!({
+            "premises": premises,
+            "conclusion": conclusion,
+            "steps": steps,
+        })
+    }
+}
+
+impl SolveStats {
+    // Converts the statistics to a JSON object, with the number of evaluation steps using each
+    // rule under its name and the duration in seconds
+    pub fn to_json(&self) -> Value {
+        let rewrites: Map<String, Value> = self
+            .rewrites
+            .iter()
+            .map(|(rule, count)| (rule.to_string(), json!(count)))
+            .collect();
+
+        json!({
+            "passes": self.passes,
+            "steps": self.steps,
+            "substitutions": self.substitutions,
+            "evaluations": self.get_num_evaluations(),
+            "rewrites": rewrites,
+            "premises_removed": self.premises_removed,
+            "peak_stack_size": self.peak_stack_size,
+            "initial_complexity": self.initial_complexity,
+            "final_complexity": self.final_complexity,
+            "duration": self.duration.as_secs_f64(),
+        })
+    }
+}
+
+impl HistoryStep {
+    // Converts the step to a JSON object, numbered from 1 like the trace lines
+    // The rule is null for substitutions
+    pub fn to_json(&self, number: usize) -> Value {
+        let rule = match self.get_action() {
+            StepAction::Substitute | StepAction::Rename { .. } => None,
+            StepAction::Evaluate(rule) => Some(rule.to_string()),
+        };
+
+        json!({
+            "step": number,
+            "pass": self.get_pass(),
+            "premise": self.get_label(),
+            "action": self.get_action().to_string(),
+            "rule": rule,
+            "from": self.get_before().to_string(),
+            "to": self.get_after().to_string(),
+        })
+    }
+}
+
+impl Entailment {
+    // Converts the result to a JSON object with the counterexample, or null if it was proved
+    pub fn to_json(&self) -> Value {
+        let (result, counterexample) = match self {
+            Entailment::Proved => ("proved", Value::Null),
+            Entailment::NotProvable { counterexample } => {
+                ("not provable", counterexample.to_json())
+            }
+            Entailment::Contradicted { counterexample } => {
+                ("contradicted", counterexample.to_json())
+            }
+        };
+
+        json!({ "result": result, "counterexample": counterexample })
+    }
+}
+
+impl Consistency {
+    // Converts the result to a JSON object with the model if the premises are consistent,
+    // or the premises in the conflicting core if they are not
+    pub fn to_json(&self) -> Value {
+        match self {
+            Consistency::Consistent { model } => {
+                json!({ "consistent": true, "model": model.to_json() })
+            }
+            Consistency::Inconsistent { core } => {
+                let core: Vec<Value> = core
+                    .iter()
+                    .map(|premise| {
+                        json!({
+                            "label": premise.label,
+                            "line": premise.line,
+                            "premise": premise.premise.to_string(),
+                        })
+                    })
+                    .collect();
+
+                json!({ "consistent": false, "core": core })
+            }
+        }
+    }
+}
+
+impl<F: Formula> TruthTable<F> {
+    // Converts the rows which match the filter to a JSON object, with the values of each row
+    // keyed by proposition
+    pub fn to_json(&self, filter: RowFilter) -> Value {
+        let rows: Vec<Value> = self
+            .get_rows(filter)
+            .map(|row| {
+                let values: Map<String, Value> = self
+                    .get_propositions()
+                    .iter()
+                    .zip(row.get_values())
+                    .map(|(proposition, value)| (proposition.to_string(), json!(value)))
+                    .collect();
+
+                json!({ "values": values, "result": row.get_result() })
+            })
+            .collect();
+
+        let propositions: Vec<String> = self
+            .get_propositions()
+            .iter()
+            .map(char::to_string)
+            .collect();
+
+        json!({
+            "formula": self.get_formula().to_string(),
+            "propositions": propositions,
+            "rows": rows,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::truth_table::DEFAULT_MAX_VARIABLES;
+    use crate::Deduction;
+    use crate::Premise;
+
+    #[test]
+    fn test_history_to_json() {
+        let mut deduction = Deduction::from_strs(vec!["p > q", "p"]).unwrap();
+        let history = deduction.solve();
+
+        assert_eq!(
+            history.to_json(),
+            json!({
+                "premises": [
+                    { "label": "P1", "premise": "p → q" },
+                    { "label": "P2", "premise": "p" },
+                ],
+                "conclusion": null,
+                "steps": [
+                    {
+                        "step": 1,
+                        "pass": 1,
+                        "premise": "P1",
+                        "action": "SUBSTITUTE",
+                        "rule": null,
+                        "from": "p → q",
+                        "to": "TRUE → q",
+                    },
+                    {
+                        "step": 2,
+                        "pass": 2,
+                        "premise": "P1",
+                        "action": "EVALUATE",
+                        "rule": "implication",
+                        "from": "TRUE → q",
+                        "to": "q",
+                    },
+                ],
+            })
+        );
+        assert_eq!(
+            deduction.get_values().to_json(),
+            json!({ "p": true, "q": true })
+        );
+    }
+
+    #[test]
+    fn test_truth_table_to_json() {
+        let table =
+            TruthTable::new(&Premise::parse_str("p & q").unwrap(), DEFAULT_MAX_VARIABLES).unwrap();
+
+        assert_eq!(
+            table.to_json(RowFilter::Satisfying),
+            json!({
+                "formula": "p ∧ q",
+                "propositions": ["p", "q"],
+                "rows": [{ "values": { "p": true, "q": true }, "result": true }],
+            })
+        );
+    }
+}
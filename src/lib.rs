@@ -0,0 +1,69 @@
+//! The deduction engine, which parses propositional logic premises, solves them step by step and
+//! checks arguments, for use by the deductions binary or any other crate
+//!
+//! ```
+//! use deductions::Deduction;
+//!
+//! let mut deduction =
+//!     Deduction::from_strs(vec!["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"]).unwrap();
+//! let history = deduction.solve();
+//!
+//! assert_eq!(deduction.get_values().get_value('j'), Some(true));
+//! assert!(history.to_string().contains("j = TRUE [EVALUATE]"));
+//! ```
+
+mod arena;
+pub mod ast;
+pub mod batch;
+pub mod builder;
+pub mod cache;
+mod clock;
+#[cfg(feature = "color")]
+pub mod color;
+pub mod connective;
+pub mod deductions;
+pub mod entailment;
+pub mod errors;
+pub mod events;
+#[cfg(feature = "capi")]
+pub mod ffi;
+pub mod generator;
+pub mod grading;
+pub mod history;
+mod json;
+pub mod loader;
+pub mod normal_form;
+pub mod notation;
+pub mod observer;
+pub mod parallel;
+pub mod pattern;
+pub mod premises;
+pub mod proof;
+mod rewrite;
+pub mod sat;
+pub mod sexpr;
+mod smtlib;
+pub mod stats;
+pub mod structure;
+mod symbols;
+pub mod truth_table;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod watch;
+
+pub use builder::DeductionBuilder;
+pub use deductions::Deduction;
+pub use deductions::ValueMap;
+pub use errors::DeductionError;
+// The single error type of the crate, under a shorter name
+pub use errors::DeductionError as Error;
+pub use errors::LoadError;
+pub use errors::ParseError;
+pub use history::EvaluationHistory;
+#[cfg(feature = "parallel")]
+pub use parallel::evaluate_batch;
+pub use parallel::{DeductionResult, Problem};
+pub use premises::Operator;
+pub use premises::Premise;
+pub use premises::PremiseNode;
+pub use premises::PremiseRef;
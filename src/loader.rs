@@ -0,0 +1,895 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+use serde_json::{Map, Value};
+
+use crate::errors::LoadError;
+use crate::history::HistoryDetail;
+use crate::observer::SolveOptions;
+use crate::Deduction;
+use crate::Premise;
+
+// The fields each object in a JSON argument may have
+const ARGUMENT_FIELDS: [&str; 4] = ["premises", "conclusion", "assumptions", "options"];
+const PREMISE_FIELDS: [&str; 3] = ["formula", "label", "comment"];
+const OPTION_FIELDS: [&str; 4] = ["strategy", "max_steps", "timeout_ms", "history"];
+
+// The only way the solver works through an argument, which is the only strategy a JSON argument
+// may ask for
+const STRATEGY: &str = "passes";
+
+// Stores one argument read from a batch file along with the line it starts on,
+// or the error found while loading it
+#[derive(Debug)]
+pub struct BatchBlock {
+    pub line: usize,
+    pub deduction: std::result::Result<Deduction, LoadError>,
+}
+
+impl Deduction {
+    // Creates a Deduction from a file containing one premise per line
+    // See parse_lines for the format of the file
+    pub fn from_file(path: &Path) -> std::result::Result<Self, LoadError> {
+        let contents = fs::read_to_string(path).map_err(|error| LoadError::Io {
+            path: path.to_path_buf(),
+            error,
+        })?;
+
+        Self::parse_lines(&contents, path)
+    }
+
+    // Creates a Deduction from a reader such as stdin, in the same format as from_file
+    // The path is only used to name the input in errors
+    pub fn from_reader(mut reader: impl Read, path: &Path) -> std::result::Result<Self, LoadError> {
+        let mut contents = String::new();
+
+        reader
+            .read_to_string(&mut contents)
+            .map_err(|error| LoadError::Io {
+                path: path.to_path_buf(),
+                error,
+            })?;
+
+        Self::parse_lines(&contents, path)
+    }
+
+    // Creates a Deduction and the options to solve it with from a JSON argument such as
+    // {"premises": [{"formula": "p > q", "label": "rule", "comment": "..."}, "p"],
+    // "conclusion": "q", "assumptions": {"r": true},
+    // "options": {"max_steps": 100, "timeout_ms": 500, "history": "diff"}}
+    // Only the premises and their formulas are required, and a premise may be given as just its
+    // formula
+    // The history may be full, diff or written, and the strategy may only be "passes"
+    // A premise without a label keeps its number, such as P2, which no other label may be
+    pub fn from_json(json: &str) -> std::result::Result<(Self, SolveOptions), LoadError> {
+        Self::parse_json(json, Path::new("<json>"))
+    }
+
+    // Creates a Deduction and its options from a file containing a JSON argument, like from_json
+    pub fn from_json_file(path: &Path) -> std::result::Result<(Self, SolveOptions), LoadError> {
+        let contents = fs::read_to_string(path).map_err(|error| LoadError::Io {
+            path: path.to_path_buf(),
+            error,
+        })?;
+
+        Self::parse_json(&contents, path)
+    }
+
+    // Loads every argument in a batch file, where the arguments are separated by blank lines or
+    // lines of "---" and each is in the same format as from_file
+    // An argument which cannot be loaded does not stop the others from being loaded
+    pub fn batch_from_file(path: &Path) -> std::result::Result<Vec<BatchBlock>, LoadError> {
+        let contents = fs::read_to_string(path).map_err(|error| LoadError::Io {
+            path: path.to_path_buf(),
+            error,
+        })?;
+
+        Ok(Self::parse_batch(&contents, path))
+    }
+
+    // Loads every argument in a batch from a reader such as stdin, like batch_from_file
+    pub fn batch_from_reader(
+        mut reader: impl Read,
+        path: &Path,
+    ) -> std::result::Result<Vec<BatchBlock>, LoadError> {
+        let mut contents = String::new();
+
+        reader
+            .read_to_string(&mut contents)
+            .map_err(|error| LoadError::Io {
+                path: path.to_path_buf(),
+                error,
+            })?;
+
+        Ok(Self::parse_batch(&contents, path))
+    }
+
+    // Splits a batch into blocks of lines and parses each one as an argument
+    // Blocks with nothing but comments are skipped
+    fn parse_batch(contents: &str, path: &Path) -> Vec<BatchBlock> {
+        let mut blocks = Vec::new();
+        let mut block: Vec<(usize, &str)> = Vec::new();
+
+        // A separator is added after the last line so the final block is parsed too
+        for (i, line) in contents.lines().chain(std::iter::once("")).enumerate() {
+            let trimmed = line.trim();
+
+            if !trimmed.is_empty() && trimmed != "---" {
+                block.push((i + 1, line));
+                continue;
+            }
+
+            if block.iter().any(|(_, line)| !line.trim().starts_with('#')) {
+                blocks.push(BatchBlock {
+                    line: block[0].0,
+                    deduction: Self::parse_numbered_lines(block.iter().copied(), path),
+                });
+            }
+
+            block.clear();
+        }
+
+        blocks
+    }
+
+    // Parses an argument with one premise per line
+    // Blank lines and lines starting with '#' are ignored, a line starting with '∴' or
+    // "conclusion:" gives the conclusion, such as "∴ j", and a line starting with "assume:" gives
+    // a proposition its value before solving, such as "assume: p=true"
+    fn parse_lines(contents: &str, path: &Path) -> std::result::Result<Self, LoadError> {
+        Self::parse_numbered_lines(
+            contents.lines().enumerate().map(|(i, line)| (i + 1, line)),
+            path,
+        )
+    }
+
+    // Parses an argument like parse_lines from lines which are already numbered from 1
+    pub(crate) fn parse_numbered_lines<'a>(
+        lines: impl Iterator<Item = (usize, &'a str)>,
+        path: &Path,
+    ) -> std::result::Result<Self, LoadError> {
+        let mut premises = Vec::new();
+        let mut premise_lines = Vec::new();
+        let mut conclusion = None;
+        let mut assumptions: Vec<(char, bool)> = Vec::new();
+
+        for (number, line) in lines {
+            let trimmed = line.trim();
+
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let parse_error = |error| LoadError::Parse {
+                path: path.to_path_buf(),
+                line: number,
+                error,
+            };
+
+            if let Some(assumption) = trimmed.strip_prefix("assume:") {
+                let (proposition, value) =
+                    parse_assumption(assumption).ok_or_else(|| LoadError::InvalidAssumption {
+                        path: path.to_path_buf(),
+                        line: number,
+                        assumption: assumption.trim().to_string(),
+                    })?;
+
+                if assumptions.contains(&(proposition, !value)) {
+                    return Err(LoadError::ConflictingAssumption {
+                        path: path.to_path_buf(),
+                        line: number,
+                        proposition,
+                    });
+                }
+
+                assumptions.push((proposition, value));
+                continue;
+            }
+
+            let conclusion_line = trimmed
+                .strip_prefix('∴')
+                .or_else(|| trimmed.strip_prefix("conclusion:"));
+
+            match conclusion_line {
+                Some(_) if conclusion.is_some() => {
+                    return Err(LoadError::DuplicateConclusion {
+                        path: path.to_path_buf(),
+                        line: number,
+                    });
+                }
+                Some(rest) => conclusion = Some(Premise::parse_str(rest).map_err(parse_error)?),
+                None => {
+                    premises.push(Premise::parse_str(line).map_err(parse_error)?);
+                    premise_lines.push(number);
+                }
+            }
+        }
+
+        if premises.is_empty() {
+            return Err(LoadError::Empty {
+                path: path.to_path_buf(),
+            });
+        }
+
+        let mut deduction = Self::from_premises(premises);
+        deduction.set_premise_lines(premise_lines);
+
+        if let Some(conclusion) = conclusion {
+            deduction.set_conclusion(conclusion);
+        }
+
+        for (proposition, value) in assumptions {
+            deduction.assume_value(proposition, value);
+        }
+
+        Ok(deduction)
+    }
+
+    // Parses a JSON argument, as described in from_json
+    fn parse_json(
+        contents: &str,
+        path: &Path,
+    ) -> std::result::Result<(Self, SolveOptions), LoadError> {
+        let json: Value = serde_json::from_str(contents).map_err(|error| LoadError::Json {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        let schema = Schema { path };
+
+        let argument = schema.object(&json, "$", &ARGUMENT_FIELDS)?;
+
+        let Some(premise_values) = argument.get("premises") else {
+            return Err(schema.error("premises", "Missing field"));
+        };
+        let Value::Array(premise_values) = premise_values else {
+            return Err(schema.error("premises", "Expected an array of premises"));
+        };
+
+        if premise_values.is_empty() {
+            return Err(LoadError::Empty {
+                path: path.to_path_buf(),
+            });
+        }
+
+        let mut premises = Vec::new();
+        let mut notes = Vec::new();
+
+        for (i, value) in premise_values.iter().enumerate() {
+            let field = format!("premises[{}]", i);
+
+            if let Value::String(formula) = value {
+                premises.push(schema.formula(formula, &field)?);
+                notes.push((None, None));
+                continue;
+            }
+
+            let object = schema.object(value, &field, &PREMISE_FIELDS)?;
+
+            let formula = schema
+                .string(object, &field, "formula")?
+                .ok_or_else(|| schema.error(&format!("{}.formula", field), "Missing field"))?;
+            premises.push(schema.formula(formula, &format!("{}.formula", field))?);
+
+            let label = schema.string(object, &field, "label")?;
+            if label.is_some_and(|label| label.trim().is_empty()) {
+                return Err(schema.error(&format!("{}.label", field), "Labels cannot be empty"));
+            }
+
+            let comment = schema.string(object, &field, "comment")?;
+            notes.push((label.map(str::to_string), comment.map(str::to_string)));
+        }
+
+        // Every label must name a single premise, including those which keep their numbers
+        let labels: Vec<String> = notes
+            .iter()
+            .enumerate()
+            .map(|(i, (label, _))| label.clone().unwrap_or_else(|| format!("P{}", i + 1)))
+            .collect();
+
+        for (i, (label, _)) in notes.iter().enumerate() {
+            let Some(label) = label else {
+                continue;
+            };
+
+            if let Some(j) = (0..labels.len()).find(|&j| j != i && labels[j] == *label) {
+                return Err(schema.error(
+                    &format!("premises[{}].label", i),
+                    &format!("The label '{}' is also the label of premises[{}]", label, j),
+                ));
+            }
+        }
+
+        let mut deduction = Self::from_premises(premises);
+        deduction.set_premise_notes(notes);
+
+        match argument.get("conclusion") {
+            None | Some(Value::Null) => (),
+            Some(Value::String(conclusion)) => {
+                deduction.set_conclusion(schema.formula(conclusion, "conclusion")?)
+            }
+            Some(_) => return Err(schema.error("conclusion", "Expected a string")),
+        }
+
+        match argument.get("assumptions") {
+            None | Some(Value::Null) => (),
+            Some(Value::Object(assumptions)) => {
+                for (proposition, value) in assumptions {
+                    let field = format!("assumptions.{}", proposition);
+
+                    let mut chars = proposition.chars();
+                    let (Some(proposition @ 'a'..='z'), None) = (chars.next(), chars.next()) else {
+                        return Err(schema.error(&field, "Expected a single lowercase letter"));
+                    };
+                    let Value::Bool(value) = value else {
+                        return Err(schema.error(&field, "Expected true or false"));
+                    };
+
+                    deduction.assume_value(proposition, *value);
+                }
+            }
+            Some(_) => {
+                return Err(schema.error(
+                    "assumptions",
+                    "Expected an object from each proposition to its value",
+                ))
+            }
+        }
+
+        let options = match argument.get("options") {
+            None | Some(Value::Null) => SolveOptions::default(),
+            Some(options) => schema.options(options)?,
+        };
+
+        Ok((deduction, options))
+    }
+}
+
+// Reads the proposition and value of an assumption such as "p=true" or "q = FALSE"
+fn parse_assumption(assumption: &str) -> Option<(char, bool)> {
+    let (proposition, value) = assumption.split_once('=')?;
+
+    let mut chars = proposition.trim().chars();
+    let proposition = chars.next().filter(char::is_ascii_lowercase)?;
+    if chars.next().is_some() {
+        return None;
+    }
+
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" => Some((proposition, true)),
+        "false" => Some((proposition, false)),
+        _ => None,
+    }
+}
+
+// Checks the fields of a JSON argument read from the path, naming the field in every error
+struct Schema<'a> {
+    path: &'a Path,
+}
+
+impl Schema<'_> {
+    // Creates the error for the field, such as "premises[2].formula", or "$" for the whole argument
+    fn error(&self, field: &str, message: &str) -> LoadError {
+        LoadError::Schema {
+            path: self.path.to_path_buf(),
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    // Returns the value as an object, checking that it only has the allowed fields
+    fn object<'a>(
+        &self,
+        value: &'a Value,
+        field: &str,
+        allowed: &[&str],
+    ) -> std::result::Result<&'a Map<String, Value>, LoadError> {
+        let Value::Object(object) = value else {
+            return Err(self.error(field, "Expected an object"));
+        };
+
+        if let Some(unknown) = object.keys().find(|key| !allowed.contains(&key.as_str())) {
+            let field = match field {
+                "$" => unknown.clone(),
+                _ => format!("{}.{}", field, unknown),
+            };
+
+            return Err(self.error(
+                &field,
+                &format!("Unknown field, expected one of {}", allowed.join(", ")),
+            ));
+        }
+
+        Ok(object)
+    }
+
+    // Returns the named field of the object if it is a string, or None if it is missing or null
+    fn string<'a>(
+        &self,
+        object: &'a Map<String, Value>,
+        field: &str,
+        name: &str,
+    ) -> std::result::Result<Option<&'a str>, LoadError> {
+        match object.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(value)) => Ok(Some(value)),
+            Some(_) => Err(self.error(&format!("{}.{}", field, name), "Expected a string")),
+        }
+    }
+
+    // Parses the formula given in the field
+    fn formula(&self, formula: &str, field: &str) -> std::result::Result<Premise, LoadError> {
+        Premise::parse_str(formula).map_err(|error| LoadError::FieldParse {
+            path: self.path.to_path_buf(),
+            field: field.to_string(),
+            error,
+        })
+    }
+
+    // Reads the options an argument is solved with
+    fn options(&self, value: &Value) -> std::result::Result<SolveOptions, LoadError> {
+        let object = self.object(value, "options", &OPTION_FIELDS)?;
+        let mut options = SolveOptions::default();
+
+        if let Some(strategy) = self.string(object, "options", "strategy")? {
+            if strategy != STRATEGY {
+                return Err(self.error(
+                    "options.strategy",
+                    &format!("Unknown strategy '{}', expected '{}'", strategy, STRATEGY),
+                ));
+            }
+        }
+
+        options.max_steps = self
+            .integer(object, "max_steps")?
+            .map(|steps| steps as usize);
+        options.timeout = self
+            .integer(object, "timeout_ms")?
+            .map(Duration::from_millis);
+
+        options.history = match self.string(object, "options", "history")? {
+            None => None,
+            Some("full") => Some(HistoryDetail::Full),
+            Some("diff") => Some(HistoryDetail::Diff),
+            Some("written") => Some(HistoryDetail::Written),
+            Some(_) => {
+                return Err(self.error("options.history", "Expected one of full, diff or written"))
+            }
+        };
+
+        Ok(options)
+    }
+
+    // Returns the named field of the options if it is a whole number of at least 0, or None if it
+    // is missing or null
+    fn integer(
+        &self,
+        object: &Map<String, Value>,
+        name: &str,
+    ) -> std::result::Result<Option<u64>, LoadError> {
+        match object.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => value.as_u64().map(Some).ok_or_else(|| {
+                self.error(
+                    &format!("options.{}", name),
+                    "Expected a whole number of at least 0",
+                )
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // Writes the contents to a uniquely-named file in the system's temporary directory
+    fn write_temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("deductions_{}_{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn test_from_file() {
+        let path = write_temp_file(
+            "valid.txt",
+            "(m & !b) > j\n\n(f | s) > m\nb > t\nf > !t\nf\n",
+        );
+
+        let mut deduction = Deduction::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(deduction.get_num_premises(), 5);
+
+        deduction.solve();
+        assert_eq!(deduction.get_values().get_value('j'), Some(true));
+    }
+
+    #[test]
+    fn test_from_file_errors() {
+        let path = write_temp_file("invalid.txt", "a > b\n\na # b\n");
+
+        let error = Deduction::from_file(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(error, LoadError::Parse { line: 3, .. }));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "'{}', line 3: Invalid character in premise: '#' at position 2",
+                path.display()
+            )
+        );
+
+        let error = Deduction::from_file(Path::new("does/not/exist.txt")).unwrap_err();
+        assert!(matches!(error, LoadError::Io { .. }));
+        assert!(error
+            .to_string()
+            .starts_with("Could not read 'does/not/exist.txt'"));
+    }
+
+    #[test]
+    fn test_from_file_with_assumptions() {
+        let path = write_temp_file(
+            "assumptions.txt",
+            "# Whether the alarm sounds\n\
+             (m & !b) > j\n\
+             assume: m=true\n\
+             \n\
+             # The battery is charged\n\
+             assume: b = FALSE\n\
+             conclusion: j\n",
+        );
+
+        let mut deduction = Deduction::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(deduction.get_num_premises(), 1);
+        assert_eq!(deduction.get_premise_line("P1"), Some(2));
+        assert_eq!(
+            deduction.get_conclusion(),
+            Some(&Premise::parse_str("j").unwrap())
+        );
+        assert_eq!(deduction.get_values().get_value('m'), Some(true));
+        assert_eq!(deduction.get_values().get_value('b'), Some(false));
+        assert_eq!(deduction.get_values().get_value('j'), None);
+
+        deduction.solve();
+        assert_eq!(deduction.evaluate_conclusion(), Some(true));
+        assert!(!deduction.is_contradictory());
+
+        let error =
+            Deduction::from_reader("p > q\nassume: p=maybe\n".as_bytes(), Path::new("<stdin>"))
+                .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "'<stdin>', line 2: Invalid assumption 'p=maybe', expected a proposition and its \
+             value such as 'p=true'"
+        );
+
+        for assumption in [
+            "assume: p",
+            "assume: pq=true",
+            "assume: =true",
+            "assume: P=true",
+        ] {
+            let input = format!("p\n{}\n", assumption);
+            let error = Deduction::from_reader(input.as_bytes(), Path::new("<stdin>")).unwrap_err();
+            assert!(matches!(
+                error,
+                LoadError::InvalidAssumption { line: 2, .. }
+            ));
+        }
+
+        let error = Deduction::from_reader(
+            "p\nassume: q=true\nassume: q=true\nassume: q=false\n".as_bytes(),
+            Path::new("<stdin>"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "'<stdin>', line 4: q is already assumed to have the other value"
+        );
+
+        let error =
+            Deduction::from_reader("p\nconclusion: p\n∴ q\n".as_bytes(), Path::new("<stdin>"))
+                .unwrap_err();
+        assert!(matches!(
+            error,
+            LoadError::DuplicateConclusion { line: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let input = "# Modus ponens\np > q\n\np\n∴ q\n";
+
+        let mut deduction = Deduction::from_reader(input.as_bytes(), Path::new("<stdin>")).unwrap();
+        assert_eq!(deduction.get_num_premises(), 2);
+        assert_eq!(deduction.evaluate_conclusion(), None);
+        assert_eq!(deduction.get_premise_line("P2"), Some(4));
+
+        deduction.solve();
+        assert_eq!(deduction.evaluate_conclusion(), Some(true));
+
+        let error = Deduction::from_reader("# Nothing here\n\n".as_bytes(), Path::new("<stdin>"))
+            .unwrap_err();
+        assert_eq!(error.to_string(), "'<stdin>' contains no premises");
+
+        let error =
+            Deduction::from_reader("p\n∴ p\n∴ q\n".as_bytes(), Path::new("<stdin>")).unwrap_err();
+        assert!(matches!(
+            error,
+            LoadError::DuplicateConclusion { line: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn test_from_json() {
+        let (mut deduction, options) = Deduction::from_json(
+            r#"{
+                "premises": [
+                    { "formula": "p > q", "label": "rule", "comment": "If p then q" },
+                    { "formula": "p", "comment": null }
+                ],
+                "conclusion": "q",
+                "options": { "strategy": "passes", "max_steps": 10, "timeout_ms": 500, "history": "written" }
+            }"#,
+        )
+        .unwrap();
+
+        let labels: Vec<String> = deduction
+            .get_labeled_premises()
+            .into_iter()
+            .map(|(label, _)| label)
+            .collect();
+        assert_eq!(labels, ["rule", "P2"]);
+        assert_eq!(deduction.get_premise_comment("rule"), Some("If p then q"));
+        assert_eq!(deduction.get_premise_comment("P2"), None);
+
+        assert_eq!(
+            options,
+            SolveOptions {
+                max_steps: Some(10),
+                timeout: Some(Duration::from_millis(500)),
+                history: Some(HistoryDetail::Written),
+            }
+        );
+
+        deduction.solve();
+        assert_eq!(deduction.evaluate_conclusion(), Some(true));
+
+        let (_, options) = Deduction::from_json(r#"{ "premises": [{ "formula": "p" }] }"#).unwrap();
+        assert_eq!(options, SolveOptions::default());
+    }
+
+    #[test]
+    fn test_from_json_errors() {
+        // Returns the field and message of the error found in the argument
+        let error = |json: &str| match Deduction::from_json(json).unwrap_err() {
+            LoadError::Schema { field, message, .. } => (field, message),
+            LoadError::FieldParse { field, error, .. } => (field, error.to_string()),
+            other => panic!("Expected an error in a field, found {:?}", other),
+        };
+
+        let premises = |premises: &str| format!(r#"{{ "premises": [{}] }}"#, premises);
+
+        assert_eq!(
+            error(&premises(r#"{ "formula": "p" }, { "formula": "p & #" }"#)),
+            (
+                String::from("premises[1].formula"),
+                String::from("Invalid character in premise: '#' at position 4")
+            )
+        );
+        assert_eq!(
+            error(&premises(r#"{ "label": "a" }"#)).0,
+            "premises[0].formula"
+        );
+        assert_eq!(
+            error(&premises(r#"{ "formula": 1 }"#)).1,
+            "Expected a string"
+        );
+        assert_eq!(error(&premises("1")).1, "Expected an object");
+        assert_eq!(
+            error(&premises(r#""p", "q", "(m & b) >""#)),
+            (
+                String::from("premises[2]"),
+                String::from("Invalid premise structure")
+            )
+        );
+        assert_eq!(
+            error(&premises(r#"{ "formula": "p", "note": "" }"#)),
+            (
+                String::from("premises[0].note"),
+                String::from("Unknown field, expected one of formula, label, comment")
+            )
+        );
+        assert_eq!(
+            error(&premises(r#"{ "formula": "p", "label": " " }"#)).1,
+            "Labels cannot be empty"
+        );
+
+        // A label may not be the number of another premise
+        assert_eq!(
+            error(&premises(
+                r#"{ "formula": "p", "label": "P2" }, { "formula": "q" }"#
+            )),
+            (
+                String::from("premises[0].label"),
+                String::from("The label 'P2' is also the label of premises[1]")
+            )
+        );
+
+        assert_eq!(
+            error("[]"),
+            (String::from("$"), String::from("Expected an object"))
+        );
+        assert_eq!(error("{}").0, "premises");
+        assert_eq!(
+            error(r#"{ "premises": "p" }"#).1,
+            "Expected an array of premises"
+        );
+        assert_eq!(error(r#"{ "premises": [], "steps": 1 }"#).0, "steps");
+
+        let assumptions = |assumptions: &str| {
+            format!(r#"{{ "premises": ["p"], "assumptions": {} }}"#, assumptions)
+        };
+
+        assert_eq!(
+            error(&assumptions(r#"{ "f": "yes" }"#)),
+            (
+                String::from("assumptions.f"),
+                String::from("Expected true or false")
+            )
+        );
+        assert_eq!(
+            error(&assumptions(r#"{ "ab": true }"#)).1,
+            "Expected a single lowercase letter"
+        );
+        assert_eq!(error(&assumptions(r#"["f"]"#)).0, "assumptions");
+
+        let options = |options: &str| {
+            format!(
+                r#"{{ "premises": [{{ "formula": "p" }}], "options": {} }}"#,
+                options
+            )
+        };
+
+        assert_eq!(
+            error(&options(r#"{ "max_steps": -1 }"#)),
+            (
+                String::from("options.max_steps"),
+                String::from("Expected a whole number of at least 0")
+            )
+        );
+        assert_eq!(
+            error(&options(r#"{ "timeout_ms": "1s" }"#)).0,
+            "options.timeout_ms"
+        );
+        assert_eq!(
+            error(&options(r#"{ "history": "all" }"#)).0,
+            "options.history"
+        );
+        assert_eq!(
+            error(&options(r#"{ "strategy": "dpll" }"#)).1,
+            "Unknown strategy 'dpll', expected 'passes'"
+        );
+
+        let conclusion = r#"{ "premises": [{ "formula": "p" }], "conclusion": "p >" }"#;
+        assert_eq!(error(conclusion).0, "conclusion");
+
+        assert!(matches!(
+            Deduction::from_json(r#"{ "premises": [] }"#),
+            Err(LoadError::Empty { .. })
+        ));
+
+        let error = Deduction::from_json("{ \"premises\": ").unwrap_err();
+        assert!(matches!(error, LoadError::Json { .. }));
+        assert!(error
+            .to_string()
+            .starts_with("'<json>' is not valid JSON: "));
+    }
+
+    #[test]
+    fn test_problem_json_round_trip() {
+        let (mut deduction, _) = Deduction::from_json(
+            r#"{
+                "premises": ["(m & b) > j", "(f | s) > m", { "formula": "f > b" }],
+                "conclusion": "j",
+                "assumptions": { "f": true, "s": false }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(deduction.get_num_premises(), 3);
+        assert_eq!(
+            deduction.get_assumptions().iter().collect::<Vec<_>>(),
+            [(&'f', &true), (&'s', &false)]
+        );
+        assert_eq!(deduction.get_values().get_value('f'), Some(true));
+        assert_eq!(deduction.get_values().get_value('m'), None);
+
+        let problem = deduction.problem_to_json();
+        assert_eq!(
+            problem,
+            serde_json::json!({
+                "premises": ["(m ∧ b) → j", "(f ∨ s) → m", "f → b"],
+                "conclusion": "j",
+                "assumptions": { "f": true, "s": false },
+            })
+        );
+
+        let (mut reloaded, _) = Deduction::from_json(&problem.to_string()).unwrap();
+        assert_eq!(reloaded.problem_to_json(), problem);
+
+        deduction.solve();
+        reloaded.solve();
+        assert_eq!(deduction.evaluate_conclusion(), Some(true));
+        assert_eq!(
+            deduction.get_values().to_json(),
+            reloaded.get_values().to_json()
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let path = Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/argument.json"
+        ));
+        let (deduction, options) = Deduction::from_json_file(path).unwrap();
+
+        let exported = deduction.to_json(&options);
+        assert_eq!(exported["premises"][0]["label"], "jump");
+        assert_eq!(
+            exported["premises"][0]["comment"],
+            "Jumping needs motivation and no blockers"
+        );
+        assert_eq!(exported["premises"][1]["label"], "P2");
+        assert_eq!(exported["premises"][1]["formula"], "(f ∨ s) → m");
+        assert_eq!(exported["conclusion"], "j");
+        assert_eq!(exported["options"]["history"], "diff");
+
+        // Reading the export back gives the same argument, which solves the same way
+        let (mut reloaded, reloaded_options) = Deduction::from_json(&exported.to_string()).unwrap();
+        assert_eq!(reloaded.to_json(&reloaded_options), exported);
+        assert_eq!(reloaded_options, options);
+
+        let mut deduction = deduction;
+        let history = deduction.solve();
+        let reloaded_history = reloaded.solve();
+        assert_eq!(history.to_json(), reloaded_history.to_json());
+        assert_eq!(history.to_json()["premises"][4]["label"], "fact");
+        assert_eq!(
+            deduction.get_values().to_json(),
+            reloaded.get_values().to_json()
+        );
+    }
+
+    #[test]
+    fn test_batch_from_reader() {
+        let input = "# Modus ponens\np > q\np\n∴ q\n\n\n# Only a comment\n---\np & # q\n∴ p\n---\n# Modus tollens\np > q\n!q\n∴ !p";
+
+        let blocks = Deduction::batch_from_reader(input.as_bytes(), Path::new("<stdin>")).unwrap();
+        assert_eq!(blocks.len(), 3);
+
+        assert_eq!(blocks[0].line, 1);
+        assert_eq!(blocks[0].deduction.as_ref().unwrap().get_num_premises(), 2);
+
+        assert_eq!(blocks[1].line, 9);
+        assert!(matches!(
+            blocks[1].deduction,
+            Err(LoadError::Parse { line: 9, .. })
+        ));
+
+        assert_eq!(blocks[2].line, 12);
+        assert_eq!(
+            blocks[2].deduction.as_ref().unwrap().get_conclusion(),
+            Some(&Premise::parse_str("!p").unwrap())
+        );
+    }
+}
@@ -1,22 +1,28 @@
-// #![allow(dead_code, unused_variables)]
-
-mod deductions;
-mod history;
-mod premises;
-
-use deductions::Deduction;
-use deductions::ValueMap;
-use premises::Premise;
-use premises::PremiseNode;
-
-fn main() {
-    let propositions = ["(m & b) > j", "(f | s) > m", "b > t", "f > !t", "f"].to_vec();
-    let mut deduction = Deduction::from_strs(propositions);
-
-    deduction.update_actual_values();
-    deduction.substitute_all();
-
-    println!("{}", &deduction);
+mod assist;
+mod cli;
+mod repl;
+
+use std::process::ExitCode;
+
+use cli::CliOptions;
+
+// The built-in example argument in cli::EXAMPLE_PREMISES is worked through below
+fn main() -> ExitCode {
+    let options = match CliOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("Error: {}\n\n{}", error, cli::USAGE);
+            return error.exit_code().into();
+        }
+    };
+
+    match cli::run(&options) {
+        Ok(exit) => exit.into(),
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            error.exit_code().into()
+        }
+    }
 }
 
 /*
@@ -0,0 +1,585 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::notation::Notation;
+use crate::premises::{find_main_operator, Operator};
+use crate::Premise;
+use crate::PremiseNode;
+
+// The most clauses a conversion to CNF or DNF may produce, as distributing one operator over
+// the other can double the number of clauses with each step
+pub const DEFAULT_MAX_CLAUSES: usize = 4096;
+
+// The normal forms a premise can be converted to
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum NormalForm {
+    // Negations only apply to propositions, and the only operators are ∧ and ∨
+    Negation,
+    // A conjunction of clauses, each of which is a disjunction of literals
+    Conjunctive,
+    // A disjunction of terms, each of which is a conjunction of literals
+    Disjunctive,
+}
+
+// Represents a proposition or its negation
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+pub struct Literal {
+    pub proposition: char,
+    pub negated: bool,
+}
+
+// A clause of a CNF or a term of a DNF, depending on which form it is part of
+pub type Clause = Vec<Literal>;
+
+// Represents a conversion which would produce more clauses than the limit
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TooManyClauses {
+    pub form: NormalForm,
+    pub limit: usize,
+}
+
+// Represents why a premise could not be converted to a normal form
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum NormalFormError {
+    // The conversion would produce more clauses than the limit
+    TooManyClauses(TooManyClauses),
+    // The premise does not form a valid formula, such as one changed with visit_mut
+    InvalidStructure,
+    // Clauses were asked for in negation normal form, which is not made of clauses
+    NoClauses,
+}
+
+// Represents a premise in negation normal form, with chains of the same operator flattened
+#[derive(Debug, Clone)]
+enum Nnf {
+    Literal(Literal),
+    TruthValue(bool),
+    And(Vec<Nnf>),
+    Or(Vec<Nnf>),
+}
+
+impl Display for NormalForm {
+    // Displays the full name of the normal form
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            NormalForm::Negation => write!(f, "negation normal form"),
+            NormalForm::Conjunctive => write!(f, "conjunctive normal form"),
+            NormalForm::Disjunctive => write!(f, "disjunctive normal form"),
+        }
+    }
+}
+
+impl Display for TooManyClauses {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "Converting the formula to {} would give more than {} clauses",
+            self.form, self.limit
+        )
+    }
+}
+
+impl std::error::Error for TooManyClauses {}
+
+impl Display for NormalFormError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            NormalFormError::TooManyClauses(error) => write!(f, "{}", error),
+            NormalFormError::InvalidStructure => {
+                write!(f, "Invalid premise structure, so it has no normal form")
+            }
+            NormalFormError::NoClauses => write!(f, "Only CNF and DNF can be written as clauses"),
+        }
+    }
+}
+
+impl std::error::Error for NormalFormError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NormalFormError::TooManyClauses(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<TooManyClauses> for NormalFormError {
+    fn from(error: TooManyClauses) -> Self {
+        NormalFormError::TooManyClauses(error)
+    }
+}
+
+impl Literal {
+    // Renders the literal using the symbols of the given notation, such as "¬p"
+    pub fn render(&self, notation: Notation) -> String {
+        match self.negated {
+            true => format!("{}{}", notation.negation(), self.proposition),
+            false => self.proposition.to_string(),
+        }
+    }
+}
+
+impl Premise {
+    // Converts the premise to negation normal form by pushing every negation inward with
+    // De Morgan's laws and rewriting each implication "a → b" as "¬a ∨ b"
+    pub fn to_nnf(&self) -> std::result::Result<Premise, NormalFormError> {
+        Ok(Premise::new(nnf_to_nodes(&nodes_to_nnf(
+            self.get_nodes(),
+            false,
+        )?)))
+    }
+
+    // Converts the premise to conjunctive normal form, such as "(p ∨ q) ∧ ¬r"
+    pub fn to_cnf(&self, max_clauses: usize) -> std::result::Result<Premise, NormalFormError> {
+        Ok(Premise::from_clauses(
+            NormalForm::Conjunctive,
+            &self.to_clauses(NormalForm::Conjunctive, max_clauses)?,
+        ))
+    }
+
+    // Converts the premise to disjunctive normal form, such as "(p ∧ q) ∨ ¬r"
+    pub fn to_dnf(&self, max_clauses: usize) -> std::result::Result<Premise, NormalFormError> {
+        Ok(Premise::from_clauses(
+            NormalForm::Disjunctive,
+            &self.to_clauses(NormalForm::Disjunctive, max_clauses)?,
+        ))
+    }
+
+    // Returns the clauses of the premise in conjunctive normal form, or its terms in disjunctive
+    // normal form, with the literals of each sorted and clauses which are always TRUE in CNF or
+    // always FALSE in DNF left out
+    // Fails with NoClauses for NormalForm::Negation, which is not made of clauses
+    pub fn to_clauses(
+        &self,
+        form: NormalForm,
+        max_clauses: usize,
+    ) -> std::result::Result<Vec<Clause>, NormalFormError> {
+        let nnf = nodes_to_nnf(self.get_nodes(), false)?;
+
+        let clauses = match form {
+            NormalForm::Conjunctive => nnf_to_clauses(&nnf, true, max_clauses),
+            NormalForm::Disjunctive => nnf_to_clauses(&nnf, false, max_clauses),
+            NormalForm::Negation => return Err(NormalFormError::NoClauses),
+        };
+
+        clauses.map_err(|_| {
+            NormalFormError::TooManyClauses(TooManyClauses {
+                form,
+                limit: max_clauses,
+            })
+        })
+    }
+
+    // Creates a premise from the clauses of a CNF or the terms of a DNF
+    // No clauses is TRUE in CNF and FALSE in DNF, while an empty clause is the opposite
+    pub fn from_clauses(form: NormalForm, clauses: &[Clause]) -> Premise {
+        let conjunctive = form == NormalForm::Conjunctive;
+
+        let nnf = clauses
+            .iter()
+            .map(|clause| {
+                let literals = clause.iter().copied().map(Nnf::Literal).collect();
+
+                match conjunctive {
+                    true => flatten(Nnf::Or(literals), false),
+                    false => flatten(Nnf::And(literals), true),
+                }
+            })
+            .collect();
+
+        let nnf = match conjunctive {
+            true => flatten(Nnf::And(nnf), true),
+            false => flatten(Nnf::Or(nnf), false),
+        };
+
+        Premise::new(nnf_to_nodes(&nnf))
+    }
+}
+
+// Renders the clauses one per line as sets of literals, such as "{p, ¬q}"
+pub fn render_clauses(clauses: &[Clause], notation: Notation) -> String {
+    clauses
+        .iter()
+        .map(|clause| {
+            let literals: Vec<String> = clause
+                .iter()
+                .map(|literal| literal.render(notation))
+                .collect();
+
+            format!("{{{}}}\n", literals.join(", "))
+        })
+        .collect()
+}
+
+// Renders the clauses of a CNF in the DIMACS format read by SAT solvers, numbering the
+// propositions of the original premise from 1 in alphabetical order, with a comment line
+// giving the number of each
+pub fn render_dimacs(premise: &Premise, clauses: &[Clause]) -> String {
+    let propositions: Vec<char> = premise.propositions().into_iter().collect();
+
+    render_dimacs_over(&propositions, clauses)
+}
+
+// Renders the clauses in the DIMACS format like render_dimacs, numbering the given propositions
+// from 1 in order
+pub(crate) fn render_dimacs_over(propositions: &[char], clauses: &[Clause]) -> String {
+    let mut dimacs = String::new();
+
+    for (i, proposition) in propositions.iter().enumerate() {
+        dimacs.push_str(&format!("c {} = {}\n", i + 1, proposition));
+    }
+
+    dimacs.push_str(&format!("p cnf {} {}\n", propositions.len(), clauses.len()));
+
+    for clause in clauses {
+        for literal in clause {
+            let number = propositions
+                .iter()
+                .position(|proposition| *proposition == literal.proposition)
+                .map_or(0, |i| i + 1);
+
+            match literal.negated {
+                true => dimacs.push_str(&format!("-{} ", number)),
+                false => dimacs.push_str(&format!("{} ", number)),
+            }
+        }
+
+        dimacs.push_str("0\n");
+    }
+
+    dimacs
+}
+
+// Converts a list of nodes to negation normal form, negating the result if asked to
+// Fails if the nodes do not form a valid formula
+fn nodes_to_nnf(nodes: &[PremiseNode], negated: bool) -> std::result::Result<Nnf, NormalFormError> {
+    if let Some(i) = find_main_operator(nodes) {
+        let (left, right) = (&nodes[..i], &nodes[i + 1..]);
+
+        let nnf = match (nodes[i].clone(), negated) {
+            (PremiseNode::Operator(Operator::And), false) => Nnf::And(vec![
+                nodes_to_nnf(left, false)?,
+                nodes_to_nnf(right, false)?,
+            ]),
+            (PremiseNode::Operator(Operator::And), true) => {
+                Nnf::Or(vec![nodes_to_nnf(left, true)?, nodes_to_nnf(right, true)?])
+            }
+            (PremiseNode::Operator(Operator::Or), false) => Nnf::Or(vec![
+                nodes_to_nnf(left, false)?,
+                nodes_to_nnf(right, false)?,
+            ]),
+            (PremiseNode::Operator(Operator::Or), true) => {
+                Nnf::And(vec![nodes_to_nnf(left, true)?, nodes_to_nnf(right, true)?])
+            }
+            (PremiseNode::Operator(Operator::Implies), false) => {
+                Nnf::Or(vec![nodes_to_nnf(left, true)?, nodes_to_nnf(right, false)?])
+            }
+            (PremiseNode::Operator(Operator::Implies), true) => {
+                Nnf::And(vec![nodes_to_nnf(left, false)?, nodes_to_nnf(right, true)?])
+            }
+            // A biconditional holds when both sides agree, and its negation when they differ
+            // An exclusive disjunction is the negation of a biconditional
+            (PremiseNode::Operator(operator @ (Operator::Iff | Operator::Xor)), negated) => {
+                let differ = negated ^ (operator == Operator::Xor);
+
+                Nnf::Or(vec![
+                    Nnf::And(vec![
+                        nodes_to_nnf(left, false)?,
+                        nodes_to_nnf(right, differ)?,
+                    ]),
+                    Nnf::And(vec![
+                        nodes_to_nnf(left, true)?,
+                        nodes_to_nnf(right, !differ)?,
+                    ]),
+                ])
+            }
+            _ => return Err(NormalFormError::InvalidStructure),
+        };
+
+        return Ok(flatten(nnf, false));
+    }
+
+    // An operand is any number of negations followed by a single proposition, truth value or
+    // subpremise
+    let negations = nodes
+        .iter()
+        .take_while(|node| **node == PremiseNode::Negation)
+        .count();
+    let negated = negated ^ (negations % 2 == 1);
+
+    match &nodes[negations..] {
+        [PremiseNode::Proposition(proposition)] => Ok(Nnf::Literal(Literal {
+            proposition: *proposition,
+            negated,
+        })),
+        [PremiseNode::TruthValue(value)] => Ok(Nnf::TruthValue(value ^ negated)),
+        [PremiseNode::Subpremise(subpremise)] => nodes_to_nnf(subpremise.get_nodes(), negated),
+        _ => Err(NormalFormError::InvalidStructure),
+    }
+}
+
+// Merges children which use the same operator as their parent into it, and removes a
+// single-child operator, as in "a ∧ (b ∧ c)" becoming "a ∧ b ∧ c"
+// An operator without children becomes the given truth value
+fn flatten(nnf: Nnf, empty_value: bool) -> Nnf {
+    let (children, is_and) = match nnf {
+        Nnf::And(children) => (children, true),
+        Nnf::Or(children) => (children, false),
+        other => return other,
+    };
+
+    let mut flattened = Vec::new();
+
+    for child in children {
+        match (child, is_and) {
+            (Nnf::And(grandchildren), true) | (Nnf::Or(grandchildren), false) => {
+                flattened.extend(grandchildren)
+            }
+            (child, _) => flattened.push(child),
+        }
+    }
+
+    match (flattened.len(), is_and) {
+        (0, _) => Nnf::TruthValue(empty_value),
+        (1, _) => flattened.pop().unwrap(),
+        (_, true) => Nnf::And(flattened),
+        (_, false) => Nnf::Or(flattened),
+    }
+}
+
+// Converts a premise in negation normal form to clauses, which are the clauses of a CNF if
+// conjunctive is true and the terms of a DNF otherwise
+// Fails if more than the given number of clauses would be produced
+fn nnf_to_clauses(
+    nnf: &Nnf,
+    conjunctive: bool,
+    max_clauses: usize,
+) -> std::result::Result<Vec<Clause>, ()> {
+    // In CNF the clauses are joined by ∧ and in DNF by ∨, so that operator just collects the
+    // clauses of its children while the other one distributes over them
+    let (children, distributes) = match (nnf, conjunctive) {
+        (Nnf::Literal(literal), _) => return Ok(vec![vec![*literal]]),
+        // TRUE has no clauses in CNF, while FALSE has one which can never be satisfied
+        (Nnf::TruthValue(value), _) if *value == conjunctive => return Ok(Vec::new()),
+        (Nnf::TruthValue(_), _) => return Ok(vec![Vec::new()]),
+        (Nnf::And(children), true) | (Nnf::Or(children), false) => (children, false),
+        (Nnf::And(children), false) | (Nnf::Or(children), true) => (children, true),
+    };
+
+    // Distributing starts from a single empty clause, which every combination is added to
+    let mut clauses: Vec<Clause> = match distributes {
+        true => vec![Vec::new()],
+        false => Vec::new(),
+    };
+
+    for child in children {
+        let child_clauses = nnf_to_clauses(child, conjunctive, max_clauses)?;
+
+        if !distributes {
+            clauses.extend(child_clauses);
+        } else {
+            if clauses.len() * child_clauses.len() > max_clauses {
+                return Err(());
+            }
+
+            clauses = clauses
+                .iter()
+                .flat_map(|clause| {
+                    child_clauses
+                        .iter()
+                        .map(move |child_clause| [clause.as_slice(), child_clause].concat())
+                })
+                .collect();
+        }
+
+        clauses = simplify_clauses(clauses);
+
+        if clauses.len() > max_clauses {
+            return Err(());
+        }
+    }
+
+    Ok(clauses)
+}
+
+// Sorts the literals of each clause and removes repeated literals and clauses, any clause holding
+// both a proposition and its negation, and any clause which holds every literal of another,
+// since by absorption it adds nothing
+fn simplify_clauses(clauses: Vec<Clause>) -> Vec<Clause> {
+    let mut simplified: Vec<Clause> = Vec::new();
+
+    for mut clause in clauses {
+        clause.sort();
+        clause.dedup();
+
+        let is_complementary = clause
+            .windows(2)
+            .any(|pair| pair[0].proposition == pair[1].proposition);
+
+        if !is_complementary && !simplified.contains(&clause) {
+            simplified.push(clause);
+        }
+    }
+
+    let is_subset = |smaller: &Clause, larger: &Clause| {
+        smaller.len() < larger.len() && smaller.iter().all(|literal| larger.contains(literal))
+    };
+
+    simplified
+        .iter()
+        .filter(|clause| !simplified.iter().any(|other| is_subset(other, clause)))
+        .cloned()
+        .collect()
+}
+
+// Converts a premise in negation normal form back to nodes, wrapping compound operands in
+// subpremises so the grouping is clear
+fn nnf_to_nodes(nnf: &Nnf) -> Vec<PremiseNode> {
+    let (children, operator) = match nnf {
+        Nnf::Literal(literal) => {
+            return match literal.negated {
+                true => vec![
+                    PremiseNode::Negation,
+                    PremiseNode::Proposition(literal.proposition),
+                ],
+                false => vec![PremiseNode::Proposition(literal.proposition)],
+            }
+        }
+        Nnf::TruthValue(value) => return vec![PremiseNode::TruthValue(*value)],
+        Nnf::And(children) => (children, Operator::And),
+        Nnf::Or(children) => (children, Operator::Or),
+    };
+
+    let mut nodes = Vec::new();
+
+    for (i, child) in children.iter().enumerate() {
+        if i > 0 {
+            nodes.push(PremiseNode::Operator(operator));
+        }
+
+        match child {
+            Nnf::And(_) | Nnf::Or(_) => {
+                nodes.push(PremiseNode::Subpremise(Premise::new(nnf_to_nodes(child))))
+            }
+            _ => nodes.extend(nnf_to_nodes(child)),
+        }
+    }
+
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_nnf() {
+        assert_eq!(
+            Premise::parse_str("!(p & (q > r))")
+                .unwrap()
+                .to_nnf()
+                .unwrap()
+                .to_string(),
+            "¬p ∨ (q ∧ ¬r)"
+        );
+        assert_eq!(
+            Premise::parse_str("!!(a | b) | c")
+                .unwrap()
+                .to_nnf()
+                .unwrap()
+                .to_string(),
+            "a ∨ b ∨ c"
+        );
+    }
+
+    #[test]
+    fn test_to_cnf_and_dnf() {
+        let premise = Premise::parse_str("(p & q) | (r > p)").unwrap();
+
+        let cnf = premise.to_cnf(DEFAULT_MAX_CLAUSES).unwrap();
+        assert_eq!(cnf.to_string(), "p ∨ ¬r");
+        assert!(cnf.is_equivalent(&premise));
+
+        let premise = Premise::parse_str("(a | b) & (c | !a)").unwrap();
+
+        let dnf = premise.to_dnf(DEFAULT_MAX_CLAUSES).unwrap();
+        assert_eq!(dnf.to_string(), "(a ∧ c) ∨ (b ∧ c) ∨ (¬a ∧ b)");
+        assert!(dnf.is_equivalent(&premise));
+
+        // A contradiction has no terms, and a tautology has no clauses
+        assert_eq!(
+            Premise::parse_str("p & !p")
+                .unwrap()
+                .to_dnf(DEFAULT_MAX_CLAUSES)
+                .unwrap()
+                .to_string(),
+            "FALSE"
+        );
+        assert_eq!(
+            Premise::parse_str("p | !p")
+                .unwrap()
+                .to_cnf(DEFAULT_MAX_CLAUSES)
+                .unwrap()
+                .to_string(),
+            "TRUE"
+        );
+    }
+
+    #[test]
+    fn test_normal_form_errors() {
+        let premise = Premise::parse_str("p & q").unwrap();
+        assert_eq!(
+            premise.to_clauses(NormalForm::Negation, DEFAULT_MAX_CLAUSES),
+            Err(NormalFormError::NoClauses)
+        );
+
+        // A premise can only be made malformed through visit_mut
+        let mut malformed = premise.clone();
+        malformed.visit_mut(&mut |node| {
+            if *node == PremiseNode::Proposition('q') {
+                *node = PremiseNode::Negation;
+            }
+        });
+
+        assert_eq!(malformed.to_nnf(), Err(NormalFormError::InvalidStructure));
+        assert_eq!(
+            malformed.to_cnf(DEFAULT_MAX_CLAUSES),
+            Err(NormalFormError::InvalidStructure)
+        );
+    }
+
+    #[test]
+    fn test_too_many_clauses() {
+        let premise = Premise::parse_str("(a & b) | (c & d) | (e & f)").unwrap();
+
+        assert_eq!(
+            premise.to_clauses(NormalForm::Conjunctive, 4),
+            Err(NormalFormError::TooManyClauses(TooManyClauses {
+                form: NormalForm::Conjunctive,
+                limit: 4
+            }))
+        );
+        assert_eq!(
+            premise
+                .to_clauses(NormalForm::Conjunctive, 8)
+                .unwrap()
+                .len(),
+            8
+        );
+    }
+
+    #[test]
+    fn test_render_clauses() {
+        let premise = Premise::parse_str("(p > q) & !r").unwrap();
+        let clauses = premise
+            .to_clauses(NormalForm::Conjunctive, DEFAULT_MAX_CLAUSES)
+            .unwrap();
+
+        assert_eq!(
+            render_clauses(&clauses, Notation::Unicode),
+            "{¬p, q}\n{¬r}\n"
+        );
+        assert_eq!(
+            render_dimacs(&premise, &clauses),
+            "c 1 = p\nc 2 = q\nc 3 = r\np cnf 3 2\n-1 2 0\n-3 0\n"
+        );
+    }
+}
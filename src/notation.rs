@@ -0,0 +1,191 @@
+use crate::premises::{Operator, PremiseNode};
+use crate::Premise;
+use crate::ValueMap;
+
+// The set of symbols used when rendering premises as text
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Notation {
+    // The symbols used by Display, such as "¬p ∧ q"
+    Unicode,
+    // The characters accepted by the parser, such as "!p & q"
+    Ascii,
+    // LaTeX math mode commands, such as "\neg p \land q"
+    Latex,
+}
+
+impl Notation {
+    // Returns the symbol for an operator
+    pub fn operator(&self, operator: Operator) -> &'static str {
+        match (self, operator) {
+            (Notation::Unicode, Operator::And) => "∧",
+            (Notation::Unicode, Operator::Or) => "∨",
+            (Notation::Unicode, Operator::Implies) => "→",
+            (Notation::Unicode, Operator::Iff) => "↔",
+            (Notation::Unicode, Operator::Xor) => "⊕",
+            (Notation::Ascii, Operator::And) => "&",
+            (Notation::Ascii, Operator::Or) => "|",
+            (Notation::Ascii, Operator::Implies) => ">",
+            (Notation::Ascii, Operator::Iff) => "<>",
+            (Notation::Ascii, Operator::Xor) => "^",
+            (Notation::Latex, Operator::And) => "\\land",
+            (Notation::Latex, Operator::Or) => "\\lor",
+            (Notation::Latex, Operator::Implies) => "\\to",
+            (Notation::Latex, Operator::Iff) => "\\leftrightarrow",
+            (Notation::Latex, Operator::Xor) => "\\oplus",
+        }
+    }
+
+    // Returns the symbol for a negation, including the space LaTeX needs before a proposition
+    pub fn negation(&self) -> &'static str {
+        match self {
+            Notation::Unicode => "¬",
+            Notation::Ascii => "!",
+            Notation::Latex => "\\neg ",
+        }
+    }
+
+    // Returns the symbol for a truth value
+    pub fn truth_value(&self, value: bool) -> &'static str {
+        match (self, value) {
+            (Notation::Latex, true) => "\\top",
+            (Notation::Latex, false) => "\\bot",
+            (_, true) => "TRUE",
+            (_, false) => "FALSE",
+        }
+    }
+
+    // Returns the symbol placed before a conclusion
+    pub fn therefore(&self) -> &'static str {
+        match self {
+            Notation::Unicode => "∴",
+            Notation::Ascii => "therefore",
+            Notation::Latex => "\\therefore",
+        }
+    }
+}
+
+impl Premise {
+    // Renders the premise using the symbols of the given notation
+    // Rendering with Notation::Unicode gives the same string as Display
+    // Subpremises are rendered from a list instead of by recursing into them, so deeply nested
+    // premises can be rendered without overflowing the stack
+    pub fn render(&self, notation: Notation) -> String {
+        let mut rendered = String::new();
+
+        // Each entry is the rest of a list of nodes and whether the next one is rendered after a
+        // space, which every node but the first is unless it follows a negation
+        let mut stack = vec![(self.get_nodes().iter(), false)];
+
+        while let Some((nodes, spaced)) = stack.last_mut() {
+            let Some(node) = nodes.next() else {
+                stack.pop();
+
+                // The list was a subpremise unless it was the premise itself
+                if !stack.is_empty() {
+                    rendered.push(')');
+                }
+                continue;
+            };
+
+            if *spaced {
+                rendered.push(' ');
+            }
+            *spaced = !node.is_negation();
+
+            match node {
+                PremiseNode::Proposition(proposition) => rendered.push(*proposition),
+                PremiseNode::TruthValue(value) => rendered.push_str(notation.truth_value(*value)),
+                PremiseNode::Operator(operator) => rendered.push_str(notation.operator(*operator)),
+                PremiseNode::Negation => rendered.push_str(notation.negation()),
+                PremiseNode::Subpremise(subpremise) => {
+                    rendered.push('(');
+                    stack.push((subpremise.get_nodes().iter(), false));
+                }
+            }
+        }
+
+        rendered
+    }
+
+    // Renders the premise like render, followed by the string it was parsed from if that is
+    // written differently, such as "TRUE → m (as written: (f | s) > m)"
+    // The string is left out in LaTeX, where it could not be shown as it was written
+    pub fn render_as_written(&self, notation: Notation) -> String {
+        let rendered = self.render(notation);
+
+        match self.origin() {
+            Some(origin) if notation != Notation::Latex && origin != rendered => {
+                format!("{} (as written: {})", rendered, origin)
+            }
+            _ => rendered,
+        }
+    }
+}
+
+impl ValueMap {
+    // Renders each proposition and its value on its own line in alphabetical order like Display,
+    // using the symbols of the given notation for the values
+    pub fn render(&self, notation: Notation) -> String {
+        let mut values: Vec<(char, Option<bool>)> = self.iter().collect();
+        values.sort();
+
+        values
+            .into_iter()
+            .map(|(proposition, value)| match value {
+                Some(value) => format!("{} = {}\n", proposition, notation.truth_value(value)),
+                None => format!("{} = UNKNOWN\n", proposition),
+            })
+            .collect()
+    }
+
+    // Renders every proposition and its value on a single line in alphabetical order,
+    // with T, F or ? for the values, such as "b=F, f=T, s=?"
+    pub fn render_terse(&self) -> String {
+        let mut values: Vec<(char, Option<bool>)> = self.iter().collect();
+        values.sort();
+
+        let values: Vec<String> = values
+            .into_iter()
+            .map(|(proposition, value)| match value {
+                Some(true) => format!("{}=T", proposition),
+                Some(false) => format!("{}=F", proposition),
+                None => format!("{}=?", proposition),
+            })
+            .collect();
+
+        values.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut premise = Premise::parse_str("(m & !b) > !(j | k)").unwrap();
+
+        assert_eq!(premise.render(Notation::Unicode), premise.to_string());
+        assert_eq!(premise.render(Notation::Ascii), "(m & !b) > !(j | k)");
+
+        // The ASCII rendering can be parsed back
+        assert_eq!(
+            Premise::parse_str(&premise.render(Notation::Ascii)).unwrap(),
+            premise
+        );
+
+        let mut values = ValueMap::default();
+        values.add_propositions(&premise);
+        values.set_value('k', Some(true));
+        premise.substitute(&values);
+
+        assert_eq!(premise.render(Notation::Unicode), premise.to_string());
+        assert_eq!(
+            premise.render(Notation::Latex),
+            "(m \\land \\neg b) \\to \\neg (j \\lor \\top)"
+        );
+        assert_eq!(values.render(Notation::Unicode), values.to_string());
+        assert!(values.render(Notation::Latex).contains("k = \\top\n"));
+        assert_eq!(values.render_terse(), "b=?, j=?, k=T, m=?");
+    }
+}
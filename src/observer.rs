@@ -0,0 +1,469 @@
+use std::ops::ControlFlow;
+use std::time::Duration;
+
+use crate::clock::Stopwatch;
+use crate::errors::SolveError;
+use crate::history::{HistoryDetail, StepAction};
+use crate::Deduction;
+use crate::Premise;
+
+// Receives events from a Deduction while it is being solved
+// Every event can stop the solve early by returning ControlFlow::Break
+// All methods do nothing by default, so observers only need to implement the events they use
+pub trait SolveObserver {
+    // Called at the start of each pass over the premise stack, with the Deduction as it is before the pass
+    fn on_step_start(&mut self, _pass: usize, _deduction: &Deduction) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    // Called whenever a premise is substituted or evaluated
+    fn on_premise_changed(
+        &mut self,
+        _label: &str,
+        _before: &Premise,
+        _after: &Premise,
+        _action: StepAction,
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    // Called when a proposition is renamed throughout the Deduction, after each premise it changed
+    // has been reported with on_premise_changed
+    fn on_proposition_renamed(&mut self, _from: char, _to: char) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    // Called whenever the value of a root proposition is determined, with the label of the premise it came from
+    fn on_value_derived(
+        &mut self,
+        _proposition: char,
+        _value: bool,
+        _source: &str,
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    // Called once when the solve ends, just before on_finished, with how long the solve took
+    fn on_elapsed(&mut self, _elapsed: Duration) {}
+
+    // Called once when the solve ends, whether it completed or was stopped by the observer
+    fn on_finished(&mut self, _result: &SolveResult) {}
+}
+
+// Ignores every event, for when nothing needs to be observed
+impl SolveObserver for () {}
+
+// Passes every event on to the observer it borrows
+impl<T: SolveObserver + ?Sized> SolveObserver for &mut T {
+    fn on_step_start(&mut self, pass: usize, deduction: &Deduction) -> ControlFlow<()> {
+        (**self).on_step_start(pass, deduction)
+    }
+
+    fn on_premise_changed(
+        &mut self,
+        label: &str,
+        before: &Premise,
+        after: &Premise,
+        action: StepAction,
+    ) -> ControlFlow<()> {
+        (**self).on_premise_changed(label, before, after, action)
+    }
+
+    fn on_proposition_renamed(&mut self, from: char, to: char) -> ControlFlow<()> {
+        (**self).on_proposition_renamed(from, to)
+    }
+
+    fn on_value_derived(
+        &mut self,
+        proposition: char,
+        value: bool,
+        source: &str,
+    ) -> ControlFlow<()> {
+        (**self).on_value_derived(proposition, value, source)
+    }
+
+    fn on_elapsed(&mut self, elapsed: Duration) {
+        (**self).on_elapsed(elapsed)
+    }
+
+    fn on_finished(&mut self, result: &SolveResult) {
+        (**self).on_finished(result)
+    }
+}
+
+// Passes every event to both observers in turn, stopping the solve if either of them stops it
+// The second observer still receives an event the first stopped the solve on
+impl<A: SolveObserver, B: SolveObserver> SolveObserver for (A, B) {
+    fn on_step_start(&mut self, pass: usize, deduction: &Deduction) -> ControlFlow<()> {
+        let first = self.0.on_step_start(pass, deduction);
+        let second = self.1.on_step_start(pass, deduction);
+        first?;
+        second
+    }
+
+    fn on_premise_changed(
+        &mut self,
+        label: &str,
+        before: &Premise,
+        after: &Premise,
+        action: StepAction,
+    ) -> ControlFlow<()> {
+        let first = self.0.on_premise_changed(label, before, after, action);
+        let second = self.1.on_premise_changed(label, before, after, action);
+        first?;
+        second
+    }
+
+    fn on_proposition_renamed(&mut self, from: char, to: char) -> ControlFlow<()> {
+        let first = self.0.on_proposition_renamed(from, to);
+        let second = self.1.on_proposition_renamed(from, to);
+        first?;
+        second
+    }
+
+    fn on_value_derived(
+        &mut self,
+        proposition: char,
+        value: bool,
+        source: &str,
+    ) -> ControlFlow<()> {
+        let first = self.0.on_value_derived(proposition, value, source);
+        let second = self.1.on_value_derived(proposition, value, source);
+        first?;
+        second
+    }
+
+    fn on_elapsed(&mut self, elapsed: Duration) {
+        self.0.on_elapsed(elapsed);
+        self.1.on_elapsed(elapsed);
+    }
+
+    fn on_finished(&mut self, result: &SolveResult) {
+        self.0.on_finished(result);
+        self.1.on_finished(result);
+    }
+}
+
+// Represents how a solve ended
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum SolveResult {
+    // No more premises could be substituted or evaluated
+    Complete,
+    // An observer stopped the solve early
+    Aborted,
+    // One of the limits in the SolveOptions stopped the solve early
+    LimitReached(SolveLimit),
+}
+
+impl SolveResult {
+    // Converts the result to Ok if the solve completed, or the reason it stopped early otherwise,
+    // so it can be used with ?
+    pub fn into_result(self) -> Result<(), SolveError> {
+        match self {
+            SolveResult::Complete => Ok(()),
+            SolveResult::Aborted => Err(SolveError::Aborted),
+            SolveResult::LimitReached(limit) => Err(SolveError::LimitReached(limit)),
+        }
+    }
+}
+
+// Represents which limit stopped a solve
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum SolveLimit {
+    Steps,
+    Timeout,
+}
+
+// Limits how much work a solve may do, with no limits by default, along with how much of its
+// history should be shown
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy)]
+pub struct SolveOptions {
+    // The most premises which may be substituted or evaluated
+    pub max_steps: Option<usize>,
+    // How long the solve may run for
+    pub timeout: Option<Duration>,
+    // How much of the history a caller which prints it should show, if any
+    // The solve itself records the same history whatever this is
+    pub history: Option<HistoryDetail>,
+}
+
+// Passes every event on to another observer, stopping the solve once a limit is reached
+pub(crate) struct LimitObserver<'a> {
+    inner: &'a mut dyn SolveObserver,
+    remaining_steps: Option<usize>,
+    stopwatch: Stopwatch,
+    timeout: Option<Duration>,
+    reached: Option<SolveLimit>,
+}
+
+impl<'a> LimitObserver<'a> {
+    // Creates a LimitObserver for the given options, with the timeout starting now
+    pub(crate) fn new(inner: &'a mut dyn SolveObserver, options: &SolveOptions) -> Self {
+        Self {
+            inner,
+            remaining_steps: options.max_steps,
+            stopwatch: Stopwatch::start(),
+            timeout: options.timeout,
+            reached: None,
+        }
+    }
+
+    // Returns the limit which stopped the solve, if any
+    pub(crate) fn get_reached(&self) -> Option<SolveLimit> {
+        self.reached
+    }
+
+    // Stops the solve if the timeout has passed
+    fn check_deadline(&mut self) -> ControlFlow<()> {
+        match self.timeout {
+            Some(timeout) if self.stopwatch.elapsed() >= timeout => {
+                self.reached = Some(SolveLimit::Timeout);
+                ControlFlow::Break(())
+            }
+            _ => ControlFlow::Continue(()),
+        }
+    }
+}
+
+impl SolveObserver for LimitObserver<'_> {
+    fn on_step_start(&mut self, pass: usize, deduction: &Deduction) -> ControlFlow<()> {
+        self.check_deadline()?;
+        self.inner.on_step_start(pass, deduction)
+    }
+
+    // Each change counts as one step, and the change which would go over the limit is not made
+    fn on_premise_changed(
+        &mut self,
+        label: &str,
+        before: &Premise,
+        after: &Premise,
+        action: StepAction,
+    ) -> ControlFlow<()> {
+        self.check_deadline()?;
+
+        match &mut self.remaining_steps {
+            Some(0) => {
+                self.reached = Some(SolveLimit::Steps);
+                return ControlFlow::Break(());
+            }
+            Some(remaining) => *remaining -= 1,
+            None => (),
+        }
+
+        self.inner.on_premise_changed(label, before, after, action)
+    }
+
+    fn on_value_derived(
+        &mut self,
+        proposition: char,
+        value: bool,
+        source: &str,
+    ) -> ControlFlow<()> {
+        self.inner.on_value_derived(proposition, value, source)
+    }
+
+    fn on_proposition_renamed(&mut self, from: char, to: char) -> ControlFlow<()> {
+        self.inner.on_proposition_renamed(from, to)
+    }
+
+    fn on_elapsed(&mut self, elapsed: Duration) {
+        self.inner.on_elapsed(elapsed)
+    }
+
+    // Tells the inner observer which limit was reached, if any
+    fn on_finished(&mut self, result: &SolveResult) {
+        match self.reached {
+            Some(limit) => self.inner.on_finished(&SolveResult::LimitReached(limit)),
+            None => self.inner.on_finished(result),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EvaluationHistory;
+
+    // Counts every event it receives, optionally stopping after a number of premise changes
+    #[derive(Default)]
+    struct CountingObserver {
+        step_starts: usize,
+        premise_changes: usize,
+        derived_values: usize,
+        finished: usize,
+        stop_after_changes: Option<usize>,
+    }
+
+    impl SolveObserver for CountingObserver {
+        fn on_step_start(&mut self, _pass: usize, _deduction: &Deduction) -> ControlFlow<()> {
+            self.step_starts += 1;
+            ControlFlow::Continue(())
+        }
+
+        fn on_premise_changed(
+            &mut self,
+            _label: &str,
+            _before: &Premise,
+            _after: &Premise,
+            _action: StepAction,
+        ) -> ControlFlow<()> {
+            self.premise_changes += 1;
+
+            match self.stop_after_changes {
+                Some(limit) if self.premise_changes >= limit => ControlFlow::Break(()),
+                _ => ControlFlow::Continue(()),
+            }
+        }
+
+        fn on_value_derived(
+            &mut self,
+            _proposition: char,
+            _value: bool,
+            _source: &str,
+        ) -> ControlFlow<()> {
+            self.derived_values += 1;
+            ControlFlow::Continue(())
+        }
+
+        fn on_finished(&mut self, _result: &SolveResult) {
+            self.finished += 1;
+        }
+    }
+
+    const PREMISES: [&str; 5] = ["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"];
+
+    #[test]
+    fn test_counts_match_history() {
+        let mut observer = CountingObserver::default();
+        let result = Deduction::from_strs(PREMISES.to_vec())
+            .unwrap()
+            .solve_with_observer(&mut observer);
+
+        let history = Deduction::from_strs(PREMISES.to_vec()).unwrap().solve();
+
+        assert_eq!(result, SolveResult::Complete);
+        assert_eq!(observer.step_starts, history.get_deductions().len());
+        assert_eq!(observer.premise_changes, history.get_steps().len());
+        // f, t, m, b and j are all determined
+        assert_eq!(observer.derived_values, 5);
+        assert_eq!(observer.finished, 1);
+    }
+
+    #[test]
+    fn test_abort() {
+        let mut observer = CountingObserver {
+            stop_after_changes: Some(2),
+            ..Default::default()
+        };
+
+        let result = Deduction::from_strs(PREMISES.to_vec())
+            .unwrap()
+            .solve_with_observer(&mut observer);
+
+        assert_eq!(result, SolveResult::Aborted);
+        assert_eq!(observer.premise_changes, 2);
+        assert_eq!(observer.finished, 1);
+    }
+
+    #[test]
+    fn test_pair() {
+        let mut first = CountingObserver {
+            stop_after_changes: Some(2),
+            ..Default::default()
+        };
+        let mut second = CountingObserver::default();
+
+        let result = Deduction::from_strs(PREMISES.to_vec())
+            .unwrap()
+            .solve_with_observer(&mut (&mut first, &mut second));
+
+        // Both observers see the change the first stopped the solve on
+        assert_eq!(result, SolveResult::Aborted);
+        assert_eq!(first.premise_changes, 2);
+        assert_eq!(second.premise_changes, 2);
+        assert_eq!(second.step_starts, first.step_starts);
+        assert_eq!(second.finished, 1);
+    }
+
+    #[test]
+    fn test_solve_options() {
+        let mut observer = CountingObserver::default();
+        let options = SolveOptions {
+            max_steps: Some(3),
+            ..Default::default()
+        };
+
+        let mut deduction = Deduction::from_strs(PREMISES.to_vec()).unwrap();
+        let result = deduction.solve_with_options(&options, &mut observer);
+
+        assert_eq!(result, SolveResult::LimitReached(SolveLimit::Steps));
+        assert_eq!(observer.premise_changes, 3);
+        assert_eq!(observer.finished, 1);
+
+        let options = SolveOptions {
+            timeout: Some(Duration::ZERO),
+            ..Default::default()
+        };
+
+        let result = Deduction::from_strs(PREMISES.to_vec())
+            .unwrap()
+            .solve_with_options(&options, &mut CountingObserver::default());
+        assert_eq!(result, SolveResult::LimitReached(SolveLimit::Timeout));
+
+        // A limit which is never reached does not change the result
+        let options = SolveOptions {
+            max_steps: Some(100),
+            timeout: Some(Duration::from_secs(60)),
+            history: None,
+        };
+
+        let result = Deduction::from_strs(PREMISES.to_vec())
+            .unwrap()
+            .solve_with_options(&options, &mut CountingObserver::default());
+        assert_eq!(result, SolveResult::Complete);
+
+        // A solve stopped by the step limit leaves the Deduction as the history last recorded it,
+        // which is the last snapshot with the steps of its pass applied
+        for max_steps in 0..10 {
+            let options = SolveOptions {
+                max_steps: Some(max_steps),
+                ..Default::default()
+            };
+
+            let mut history = EvaluationHistory::new();
+            let mut deduction = Deduction::from_strs(PREMISES.to_vec()).unwrap();
+            let result = deduction.solve_with_options(&options, &mut history);
+            assert_eq!(result, SolveResult::LimitReached(SolveLimit::Steps));
+            assert_eq!(history.get_steps().len(), max_steps);
+
+            let last_pass = history.get_deductions().len();
+            let mut recorded: Vec<(String, Premise)> = history.get_deductions()[last_pass - 1]
+                .get_labeled_premises()
+                .into_iter()
+                .map(|(label, premise)| (label, premise.clone()))
+                .collect();
+
+            for step in history.get_steps() {
+                if step.get_pass() == last_pass {
+                    let (_, premise) = recorded
+                        .iter_mut()
+                        .find(|(label, _)| label == step.get_label())
+                        .unwrap();
+                    assert_eq!(premise, step.get_before());
+                    *premise = step.get_after().clone();
+                }
+            }
+
+            // Premises resolved at the start of the pass are removed without a step
+            for (label, premise) in deduction.get_labeled_premises() {
+                assert!(
+                    recorded.contains(&(label.clone(), premise.clone())),
+                    "{} steps: {} is {}",
+                    max_steps,
+                    label,
+                    premise
+                );
+            }
+        }
+    }
+}
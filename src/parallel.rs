@@ -0,0 +1,414 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::observer::SolveResult;
+use crate::truth_table::{Formula, RowOrder, TruthRow, TruthRows};
+use crate::Deduction;
+use crate::Premise;
+use crate::ValueMap;
+
+// How many parts of the table each thread is given on average, so a thread which finishes its
+// parts early can take more instead of waiting for the others
+const PARTS_PER_THREAD: usize = 8;
+
+// How many rows a thread checks between looking for whether another thread has found an earlier
+// row, so it can stop searching a part which can no longer hold the result
+const ROWS_PER_CHECK: usize = 1024;
+
+// Checks every row of a formula's truth table across several threads
+// The table is split into parts by the values of its first propositions, which the threads take
+// in the standard order, so every result is the same as checking the rows one at a time
+#[derive(Debug, Clone)]
+pub struct ParallelTable<'a, F: Formula + Sync = Premise> {
+    formula: &'a F,
+    propositions: Vec<char>,
+    threads: usize,
+}
+
+impl<'a, F: Formula + Sync> ParallelTable<'a, F> {
+    // Creates a ParallelTable which uses as many threads as the system can run at once
+    pub fn new(formula: &'a F) -> Self {
+        Self {
+            formula,
+            propositions: formula.propositions().into_iter().collect(),
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+        }
+    }
+
+    // Sets how many threads are used, where 0 is treated as 1
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    // Returns the propositions in the order of each row's values
+    pub fn get_propositions(&self) -> &[char] {
+        &self.propositions
+    }
+
+    // Checks whether the formula is true under every assignment
+    pub fn is_tautology(&self) -> bool {
+        self.find_falsifying().is_none()
+    }
+
+    // Checks whether the formula is true under some assignment
+    pub fn is_satisfiable(&self) -> bool {
+        self.find_satisfying().is_some()
+    }
+
+    // Returns the first row in the standard order where the formula is false
+    pub fn find_falsifying(&self) -> Option<TruthRow> {
+        self.find_first(false)
+    }
+
+    // Returns the first row in the standard order where the formula is true
+    pub fn find_satisfying(&self) -> Option<TruthRow> {
+        self.find_first(true)
+    }
+
+    // Counts the rows where the formula is true
+    pub fn count_satisfying(&self) -> usize {
+        let next_part = AtomicUsize::new(0);
+        let count = AtomicUsize::new(0);
+
+        self.run_workers(|| {
+            while let Some(rows) = self.take_part(&next_part).map(|(_, rows)| rows) {
+                let satisfying = rows.filter(|row| row.get_result()).count();
+                count.fetch_add(satisfying, Ordering::Relaxed);
+            }
+        });
+
+        count.into_inner()
+    }
+
+    // Returns the first row in the standard order whose result is the target
+    // Each thread stops at the first such row in its part, and gives up on a part once a row has
+    // been found in an earlier one, so the earliest part with such a row always finishes
+    fn find_first(&self, target: bool) -> Option<TruthRow> {
+        let next_part = AtomicUsize::new(0);
+        let earliest = AtomicUsize::new(usize::MAX);
+        let found = Mutex::new(Vec::new());
+
+        self.run_workers(|| {
+            while let Some((part, rows)) = self.take_part(&next_part) {
+                if earliest.load(Ordering::Relaxed) < part {
+                    return;
+                }
+
+                for (i, row) in rows.enumerate() {
+                    if i % ROWS_PER_CHECK == 0 && earliest.load(Ordering::Relaxed) < part {
+                        break;
+                    }
+
+                    if row.get_result() == target {
+                        earliest.fetch_min(part, Ordering::Relaxed);
+                        found.lock().unwrap().push((part, row));
+                        break;
+                    }
+                }
+            }
+        });
+
+        found
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .min_by_key(|(part, _)| *part)
+            .map(|(_, row)| row)
+    }
+
+    // Runs the work on every thread, waiting for all of them to finish
+    // A single thread runs it on the calling thread without spawning one, so the table can still
+    // be checked where threads cannot be spawned, such as WebAssembly in the browser, where
+    // available_parallelism fails and leaves one thread
+    fn run_workers(&self, work: impl Fn() + Sync) {
+        if self.threads == 1 {
+            work();
+            return;
+        }
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads {
+                scope.spawn(&work);
+            }
+        });
+    }
+
+    // Takes the next part of the table which no thread has started, returning its number and its
+    // rows, or None once every part has been taken
+    fn take_part(&self, next_part: &AtomicUsize) -> Option<(usize, TruthRows<'a, F>)> {
+        let prefix_len = self.get_prefix_len();
+        let part = next_part.fetch_add(1, Ordering::Relaxed);
+
+        if part >= 1 << prefix_len {
+            return None;
+        }
+
+        // The first proposition is the most significant bit, with 0 being TRUE as in the table
+        let prefix: Vec<bool> = (0..prefix_len)
+            .map(|i| part & (1 << (prefix_len - 1 - i)) == 0)
+            .collect();
+
+        let rows = TruthRows::with_prefix(
+            self.formula,
+            self.propositions.clone(),
+            RowOrder::Standard,
+            &prefix,
+        );
+
+        Some((part, rows))
+    }
+
+    // Returns how many of the first propositions the table is split by, which gives each thread
+    // about PARTS_PER_THREAD parts
+    fn get_prefix_len(&self) -> usize {
+        let parts = (self.threads * PARTS_PER_THREAD).next_power_of_two();
+
+        (parts.trailing_zeros() as usize).min(self.propositions.len())
+    }
+}
+
+// Checks whether the conclusion is true under every assignment which makes all the premises true,
+// by checking whether "(P1 ∧ P2 ∧ ...) → conclusion" is a tautology across several threads
+// Returns the first row of that formula's truth table where it is false, or None if the argument
+// is valid
+pub fn find_counterexample(
+    premises: &[Premise],
+    conclusion: &Premise,
+    threads: usize,
+) -> Option<(Vec<char>, TruthRow)> {
+    let argument = match premises.iter().cloned().reduce(Premise::and) {
+        Some(antecedent) => antecedent.implies(conclusion.clone()),
+        None => conclusion.clone(),
+    };
+
+    let table = ParallelTable::new(&argument).with_threads(threads);
+
+    table
+        .find_falsifying()
+        .map(|row| (table.get_propositions().to_vec(), row))
+}
+
+// Checks whether the argument is valid like find_counterexample
+pub fn is_valid(premises: &[Premise], conclusion: &Premise, threads: usize) -> bool {
+    find_counterexample(premises, conclusion, threads).is_none()
+}
+
+// An argument to evaluate in a batch, with its premises and an optional conclusion
+#[derive(Debug, Clone)]
+pub struct Problem {
+    premises: Vec<Premise>,
+    conclusion: Option<Premise>,
+}
+
+// Stores what evaluating one Problem found
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct DeductionResult {
+    values: ValueMap,
+    conclusion: Option<bool>,
+    consistent: bool,
+    completed: bool,
+}
+
+impl Problem {
+    // Creates a Problem from its premises and conclusion
+    pub fn new(premises: Vec<Premise>, conclusion: Option<Premise>) -> Self {
+        Self {
+            premises,
+            conclusion,
+        }
+    }
+
+    // Solves the Problem on the current thread
+    pub fn evaluate(&self) -> DeductionResult {
+        let mut deduction = Deduction::from_premises(self.premises.clone());
+        if let Some(conclusion) = &self.conclusion {
+            deduction.set_conclusion(conclusion.clone());
+        }
+
+        let result = deduction.solve_with_observer(&mut ());
+
+        DeductionResult {
+            values: deduction.get_values().clone(),
+            conclusion: deduction.evaluate_conclusion(),
+            consistent: !deduction.is_contradictory(),
+            completed: result == SolveResult::Complete,
+        }
+    }
+}
+
+impl DeductionResult {
+    // Returns the values found for every proposition
+    pub fn get_values(&self) -> &ValueMap {
+        &self.values
+    }
+
+    // Returns the value found for the conclusion, or None if there is none or it is unknown
+    pub fn get_conclusion_value(&self) -> Option<bool> {
+        self.conclusion
+    }
+
+    // Checks whether solving did not show the premises to be inconsistent
+    pub fn is_consistent(&self) -> bool {
+        self.consistent
+    }
+
+    // Checks whether the solve ran until no premise could be changed
+    pub fn is_completed(&self) -> bool {
+        self.completed
+    }
+}
+
+// Evaluates every Problem independently across as many threads as the system can run at once,
+// returning the results in the same order as the problems
+#[cfg(feature = "parallel")]
+pub fn evaluate_batch(problems: Vec<Problem>) -> Vec<DeductionResult> {
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    evaluate_batch_on(&problems, threads)
+}
+
+// Evaluates the problems like evaluate_batch using the given number of threads, where each thread
+// takes the next problem no thread has started until there are none left
+#[cfg(feature = "parallel")]
+fn evaluate_batch_on(problems: &[Problem], threads: usize) -> Vec<DeductionResult> {
+    let next_problem = AtomicUsize::new(0);
+    let results = Mutex::new(vec![None; problems.len()]);
+
+    let work = || loop {
+        let i = next_problem.fetch_add(1, Ordering::Relaxed);
+        let Some(problem) = problems.get(i) else {
+            return;
+        };
+
+        let result = problem.evaluate();
+        results.lock().unwrap()[i] = Some(result);
+    };
+
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(work);
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("[INTERNAL ERROR] A problem in the batch was not evaluated"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Generates a formula over the first propositions from the seed, where most subformulas are
+    // disjunctions so a few rows are false
+    fn generate(seed: &mut u64, depth: usize, propositions: usize) -> Premise {
+        *seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let choice = (*seed >> 33) % 6;
+
+        if depth == 0 || choice == 0 {
+            let proposition = (b'a' + ((*seed >> 40) as usize % propositions) as u8) as char;
+
+            return match choice % 2 {
+                0 => Premise::atom(proposition),
+                _ => !Premise::atom(proposition),
+            };
+        }
+
+        let (left, right) = (
+            generate(seed, depth - 1, propositions),
+            generate(seed, depth - 1, propositions),
+        );
+
+        match choice {
+            1 => left & right,
+            2 => left.implies(right),
+            _ => left | right,
+        }
+    }
+
+    // Fails to compile if the type cannot be shared between threads
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_send_sync() {
+        assert_send_sync::<Deduction>();
+        assert_send_sync::<Premise>();
+        assert_send_sync::<ValueMap>();
+        assert_send_sync::<crate::EvaluationHistory>();
+        assert_send_sync::<Problem>();
+        assert_send_sync::<DeductionResult>();
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_evaluate_batch() {
+        use crate::generator::SeededRng;
+
+        let mut rng = SeededRng::new(7);
+        let problems: Vec<Problem> = (0..50)
+            .map(|_| {
+                let premises = (0..4).map(|_| Premise::arbitrary(3, 5, &mut rng)).collect();
+                Problem::new(premises, Some(Premise::arbitrary(2, 5, &mut rng)))
+            })
+            .collect();
+
+        let sequential: Vec<DeductionResult> = problems.iter().map(Problem::evaluate).collect();
+
+        for threads in [1, 3, 8] {
+            assert_eq!(evaluate_batch_on(&problems, threads), sequential);
+        }
+        assert_eq!(evaluate_batch(problems), sequential);
+    }
+
+    #[test]
+    fn test_matches_serial() {
+        let mut seed = 3;
+
+        for _ in 0..100 {
+            let premise = generate(&mut seed, 6, 10);
+            let falsifying = premise.truth_rows().find(|row| !row.get_result());
+            let satisfying = premise.truth_rows().find(|row| row.get_result());
+            let count = premise.truth_rows().filter(|row| row.get_result()).count();
+
+            for threads in [1, 2, 3, 8] {
+                let table = ParallelTable::new(&premise).with_threads(threads);
+
+                assert_eq!(table.find_falsifying(), falsifying, "{}", premise);
+                assert_eq!(table.find_satisfying(), satisfying, "{}", premise);
+                assert_eq!(table.count_satisfying(), count, "{}", premise);
+                assert_eq!(table.is_tautology(), premise.is_tautology());
+                assert_eq!(table.is_satisfiable(), premise.is_satisfiable());
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_counterexample() {
+        let premises = [
+            Premise::parse_str("a > b").unwrap(),
+            Premise::parse_str("b > c").unwrap(),
+        ];
+
+        assert!(is_valid(
+            &premises,
+            &Premise::parse_str("a > c").unwrap(),
+            4
+        ));
+
+        // Every row where c → a is false has c TRUE and a FALSE, and the first has b TRUE
+        let (propositions, row) =
+            find_counterexample(&premises, &Premise::parse_str("c > a").unwrap(), 4).unwrap();
+        assert_eq!(propositions, ['a', 'b', 'c']);
+        assert_eq!(row.get_values(), [false, true, true]);
+
+        // Without premises, the conclusion itself has to be a tautology
+        assert!(is_valid(&[], &Premise::parse_str("a | !a").unwrap(), 2));
+        assert!(!is_valid(&[], &Premise::parse_str("a").unwrap(), 2));
+    }
+}
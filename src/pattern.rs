@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+
+use crate::ast::BinaryExpr;
+use crate::errors::ParseError;
+use crate::premises::{find_main_operator, Operator};
+use crate::Premise;
+use crate::PremiseNode;
+
+// Represents the shape of a formula, written like a premise in which metavariables such as "_A"
+// stand for any subformula, so "_A > _B" matches every implication
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Pattern {
+    root: PatternNode,
+}
+
+// Represents one part of a pattern, where every operator has exactly two operands
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum PatternNode {
+    Metavariable(String),
+    Proposition(char),
+    TruthValue(bool),
+    Not(Box<PatternNode>),
+    Binary(Operator, Box<PatternNode>, Box<PatternNode>),
+}
+
+// Stores the subformula each metavariable of a pattern matched, by the name of the metavariable
+// without its underscore
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+pub struct Bindings {
+    bindings: BTreeMap<String, Premise>,
+}
+
+impl Pattern {
+    // Parses a pattern, where a metavariable is an underscore followed by letters or digits
+    // Everything else is parsed like a premise, so "p" in a pattern only matches the proposition p
+    pub fn parse(pattern: &str) -> Result<Self, ParseError> {
+        let mut premise_string = String::with_capacity(pattern.len());
+        let mut metavariables: Vec<(char, String)> = Vec::new();
+        let mut unused = ('a'..='z').filter(|c| !pattern.contains(*c));
+
+        let mut pattern_chars = pattern.char_indices().peekable();
+
+        while let Some((i, c)) = pattern_chars.next() {
+            let mut name = String::new();
+            while let Some((_, next)) =
+                pattern_chars.next_if(|(_, next)| c == '_' && next.is_ascii_alphanumeric())
+            {
+                name.push(next);
+            }
+
+            if name.is_empty() {
+                premise_string.push(c);
+                continue;
+            }
+
+            // Each metavariable is parsed as a proposition the pattern does not otherwise use,
+            // padded with spaces so the positions in any parse error stay the same
+            let placeholder = match metavariables.iter().find(|(_, other)| *other == name) {
+                Some((placeholder, _)) => *placeholder,
+                None => {
+                    let placeholder = unused.next().ok_or(ParseError::InvalidCharacter {
+                        character: '_',
+                        position: i,
+                    })?;
+                    metavariables.push((placeholder, name.clone()));
+
+                    placeholder
+                }
+            };
+
+            premise_string.push(placeholder);
+            premise_string.push_str(&" ".repeat(name.len()));
+        }
+
+        let expression = Premise::parse_str(&premise_string)?.to_binary()?;
+
+        Ok(Self {
+            root: PatternNode::from_binary(&expression, &metavariables),
+        })
+    }
+
+    // Matches the premise against the pattern, returning the subformula each metavariable matched
+    pub fn match_premise(&self, premise: &Premise) -> Option<Bindings> {
+        self.match_with(premise, Bindings::default())
+    }
+
+    // Matches the premise against the pattern like match_premise, where the metavariables which
+    // are already bound must match the same subformulas again
+    // This lets one pattern be matched against the result of another, such as the antecedent of
+    // an implication against another premise
+    pub fn match_with(&self, premise: &Premise, mut bindings: Bindings) -> Option<Bindings> {
+        self.root
+            .match_nodes(premise.get_nodes(), &mut bindings)
+            .then_some(bindings)
+    }
+}
+
+impl PatternNode {
+    // Converts a parsed pattern to a tree, turning the placeholders back into metavariables
+    fn from_binary(expression: &BinaryExpr, metavariables: &[(char, String)]) -> Self {
+        match expression {
+            BinaryExpr::Atom(proposition) => {
+                match metavariables
+                    .iter()
+                    .find(|(placeholder, _)| placeholder == proposition)
+                {
+                    Some((_, name)) => PatternNode::Metavariable(name.clone()),
+                    None => PatternNode::Proposition(*proposition),
+                }
+            }
+            BinaryExpr::Const(value) => PatternNode::TruthValue(*value),
+            BinaryExpr::Not(operand) => {
+                PatternNode::Not(Box::new(PatternNode::from_binary(operand, metavariables)))
+            }
+            BinaryExpr::Binary(operator, left, right) => PatternNode::Binary(
+                *operator,
+                Box::new(PatternNode::from_binary(left, metavariables)),
+                Box::new(PatternNode::from_binary(right, metavariables)),
+            ),
+        }
+    }
+
+    // Checks whether the nodes have the shape of this part of the pattern, adding the subformulas
+    // of any new metavariables to the bindings
+    // A metavariable which is already bound only matches a subformula with the same structure,
+    // ignoring the order and grouping of the operands of ∧ and ∨ like equivalent_structure
+    fn match_nodes(&self, nodes: &[PremiseNode], bindings: &mut Bindings) -> bool {
+        let nodes = strip_parentheses(nodes);
+
+        match self {
+            PatternNode::Metavariable(name) => match bindings.bindings.get(name) {
+                Some(bound) => bound.equivalent_structure(&Premise::new(nodes.to_vec())),
+                None => {
+                    bindings
+                        .bindings
+                        .insert(name.clone(), Premise::new(nodes.to_vec()));
+                    true
+                }
+            },
+            PatternNode::Proposition(proposition) => {
+                nodes == [PremiseNode::Proposition(*proposition)]
+            }
+            PatternNode::TruthValue(value) => nodes == [PremiseNode::TruthValue(*value)],
+            PatternNode::Not(operand) => match nodes {
+                [PremiseNode::Negation, negated @ ..] if find_main_operator(nodes).is_none() => {
+                    operand.match_nodes(negated, bindings)
+                }
+                _ => false,
+            },
+            PatternNode::Binary(operator, left, right) => match find_main_operator(nodes) {
+                Some(i) if nodes[i] == PremiseNode::Operator(*operator) => {
+                    left.match_nodes(&nodes[..i], bindings)
+                        && right.match_nodes(&nodes[i + 1..], bindings)
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Bindings {
+    // Returns the subformula the metavariable with the given name matched, without any
+    // parentheses around it
+    pub fn get(&self, name: &str) -> Option<&Premise> {
+        self.bindings.get(name)
+    }
+
+    // Returns every metavariable and the subformula it matched, in alphabetical order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Premise)> + '_ {
+        self.bindings
+            .iter()
+            .map(|(name, premise)| (name.as_str(), premise))
+    }
+}
+
+impl Premise {
+    // Matches the Premise against the pattern, returning the subformula each metavariable matched
+    pub fn match_pattern(&self, pattern: &Pattern) -> Option<Bindings> {
+        pattern.match_premise(self)
+    }
+}
+
+// Returns the nodes inside any parentheses which surround all of them
+pub(crate) fn strip_parentheses(mut nodes: &[PremiseNode]) -> &[PremiseNode] {
+    while let [PremiseNode::Subpremise(subpremise)] = nodes {
+        nodes = subpremise.get_nodes();
+    }
+
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Matches the premise against the pattern, returning each binding as it is displayed
+    fn bindings(premise: &str, pattern: &str) -> Option<Vec<(String, String)>> {
+        let pattern = Pattern::parse(pattern).unwrap();
+
+        Premise::parse_str(premise)
+            .unwrap()
+            .match_pattern(&pattern)
+            .map(|bindings| {
+                bindings
+                    .iter()
+                    .map(|(name, premise)| (name.to_string(), premise.to_string()))
+                    .collect()
+            })
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(Pattern::parse("_A -> _B").is_ok());
+        assert!(Pattern::parse("!(_Left & p) | _Right2").is_ok());
+        assert_eq!(
+            Pattern::parse("_A & # _B"),
+            Err(ParseError::InvalidCharacter {
+                character: '#',
+                position: 5
+            })
+        );
+        assert_eq!(
+            Pattern::parse("_ & p"),
+            Err(ParseError::InvalidCharacter {
+                character: '_',
+                position: 0
+            })
+        );
+        assert_eq!(Pattern::parse("_A &"), Err(ParseError::InvalidStructure));
+    }
+
+    #[test]
+    fn test_match_pattern() {
+        let pair = |name: &str, premise: &str| (name.to_string(), premise.to_string());
+
+        // Each metavariable binds the whole operand, without its parentheses
+        assert_eq!(
+            bindings("(m & !b) > j", "_A -> _B"),
+            Some(vec![pair("A", "m ∧ ¬b"), pair("B", "j")])
+        );
+        assert_eq!(
+            bindings("!(p | q) | (r > s)", "!_X | _Y"),
+            Some(vec![pair("X", "p ∨ q"), pair("Y", "r → s")])
+        );
+
+        // The operator has to be the main one, and a proposition in the pattern only matches itself
+        assert_eq!(bindings("a & b > c", "_A & _B"), None);
+        assert_eq!(bindings("a | b", "!_A | _B"), None);
+        assert_eq!(
+            bindings("p > q | r", "p > _Q"),
+            Some(vec![pair("Q", "q ∨ r")])
+        );
+        assert_eq!(bindings("r > q", "p > _Q"), None);
+    }
+
+    #[test]
+    fn test_consistent_bindings() {
+        let pattern = Pattern::parse("_A > _A").unwrap();
+
+        assert!(Premise::parse_str("(p | q) > (p | q)")
+            .unwrap()
+            .match_pattern(&pattern)
+            .is_some());
+        assert!(Premise::parse_str("(p | q) > (q | p)")
+            .unwrap()
+            .match_pattern(&pattern)
+            .is_some());
+        assert!(Premise::parse_str("p > q")
+            .unwrap()
+            .match_pattern(&pattern)
+            .is_none());
+        assert!(Premise::parse_str("p > !p")
+            .unwrap()
+            .match_pattern(&pattern)
+            .is_none());
+
+        // Bindings from one match carry over to the next
+        let implication = Pattern::parse("_A > _B").unwrap();
+        let antecedent = Pattern::parse("_A").unwrap();
+        let bindings = Premise::parse_str("p & q > r")
+            .unwrap()
+            .match_pattern(&implication)
+            .unwrap();
+
+        assert!(antecedent
+            .match_with(&Premise::parse_str("(q & p)").unwrap(), bindings.clone())
+            .is_some());
+        assert!(antecedent
+            .match_with(&Premise::parse_str("q").unwrap(), bindings)
+            .is_none());
+    }
+}
@@ -1,15 +1,38 @@
-use std::fmt::{Display, Formatter, Result};
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Result, Write};
+use std::hash::{Hash, Hasher};
+use std::ops::ControlFlow;
+use std::sync::Arc;
 
+use crate::arena::PremiseArena;
+use crate::errors::{EvalError, ParseError, StructureError};
+use crate::notation::Notation;
 use crate::ValueMap;
 
+// How many levels of subpremises clone recurses into before it keeps a list of them instead
+const MAX_RECURSIVE_CLONE_DEPTH: usize = 64;
+
 // Represents a propositional logic premise through a concrete syntax tree
-#[derive(Debug, Eq, PartialEq)]
+// Premises are equal and hash the same when they have the same nodes, however they were written
+#[derive(Debug)]
 pub struct Premise {
     nodes: Vec<PremiseNode>,
+    // The string the Premise was parsed from, which is kept through substitution and evaluation
+    // but not given to premises built from other premises
+    // It is shared rather than copied, as every step of a solve makes a new Premise which keeps it
+    origin: Option<Arc<str>>,
+}
+
+// Represents a premise parsed by Premise::parse_borrowed, which borrows the string it was parsed
+// from instead of owning a copy of it
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PremiseRef<'a> {
+    nodes: Vec<PremiseNode>,
+    origin: &'a str,
 }
 
 // Represents nodes in the premise tree
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub enum PremiseNode {
     Proposition(char),
     TruthValue(bool),
@@ -18,31 +41,93 @@ pub enum PremiseNode {
     Subpremise(Premise),
 }
 
-// Represents one of 3 required binary operators for this project
-#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+// Represents the kind of a PremiseNode without its contents
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum NodeKind {
+    Proposition,
+    TruthValue,
+    Operator,
+    Negation,
+    Subpremise,
+}
+
+// Represents the logical rule applied in a single evaluation step
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy)]
+pub enum EvaluationRule {
+    // ¬TRUE becomes FALSE and ¬FALSE becomes TRUE
+    Negation,
+    // ¬¬p becomes p
+    DoubleNegation,
+    // (p) becomes p
+    Parentheses,
+    // An ∧ with a known operand
+    Conjunction,
+    // An ∨ with a known operand
+    Disjunction,
+    // An → with a known operand
+    Implication,
+    // An ↔ with a known operand
+    Biconditional,
+    // An ⊕ with a known operand
+    ExclusiveDisjunction,
+}
+
+// Represents one of the binary operators for this project
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
 pub enum Operator {
     And,
     Or,
     Implies,
+    // True exactly when both operands have the same value
+    Iff,
+    // True exactly when the operands have different values
+    Xor,
 }
 
-impl Display for Premise {
-    // Displays the premise as a string
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // Join all nodes together with a space except for not operators
-        for (i, node) in self.nodes.iter().enumerate() {
-            // Don't print a space before the first node
-            if i == 0 {
-                write!(f, "{}", node)?;
-            } else {
-                match self.nodes[i - 1] {
-                    PremiseNode::Negation => write!(f, "{}", node)?,
-                    _ => write!(f, " {}", node)?,
+impl PartialEq for Premise {
+    // Compares the nodes of the premises, ignoring how they were written
+    // Subpremises are compared from a list instead of by recursing into them, so deeply nested
+    // premises can be compared without overflowing the stack
+    fn eq(&self, other: &Self) -> bool {
+        let mut pairs = Vec::new();
+        let (mut left, mut right) = (self, other);
+
+        loop {
+            if left.nodes.len() != right.nodes.len() {
+                return false;
+            }
+
+            for pair in left.nodes.iter().zip(&right.nodes) {
+                match pair {
+                    (PremiseNode::Subpremise(left), PremiseNode::Subpremise(right)) => {
+                        pairs.push((left, right))
+                    }
+                    (left, right) if left != right => return false,
+                    _ => (),
                 }
             }
+
+            match pairs.pop() {
+                Some(pair) => (left, right) = pair,
+                None => return true,
+            }
         }
+    }
+}
+
+impl Eq for Premise {}
+
+impl Hash for Premise {
+    // Hashes the nodes of the premise, ignoring how it was written, to agree with eq
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.nodes.hash(state);
+    }
+}
 
-        Ok(())
+impl Display for Premise {
+    // Displays the premise as a string, which is how it renders with Notation::Unicode
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render(Notation::Unicode))
     }
 }
 
@@ -51,7 +136,8 @@ impl Display for PremiseNode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             PremiseNode::Proposition(proposition) => write!(f, "{}", proposition),
-            PremiseNode::TruthValue(value) => write!(f, "{}", value),
+            PremiseNode::TruthValue(true) => write!(f, "TRUE"),
+            PremiseNode::TruthValue(false) => write!(f, "FALSE"),
             PremiseNode::Operator(operator) => write!(f, "{}", operator),
             PremiseNode::Negation => write!(f, "¬"),
             PremiseNode::Subpremise(subpremise) => write!(f, "({})", subpremise),
@@ -66,46 +152,269 @@ impl Display for Operator {
             Operator::And => write!(f, "∧"),
             Operator::Or => write!(f, "∨"),
             Operator::Implies => write!(f, "→"),
+            Operator::Iff => write!(f, "↔"),
+            Operator::Xor => write!(f, "⊕"),
+        }
+    }
+}
+
+impl<'a> PremiseRef<'a> {
+    // Returns the nodes in the premise
+    pub fn get_nodes(&self) -> &[PremiseNode] {
+        &self.nodes
+    }
+
+    // Returns the string the premise was parsed from, without any surrounding whitespace
+    pub fn origin(&self) -> &'a str {
+        self.origin
+    }
+
+    // Converts the premise to a Premise, copying the string it was parsed from
+    pub fn into_owned(self) -> Premise {
+        Premise {
+            nodes: self.nodes,
+            origin: Some(Arc::from(self.origin)),
+        }
+    }
+}
+
+impl Clone for Premise {
+    // Copies the Premise, switching from recursing into each subpremise to keeping a list of them
+    // once it is nested deeply, so a deeply nested Premise can be copied, such as by substituted,
+    // without overflowing the stack
+    // Most premises are shallow, so they are copied without allocating anything but their nodes
+    fn clone(&self) -> Self {
+        self.clone_nested(MAX_RECURSIVE_CLONE_DEPTH)
+    }
+}
+
+impl Drop for Premise {
+    // Drops the subpremises one level at a time instead of recursing into each of them, so a deeply
+    // nested Premise can be dropped without overflowing the stack
+    fn drop(&mut self) {
+        let mut subpremises = Vec::new();
+        let mut nodes = std::mem::take(&mut self.nodes);
+
+        loop {
+            subpremises.extend(nodes.drain(..).filter_map(|node| match node {
+                PremiseNode::Subpremise(subpremise) => Some(subpremise),
+                _ => None,
+            }));
+
+            match subpremises.pop() {
+                Some(mut subpremise) => nodes = std::mem::take(&mut subpremise.nodes),
+                None => return,
+            }
         }
     }
 }
 
+impl std::ops::BitAnd for Premise {
+    type Output = Premise;
+
+    // Creates the conjunction of the Premises, parenthesizing either one if it is compound
+    fn bitand(self, right: Premise) -> Premise {
+        self.and(right)
+    }
+}
+
+impl std::ops::BitOr for Premise {
+    type Output = Premise;
+
+    // Creates the disjunction of the Premises, parenthesizing either one if it is compound
+    fn bitor(self, right: Premise) -> Premise {
+        self.or(right)
+    }
+}
+
+impl std::ops::Not for Premise {
+    type Output = Premise;
+
+    // Creates the negation of the Premise, parenthesizing it if it is compound
+    // Unlike negated, this always adds a negation, so it builds ¬¬p from ¬p
+    fn not(self) -> Premise {
+        let mut nodes = vec![PremiseNode::Negation];
+        nodes.extend(wrap_operand(self.into_nodes()));
+
+        Premise::new(nodes)
+    }
+}
+
 impl Premise {
     // Creates a new Premise from the given fields
-    fn new(nodes: Vec<PremiseNode>) -> Self {
-        Self { nodes }
+    pub(crate) fn new(nodes: Vec<PremiseNode>) -> Self {
+        Self {
+            nodes,
+            origin: None,
+        }
     }
 
-    // Creates an Premise from a string
-    pub fn parse_str(premise_string: &str) -> Self {
-        let mut nodes = Vec::new();
+    // Creates a Premise from its nodes, returning an error if they do not form a valid premise
+    // by the same rules the parser follows
+    pub fn from_nodes(nodes: Vec<PremiseNode>) -> std::result::Result<Self, StructureError> {
+        let premise = Self::new(nodes);
+        premise.check_structure()?;
 
-        let mut premise_chars = premise_string.char_indices();
+        Ok(premise)
+    }
+
+    // Creates a Premise of a single proposition
+    pub fn atom(proposition: char) -> Self {
+        Self::new(vec![PremiseNode::Proposition(proposition)])
+    }
+
+    // Creates the conjunction of two Premises, which is also written "left & right"
+    pub fn and(self, right: Premise) -> Self {
+        Self::combine(Operator::And, self, right)
+    }
+
+    // Creates the disjunction of two Premises, which is also written "left | right"
+    pub fn or(self, right: Premise) -> Self {
+        Self::combine(Operator::Or, self, right)
+    }
+
+    // Creates the implication from the Premise to another
+    // There is no operator for this, as ">>" would read like a shift rather than an implication
+    pub fn implies(self, consequent: Premise) -> Self {
+        Self::combine(Operator::Implies, self, consequent)
+    }
+
+    // Creates the biconditional of two Premises, which holds when both have the same value
+    pub fn iff(self, right: Premise) -> Self {
+        Self::combine(Operator::Iff, self, right)
+    }
+
+    // Creates the exclusive disjunction of two Premises, which is also written "left ^ right"
+    pub fn xor(self, right: Premise) -> Self {
+        Self::combine(Operator::Xor, self, right)
+    }
+
+    // Joins two Premises with an operator, parenthesizing either one if it is compound so the
+    // operator is always the main one
+    // The nodes of both are moved rather than copied, so a premise can be built up one operator
+    // at a time in time linear in its size
+    pub fn combine(operator: Operator, left: Premise, right: Premise) -> Self {
+        let mut nodes = wrap_operand(left.into_nodes());
+        nodes.push(PremiseNode::Operator(operator));
+        nodes.extend(wrap_operand(right.into_nodes()));
+
+        Self::new(nodes)
+    }
+
+    // Creates a Premise from a string, returning an error if the string contains an invalid
+    // character, is empty, has a parenthesis which is never closed or opened, or does not form a
+    // valid premise
+    pub fn parse_str(premise_string: &str) -> std::result::Result<Self, ParseError> {
+        Ok(Self::parse_borrowed(premise_string)?.into_owned())
+    }
+
+    // Parses a string like parse_str without checking its structure or parentheses, panicking on an
+    // invalid character, for tests which build malformed premises on purpose
+    #[cfg(test)]
+    pub(crate) fn parse_lenient(premise_string: &str) -> Self {
+        let mut premise =
+            Self::parse_nodes(premise_string).unwrap_or_else(|error| panic!("{}", error));
+        premise.origin = Some(Arc::from(premise_string.trim()));
+
+        premise
+    }
+
+    // Parses a string like parse_str, but borrows the string the premise was parsed from
+    // instead of copying it, for parsing many premises which may not all be kept
+    pub fn parse_borrowed(premise_string: &str) -> std::result::Result<PremiseRef<'_>, ParseError> {
+        let premise = Self::parse_nodes(premise_string)?;
+
+        if premise_string.trim().is_empty() {
+            return Err(ParseError::EmptyPremise);
+        }
+
+        if let Some(position) = find_unbalanced_parenthesis(premise_string) {
+            return Err(ParseError::UnbalancedParentheses { position });
+        }
+
+        if !premise.validate() {
+            return Err(ParseError::InvalidStructure);
+        }
+
+        Ok(PremiseRef {
+            nodes: premise.into_nodes(),
+            origin: premise_string.trim(),
+        })
+    }
+
+    // Parses the nodes of a premise string in a single pass
+    // Each open parenthesis starts a new list of nodes, and the matching close parenthesis wraps
+    // it in a subpremise of the list it was opened in, so no part of the string is read twice
+    // Subpremises which are never closed run to the end of the string, and stray close
+    // parentheses are ignored
+    // The outermost list of nodes is sized up front, so a long flat premise is not reallocated as
+    // it grows
+    fn parse_nodes(premise_string: &str) -> std::result::Result<Self, ParseError> {
+        let mut nodes = Vec::with_capacity(count_outer_nodes(premise_string));
+        let mut open_groups: Vec<Vec<PremiseNode>> = Vec::new();
+
+        let mut premise_chars = premise_string.char_indices().peekable();
 
         while let Some((i, c)) = premise_chars.next() {
             match c {
                 ' ' => (),
-                // If a subpremise is found, parse it recursively
-                '(' => {
-                    // Collect the subpremise string to be parsed
-                    let subpremise_string = get_subpremise_string(&premise_string[i..]);
-
-                    nodes.push(PremiseNode::Subpremise(Self::parse_str(&subpremise_string)));
-
-                    // Skip the characters in the subpremise
-                    premise_chars.nth(subpremise_string.len());
+                '(' => open_groups.push(std::mem::take(&mut nodes)),
+                ')' => {
+                    if let Some(parent) = open_groups.pop() {
+                        let subpremise = Self::new(std::mem::replace(&mut nodes, parent));
+                        nodes.push(PremiseNode::Subpremise(subpremise));
+                    }
                 }
-                ')' => (),
                 '¬' | '!' => nodes.push(PremiseNode::Negation),
                 '∧' | '&' => nodes.push(PremiseNode::Operator(Operator::And)),
                 '∨' | '|' => nodes.push(PremiseNode::Operator(Operator::Or)),
                 '→' | '>' => nodes.push(PremiseNode::Operator(Operator::Implies)),
+                // "->" is also accepted for implication, in which case the '>' is skipped
+                '-' if premise_chars.next_if(|(_, next)| *next == '>').is_some() => {
+                    nodes.push(PremiseNode::Operator(Operator::Implies));
+                }
+                '↔' | '=' => nodes.push(PremiseNode::Operator(Operator::Iff)),
+                '⊕' | '^' => nodes.push(PremiseNode::Operator(Operator::Xor)),
+                // "<>" and "<->" are accepted for the biconditional, in which case the rest of the
+                // token is skipped
+                '<' if premise_chars.next_if(|(_, next)| *next == '>').is_some() => {
+                    nodes.push(PremiseNode::Operator(Operator::Iff));
+                }
+                '<' if premise_string[i + 1..].starts_with("->") => {
+                    premise_chars.nth(1);
+                    nodes.push(PremiseNode::Operator(Operator::Iff));
+                }
                 'a'..='z' => nodes.push(PremiseNode::Proposition(c)),
-                _ => panic!("Invalid character in premise: '{}'", c),
+                _ => {
+                    return Err(ParseError::InvalidCharacter {
+                        character: c,
+                        position: i,
+                    })
+                }
             }
         }
 
-        Self::new(nodes)
+        while let Some(parent) = open_groups.pop() {
+            let subpremise = Self::new(std::mem::replace(&mut nodes, parent));
+            nodes.push(PremiseNode::Subpremise(subpremise));
+        }
+
+        Ok(Self::new(nodes))
+    }
+
+    // Returns the string the Premise was parsed from, or None if it was built from other premises
+    pub fn origin(&self) -> Option<&str> {
+        self.origin.as_deref()
+    }
+
+    // Replaces the nodes of the Premise, keeping the string it was parsed from
+    pub(crate) fn set_nodes(&mut self, nodes: Vec<PremiseNode>) {
+        self.nodes = nodes;
+    }
+
+    // Takes the nodes out of the Premise, which cannot be moved out of it as it implements Drop
+    fn into_nodes(mut self) -> Vec<PremiseNode> {
+        std::mem::take(&mut self.nodes)
     }
 
     // Returns the nodes in the Premise
@@ -113,6 +422,62 @@ impl Premise {
         &self.nodes
     }
 
+    // Calls the function on every node of the Premise in pre-order, from left to right, visiting
+    // each subpremise node before the nodes inside it
+    pub fn visit(&self, f: &mut impl FnMut(&PremiseNode)) {
+        let _ = self.try_visit(&mut |node| {
+            f(node);
+            ControlFlow::<()>::Continue(())
+        });
+    }
+
+    // Visits the nodes like visit, but stops as soon as the function returns ControlFlow::Break,
+    // returning the value it broke with
+    // Neither visitor recurses, so both can be used on deeply nested premises
+    pub fn try_visit<B>(
+        &self,
+        f: &mut impl FnMut(&PremiseNode) -> ControlFlow<B>,
+    ) -> ControlFlow<B> {
+        for node in self.iter_nodes() {
+            f(node)?;
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    // Visits the nodes like visit, but allows the function to change them in place
+    // A node which the function replaces with a subpremise is visited inside as well
+    pub fn visit_mut(&mut self, f: &mut impl FnMut(&mut PremiseNode)) {
+        let mut stack = vec![self.nodes.iter_mut()];
+
+        while let Some(nodes) = stack.last_mut() {
+            let Some(node) = nodes.next() else {
+                stack.pop();
+                continue;
+            };
+
+            f(node);
+
+            if let PremiseNode::Subpremise(subpremise) = node {
+                stack.push(subpremise.nodes.iter_mut());
+            }
+        }
+    }
+
+    // Returns every node of the Premise in the same order as visit, without recursing, so it can
+    // be used on deeply nested premises
+    pub fn iter_nodes(&self) -> impl Iterator<Item = &PremiseNode> + '_ {
+        self.iter_nodes_with_depth().map(|(node, _)| node)
+    }
+
+    // Returns every node like iter_nodes along with its depth, where the nodes of the Premise
+    // itself have a depth of 1 and the nodes of each subpremise have one more than the subpremise
+    pub fn iter_nodes_with_depth(&self) -> DepthFirstNodes<'_> {
+        DepthFirstNodes {
+            stack: vec![(self.nodes.iter(), 1)],
+        }
+    }
+
     // Returns the number of operators in the Premise
     pub fn get_num_operators(&self) -> usize {
         self.nodes.iter().filter(|node| node.is_operator()).count()
@@ -123,9 +488,141 @@ impl Premise {
         self.nodes.iter().filter(|node| node.is_operand()).count()
     }
 
+    // Returns the number of nodes in the Premise, counting each subpremise as one node along with
+    // every node inside it, so it can be used to compare the size of premises
+    pub fn node_count(&self) -> usize {
+        self.iter_nodes().count()
+    }
+
+    // Returns how deeply the Premise is nested, where a Premise without subpremises has a depth of 1
+    // and each level of subpremises adds 1, or 0 if the Premise has no nodes
+    pub fn depth(&self) -> usize {
+        self.iter_nodes_with_depth()
+            .map(|(_, depth)| depth)
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Scores how complex the Premise is, so the result of a rewrite can be compared with what it
+    // started as, such as when reporting that a solve went from complexity 17 to 5
+    // Operands and negations count 1, ∧ and ∨ count 2, →, ↔ and ⊕ count 3, and each level of nesting
+    // beyond the first adds 1
+    // Every evaluation step keeps the score the same or lowers it
+    pub fn complexity(&self) -> u32 {
+        let weights: u32 = self
+            .iter_nodes()
+            .map(|node| match node {
+                PremiseNode::Proposition(_) | PremiseNode::TruthValue(_) => 1,
+                PremiseNode::Negation => 1,
+                PremiseNode::Operator(Operator::And | Operator::Or) => 2,
+                PremiseNode::Operator(Operator::Implies | Operator::Iff | Operator::Xor) => 3,
+                PremiseNode::Subpremise(_) => 0,
+            })
+            .sum();
+
+        weights + self.depth().saturating_sub(1) as u32
+    }
+
+    // Returns every proposition in the Premise, including those inside subpremises, in
+    // alphabetical order
+    pub fn propositions(&self) -> BTreeSet<char> {
+        let mut propositions = BTreeSet::new();
+
+        self.visit(&mut |node| {
+            if let PremiseNode::Proposition(proposition) = node {
+                propositions.insert(*proposition);
+            }
+        });
+
+        propositions
+    }
+
+    // Returns the negation of the Premise, removing a leading negation of the whole Premise
+    // instead of adding a second one, so the negation of ¬(p ∨ q) is p ∨ q
+    pub fn negated(&self) -> Premise {
+        match self.nodes.as_slice() {
+            [PremiseNode::Subpremise(subpremise)] => subpremise.negated(),
+            [PremiseNode::Negation, operand @ ..] if is_single_operand(operand) => match operand {
+                [PremiseNode::Subpremise(subpremise)] => subpremise.clone(),
+                _ => Premise::new(operand.to_vec()),
+            },
+            nodes => Premise::new(negate_nodes(nodes)),
+        }
+    }
+
     // Checks whether the Premise is syntactically valid
+    // Operands and binary operators must alternate, negations may only precede an operand,
+    // and every subpremise must itself be valid
     pub fn validate(&self) -> bool {
-        todo!()
+        self.find_structure_error().is_none()
+    }
+
+    // Checks whether the Premise is syntactically valid like validate, returning what is wrong
+    // with it if it is not
+    fn check_structure(&self) -> std::result::Result<(), StructureError> {
+        let Some((path, error)) = self.find_structure_error() else {
+            return Ok(());
+        };
+
+        // The error is wrapped once for every subpremise it is inside, from the innermost out
+        Err(path
+            .into_iter()
+            .rev()
+            .fold(error, |error, index| StructureError::InvalidSubpremise {
+                index,
+                error: Box::new(error),
+            }))
+    }
+
+    // Finds the first node which makes the Premise invalid, returning what is wrong with it along
+    // with the index of each subpremise it is inside, from the outermost in
+    // Subpremises are checked from a list instead of by recursing into them, so deeply nested
+    // premises can be checked without overflowing the stack
+    fn find_structure_error(&self) -> Option<(Vec<usize>, StructureError)> {
+        // Each entry is a list of nodes being checked, how many of them have been checked, whether
+        // an operand is expected next, and the index of the subpremise it is in the list below it
+        let mut stack = vec![(self.nodes.as_slice(), 0, true, 0)];
+
+        let error = loop {
+            let (nodes, checked, expecting_operand, _) = stack.last_mut()?;
+
+            let Some(node) = nodes.get(*checked) else {
+                // A premise cannot be empty or end with an operator or negation
+                match (nodes.is_empty(), *expecting_operand) {
+                    (true, _) => break StructureError::Empty,
+                    (false, true) => break StructureError::MissingOperand,
+                    (false, false) => {
+                        stack.pop();
+                        continue;
+                    }
+                }
+            };
+
+            let index = *checked;
+            *checked += 1;
+
+            match node {
+                PremiseNode::Negation if *expecting_operand => (),
+                PremiseNode::Operator(_) if !*expecting_operand => *expecting_operand = true,
+                PremiseNode::Subpremise(subpremise) if *expecting_operand => {
+                    *expecting_operand = false;
+                    stack.push((subpremise.nodes.as_slice(), 0, true, index));
+                }
+                PremiseNode::Proposition(_) | PremiseNode::TruthValue(_) if *expecting_operand => {
+                    *expecting_operand = false
+                }
+                _ => break StructureError::UnexpectedNode { index },
+            }
+        };
+
+        // The list at the bottom of the stack is the Premise itself, which is not a subpremise
+        let path = stack
+            .iter()
+            .skip(1)
+            .map(|(_, _, _, index)| *index)
+            .collect();
+
+        Some((path, error))
     }
 
     // Checks whether a given Premise is a root proposition such as "p" or "¬p",
@@ -140,114 +637,725 @@ impl Premise {
 
     // Substitutes all Proposition nodes with their actual truth values, if known
     pub fn substitute(&mut self, proposition_values: &ValueMap) {
-        for node in &mut self.nodes {
-            match node {
-                PremiseNode::Proposition(proposition) => {
-                    if let Some(value) = proposition_values.get_value(*proposition) {
-                        *node = PremiseNode::TruthValue(value);
-                    }
-                }
-                PremiseNode::Subpremise(subpremise) => {
-                    subpremise.substitute(proposition_values);
+        self.visit_mut(&mut |node| {
+            if let PremiseNode::Proposition(proposition) = node {
+                if let Some(value) = proposition_values.get_value(*proposition) {
+                    *node = PremiseNode::TruthValue(value);
                 }
-                _ => (),
             }
+        });
+    }
+
+    // Replaces every occurrence of a root proposition with another
+    pub fn rename_proposition(&mut self, from: char, to: char) {
+        self.visit_mut(&mut |node| {
+            if *node == PremiseNode::Proposition(from) {
+                *node = PremiseNode::Proposition(to);
+            }
+        });
+    }
+
+    // Checks whether substitute would change the Premise, which it does if any of its propositions
+    // has a known value
+    pub(crate) fn can_substitute(&self, proposition_values: &ValueMap) -> bool {
+        self.try_visit(&mut |node| match node {
+            PremiseNode::Proposition(proposition)
+                if proposition_values.get_value(*proposition).is_some() =>
+            {
+                ControlFlow::Break(())
+            }
+            _ => ControlFlow::Continue(()),
+        })
+        .is_break()
+    }
+
+    // Returns a copy of the Premise with its propositions substituted like substitute, leaving the
+    // Premise itself unchanged
+    pub fn substituted(&self, proposition_values: &ValueMap) -> Premise {
+        let mut premise = self.clone();
+        premise.substitute(proposition_values);
+
+        premise
+    }
+
+    // Finds the truth value of the Premise using the given proposition values,
+    // returning None if they are not enough to determine it
+    // This substitutes the values and simplifies the Premise, so premises which are not valid are
+    // handled in the same way as solving them
+    // evaluate_with_cache shares the values of repeated subformulas between calls instead
+    pub fn evaluate(&self, proposition_values: &ValueMap) -> Option<bool> {
+        let mut premise = self.substituted(proposition_values);
+        premise.simplify();
+
+        get_truth_value(&premise.nodes)
+    }
+
+    // Finds the truth value of the Premise like evaluate, but explains why it could not be found
+    // instead of returning None, including when a proposition is missing from the values
+    pub fn try_evaluate(
+        &self,
+        proposition_values: &ValueMap,
+    ) -> std::result::Result<bool, EvalError> {
+        let propositions = self.propositions();
+
+        if let Some(missing) = propositions
+            .iter()
+            .find(|proposition| !proposition_values.contains(**proposition))
+        {
+            return Err(EvalError::MissingProposition(*missing));
+        }
+
+        if let Some(value) = self.evaluate(proposition_values) {
+            return Ok(value);
+        }
+
+        let unknown: Vec<char> = propositions
+            .into_iter()
+            .filter(|proposition| proposition_values.get_value(*proposition).is_none())
+            .collect();
+
+        match unknown.is_empty() {
+            true => Err(EvalError::InvalidStructure),
+            false => Err(EvalError::Undetermined { unknown }),
         }
     }
 
-    // Simplifies the premise by removing all unnecessary nodes based on logical rules
-    // ? Does this need to be public?
-    pub fn simplify(&mut self) {
-        // Simplify all subpremises
-        for node in &mut self.nodes {
+    // Renders the Premise as an indented tree with one node per line
+    // Each line is prefixed with the node's index path (e.g. "4.1") so it can be referenced elsewhere,
+    // and the contents of each subpremise are listed below it with one more level of indentation
+    pub fn pretty_tree(&self) -> String {
+        let mut tree = String::new();
+        self.write_tree(&mut tree, "", 0);
+
+        tree
+    }
+
+    // Writes the nodes of this level of the tree, recursing into subpremises
+    fn write_tree(&self, tree: &mut String, path: &str, depth: usize) {
+        for (i, node) in self.nodes.iter().enumerate() {
+            let node_path = match path {
+                "" => i.to_string(),
+                _ => format!("{}.{}", path, i),
+            };
+
+            // Writing to a String cannot fail
+            writeln!(
+                tree,
+                "{}{} {}",
+                "  ".repeat(depth),
+                node_path,
+                node.tree_label()
+            )
+            .unwrap();
+
             if let PremiseNode::Subpremise(subpremise) = node {
-                subpremise.simplify();
+                subpremise.write_tree(tree, &node_path, depth + 1);
             }
         }
+    }
+
+    // Simplifies the premise by removing all unnecessary nodes based on logical rules
+    // ? Does this need to be public?
+    // This takes the same steps as calling evaluate_step until it returns None, but works on a
+    // PremiseArena in a single scan so each step does not clone the parts of the premise it keeps
+    pub fn simplify(&mut self) {
+        self.simplify_with(|_| {});
+    }
+
+    // Simplifies the premise like simplify, calling on_rewrite with the rule used by each step,
+    // such as to count how often each rule is used
+    pub fn simplify_with(&mut self, mut on_rewrite: impl FnMut(EvaluationRule)) {
+        let mut arena = PremiseArena::from_premise(self);
+        arena.simplify(&mut on_rewrite);
+
+        let simplified = arena.to_premise();
+        debug_assert!(simplified.complexity() <= self.complexity());
 
-        unimplemented!()
+        self.nodes = simplified.into_nodes();
     }
-}
 
-// Returns the first subpremise found in the given premise string
-fn get_subpremise_string(premise_string: &str) -> String {
-    let mut subpremise_string = String::new();
-    let mut depth = 0;
+    // Performs a single evaluation step on the premise, returning the rule used if anything changed
+    // The innermost, leftmost reducible part is evaluated first, following operator precedence
+    // (¬, then ∧, then ∨, then → which groups from the right)
+    pub fn evaluate_step(&mut self) -> Option<EvaluationRule> {
+        let (nodes, rule) = self.next_step()?;
+        self.nodes = nodes;
 
-    for character in premise_string.chars() {
-        match character {
-            '(' => depth += 1,
-            ')' => depth -= 1,
-            _ => (),
+        Some(rule)
+    }
+
+    // Returns the nodes the Premise would have after evaluate_step and the rule it would use,
+    // without changing the Premise
+    pub(crate) fn next_step(&self) -> Option<(Vec<PremiseNode>, EvaluationRule)> {
+        if let Some(step) = evaluate_nodes_once(&self.nodes) {
+            return Some(step);
         }
 
-        // If the depth is 0, the subpremise has been collected
-        if depth == 0 {
-            break;
+        // A premise which is entirely one subpremise does not need the parentheses
+        match self.nodes.as_slice() {
+            [PremiseNode::Subpremise(subpremise)] => {
+                Some((subpremise.nodes.clone(), EvaluationRule::Parentheses))
+            }
+            _ => None,
         }
+    }
 
-        // Do not add the open parenthesis to the subpremise string
-        if depth == 1 && character == '(' {
-            continue;
+    // Copies the Premise by recursing into its subpremises, until the given number of levels
+    // remain, below which it copies them with clone_iteratively
+    fn clone_nested(&self, levels: usize) -> Premise {
+        if levels == 0 {
+            return self.clone_iteratively();
         }
 
-        subpremise_string.push(character);
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| match node {
+                PremiseNode::Subpremise(subpremise) => {
+                    PremiseNode::Subpremise(subpremise.clone_nested(levels - 1))
+                }
+                node => node.clone(),
+            })
+            .collect();
+
+        self.with_nodes(nodes)
+    }
+
+    // Copies the Premise one level at a time instead of recursing into each subpremise
+    // Each copy is finished once all of its nodes are, and is then added to the one it is inside
+    fn clone_iteratively(&self) -> Premise {
+        let mut stack = vec![(
+            self.nodes.iter(),
+            Vec::with_capacity(self.nodes.len()),
+            self,
+        )];
+
+        loop {
+            let (nodes, copied, _) = stack.last_mut().unwrap();
+
+            match nodes.next() {
+                Some(PremiseNode::Subpremise(subpremise)) => stack.push((
+                    subpremise.nodes.iter(),
+                    Vec::with_capacity(subpremise.nodes.len()),
+                    subpremise,
+                )),
+                Some(node) => copied.push(node.clone()),
+                None => {
+                    let (_, nodes, original) = stack.pop().unwrap();
+                    let copy = original.with_nodes(nodes);
+
+                    match stack.last_mut() {
+                        Some((_, copied, _)) => copied.push(PremiseNode::Subpremise(copy)),
+                        None => return copy,
+                    }
+                }
+            }
+        }
     }
 
-    subpremise_string
+    // Returns a Premise with the given nodes which keeps the string this Premise was parsed from
+    pub(crate) fn with_nodes(&self, nodes: Vec<PremiseNode>) -> Premise {
+        Premise {
+            nodes,
+            origin: self.origin.clone(),
+        }
+    }
 }
 
-impl PremiseNode {
-    fn is_proposition(&self) -> Option<char> {
+impl Display for EvaluationRule {
+    // Displays the name of the rule
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match self {
-            PremiseNode::Proposition(p) => Some(*p),
-            _ => None,
+            EvaluationRule::Negation => write!(f, "negation"),
+            EvaluationRule::DoubleNegation => write!(f, "double negation"),
+            EvaluationRule::Parentheses => write!(f, "parentheses"),
+            EvaluationRule::Conjunction => write!(f, "conjunction"),
+            EvaluationRule::Disjunction => write!(f, "disjunction"),
+            EvaluationRule::Implication => write!(f, "implication"),
+            EvaluationRule::Biconditional => write!(f, "biconditional"),
+            EvaluationRule::ExclusiveDisjunction => write!(f, "exclusive disjunction"),
         }
     }
+}
 
-    fn is_operator(&self) -> bool {
+impl Operator {
+    // Returns the value of the operator applied to the two truth values
+    pub fn apply(self, left: bool, right: bool) -> bool {
         match self {
-            PremiseNode::Operator(o) => true,
-            _ => false,
+            Operator::And => left && right,
+            Operator::Or => left || right,
+            Operator::Implies => !left || right,
+            Operator::Iff => left == right,
+            Operator::Xor => left != right,
         }
     }
 
-    fn is_negation(&self) -> bool {
-        match self {
-            PremiseNode::Negation => true,
-            _ => false,
+    // Returns the value of the operator applied to the two truth values like apply, where either
+    // may be unknown, or None if the known values are not enough to determine it
+    pub fn apply_partial(self, left: Option<bool>, right: Option<bool>) -> Option<bool> {
+        match (self, left, right) {
+            (_, Some(left), Some(right)) => Some(self.apply(left, right)),
+            (Operator::And, Some(false), _) | (Operator::And, _, Some(false)) => Some(false),
+            (Operator::Or, Some(true), _) | (Operator::Or, _, Some(true)) => Some(true),
+            (Operator::Implies, Some(false), _) | (Operator::Implies, _, Some(true)) => Some(true),
+            _ => None,
         }
     }
 
-    fn is_operand(&self) -> bool {
+    // Checks whether the operands of the operator can be swapped without changing its value
+    pub fn is_commutative(self) -> bool {
+        matches!(
+            self,
+            Operator::And | Operator::Or | Operator::Iff | Operator::Xor
+        )
+    }
+
+    // Checks whether a chain of the operator has the same value however it is grouped
+    pub fn is_associative(self) -> bool {
+        matches!(
+            self,
+            Operator::And | Operator::Or | Operator::Iff | Operator::Xor
+        )
+    }
+
+    // Returns how tightly the operator binds its operands, where higher binds more tightly
+    pub(crate) fn precedence(&self) -> u8 {
         match self {
-            PremiseNode::Proposition(_)
-            | PremiseNode::Subpremise(_)
-            | PremiseNode::TruthValue(_) => true,
-            _ => false,
+            Operator::And => 5,
+            Operator::Xor => 4,
+            Operator::Or => 3,
+            Operator::Implies => 2,
+            Operator::Iff => 1,
         }
     }
+
+    // Checks whether a chain of this operator groups from the right, such as "a → b → c"
+    pub(crate) fn is_right_associative(&self) -> bool {
+        matches!(self, Operator::Implies)
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+// Finds the index of the operator which is applied last in the given nodes, if there is one
+// This is the loosest-binding operator, taking the leftmost one for right-associative operators
+// and the rightmost one otherwise
+pub(crate) fn find_main_operator(nodes: &[PremiseNode]) -> Option<usize> {
+    find_main_operator_of(nodes.iter().map(|node| match node {
+        PremiseNode::Operator(operator) => Some(*operator),
+        _ => None,
+    }))
+}
 
-    #[test]
-    fn test_parse_simple() {
-        let premise = Premise::parse_str("a");
+// Finds the main operator like find_main_operator, given the operator of each node if it is one
+pub(crate) fn find_main_operator_of(
+    operators: impl Iterator<Item = Option<Operator>>,
+) -> Option<usize> {
+    let mut main_operator: Option<(usize, Operator)> = None;
 
-        assert_eq!(premise.get_nodes().len(), 1);
-        assert_eq!(premise.get_nodes()[0], PremiseNode::Proposition('a'));
+    for (i, operator) in operators.enumerate() {
+        if let Some(operator) = operator {
+            let is_main = match main_operator {
+                None => true,
+                Some((_, current)) => {
+                    operator.precedence() < current.precedence()
+                        || (operator.precedence() == current.precedence()
+                            && !operator.is_right_associative())
+                }
+            };
+
+            if is_main {
+                main_operator = Some((i, operator));
+            }
+        }
     }
 
-    #[test]
-    fn test_parse_complex_1() {
-        let premise = Premise::parse_str("a ∧ b ∨ (c → d)");
+    main_operator.map(|(i, _)| i)
+}
 
-        assert_eq!(premise.get_nodes().len(), 5);
-        assert_eq!(premise.get_nodes()[0], PremiseNode::Proposition('a'));
-        assert_eq!(premise.get_nodes()[1], PremiseNode::Operator(Operator::And));
+// How far the search of a part of the nodes for the next evaluation step has got
+#[derive(Clone, Copy)]
+enum StepSearch {
+    // Nothing has been searched yet
+    Start,
+    // The left operand of the main operator at the index is being searched
+    Left(usize),
+    // The right operand of the main operator at the index is being searched
+    Right(usize),
+    // The subpremise of the operand, after any negations, is being searched
+    Inside,
+}
+
+// Evaluates the innermost, leftmost reducible part of the given nodes
+// Returns the rewritten nodes and the rule used, or None if nothing can be evaluated
+// The parts being searched are kept on a stack instead of being recursed into, so deeply nested
+// premises can be evaluated without overflowing the stack
+fn evaluate_nodes_once(nodes: &[PremiseNode]) -> Option<(Vec<PremiseNode>, EvaluationRule)> {
+    // The stack is sized for most premises up front, so it is not reallocated as it grows
+    let mut stack = Vec::with_capacity(16);
+    stack.push((nodes, StepSearch::Start));
+
+    // A part which has nothing to evaluate is popped, and the part below it carries on searching
+    // with its next operand or then itself
+    let (mut new_nodes, rule) = loop {
+        let (nodes, search) = stack.last_mut()?;
+        let nodes = *nodes;
+
+        let step = match *search {
+            StepSearch::Start => match find_main_operator(nodes) {
+                Some(i) => {
+                    *search = StepSearch::Left(i);
+                    stack.push((&nodes[..i], StepSearch::Start));
+                    continue;
+                }
+                None => match operand_subpremise(nodes) {
+                    Some(subpremise) => {
+                        *search = StepSearch::Inside;
+                        stack.push((&subpremise.nodes, StepSearch::Start));
+                        continue;
+                    }
+                    None => evaluate_operand_once(nodes),
+                },
+            },
+            StepSearch::Left(i) => {
+                *search = StepSearch::Right(i);
+                stack.push((&nodes[i + 1..], StepSearch::Start));
+                continue;
+            }
+            StepSearch::Right(i) => {
+                let PremiseNode::Operator(operator) = nodes[i] else {
+                    unreachable!()
+                };
+
+                apply_operator(operator, &nodes[..i], &nodes[i + 1..])
+            }
+            StepSearch::Inside => evaluate_operand_once(nodes),
+        };
+
+        match step {
+            Some(step) => break step,
+            None => {
+                stack.pop();
+            }
+        }
+    };
+
+    // The part which was evaluated is put back in place in each part it is inside, from the
+    // innermost out
+    stack.pop();
+
+    // The rewritten nodes are moved rather than copied, as they hold everything evaluated so far
+    while let Some((nodes, search)) = stack.pop() {
+        new_nodes = match search {
+            StepSearch::Left(i) => {
+                new_nodes.extend_from_slice(&nodes[i..]);
+                new_nodes
+            }
+            StepSearch::Right(i) => {
+                let mut rewritten = nodes[..=i].to_vec();
+                rewritten.extend(new_nodes);
+                rewritten
+            }
+            StepSearch::Inside => {
+                let negations = nodes.iter().take_while(|node| node.is_negation()).count();
+                let mut rewritten = nodes[..negations].to_vec();
+                rewritten.extend(wrap_operand(new_nodes));
+                rewritten
+            }
+            StepSearch::Start => unreachable!(),
+        };
+    }
+
+    Some((new_nodes, rule))
+}
+
+// Returns the subpremise of an operand, which is any number of negations followed by a single
+// subpremise, or None if the nodes are not such an operand
+fn operand_subpremise(nodes: &[PremiseNode]) -> Option<&Premise> {
+    let negations = nodes.iter().take_while(|node| node.is_negation()).count();
+
+    match &nodes[negations..] {
+        [PremiseNode::Subpremise(subpremise)] => Some(subpremise),
+        _ => None,
+    }
+}
+
+// Evaluates an operand, which is any number of negations followed by a single proposition,
+// truth value or subpremise, once nothing inside its subpremise can be evaluated
+fn evaluate_operand_once(nodes: &[PremiseNode]) -> Option<(Vec<PremiseNode>, EvaluationRule)> {
+    let negations = nodes.iter().take_while(|node| node.is_negation()).count();
+    let (prefix, operand) = nodes.split_at(negations);
+
+    // Malformed input cannot be evaluated
+    let [operand] = operand else {
+        return None;
+    };
+
+    // A subpremise which only holds a single operand does not need the parentheses
+    if let PremiseNode::Subpremise(subpremise) = operand {
+        if is_single_operand(&subpremise.nodes) {
+            return Some((
+                [prefix, &subpremise.nodes].concat(),
+                EvaluationRule::Parentheses,
+            ));
+        }
+    }
+
+    match (negations, operand) {
+        // Evaluate the innermost negation of a truth value
+        (1.., PremiseNode::TruthValue(value)) => Some((
+            [&prefix[1..], &[PremiseNode::TruthValue(!value)]].concat(),
+            EvaluationRule::Negation,
+        )),
+        // Remove double negations
+        (2.., _) => Some((nodes[2..].to_vec(), EvaluationRule::DoubleNegation)),
+        _ => None,
+    }
+}
+
+// Applies a binary operator when at least one of its operands is a known truth value
+fn apply_operator(
+    operator: Operator,
+    left: &[PremiseNode],
+    right: &[PremiseNode],
+) -> Option<(Vec<PremiseNode>, EvaluationRule)> {
+    let left_value = get_truth_value(left);
+    let right_value = get_truth_value(right);
+
+    let nodes = match operator.apply_partial(left_value, right_value) {
+        Some(value) => vec![PremiseNode::TruthValue(value)],
+        // Otherwise the known operand does not decide the value, so it is dropped,
+        // which for "p → FALSE", "FALSE ↔ p" and "TRUE ⊕ p" leaves ¬p
+        None => match (operator, left_value, right_value) {
+            (Operator::Iff, Some(false), _) | (Operator::Xor, Some(true), _) => negate_nodes(right),
+            (_, Some(_), _) => right.to_vec(),
+            (Operator::Implies | Operator::Iff, _, Some(false))
+            | (Operator::Xor, _, Some(true)) => negate_nodes(left),
+            (_, _, Some(_)) => left.to_vec(),
+            (_, None, None) => return None,
+        },
+    };
+
+    let rule = match operator {
+        Operator::And => EvaluationRule::Conjunction,
+        Operator::Or => EvaluationRule::Disjunction,
+        Operator::Implies => EvaluationRule::Implication,
+        Operator::Iff => EvaluationRule::Biconditional,
+        Operator::Xor => EvaluationRule::ExclusiveDisjunction,
+    };
+
+    Some((nodes, rule))
+}
+
+// Creates a Premise of a single proposition, for building premises with operators such as
+// "(p('m') & !p('b')).implies(p('j'))"
+pub fn p(proposition: char) -> Premise {
+    Premise::atom(proposition)
+}
+
+// Returns the truth value of the given nodes if they are exactly one truth value
+fn get_truth_value(nodes: &[PremiseNode]) -> Option<bool> {
+    match nodes {
+        [PremiseNode::TruthValue(value)] => Some(*value),
+        _ => None,
+    }
+}
+
+// Checks whether the given nodes form a single operand, such as "p", "¬TRUE" or "¬(a ∨ b)"
+pub(crate) fn is_single_operand(nodes: &[PremiseNode]) -> bool {
+    let negations = nodes.iter().take_while(|node| node.is_negation()).count();
+
+    nodes.len() == negations + 1 && nodes[negations].is_operand()
+}
+
+// Wraps the given nodes in a subpremise unless they already form a single operand
+pub(crate) fn wrap_operand(nodes: Vec<PremiseNode>) -> Vec<PremiseNode> {
+    if is_single_operand(&nodes) {
+        nodes
+    } else {
+        vec![PremiseNode::Subpremise(Premise::new(nodes))]
+    }
+}
+
+// Negates the given nodes, wrapping them in a subpremise if needed
+pub(crate) fn negate_nodes(nodes: &[PremiseNode]) -> Vec<PremiseNode> {
+    let mut negated = vec![PremiseNode::Negation];
+    negated.extend(wrap_operand(nodes.to_vec()));
+
+    negated
+}
+
+// Returns the byte offset of the first parenthesis in the string which is never matched, which is
+// a close parenthesis with nothing open before it, or otherwise the first open one never closed
+// Parentheses are ASCII, so the bytes can be scanned without decoding the string
+fn find_unbalanced_parenthesis(premise_string: &str) -> Option<usize> {
+    let mut open = Vec::new();
+
+    for (i, byte) in premise_string.bytes().enumerate() {
+        match byte {
+            b'(' => open.push(i),
+            b')' if open.pop().is_none() => return Some(i),
+            _ => (),
+        }
+    }
+
+    open.first().copied()
+}
+
+// Returns an upper bound on the number of nodes outside any parentheses in the string, counting each
+// character there as a node and each outermost group as one, which overcounts only for operators
+// written with more than one character such as "->"
+fn count_outer_nodes(premise_string: &str) -> usize {
+    let mut depth = 0_usize;
+    let mut count = 0;
+
+    for byte in premise_string.bytes() {
+        match byte {
+            b'(' => {
+                count += usize::from(depth == 0);
+                depth += 1;
+            }
+            b')' => depth = depth.saturating_sub(1),
+            b' ' => (),
+            // Only the first byte of each character is counted
+            _ if depth == 0 && byte & 0xc0 != 0x80 => count += 1,
+            _ => (),
+        }
+    }
+
+    count
+}
+
+// Iterates over the nodes of a Premise and its subpremises in pre-order along with their depth,
+// keeping the position in each subpremise being walked on a stack
+#[derive(Debug, Clone)]
+pub struct DepthFirstNodes<'a> {
+    stack: Vec<(std::slice::Iter<'a, PremiseNode>, usize)>,
+}
+
+impl<'a> Iterator for DepthFirstNodes<'a> {
+    type Item = (&'a PremiseNode, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (nodes, depth) = self.stack.last_mut()?;
+            let depth = *depth;
+
+            match nodes.next() {
+                Some(node) => {
+                    if let PremiseNode::Subpremise(subpremise) = node {
+                        self.stack.push((subpremise.nodes.iter(), depth + 1));
+                    }
+
+                    return Some((node, depth));
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+// Iterates over the nodes of the Premise itself, without going into subpremises
+impl<'a> IntoIterator for &'a Premise {
+    type Item = &'a PremiseNode;
+    type IntoIter = std::slice::Iter<'a, PremiseNode>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.nodes.iter()
+    }
+}
+
+impl PremiseNode {
+    // Returns the label used for the node in the pretty-printed tree
+    fn tree_label(&self) -> String {
+        match self {
+            PremiseNode::Proposition(proposition) => format!("Prop {}", proposition),
+            PremiseNode::TruthValue(value) => format!("Value {}", value),
+            PremiseNode::Operator(Operator::And) => "And".to_string(),
+            PremiseNode::Operator(Operator::Or) => "Or".to_string(),
+            PremiseNode::Operator(Operator::Implies) => "Implies".to_string(),
+            PremiseNode::Operator(Operator::Iff) => "Iff".to_string(),
+            PremiseNode::Operator(Operator::Xor) => "Xor".to_string(),
+            PremiseNode::Negation => "Neg".to_string(),
+            PremiseNode::Subpremise(_) => "Subpremise".to_string(),
+        }
+    }
+
+    // Returns the kind of the node, without any of its contents
+    pub fn kind(&self) -> NodeKind {
+        match self {
+            PremiseNode::Proposition(_) => NodeKind::Proposition,
+            PremiseNode::TruthValue(_) => NodeKind::TruthValue,
+            PremiseNode::Operator(_) => NodeKind::Operator,
+            PremiseNode::Negation => NodeKind::Negation,
+            PremiseNode::Subpremise(_) => NodeKind::Subpremise,
+        }
+    }
+
+    // Returns the proposition if the node is one
+    pub fn is_proposition(&self) -> Option<char> {
+        match self {
+            PremiseNode::Proposition(p) => Some(*p),
+            _ => None,
+        }
+    }
+
+    // Returns the truth value if the node is one
+    pub fn as_truth_value(&self) -> Option<bool> {
+        match self {
+            PremiseNode::TruthValue(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    // Returns the subpremise if the node is one
+    pub fn as_subpremise(&self) -> Option<&Premise> {
+        match self {
+            PremiseNode::Subpremise(subpremise) => Some(subpremise),
+            _ => None,
+        }
+    }
+
+    // Checks whether the node is a binary operator
+    pub fn is_operator(&self) -> bool {
+        matches!(self, PremiseNode::Operator(_))
+    }
+
+    // Checks whether the node is a negation
+    pub fn is_negation(&self) -> bool {
+        matches!(self, PremiseNode::Negation)
+    }
+
+    // Checks whether the node can be an operand of an operator, which a negation cannot be alone
+    pub fn is_operand(&self) -> bool {
+        matches!(
+            self,
+            PremiseNode::Proposition(_) | PremiseNode::Subpremise(_) | PremiseNode::TruthValue(_)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_simple() {
+        let premise = Premise::parse_str("a").unwrap();
+
+        assert_eq!(premise.get_nodes().len(), 1);
+        assert_eq!(premise.get_nodes()[0], PremiseNode::Proposition('a'));
+    }
+
+    #[test]
+    fn test_parse_complex_1() {
+        let premise = Premise::parse_str("a ∧ b ∨ (c → d)").unwrap();
+
+        assert_eq!(premise.get_nodes().len(), 5);
+        assert_eq!(premise.get_nodes()[0], PremiseNode::Proposition('a'));
+        assert_eq!(premise.get_nodes()[1], PremiseNode::Operator(Operator::And));
         assert_eq!(premise.get_nodes()[2], PremiseNode::Proposition('b'));
         assert_eq!(premise.get_nodes()[3], PremiseNode::Operator(Operator::Or));
         assert!(matches!(premise.get_nodes()[4], PremiseNode::Subpremise(_)));
@@ -265,7 +1373,7 @@ mod tests {
 
     #[test]
     fn test_parse_complex_2() {
-        let premise = Premise::parse_str("(m & b) > j");
+        let premise = Premise::parse_str("(m & b) > j").unwrap();
 
         assert_eq!(premise.get_nodes().len(), 3);
 
@@ -289,30 +1397,365 @@ mod tests {
         assert_eq!(premise.get_nodes()[2], PremiseNode::Proposition('j'));
     }
 
+    #[test]
+    fn test_parse_str() {
+        assert_eq!(
+            Premise::parse_str("(m & !b) > j"),
+            Ok(Premise::parse_str("(m & !b) > j").unwrap())
+        );
+        assert_eq!(
+            Premise::parse_str("a & (b # c)"),
+            Err(ParseError::InvalidCharacter {
+                character: '#',
+                position: 7
+            })
+        );
+        assert_eq!(
+            Premise::parse_str("a ∧∧ b"),
+            Err(ParseError::InvalidStructure)
+        );
+        assert_eq!(
+            Premise::parse_str("p -> (q | !p)"),
+            Ok(Premise::parse_str("p > (q | !p)").unwrap())
+        );
+        assert_eq!(
+            Premise::parse_str("p - q"),
+            Err(ParseError::InvalidCharacter {
+                character: '-',
+                position: 2
+            })
+        );
+
+        // Positions are byte offsets, so they count the bytes of the symbols before them
+        assert_eq!(
+            Premise::parse_str("¬a ∧ $"),
+            Err(ParseError::InvalidCharacter {
+                character: '$',
+                position: 8
+            })
+        );
+        assert_eq!(Premise::parse_str(""), Err(ParseError::EmptyPremise));
+        assert_eq!(Premise::parse_str("   "), Err(ParseError::EmptyPremise));
+
+        // A stray close parenthesis is found before an unclosed open one
+        assert_eq!(
+            Premise::parse_str("!(p & (q"),
+            Err(ParseError::UnbalancedParentheses { position: 1 })
+        );
+        assert_eq!(
+            Premise::parse_str("(a) & b) | (c"),
+            Err(ParseError::UnbalancedParentheses { position: 7 })
+        );
+        assert_eq!(
+            Premise::parse_str("(¬a ∧ (b)"),
+            Err(ParseError::UnbalancedParentheses { position: 0 })
+        );
+    }
+
+    #[test]
+    fn test_visit() {
+        let premise = Premise::parse_str("!m > (j | (k > l))").unwrap();
+
+        // Each subpremise comes before its contents
+        let mut visited = Vec::new();
+        premise.visit(&mut |node| visited.push(node.clone()));
+        assert_eq!(visited.len(), premise.node_count());
+        assert_eq!(visited[3], premise.get_nodes()[3]);
+        assert_eq!(visited[4], PremiseNode::Proposition('j'));
+        assert_eq!(visited[5], PremiseNode::Operator(Operator::Or));
+        assert!(matches!(visited[6], PremiseNode::Subpremise(_)));
+
+        let propositions: Vec<char> = visited
+            .iter()
+            .filter_map(|node| match node {
+                PremiseNode::Proposition(proposition) => Some(*proposition),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(propositions, ['m', 'j', 'k', 'l']);
+
+        // The walk stops at the first break
+        let mut count = 0;
+        let found = premise.try_visit(&mut |node| {
+            count += 1;
+            match node {
+                PremiseNode::Proposition('k') => ControlFlow::Break('k'),
+                _ => ControlFlow::Continue(()),
+            }
+        });
+        assert_eq!(found, ControlFlow::Break('k'));
+        assert_eq!(count, 8);
+
+        let mut premise = premise;
+        premise.visit_mut(&mut |node| {
+            if *node == PremiseNode::Operator(Operator::Implies) {
+                *node = PremiseNode::Operator(Operator::And);
+            }
+        });
+        assert_eq!(premise, Premise::parse_str("!m & (j | (k & l))").unwrap());
+    }
+
+    #[test]
+    fn test_iter_nodes() {
+        for premise in [
+            "!m > (j | (k > l))",
+            "((a & (b | c)) > d) & (e)",
+            "p",
+            "(((p)))",
+        ] {
+            let premise = Premise::parse_str(premise).unwrap();
+
+            let mut visited = Vec::new();
+            premise.visit(&mut |node| visited.push(node.clone()));
+            assert!(premise.iter_nodes().eq(visited.iter()));
+        }
+
+        let premise = Premise::parse_str("!m > (j | (k > l))").unwrap();
+        let depths: Vec<usize> = premise
+            .iter_nodes_with_depth()
+            .map(|(_, depth)| depth)
+            .collect();
+        assert_eq!(depths, [1, 1, 1, 1, 2, 2, 2, 3, 3, 3]);
+
+        // Iterating over a reference only gives the nodes of the Premise itself
+        assert_eq!((&premise).into_iter().count(), 4);
+
+        // Nesting deep enough to overflow a recursive walk is fine
+        let mut deep = Premise::parse_str("p").unwrap();
+        for _ in 0..100_000 {
+            deep = Premise::new(vec![PremiseNode::Negation, PremiseNode::Subpremise(deep)]);
+        }
+        assert_eq!(deep.iter_nodes().count(), 200_001);
+
+        // Dropping it does not recurse either
+        drop(deep);
+    }
+
+    #[test]
+    fn test_deeply_nested() {
+        // Builds "b ∨ (a ∧ (b ∨ (a ∧ ... (p ∨ q))))" with 50,000 levels around "p ∨ q"
+        let mut deep = Premise::parse_str("p | q").unwrap();
+        for level in 1..=50_000 {
+            let (proposition, operator) = match level % 2 {
+                1 => ('a', Operator::And),
+                _ => ('b', Operator::Or),
+            };
+
+            deep = Premise::new(vec![
+                PremiseNode::Proposition(proposition),
+                PremiseNode::Operator(operator),
+                PremiseNode::Subpremise(deep),
+            ]);
+        }
+
+        let mut values = ValueMap::default();
+        values.add_propositions(&deep);
+        assert!(['a', 'b', 'p', 'q']
+            .iter()
+            .all(|proposition| values.contains(*proposition)));
+
+        values.set_value('p', Some(true));
+        deep.substitute(&values);
+        assert!(!deep.can_substitute(&values));
+        assert_eq!(deep.depth(), 50_001);
+
+        // "p ∨ q" becomes TRUE and "a ∧ TRUE" becomes a, which leaves "b ∨ a" at the bottom
+        deep.simplify();
+        assert_eq!(deep.depth(), 49_999);
+
+        let bottom: Vec<&PremiseNode> = deep.iter_nodes().skip(3 * 49_998).collect();
+        assert_eq!(
+            bottom,
+            [
+                &PremiseNode::Proposition('b'),
+                &PremiseNode::Operator(Operator::Or),
+                &PremiseNode::Proposition('a'),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_combine_deeply_nested() {
+        // Builds "¬(a ∧ ¬(a ∧ ¬(... (p ∨ q))))" with 10,000 levels around "p ∨ q" one operator at a
+        // time, which only finishes quickly if combining moves the nodes instead of copying them
+        let mut deep = p('p') | p('q');
+        for _ in 0..10_000 {
+            deep = !(p('a') & deep);
+        }
+
+        assert_eq!(deep.depth(), 10_002);
+        assert_eq!(deep.node_count(), 4 * 10_000 + 4);
+
+        let mut values = ValueMap::default();
+        values.set_value('a', Some(true));
+        values.set_value('p', Some(true));
+        assert_eq!(deep.evaluate(&values), Some(true));
+    }
+
+    #[test]
+    fn test_substitute_deeply_nested() {
+        // Builds "a ∧ (a ∧ (a ∧ ... (p ∨ q)))" with 10,000 levels around "p ∨ q", along with the
+        // premise substituting p should give
+        let nest = |bottom: Premise| {
+            let mut deep = bottom;
+            for _ in 0..10_000 {
+                deep = Premise::new(vec![
+                    PremiseNode::Proposition('a'),
+                    PremiseNode::Operator(Operator::And),
+                    PremiseNode::Subpremise(deep),
+                ]);
+            }
+
+            deep
+        };
+
+        // A small stack shows none of the walks recurse once per level
+        std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(move || {
+                let deep = nest(Premise::parse_str("p | q").unwrap());
+                let mut values = ValueMap::default();
+                values.set_value('p', Some(true));
+
+                let substituted = deep.substituted(&values);
+                assert!(
+                    substituted
+                        == nest(Premise::new(vec![
+                            PremiseNode::TruthValue(true),
+                            PremiseNode::Operator(Operator::Or),
+                            PremiseNode::Proposition('q'),
+                        ]))
+                );
+                assert!(substituted != deep);
+                assert!(deep.clone() == deep);
+
+                // A missing operand at the bottom is found through every level
+                assert!(deep.validate());
+                let invalid = nest(Premise::new(vec![
+                    PremiseNode::Proposition('p'),
+                    PremiseNode::Operator(Operator::Or),
+                ]));
+                assert!(!invalid.validate());
+
+                // "TRUE ∨ q" becomes TRUE and "a ∧ TRUE" becomes a, which leaves "a ∧ a" at the
+                // bottom
+                let mut simplified = substituted;
+                simplified.simplify();
+                assert_eq!(simplified.depth(), 9_999);
+                assert_eq!(simplified.node_count(), 3 * 9_999);
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_node_count_and_depth() {
+        let premise = Premise::parse_str("p & !q").unwrap();
+        assert_eq!(premise.node_count(), 4);
+        assert_eq!(premise.depth(), 1);
+
+        // The subpremises are (j | (k > l)) and (k > l)
+        let premise = Premise::parse_str("!m > (j | (k > l))").unwrap();
+        assert_eq!(premise.node_count(), 10);
+        assert_eq!(premise.depth(), 3);
+
+        let premise = Premise::new(Vec::new());
+        assert_eq!(premise.node_count(), 0);
+        assert_eq!(premise.depth(), 0);
+    }
+
+    #[test]
+    fn test_complexity() {
+        assert_eq!(Premise::parse_str("p").unwrap().complexity(), 1);
+        assert_eq!(Premise::parse_str("p & q | r").unwrap().complexity(), 7);
+        assert_eq!(Premise::parse_str("(m & !b) > j").unwrap().complexity(), 10);
+        assert_eq!(
+            Premise::parse_str("!m > (j | (k > l))")
+                .unwrap()
+                .complexity(),
+            15
+        );
+        assert_eq!(Premise::new(Vec::new()).complexity(), 0);
+
+        // Redundant parentheses and double negations add to the score
+        assert!(
+            Premise::parse_str("((p))").unwrap().complexity()
+                > Premise::parse_str("p").unwrap().complexity()
+        );
+        assert!(
+            Premise::parse_str("!!p").unwrap().complexity()
+                > Premise::parse_str("p").unwrap().complexity()
+        );
+    }
+
+    #[test]
+    fn test_propositions() {
+        // The same proposition at several depths is only listed once
+        let premise = Premise::parse_str("(q & (p | !(q > r))) > p").unwrap();
+        assert_eq!(
+            premise.propositions().into_iter().collect::<Vec<char>>(),
+            ['p', 'q', 'r']
+        );
+
+        let premise = Premise::new(vec![PremiseNode::TruthValue(true)]);
+        assert!(premise.propositions().is_empty());
+    }
+
+    #[test]
+    fn test_negated() {
+        let cases = [
+            ("p", "¬p"),
+            ("p & q", "¬(p ∧ q)"),
+            ("!p & q", "¬(¬p ∧ q)"),
+            ("!p", "p"),
+            ("!!p", "¬p"),
+            ("!(p | q)", "p ∨ q"),
+            ("(!(p > q))", "p → q"),
+        ];
+
+        for (premise, negated) in cases {
+            let premise = Premise::parse_str(premise).unwrap();
+            assert_eq!(premise.negated().to_string(), negated);
+
+            let mut values = ValueMap::default();
+            for p in [false, true] {
+                for q in [false, true] {
+                    values.set_value('p', Some(p));
+                    values.set_value('q', Some(q));
+                    assert_ne!(
+                        premise.evaluate(&values),
+                        premise.negated().evaluate(&values)
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_validate() {
-        let mut premise = Premise::parse_str("a ∧ b ∨ (c → d)");
+        let mut premise = Premise::parse_lenient("a ∧ b ∨ (c → d)");
         assert!(premise.validate());
 
-        premise = Premise::parse_str("a ∧ b ∨ ¬¬(c → d");
+        premise = Premise::parse_lenient("a ∧ b ∨ ¬¬(c → d");
         assert!(premise.validate());
 
-        premise = Premise::parse_str("a ¬∧ b ∨ (c → d");
+        premise = Premise::parse_lenient("a ¬∧ b ∨ (c → d");
         assert!(!premise.validate());
 
-        premise = Premise::parse_str("a ∧∧ b ∨ (c → d)");
+        premise = Premise::parse_lenient("a ∧∧ b ∨ (c → d)");
         assert!(!premise.validate());
 
-        premise = Premise::parse_str("a ∧ b ∨ (c → d) ∧");
+        premise = Premise::parse_lenient("a ∧ b ∨ (c → d) ∧");
         assert!(!premise.validate());
 
-        premise = Premise::parse_str("a ∧ b ∨ (c → d ∧)");
+        premise = Premise::parse_lenient("a ∧ b ∨ (c → d ∧)");
         assert!(!premise.validate());
     }
 
     #[test]
     fn test_substitute() {
-        let mut premise = Premise::parse_str("a ∧ b ∨ (c → d)");
+        let mut premise = Premise::parse_str("a ∧ b ∨ (c → d)").unwrap();
 
         let mut proposition_values = ValueMap::default();
         proposition_values.set_value('a', Some(true));
@@ -339,4 +1782,727 @@ mod tests {
             assert_eq!(subpremise.get_nodes()[2], PremiseNode::Proposition('d'));
         }
     }
+
+    #[test]
+    fn test_substitute_after_truth_values() {
+        // Substituting one proposition at a time never changes the truth values already in the
+        // premise, even for propositions which are letters of "TRUE" or "FALSE"
+        let mut premise = Premise::parse_str("(t & r) | (f > (a & l & s & e & u))").unwrap();
+
+        for (proposition, value) in [
+            ('t', true),
+            ('r', true),
+            ('u', true),
+            ('e', true),
+            ('f', false),
+            ('a', false),
+            ('l', false),
+            ('s', true),
+        ] {
+            let mut proposition_values = ValueMap::default();
+            proposition_values.set_value(proposition, Some(value));
+            premise.substitute(&proposition_values);
+        }
+
+        assert_eq!(
+            premise.to_string(),
+            "(TRUE ∧ TRUE) ∨ (FALSE → (FALSE ∧ FALSE ∧ TRUE ∧ TRUE ∧ TRUE))"
+        );
+
+        premise.simplify();
+        assert_eq!(premise, Premise::new(vec![PremiseNode::TruthValue(true)]));
+    }
+
+    #[test]
+    fn test_substituted() {
+        let premise = Premise::parse_str("a ∧ (b → c)").unwrap();
+
+        let mut proposition_values = ValueMap::default();
+        proposition_values.set_value('a', Some(true));
+        proposition_values.set_value('b', Some(false));
+        proposition_values.set_value('c', None);
+
+        let substituted = premise.substituted(&proposition_values);
+        assert_eq!(premise, Premise::parse_str("a ∧ (b → c)").unwrap());
+
+        let mut expected = premise.clone();
+        expected.substitute(&proposition_values);
+        assert_eq!(substituted, expected);
+        assert_eq!(substituted.to_string(), "TRUE ∧ (FALSE → c)");
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::HashSet;
+
+        let premises: HashSet<Premise> = ["p & (q > r)", "p∧(q→r)", "p ∧ (q → r)", "p & q > r"]
+            .into_iter()
+            .map(|premise| Premise::parse_str(premise).unwrap())
+            .collect();
+
+        // Only the grouping of the last premise is different
+        assert_eq!(premises.len(), 2);
+        assert!(premises.contains(&Premise::parse_str("p & (q → r)").unwrap()));
+    }
+
+    #[test]
+    fn test_operator_apply() {
+        let operators = [
+            Operator::And,
+            Operator::Or,
+            Operator::Implies,
+            Operator::Iff,
+            Operator::Xor,
+        ];
+        // The values of each operator for TT, TF, FT and FF
+        let tables = [
+            [true, false, false, false],
+            [true, true, true, false],
+            [true, false, true, true],
+            [true, false, false, true],
+            [false, true, true, false],
+        ];
+
+        for (operator, table) in operators.into_iter().zip(tables) {
+            let inputs = [(true, true), (true, false), (false, true), (false, false)];
+
+            for ((left, right), expected) in inputs.into_iter().zip(table) {
+                assert_eq!(operator.apply(left, right), expected);
+                assert_eq!(
+                    operator.apply_partial(Some(left), Some(right)),
+                    Some(expected)
+                );
+
+                // A single known value only decides the result if it does so for both values of
+                // the other operand
+                let decides_left = operator.apply(left, true) == operator.apply(left, false);
+                assert_eq!(
+                    operator.apply_partial(Some(left), None),
+                    decides_left.then_some(operator.apply(left, true))
+                );
+
+                let decides_right = operator.apply(true, right) == operator.apply(false, right);
+                assert_eq!(
+                    operator.apply_partial(None, Some(right)),
+                    decides_right.then_some(operator.apply(true, right))
+                );
+            }
+
+            assert_eq!(operator.apply_partial(None, None), None);
+            assert_eq!(operator.is_commutative(), operator != Operator::Implies);
+            assert_eq!(operator.is_associative(), operator != Operator::Implies);
+        }
+    }
+
+    #[test]
+    fn test_node_accessors() {
+        let subpremise = Premise::parse_str("p | q").unwrap();
+        let nodes = [
+            PremiseNode::Proposition('p'),
+            PremiseNode::TruthValue(false),
+            PremiseNode::Operator(Operator::Or),
+            PremiseNode::Negation,
+            PremiseNode::Subpremise(subpremise.clone()),
+        ];
+        let kinds = [
+            NodeKind::Proposition,
+            NodeKind::TruthValue,
+            NodeKind::Operator,
+            NodeKind::Negation,
+            NodeKind::Subpremise,
+        ];
+
+        for (node, kind) in nodes.iter().zip(kinds) {
+            assert_eq!(node.kind(), kind);
+            assert_eq!(node.is_operator(), kind == NodeKind::Operator);
+            assert_eq!(node.is_negation(), kind == NodeKind::Negation);
+            assert_eq!(
+                node.is_operand(),
+                !matches!(kind, NodeKind::Operator | NodeKind::Negation)
+            );
+        }
+
+        let propositions: Vec<Option<char>> =
+            nodes.iter().map(|node| node.is_proposition()).collect();
+        assert_eq!(propositions, [Some('p'), None, None, None, None]);
+
+        let values: Vec<Option<bool>> = nodes.iter().map(|node| node.as_truth_value()).collect();
+        assert_eq!(values, [None, Some(false), None, None, None]);
+
+        let subpremises: Vec<Option<&Premise>> =
+            nodes.iter().map(|node| node.as_subpremise()).collect();
+        assert_eq!(subpremises, [None, None, None, None, Some(&subpremise)]);
+    }
+
+    #[test]
+    fn test_origin() {
+        let mut premise = Premise::parse_str(" (f | s) > m ").unwrap();
+        assert_eq!(premise.origin(), Some("(f | s) > m"));
+
+        // The origin is kept as the same premise is solved
+        let mut proposition_values = ValueMap::default();
+        proposition_values.set_value('f', Some(true));
+        proposition_values.set_value('s', None);
+        proposition_values.set_value('m', None);
+        premise.substitute(&proposition_values);
+        premise.evaluate_step();
+        premise.simplify();
+        assert_eq!(premise.to_string(), "m");
+        assert_eq!(premise.origin(), Some("(f | s) > m"));
+
+        // It does not affect equality
+        assert_eq!(premise, Premise::parse_str("m").unwrap());
+
+        // Premises built from it are new, and so are its subpremises
+        let premise = Premise::parse_str("!(p & q)").unwrap();
+        assert_eq!(premise.origin(), Some("!(p & q)"));
+        assert_eq!(premise.negated().origin(), None);
+        assert_eq!(premise.canonicalized().origin(), None);
+        assert_eq!(
+            premise.get_nodes()[1].as_subpremise().unwrap().origin(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_pretty_tree_simple() {
+        let premise = Premise::parse_str("a").unwrap();
+
+        assert_eq!(premise.pretty_tree(), "0 Prop a\n");
+    }
+
+    #[test]
+    fn test_pretty_tree_complex_1() {
+        let premise = Premise::parse_str("a ∧ b ∨ (c → d)").unwrap();
+
+        let expected = "\
+0 Prop a
+1 And
+2 Prop b
+3 Or
+4 Subpremise
+  4.0 Prop c
+  4.1 Implies
+  4.2 Prop d
+";
+
+        assert_eq!(premise.pretty_tree(), expected);
+    }
+
+    #[test]
+    fn test_pretty_tree_complex_2() {
+        let premise = Premise::parse_str("(m & !b) > j").unwrap();
+
+        let expected = "\
+0 Subpremise
+  0.0 Prop m
+  0.1 And
+  0.2 Neg
+  0.3 Prop b
+1 Implies
+2 Prop j
+";
+
+        assert_eq!(premise.pretty_tree(), expected);
+    }
+
+    #[test]
+    fn test_evaluate_step() {
+        let mut premise = Premise::parse_str("(m & !b) > j").unwrap();
+
+        let mut proposition_values = ValueMap::default();
+        proposition_values.set_value('m', Some(true));
+        proposition_values.set_value('b', Some(false));
+        proposition_values.set_value('j', None);
+
+        premise.substitute(&proposition_values);
+        assert_eq!(premise.to_string(), "(TRUE ∧ ¬FALSE) → j");
+
+        assert_eq!(premise.evaluate_step(), Some(EvaluationRule::Negation));
+        assert_eq!(premise.to_string(), "(TRUE ∧ TRUE) → j");
+
+        assert_eq!(premise.evaluate_step(), Some(EvaluationRule::Conjunction));
+        assert_eq!(premise.to_string(), "TRUE → j");
+
+        assert_eq!(premise.evaluate_step(), Some(EvaluationRule::Implication));
+        assert_eq!(premise.to_string(), "j");
+
+        assert_eq!(premise.evaluate_step(), None);
+    }
+
+    #[test]
+    fn test_simplify_precedence() {
+        let mut proposition_values = ValueMap::default();
+        proposition_values.set_value('a', Some(false));
+        proposition_values.set_value('b', None);
+        proposition_values.set_value('c', None);
+        proposition_values.set_value('d', Some(false));
+
+        // ∧ binds more tightly than ∨, so this is "FALSE ∨ (b ∧ c)"
+        let mut premise = Premise::parse_str("a | b & c").unwrap();
+        premise.substitute(&proposition_values);
+        premise.simplify();
+        assert_eq!(premise.to_string(), "b ∧ c");
+
+        // → groups from the right, so this is "b → (c → FALSE)"
+        let mut premise = Premise::parse_str("b > c > d").unwrap();
+        premise.substitute(&proposition_values);
+        premise.simplify();
+        assert_eq!(premise.to_string(), "b → ¬c");
+
+        let mut premise = Premise::parse_str("b & c > d").unwrap();
+        premise.substitute(&proposition_values);
+        premise.simplify();
+        assert_eq!(premise.to_string(), "¬(b ∧ c)");
+    }
+
+    #[test]
+    fn test_parse_iff() {
+        let expected = Premise::parse_str("p ↔ q").unwrap();
+        assert_eq!(
+            expected.get_nodes(),
+            &[
+                PremiseNode::Proposition('p'),
+                PremiseNode::Operator(Operator::Iff),
+                PremiseNode::Proposition('q'),
+            ]
+        );
+
+        for premise in ["p <> q", "p = q", "p <-> q", "p<->q"] {
+            assert_eq!(
+                Premise::parse_str(premise).unwrap(),
+                expected,
+                "{}",
+                premise
+            );
+        }
+
+        // ↔ binds more loosely than every other operator, so this is "(p → q) ↔ (¬q → ¬p)"
+        let premise = Premise::parse_str("p > q <> !q > !p").unwrap();
+        assert_eq!(premise.to_string(), "p → q ↔ ¬q → ¬p");
+        assert_eq!(Premise::parse_str(&premise.to_string()).unwrap(), premise);
+        assert_eq!(
+            premise.to_binary().unwrap(),
+            Premise::parse_str("(p > q) <> (!q > !p)")
+                .unwrap()
+                .to_binary()
+                .unwrap()
+        );
+
+        assert_eq!(
+            Premise::parse_str("p < q"),
+            Err(ParseError::InvalidCharacter {
+                character: '<',
+                position: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_xor() {
+        let expected = Premise::parse_str("p ⊕ q").unwrap();
+        assert_eq!(
+            expected.get_nodes(),
+            &[
+                PremiseNode::Proposition('p'),
+                PremiseNode::Operator(Operator::Xor),
+                PremiseNode::Proposition('q'),
+            ]
+        );
+        assert_eq!(Premise::parse_str("p ^ q").unwrap(), expected);
+        assert_eq!(Premise::parse_str("p^q").unwrap(), expected);
+
+        // ⊕ binds more tightly than ∨ and more loosely than ∧
+        for (premise, grouped) in [
+            ("p | q ^ r", "p | (q ^ r)"),
+            ("p ^ q & r", "p ^ (q & r)"),
+            ("!(p ^ q) > (r ⊕ !s)", "!(p ^ q) > (r ^ !s)"),
+        ] {
+            let premise = Premise::parse_str(premise).unwrap();
+            assert_eq!(
+                premise.to_binary().unwrap(),
+                Premise::parse_str(grouped).unwrap().to_binary().unwrap()
+            );
+            assert_eq!(Premise::parse_str(&premise.to_string()).unwrap(), premise);
+        }
+
+        assert_eq!(
+            Premise::parse_str("!(p ^ q) > (r ⊕ !s)")
+                .unwrap()
+                .to_string(),
+            "¬(p ⊕ q) → (r ⊕ ¬s)"
+        );
+    }
+
+    #[test]
+    fn test_simplify_xor() {
+        let mut proposition_values = ValueMap::default();
+        proposition_values.set_value('a', Some(true));
+        proposition_values.set_value('b', Some(false));
+        proposition_values.set_value('p', None);
+        proposition_values.set_value('q', None);
+
+        // A known side decides whether the other side is negated or kept as it is
+        for (premise, expected) in [
+            ("a ^ p", "¬p"),
+            ("p ^ a", "¬p"),
+            ("b ^ p", "p"),
+            ("p ^ b", "p"),
+            ("a ^ b", "TRUE"),
+            ("a ^ a", "FALSE"),
+            ("q & (p ^ (a ^ b))", "q ∧ ¬p"),
+            ("!(b ^ (p | q))", "¬(p ∨ q)"),
+            ("(a ^ (p & q)) | (p ^ q)", "¬(p ∧ q) ∨ (p ⊕ q)"),
+        ] {
+            let mut premise = Premise::parse_str(premise).unwrap();
+            premise.substitute(&proposition_values);
+            premise.simplify();
+            assert_eq!(premise.to_string(), expected);
+        }
+
+        let mut premise = Premise::parse_str("p ^ (b)").unwrap();
+        premise.substitute(&proposition_values);
+        assert_eq!(premise.evaluate_step(), Some(EvaluationRule::Parentheses));
+        assert_eq!(
+            premise.evaluate_step(),
+            Some(EvaluationRule::ExclusiveDisjunction)
+        );
+        assert_eq!(premise.to_string(), "p");
+    }
+
+    #[test]
+    fn test_simplify_iff() {
+        let mut proposition_values = ValueMap::default();
+        proposition_values.set_value('a', Some(true));
+        proposition_values.set_value('b', Some(false));
+        proposition_values.set_value('p', None);
+
+        // A known side decides whether the other side is kept as it is or negated
+        for (premise, expected) in [
+            ("a <> p", "p"),
+            ("p <> a", "p"),
+            ("b <> p", "¬p"),
+            ("p <> b", "¬p"),
+            ("a <> b", "FALSE"),
+            ("b <> b", "TRUE"),
+            ("p <> (q & r)", "p ↔ (q ∧ r)"),
+            ("b <> (p & a)", "¬p"),
+        ] {
+            let mut premise = Premise::parse_str(premise).unwrap();
+            premise.substitute(&proposition_values);
+            premise.simplify();
+            assert_eq!(premise.to_string(), expected);
+        }
+
+        let mut premise = Premise::parse_str("a <> p").unwrap();
+        premise.substitute(&proposition_values);
+        assert_eq!(premise.evaluate_step(), Some(EvaluationRule::Biconditional));
+        assert_eq!(premise.to_string(), "p");
+    }
+
+    #[test]
+    fn test_simplify_with() {
+        let mut proposition_values = ValueMap::default();
+        proposition_values.set_value('a', Some(true));
+        proposition_values.set_value('b', None);
+
+        // "¬¬(TRUE ∧ (b)) ∨ ¬TRUE" takes a step for each rule
+        let mut premise = Premise::parse_str("!!(a & (b)) | !a").unwrap();
+        premise.substitute(&proposition_values);
+
+        let mut rules = BTreeMap::new();
+        premise.simplify_with(|rule| *rules.entry(rule).or_insert(0) += 1);
+        assert_eq!(premise.to_string(), "b");
+        assert_eq!(
+            rules,
+            BTreeMap::from([
+                (EvaluationRule::Negation, 1),
+                (EvaluationRule::DoubleNegation, 1),
+                (EvaluationRule::Parentheses, 1),
+                (EvaluationRule::Conjunction, 1),
+                (EvaluationRule::Disjunction, 1),
+            ])
+        );
+    }
+
+    // Parses a premise string the way the parser did before it worked in a single pass, finding
+    // the matching parenthesis of each subpremise and then parsing the text inside it again
+    fn reference_parse(
+        premise_string: &str,
+        offset: usize,
+    ) -> std::result::Result<Premise, ParseError> {
+        let mut nodes = Vec::new();
+        let mut premise_chars = premise_string.char_indices();
+
+        while let Some((i, c)) = premise_chars.next() {
+            match c {
+                ' ' | ')' => (),
+                '(' => {
+                    let mut depth = 0;
+                    let inner = premise_string[i..]
+                        .char_indices()
+                        .find_map(|(j, c)| {
+                            depth += match c {
+                                '(' => 1,
+                                ')' => -1,
+                                _ => 0,
+                            };
+                            (depth == 0).then(|| &premise_string[i + 1..i + j])
+                        })
+                        .unwrap_or(&premise_string[i + 1..]);
+
+                    nodes.push(PremiseNode::Subpremise(reference_parse(
+                        inner,
+                        offset + i + 1,
+                    )?));
+                    premise_chars.nth(inner.chars().count());
+                }
+                '¬' | '!' => nodes.push(PremiseNode::Negation),
+                '∧' | '&' => nodes.push(PremiseNode::Operator(Operator::And)),
+                '∨' | '|' => nodes.push(PremiseNode::Operator(Operator::Or)),
+                '→' | '>' => nodes.push(PremiseNode::Operator(Operator::Implies)),
+                '-' if premise_string[i + 1..].starts_with('>') => {
+                    premise_chars.next();
+                    nodes.push(PremiseNode::Operator(Operator::Implies));
+                }
+                '↔' | '=' => nodes.push(PremiseNode::Operator(Operator::Iff)),
+                '⊕' | '^' => nodes.push(PremiseNode::Operator(Operator::Xor)),
+                '<' if premise_string[i + 1..].starts_with('>') => {
+                    premise_chars.next();
+                    nodes.push(PremiseNode::Operator(Operator::Iff));
+                }
+                '<' if premise_string[i + 1..].starts_with("->") => {
+                    premise_chars.nth(1);
+                    nodes.push(PremiseNode::Operator(Operator::Iff));
+                }
+                'a'..='z' => nodes.push(PremiseNode::Proposition(c)),
+                _ => {
+                    return Err(ParseError::InvalidCharacter {
+                        character: c,
+                        position: offset + i,
+                    })
+                }
+            }
+        }
+
+        Ok(Premise::new(nodes))
+    }
+
+    #[test]
+    fn test_parse_matches_reference() {
+        let alphabet = [
+            'a', 'b', 'c', ' ', '(', '(', ')', ')', '!', '¬', '&', '∧', '|', '∨', '>', '→', '-',
+            '<', '=', '↔', '^', '⊕', '#',
+        ];
+        let mut seed: u64 = 1;
+        let mut next = |limit: usize| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % limit
+        };
+
+        // Random strings cover unbalanced parentheses, stray characters and a lone '-' or '<' as
+        // well as valid premises
+        for _ in 0..5000 {
+            let length = next(30);
+            let premise_string: String = (0..length)
+                .map(|_| alphabet[next(alphabet.len())])
+                .collect();
+
+            assert_eq!(
+                Premise::parse_nodes(&premise_string),
+                reference_parse(&premise_string, 0),
+                "{}",
+                premise_string
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_deep_matches_reference() {
+        // Builds "(a ∧ (b ∨ (c → ... z)))" with 500 nested groups
+        let depth = 500;
+        let mut premise_string = String::new();
+        for i in 0..depth {
+            let proposition = (b'a' + (i % 26) as u8) as char;
+            premise_string.push_str(&format!("({} {} ", proposition, ["∧", "∨", "→"][i % 3]));
+        }
+        premise_string.push('z');
+        premise_string.push_str(&")".repeat(depth));
+
+        let start = std::time::Instant::now();
+        let premise = Premise::parse_nodes(&premise_string).unwrap();
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+
+        assert_eq!(Ok(premise), reference_parse(&premise_string, 0));
+    }
+
+    #[test]
+    fn test_count_outer_nodes() {
+        for premise_string in [
+            "",
+            "p",
+            "(m & !b) > j",
+            "a -> (b | !(c & (d > e))) <-> f",
+            "¬p ∧ (q ∨ r) ⊕ s",
+            "a) & (b",
+        ] {
+            let premise = Premise::parse_nodes(premise_string).unwrap();
+            assert!(count_outer_nodes(premise_string) >= premise.get_nodes().len());
+        }
+
+        assert_eq!(count_outer_nodes("¬p ∧ (q ∨ r) ⊕ s"), 6);
+    }
+
+    #[test]
+    fn test_parse_unbalanced() {
+        // Unclosed groups run to the end of the string and stray close parentheses are ignored
+        assert_eq!(
+            Premise::parse_lenient("!(p & (q"),
+            Premise::parse_lenient("!(p & (q))")
+        );
+        assert_eq!(
+            Premise::parse_lenient("a) & (b"),
+            Premise::parse_lenient("a & (b)")
+        );
+
+        let depth = 1000;
+        let premise =
+            Premise::parse_str(&format!("{}p{}", "(".repeat(depth), ")".repeat(depth))).unwrap();
+        let mut nodes = premise.get_nodes();
+
+        for _ in 0..depth {
+            let [PremiseNode::Subpremise(subpremise)] = nodes.as_slice() else {
+                panic!("expected a single subpremise");
+            };
+            nodes = subpremise.get_nodes();
+        }
+
+        assert_eq!(nodes, &vec![PremiseNode::Proposition('p')]);
+    }
+
+    #[test]
+    fn test_parse_borrowed() {
+        for premise_string in [
+            "p",
+            "  (m & !b) > j ",
+            "((a ∨ ¬(b → c))) ∧ d",
+            "a -> (b | !(c & (d > e))) -> f",
+        ] {
+            let borrowed = Premise::parse_borrowed(premise_string).unwrap();
+            assert_eq!(borrowed.origin(), premise_string.trim());
+            assert_eq!(
+                borrowed.get_nodes(),
+                Premise::parse_str(premise_string).unwrap().get_nodes()
+            );
+
+            let owned = borrowed.into_owned();
+            assert_eq!(owned, Premise::parse_str(premise_string).unwrap());
+            assert_eq!(
+                owned.origin(),
+                Premise::parse_str(premise_string).unwrap().origin()
+            );
+        }
+
+        // Errors inside subpremises keep their position in the full string
+        assert_eq!(
+            Premise::parse_borrowed("(a ∧ (b # c))"),
+            Err(ParseError::InvalidCharacter {
+                character: '#',
+                position: 10
+            })
+        );
+        assert_eq!(
+            Premise::parse_borrowed("a ∧ (∨ b)"),
+            Err(ParseError::InvalidStructure)
+        );
+    }
+
+    #[test]
+    fn test_operators() {
+        for (built, parsed) in [
+            ((p('m') & !p('b')).implies(p('j')), "(m & !b) > j"),
+            (p('a') | p('b') & p('c'), "a | (b & c)"),
+            ((p('a') | p('b')) & p('c'), "(a | b) & c"),
+            (!(p('p') & p('q')) | !p('r'), "!(p & q) | !r"),
+            (p('a').implies(p('b').implies(p('c'))), "a > (b > c)"),
+            (p('a').implies(p('b')).implies(p('c')), "(a > b) > c"),
+            (p('a').iff(p('b') & p('c')), "a <> (b & c)"),
+            (p('a').xor(p('b')) | p('c'), "(a ^ b) | c"),
+        ] {
+            let parsed = Premise::parse_str(parsed).unwrap();
+
+            assert_eq!(built, parsed);
+            assert!(built.is_equivalent(&parsed));
+        }
+
+        // Grouping is kept even where precedence would allow leaving out the parentheses
+        assert_eq!(
+            (p('a') & p('b')) & p('c'),
+            Premise::parse_str("(a & b) & c").unwrap()
+        );
+        assert_ne!(
+            (p('a') & p('b')) & p('c'),
+            Premise::parse_str("a & b & c").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_from_nodes() {
+        let p = PremiseNode::Proposition('p');
+        let and = PremiseNode::Operator(Operator::And);
+
+        assert!(Premise::from_nodes(vec![p.clone(), and.clone(), p.clone()]).is_ok());
+        assert_eq!(Premise::from_nodes(vec![]), Err(StructureError::Empty));
+        assert_eq!(
+            Premise::from_nodes(vec![p.clone(), and.clone(), and.clone(), p.clone()]),
+            Err(StructureError::UnexpectedNode { index: 2 })
+        );
+        assert_eq!(
+            Premise::from_nodes(vec![p.clone(), PremiseNode::Negation]),
+            Err(StructureError::UnexpectedNode { index: 1 })
+        );
+        assert_eq!(
+            Premise::from_nodes(vec![p.clone(), and.clone()]),
+            Err(StructureError::MissingOperand)
+        );
+        assert_eq!(
+            Premise::from_nodes(vec![
+                PremiseNode::Negation,
+                PremiseNode::Subpremise(Premise::new(vec![and]))
+            ]),
+            Err(StructureError::InvalidSubpremise {
+                index: 1,
+                error: Box::new(StructureError::UnexpectedNode { index: 0 })
+            })
+        );
+    }
+
+    #[test]
+    fn test_constructors() {
+        let (m, b, j) = (Premise::atom('m'), Premise::atom('b'), Premise::atom('j'));
+
+        // Compound operands are parenthesized so each operator stays the main one
+        let premise = Premise::implies(Premise::and(m.clone(), !b.clone()), j.clone());
+        assert_eq!(premise, Premise::parse_str("(m & !b) > j").unwrap());
+        assert_eq!(premise.to_string(), "(m ∧ ¬b) → j");
+
+        let premise = !Premise::or(Premise::implies(m, b), !j);
+        assert_eq!(premise.to_string(), "¬((m → b) ∨ ¬j)");
+        assert!(premise.validate());
+
+        let mut proposition_values = ValueMap::default();
+        proposition_values.set_value('m', Some(true));
+        proposition_values.set_value('b', Some(false));
+        proposition_values.set_value('j', Some(true));
+        assert_eq!(premise.evaluate(&proposition_values), Some(true));
+
+        // Implication groups from the right, so a compound antecedent keeps its parentheses
+        let chain = Premise::implies(
+            Premise::implies(Premise::atom('p'), Premise::atom('q')),
+            Premise::atom('r'),
+        );
+        assert_eq!(chain.to_string(), "(p → q) → r");
+    }
 }
@@ -0,0 +1,853 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::errors::RuleError;
+use crate::notation::Notation;
+use crate::pattern::{strip_parentheses, Bindings, Pattern};
+use crate::premises::{find_main_operator, Operator};
+use crate::Premise;
+use crate::PremiseNode;
+
+// How many rounds of rule applications the search for the next step makes before giving up
+const MAX_SEARCH_ROUNDS: usize = 6;
+
+// How many formulas the search for the next step derives before giving up
+const MAX_SEARCH_FORMULAS: usize = 256;
+
+// Represents a rule of inference which derives a new line of a proof from earlier lines
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum InferenceRule {
+    // From p → q and p, derive q
+    ModusPonens,
+    // From p → q and ¬q, derive ¬p
+    ModusTollens,
+    // From p → q and q → r, derive p → r
+    HypotheticalSyllogism,
+    // From p ∨ q and the negation of either one, derive the other
+    DisjunctiveSyllogism,
+    // From p and q, derive p ∧ q
+    Conjunction,
+    // From p ∧ q, derive p or q
+    Simplification,
+    // From p, derive any disjunction with p as one side
+    Addition,
+    // From ¬¬p, derive p
+    DoubleNegation,
+}
+
+// Represents why a line of a proof holds
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Justification {
+    Premise,
+    // The rule applied to the earlier lines, numbered from 1
+    Rule(InferenceRule, Vec<usize>),
+}
+
+// Stores one line of a proof along with its justification
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ProofLine {
+    formula: Premise,
+    justification: Justification,
+}
+
+// Stores a proof as numbered lines, starting with the premises
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Proof {
+    lines: Vec<ProofLine>,
+}
+
+// Stores a rule along with the lines it is applied to, numbered from 1, and the formula it should
+// derive for the rules which can derive more than one
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ProofStep {
+    pub rule: InferenceRule,
+    pub lines: Vec<usize>,
+    pub formula: Option<Premise>,
+}
+
+// The shape of a formula at its main operator, with any parentheses around it removed
+enum Form {
+    Binary(Operator, Premise, Premise),
+    Negation(Premise),
+    Atom,
+}
+
+impl Display for InferenceRule {
+    // Displays the name of the rule
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            InferenceRule::ModusPonens => write!(f, "Modus ponens"),
+            InferenceRule::ModusTollens => write!(f, "Modus tollens"),
+            InferenceRule::HypotheticalSyllogism => write!(f, "Hypothetical syllogism"),
+            InferenceRule::DisjunctiveSyllogism => write!(f, "Disjunctive syllogism"),
+            InferenceRule::Conjunction => write!(f, "Conjunction"),
+            InferenceRule::Simplification => write!(f, "Simplification"),
+            InferenceRule::Addition => write!(f, "Addition"),
+            InferenceRule::DoubleNegation => write!(f, "Double negation"),
+        }
+    }
+}
+
+impl Display for ProofStep {
+    // Displays the step as it would be typed, such as "mp 1 2" or "add 3 p ∨ q"
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.rule.get_short_name())?;
+
+        for line in &self.lines {
+            write!(f, " {}", line)?;
+        }
+
+        if let Some(formula) = &self.formula {
+            write!(f, " {}", formula)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl InferenceRule {
+    // Every rule, in the order they are listed to the user
+    pub const ALL: [InferenceRule; 8] = [
+        InferenceRule::ModusPonens,
+        InferenceRule::ModusTollens,
+        InferenceRule::HypotheticalSyllogism,
+        InferenceRule::DisjunctiveSyllogism,
+        InferenceRule::Conjunction,
+        InferenceRule::Simplification,
+        InferenceRule::Addition,
+        InferenceRule::DoubleNegation,
+    ];
+
+    // Returns the rule with the given short name, such as "mp", if there is one
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|rule| rule.get_short_name() == name)
+    }
+
+    // Returns the short name the rule is typed as
+    pub fn get_short_name(&self) -> &'static str {
+        match self {
+            InferenceRule::ModusPonens => "mp",
+            InferenceRule::ModusTollens => "mt",
+            InferenceRule::HypotheticalSyllogism => "hs",
+            InferenceRule::DisjunctiveSyllogism => "ds",
+            InferenceRule::Conjunction => "conj",
+            InferenceRule::Simplification => "simp",
+            InferenceRule::Addition => "add",
+            InferenceRule::DoubleNegation => "dn",
+        }
+    }
+
+    // Returns how many lines the rule is applied to
+    pub fn get_num_lines(&self) -> usize {
+        match self {
+            InferenceRule::Simplification
+            | InferenceRule::Addition
+            | InferenceRule::DoubleNegation => 1,
+            _ => 2,
+        }
+    }
+
+    // Derives a formula from the given lines and their numbers, taking the formula the user wants
+    // for the rules which can derive more than one
+    // Rules which use two lines accept them in either order, except conjunction, which keeps the
+    // order given
+    fn derive(
+        &self,
+        lines: &[(usize, &Premise)],
+        formula: Option<&Premise>,
+    ) -> std::result::Result<Premise, RuleError> {
+        if lines.len() != self.get_num_lines() {
+            return Err(RuleError::WrongLineCount {
+                rule: *self,
+                found: lines.len(),
+            });
+        }
+
+        let does_not_apply = |reason: String| RuleError::DoesNotApply {
+            rule: *self,
+            reason,
+        };
+
+        match (self, lines) {
+            (InferenceRule::Conjunction, [(_, first), (_, second)]) => {
+                Ok(Premise::and((*first).clone(), (*second).clone()))
+            }
+            (_, [first, second]) => {
+                // Each order is checked, keeping the explanation of whichever got furthest
+                let forwards = self.derive_pair(*first, *second);
+                let backwards = self.derive_pair(*second, *first);
+
+                match (forwards, backwards) {
+                    (Ok(result), _) | (_, Ok(result)) => Ok(result),
+                    (Err((progress, reason)), Err((other_progress, other_reason))) => {
+                        match other_progress > progress {
+                            true => Err(does_not_apply(other_reason)),
+                            false => Err(does_not_apply(reason)),
+                        }
+                    }
+                }
+            }
+            (InferenceRule::Simplification, [(number, line)]) => {
+                let Some(bindings) = line.match_pattern(&pattern("_A & _B")) else {
+                    return Err(does_not_apply(format!(
+                        "line {} is not a conjunction",
+                        number
+                    )));
+                };
+
+                let (left, right) = (bound(&bindings, "A"), bound(&bindings, "B"));
+
+                match formula {
+                    None => Ok(left),
+                    Some(formula) if formula.equivalent_structure(&left) => Ok(left),
+                    Some(formula) if formula.equivalent_structure(&right) => Ok(right),
+                    Some(formula) => Err(does_not_apply(format!(
+                        "{} is not a side of line {}",
+                        formula, number
+                    ))),
+                }
+            }
+            (InferenceRule::Addition, [(number, line)]) => {
+                let formula = formula.ok_or(RuleError::MissingFormula(*self))?;
+
+                let Some(bindings) = formula.match_pattern(&pattern("_A | _B")) else {
+                    return Err(does_not_apply(format!("{} is not a disjunction", formula)));
+                };
+
+                let is_side = pattern("_A")
+                    .match_with(line, bindings.clone())
+                    .or_else(|| pattern("_B").match_with(line, bindings))
+                    .is_some();
+
+                match is_side {
+                    true => Ok(formula.clone()),
+                    false => Err(does_not_apply(format!(
+                        "line {} is not a side of {}",
+                        number, formula
+                    ))),
+                }
+            }
+            (InferenceRule::DoubleNegation, [(number, line)]) => {
+                match line.match_pattern(&pattern("!!_A")) {
+                    Some(bindings) => Ok(bound(&bindings, "A")),
+                    None => Err(does_not_apply(format!(
+                        "line {} is not a double negation",
+                        number
+                    ))),
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    // Derives a formula from two lines in the given order, or explains why they do not fit
+    // The explanation comes with how many of the rule's checks passed
+    fn derive_pair(
+        &self,
+        (first_number, first): (usize, &Premise),
+        (second_number, second): (usize, &Premise),
+    ) -> std::result::Result<Premise, (usize, String)> {
+        match self {
+            InferenceRule::ModusPonens => {
+                let bindings = implication(first, first_number)?;
+
+                match pattern("_A").match_with(second, bindings.clone()) {
+                    Some(_) => Ok(bound(&bindings, "B")),
+                    None => Err((
+                        1,
+                        format!(
+                            "line {} is {}, which is not the antecedent {} of line {}",
+                            second_number,
+                            second,
+                            bound(&bindings, "A"),
+                            first_number
+                        ),
+                    )),
+                }
+            }
+            InferenceRule::ModusTollens => {
+                let bindings = implication(first, first_number)?;
+
+                match pattern("!_B").match_with(second, bindings.clone()) {
+                    Some(_) => Ok(!bound(&bindings, "A")),
+                    None => Err((
+                        1,
+                        format!(
+                            "line {} is {}, which is not the negation of the consequent {} of line {}",
+                            second_number,
+                            second,
+                            bound(&bindings, "B"),
+                            first_number
+                        ),
+                    )),
+                }
+            }
+            InferenceRule::HypotheticalSyllogism => {
+                let bindings = implication(first, first_number)?;
+                let other =
+                    implication(second, second_number).map_err(|(_, reason)| (1, reason))?;
+
+                match pattern("_B > _C").match_with(second, bindings.clone()) {
+                    Some(bindings) => Ok(Premise::implies(
+                        bound(&bindings, "A"),
+                        bound(&bindings, "C"),
+                    )),
+                    None => Err((
+                        2,
+                        format!(
+                            "the consequent {} of line {} is not the antecedent {} of line {}",
+                            bound(&bindings, "B"),
+                            first_number,
+                            bound(&other, "A"),
+                            second_number
+                        ),
+                    )),
+                }
+            }
+            InferenceRule::DisjunctiveSyllogism => {
+                let Some(bindings) = first.match_pattern(&pattern("_A | _B")) else {
+                    return Err((0, format!("line {} is not a disjunction", first_number)));
+                };
+
+                if second.match_pattern(&pattern("!_N")).is_none() {
+                    return Err((1, format!("line {} is not a negation", second_number)));
+                }
+
+                if pattern("!_A")
+                    .match_with(second, bindings.clone())
+                    .is_some()
+                {
+                    Ok(bound(&bindings, "B"))
+                } else if pattern("!_B")
+                    .match_with(second, bindings.clone())
+                    .is_some()
+                {
+                    Ok(bound(&bindings, "A"))
+                } else {
+                    Err((
+                        2,
+                        format!(
+                            "line {} is {}, which does not negate either side of line {}",
+                            second_number, second, first_number
+                        ),
+                    ))
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Proof {
+    // Creates a proof which starts with the given premises
+    pub fn new(premises: Vec<Premise>) -> Self {
+        Self {
+            lines: premises
+                .into_iter()
+                .map(|formula| ProofLine {
+                    formula,
+                    justification: Justification::Premise,
+                })
+                .collect(),
+        }
+    }
+
+    // Returns the lines of the proof
+    pub fn get_lines(&self) -> &[ProofLine] {
+        &self.lines
+    }
+
+    // Returns the number of the first line which proves the goal, if there is one
+    pub fn find_line(&self, goal: &Premise) -> Option<usize> {
+        self.lines
+            .iter()
+            .position(|line| line.formula.is_structurally_equal(goal))
+            .map(|i| i + 1)
+    }
+
+    // Applies the rule to the lines with the given numbers, adding the derived formula as a new line
+    // Returns the number of the new line
+    pub fn apply(&mut self, step: &ProofStep) -> std::result::Result<usize, RuleError> {
+        let lines = step
+            .lines
+            .iter()
+            .map(
+                |&number| match number.checked_sub(1).and_then(|i| self.lines.get(i)) {
+                    Some(line) => Ok((number, &line.formula)),
+                    None => Err(RuleError::MissingLine(number)),
+                },
+            )
+            .collect::<std::result::Result<Vec<(usize, &Premise)>, RuleError>>()?;
+
+        let formula = step.rule.derive(&lines, step.formula.as_ref())?;
+
+        self.lines.push(ProofLine {
+            formula,
+            justification: Justification::Rule(step.rule, step.lines.clone()),
+        });
+
+        Ok(self.lines.len())
+    }
+
+    // Removes the last line if it was derived, returning it
+    pub fn undo(&mut self) -> Option<ProofLine> {
+        match self.lines.last()?.justification {
+            Justification::Premise => None,
+            Justification::Rule(..) => self.lines.pop(),
+        }
+    }
+
+    // Finds the next step of a proof of the goal by applying every rule to every line until the
+    // goal is derived, then picking the first derived line the goal depends on
+    // Conjunction and addition are only used to build parts of the goal or of the lines, such as
+    // the antecedent of an implication, as they could otherwise be applied forever
+    // Returns None if the goal is already proved or no proof was found within the search limits
+    pub fn find_next_step(&self, goal: &Premise) -> Option<ProofStep> {
+        if self.find_line(goal).is_some() {
+            return None;
+        }
+
+        let mut wanted = Vec::new();
+        collect_subformulas(goal, &mut wanted);
+
+        for line in &self.lines {
+            collect_subformulas(&line.formula, &mut wanted);
+        }
+
+        // Each formula is stored with the step which derived it from earlier formulas,
+        // where the first ones are the lines of the proof
+        let mut formulas: Vec<(Premise, Option<ProofStep>)> = self
+            .lines
+            .iter()
+            .map(|line| (line.formula.clone(), None))
+            .collect();
+
+        for _ in 0..MAX_SEARCH_ROUNDS {
+            let candidates = find_candidates(&formulas, &wanted);
+            let mut found_new = false;
+
+            for step in candidates {
+                let lines: Vec<(usize, &Premise)> =
+                    step.lines.iter().map(|&i| (i, &formulas[i].0)).collect();
+
+                let Ok(formula) = step.rule.derive(&lines, step.formula.as_ref()) else {
+                    continue;
+                };
+
+                if formulas
+                    .iter()
+                    .any(|(known, _)| known.is_structurally_equal(&formula))
+                {
+                    continue;
+                }
+
+                let is_goal = formula.is_structurally_equal(goal);
+                formulas.push((formula, Some(step)));
+                found_new = true;
+
+                if is_goal {
+                    return Some(self.first_step_towards(&formulas, formulas.len() - 1));
+                }
+
+                if formulas.len() >= MAX_SEARCH_FORMULAS {
+                    return None;
+                }
+            }
+
+            if !found_new {
+                break;
+            }
+        }
+
+        None
+    }
+
+    // Returns the first derived formula which the given formula depends on, as a step applied to
+    // lines of the proof
+    fn first_step_towards(
+        &self,
+        formulas: &[(Premise, Option<ProofStep>)],
+        target: usize,
+    ) -> ProofStep {
+        let mut needed = vec![false; formulas.len()];
+        needed[target] = true;
+
+        // Formulas are only derived from earlier ones, so working backwards finds every dependency
+        for i in (0..formulas.len()).rev() {
+            if let (true, Some(step)) = (needed[i], &formulas[i].1) {
+                for &line in &step.lines {
+                    needed[line] = true;
+                }
+            }
+        }
+
+        // The earliest derived dependency can only depend on lines of the proof
+        let first = (self.lines.len()..formulas.len())
+            .find(|&i| needed[i])
+            .unwrap_or(target);
+        let step = formulas[first].1.clone().unwrap();
+
+        ProofStep {
+            lines: step.lines.iter().map(|line| line + 1).collect(),
+            ..step
+        }
+    }
+
+    // Renders the proof as two columns of the numbered formulas and their justifications, such as:
+    // 1. p → q  Premise
+    // 2. p      Premise
+    // 3. q      Modus ponens 1, 2
+    pub fn render(&self, notation: Notation) -> String {
+        let formulas: Vec<String> = self
+            .lines
+            .iter()
+            .map(|line| line.formula.render(notation))
+            .collect();
+
+        let number_width = self.lines.len().to_string().len();
+        let formula_width = formulas
+            .iter()
+            .map(|formula| formula.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut proof = String::new();
+
+        for (i, (line, formula)) in self.lines.iter().zip(&formulas).enumerate() {
+            proof.push_str(&format!(
+                "{:>number_width$}. {:<formula_width$}  {}\n",
+                i + 1,
+                formula,
+                line.justification,
+                number_width = number_width,
+                formula_width = formula_width
+            ));
+        }
+
+        proof
+    }
+}
+
+impl ProofLine {
+    // Returns the formula on the line
+    pub fn get_formula(&self) -> &Premise {
+        &self.formula
+    }
+
+    // Returns why the line holds
+    pub fn get_justification(&self) -> &Justification {
+        &self.justification
+    }
+}
+
+impl Display for Justification {
+    // Displays the justification as it is written in the second column of a proof
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Justification::Premise => write!(f, "Premise"),
+            Justification::Rule(rule, lines) => {
+                let lines: Vec<String> = lines.iter().map(usize::to_string).collect();
+                write!(f, "{} {}", rule, lines.join(", "))
+            }
+        }
+    }
+}
+
+// Returns every step worth trying on the formulas found so far, using indices into the formulas
+// in place of line numbers
+fn find_candidates(
+    formulas: &[(Premise, Option<ProofStep>)],
+    wanted: &[Premise],
+) -> Vec<ProofStep> {
+    let step = |rule, lines, formula| ProofStep {
+        rule,
+        lines,
+        formula,
+    };
+
+    let mut candidates = Vec::new();
+
+    for (i, (formula, _)) in formulas.iter().enumerate() {
+        candidates.push(step(InferenceRule::DoubleNegation, vec![i], None));
+
+        if let Form::Binary(Operator::And, left, right) = decompose(formula) {
+            candidates.push(step(InferenceRule::Simplification, vec![i], Some(left)));
+            candidates.push(step(InferenceRule::Simplification, vec![i], Some(right)));
+        }
+
+        for j in 0..formulas.len() {
+            if i != j {
+                for rule in [
+                    InferenceRule::ModusPonens,
+                    InferenceRule::ModusTollens,
+                    InferenceRule::HypotheticalSyllogism,
+                    InferenceRule::DisjunctiveSyllogism,
+                ] {
+                    candidates.push(step(rule, vec![i, j], None));
+                }
+            }
+        }
+    }
+
+    for part in wanted {
+        match decompose(part) {
+            Form::Binary(Operator::And, left, right) => {
+                let first = find_formula(formulas, &left);
+                let second = find_formula(formulas, &right);
+
+                if let (Some(first), Some(second)) = (first, second) {
+                    candidates.push(step(InferenceRule::Conjunction, vec![first, second], None));
+                }
+            }
+            Form::Binary(Operator::Or, left, right) => {
+                if let Some(side) = find_formula(formulas, &left).or(find_formula(formulas, &right))
+                {
+                    candidates.push(step(
+                        InferenceRule::Addition,
+                        vec![side],
+                        Some(part.clone()),
+                    ));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    candidates
+}
+
+// Returns the index of the formula which is structurally equal to the given one, if there is one
+fn find_formula(formulas: &[(Premise, Option<ProofStep>)], formula: &Premise) -> Option<usize> {
+    formulas
+        .iter()
+        .position(|(known, _)| known.is_structurally_equal(formula))
+}
+
+// Adds the formula and every formula it is built from to the list
+fn collect_subformulas(formula: &Premise, subformulas: &mut Vec<Premise>) {
+    match decompose(formula) {
+        Form::Binary(_, left, right) => {
+            collect_subformulas(&left, subformulas);
+            collect_subformulas(&right, subformulas);
+        }
+        Form::Negation(negated) => collect_subformulas(&negated, subformulas),
+        Form::Atom => (),
+    }
+
+    subformulas.push(formula.clone());
+}
+
+// Splits the formula at its main operator, or at a leading negation if it has no operator
+// The parts are returned without any parentheses around them
+fn decompose(formula: &Premise) -> Form {
+    let nodes = strip_parentheses(formula.get_nodes());
+
+    if let Some(i) = find_main_operator(nodes) {
+        let PremiseNode::Operator(operator) = nodes[i] else {
+            unreachable!()
+        };
+
+        return Form::Binary(
+            operator,
+            Premise::new(strip_parentheses(&nodes[..i]).to_vec()),
+            Premise::new(strip_parentheses(&nodes[i + 1..]).to_vec()),
+        );
+    }
+
+    match nodes {
+        [PremiseNode::Negation, negated @ ..] => {
+            Form::Negation(Premise::new(strip_parentheses(negated).to_vec()))
+        }
+        _ => Form::Atom,
+    }
+}
+
+// Parses one of the patterns the rules are written with
+fn pattern(pattern: &str) -> Pattern {
+    Pattern::parse(pattern).unwrap_or_else(|error| {
+        panic!(
+            "[INTERNAL ERROR] The rule pattern '{}' could not be parsed: {}",
+            pattern, error
+        )
+    })
+}
+
+// Returns the subformula a metavariable of one of the rule patterns matched
+fn bound(bindings: &Bindings, name: &str) -> Premise {
+    bindings.get(name).cloned().unwrap_or_else(|| {
+        panic!(
+            "[INTERNAL ERROR] The rule pattern has no metavariable '_{}'",
+            name
+        )
+    })
+}
+
+// Matches the formula as an implication, binding its antecedent to _A and its consequent to _B,
+// or explains that the line is not one
+fn implication(formula: &Premise, number: usize) -> std::result::Result<Bindings, (usize, String)> {
+    formula
+        .match_pattern(&pattern("_A > _B"))
+        .ok_or_else(|| (0, format!("line {} is not an implication", number)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Creates the step applying a rule to the given lines
+    fn step(rule: InferenceRule, lines: &[usize], formula: Option<&str>) -> ProofStep {
+        ProofStep {
+            rule,
+            lines: lines.to_vec(),
+            formula: formula.map(|formula| Premise::parse_str(formula).unwrap()),
+        }
+    }
+
+    // Creates a proof starting with the given premises
+    fn proof(premises: &[&str]) -> Proof {
+        Proof::new(
+            premises
+                .iter()
+                .map(|premise| Premise::parse_str(premise).unwrap())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut proof = proof(&["p > (q | r)", "!s > p", "!s", "!q"]);
+
+        assert_eq!(
+            proof.apply(&step(InferenceRule::ModusPonens, &[3, 2], None)),
+            Ok(5)
+        );
+        assert_eq!(
+            proof.apply(&step(InferenceRule::ModusPonens, &[1, 5], None)),
+            Ok(6)
+        );
+        assert_eq!(
+            proof.apply(&step(InferenceRule::DisjunctiveSyllogism, &[6, 4], None)),
+            Ok(7)
+        );
+        assert_eq!(
+            proof.apply(&step(InferenceRule::Conjunction, &[7, 3], None)),
+            Ok(8)
+        );
+        assert_eq!(
+            proof.apply(&step(InferenceRule::Simplification, &[8], Some("!s"))),
+            Ok(9)
+        );
+        assert_eq!(
+            proof.apply(&step(InferenceRule::Addition, &[7], Some("t | r"))),
+            Ok(10)
+        );
+
+        assert_eq!(
+            proof.find_line(&Premise::parse_str("r & !s").unwrap()),
+            Some(8)
+        );
+        assert_eq!(
+            proof.render(Notation::Ascii),
+            " 1. p > (q | r)  Premise\n \
+             2. !s > p       Premise\n \
+             3. !s           Premise\n \
+             4. !q           Premise\n \
+             5. p            Modus ponens 3, 2\n \
+             6. q | r        Modus ponens 1, 5\n \
+             7. r            Disjunctive syllogism 6, 4\n \
+             8. r & !s       Conjunction 7, 3\n \
+             9. !s           Simplification 8\n\
+             10. t | r        Addition 7\n"
+        );
+
+        assert!(proof.undo().is_some());
+        assert_eq!(proof.get_lines().len(), 9);
+    }
+
+    #[test]
+    fn test_apply_reordered() {
+        let mut reordered = proof(&["q | p", "!p", "(a & b) | c", "!(b & a)"]);
+
+        // The sides of ∧ and ∨ are matched in any order
+        assert_eq!(
+            reordered.apply(&step(InferenceRule::DisjunctiveSyllogism, &[1, 2], None)),
+            Ok(5)
+        );
+        assert_eq!(
+            reordered.get_lines()[4].formula,
+            Premise::parse_str("q").unwrap()
+        );
+        assert_eq!(
+            reordered.apply(&step(InferenceRule::DisjunctiveSyllogism, &[3, 4], None)),
+            Ok(6)
+        );
+        assert_eq!(
+            reordered.get_lines()[5].formula,
+            Premise::parse_str("c").unwrap()
+        );
+
+        // A negation still has to match, and ∧ is not ∨
+        let mut different = proof(&["p | q", "!!p", "(a | b) | c", "!(a & b)"]);
+        assert!(different
+            .apply(&step(InferenceRule::DisjunctiveSyllogism, &[1, 2], None))
+            .is_err());
+        assert!(different
+            .apply(&step(InferenceRule::DisjunctiveSyllogism, &[3, 4], None))
+            .is_err());
+    }
+
+    #[test]
+    fn test_apply_errors() {
+        let mut proof = proof(&["p > q", "q", "!!p", "p & q"]);
+
+        let errors = [
+            (step(InferenceRule::ModusPonens, &[1, 2], None), "Modus ponens does not apply, as line 2 is q, which is not the antecedent p of line 1"),
+            (step(InferenceRule::ModusPonens, &[2, 3], None), "Modus ponens does not apply, as line 2 is not an implication"),
+            (step(InferenceRule::ModusTollens, &[1, 3], None), "Modus tollens does not apply, as line 3 is ¬¬p, which is not the negation of the consequent q of line 1"),
+            (step(InferenceRule::ModusPonens, &[1], None), "Modus ponens is applied to 2 lines, but 1 was given"),
+            (step(InferenceRule::ModusPonens, &[1, 9], None), "There is no line 9"),
+            (step(InferenceRule::Simplification, &[4], Some("r")), "Simplification does not apply, as r is not a side of line 4"),
+            (step(InferenceRule::Addition, &[2], None), "Addition needs the formula to derive, such as 'add 1 p | q'"),
+            (step(InferenceRule::DoubleNegation, &[2], None), "Double negation does not apply, as line 2 is not a double negation"),
+        ];
+
+        for (step, message) in errors {
+            assert_eq!(proof.apply(&step).unwrap_err().to_string(), message);
+        }
+
+        // Nothing is added by a failed application
+        assert_eq!(proof.get_lines().len(), 4);
+        assert!(proof.undo().is_none());
+    }
+
+    #[test]
+    fn test_find_next_step() {
+        let mut proof = proof(&["p > q", "q > r", "!r", "s | p"]);
+        let goal = Premise::parse_str("s & !q").unwrap();
+
+        // Following the hints reaches the goal
+        while let Some(step) = proof.find_next_step(&goal) {
+            proof.apply(&step).unwrap();
+        }
+
+        assert_eq!(proof.find_line(&goal), Some(proof.get_lines().len()));
+        assert_eq!(
+            proof.render(Notation::Ascii),
+            "1. p > q   Premise\n\
+             2. q > r   Premise\n\
+             3. !r      Premise\n\
+             4. s | p   Premise\n\
+             5. !q      Modus tollens 2, 3\n\
+             6. !p      Modus tollens 1, 5\n\
+             7. s       Disjunctive syllogism 4, 6\n\
+             8. s & !q  Conjunction 7, 5\n"
+        );
+
+        // Nothing proves a goal which does not follow
+        assert_eq!(
+            proof.find_next_step(&Premise::parse_str("t").unwrap()),
+            None
+        );
+    }
+}
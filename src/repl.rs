@@ -0,0 +1,465 @@
+use std::io::{self, BufRead, Write};
+
+use deductions::entailment::Consistency;
+use deductions::Deduction;
+use deductions::Premise;
+
+const HELP: &str = "\
+Commands:
+  assert <premise>     Add a premise, such as 'assert p > q'
+  conclude <premise>   Set the conclusion to prove
+  list                 Show the premises and conclusion
+  solve                Solve the premises and show the values found
+  assume <premise>     Add a premise which can be discharged later
+  discharge            Remove the most recent assumption
+  whatif <premise>     Show what else would be found with an extra premise
+  why <proposition>    Show how the value of a proposition was found
+  reset                Remove every premise and the conclusion
+  help                 Show this message
+  quit                 Leave the REPL
+
+A line with unclosed parentheses is continued on the next one, and a blank line
+cancels it.";
+
+// Stores the argument being built up in the REPL
+// Assumptions are solved after the premises and are removed in the opposite order they were made
+// The lines of a command with unclosed parentheses are held until they are closed
+#[derive(Debug, Default)]
+pub struct Repl {
+    premises: Vec<Premise>,
+    assumptions: Vec<Premise>,
+    conclusion: Option<Premise>,
+    pending: Vec<String>,
+    balance: Balance,
+}
+
+// Tracks how many parentheses are open across the lines of a continued command
+// Parentheses inside double quotes are not counted
+#[derive(Debug, Default)]
+struct Balance {
+    depth: usize,
+    in_quotes: bool,
+}
+
+// Runs the REPL until the input ends or the user quits, printing a prompt before each line
+pub fn run(input: impl BufRead, output: &mut impl Write) -> io::Result<()> {
+    let mut repl = Repl::default();
+    let mut lines = input.lines();
+
+    loop {
+        write!(output, "{}", repl.prompt())?;
+        output.flush()?;
+
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+
+        if !repl.execute_line(&line, output)? {
+            break;
+        }
+    }
+
+    writeln!(output)
+}
+
+impl Repl {
+    // Returns the prompt, which shows how many assumptions are in effect, such as "assume(2)>> ",
+    // or "..> " while a command is being continued
+    pub fn prompt(&self) -> String {
+        match (self.pending.is_empty(), self.assumptions.len()) {
+            (false, _) => String::from("..> "),
+            (true, 0) => String::from(">> "),
+            (true, depth) => format!("assume({})>> ", depth),
+        }
+    }
+
+    // Runs a single line of input, returning whether the REPL should keep going
+    // A line which leaves parentheses open is held and joined to the following lines until they
+    // are closed, unless a blank line cancels it
+    pub fn execute_line(&mut self, line: &str, output: &mut impl Write) -> io::Result<bool> {
+        let line = line.trim();
+
+        if !self.pending.is_empty() && line.is_empty() {
+            self.pending.clear();
+            self.balance = Balance::default();
+            writeln!(output, "Cancelled")?;
+            return Ok(true);
+        }
+
+        self.balance.feed(line);
+        self.pending.push(line.to_string());
+
+        if self.balance.is_open() {
+            return Ok(true);
+        }
+
+        let command = self.pending.join(" ");
+        self.pending.clear();
+        self.balance = Balance::default();
+
+        self.execute_command(&command, output)
+    }
+
+    // Runs a complete command, returning whether the REPL should keep going
+    fn execute_command(&mut self, line: &str, output: &mut impl Write) -> io::Result<bool> {
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let rest = rest.trim();
+
+        match command {
+            "" => (),
+            "assert" => match Premise::parse_str(rest) {
+                Ok(premise) => {
+                    writeln!(output, "P{}: {}", self.premises.len() + 1, premise)?;
+                    self.premises.push(premise);
+                }
+                Err(error) => writeln!(output, "Error: {}", error)?,
+            },
+            "conclude" => match Premise::parse_str(rest) {
+                Ok(premise) => {
+                    writeln!(output, "∴ {}", premise)?;
+                    self.conclusion = Some(premise);
+                }
+                Err(error) => writeln!(output, "Error: {}", error)?,
+            },
+            "assume" => match Premise::parse_str(rest) {
+                Ok(premise) => {
+                    writeln!(
+                        output,
+                        "P{}: {} (assumed)",
+                        self.num_premises() + 1,
+                        premise
+                    )?;
+                    self.assumptions.push(premise);
+                }
+                Err(error) => writeln!(output, "Error: {}", error)?,
+            },
+            "discharge" => match self.assumptions.pop() {
+                Some(premise) => writeln!(output, "Discharged {}", premise)?,
+                None => writeln!(output, "There is no assumption to discharge")?,
+            },
+            "whatif" => match Premise::parse_str(rest) {
+                Ok(premise) => self.what_if(premise, output)?,
+                Err(error) => writeln!(output, "Error: {}", error)?,
+            },
+            "why" => self.why(rest, output)?,
+            "list" => {
+                for (i, premise) in self.premises.iter().enumerate() {
+                    writeln!(output, "P{}: {}", i + 1, premise)?;
+                }
+
+                for (i, premise) in self.assumptions.iter().enumerate() {
+                    writeln!(
+                        output,
+                        "P{}: {} (assumed)",
+                        self.premises.len() + i + 1,
+                        premise
+                    )?;
+                }
+
+                if let Some(conclusion) = &self.conclusion {
+                    writeln!(output, "∴ {}", conclusion)?;
+                }
+            }
+            "solve" => self.solve(output)?,
+            "reset" => *self = Self::default(),
+            "help" => writeln!(output, "{}", HELP)?,
+            "quit" | "exit" => return Ok(false),
+            _ => writeln!(
+                output,
+                "Unknown command '{}', type 'help' for a list of commands",
+                command
+            )?,
+        }
+
+        Ok(true)
+    }
+
+    // Returns the number of premises, including the assumptions
+    fn num_premises(&self) -> usize {
+        self.premises.len() + self.assumptions.len()
+    }
+
+    // Creates a Deduction from the premises followed by the assumptions and any extra premise,
+    // with the conclusion if there is one
+    fn deduction(&self, extra: Option<Premise>) -> Deduction {
+        let mut premises = self.premises.clone();
+        premises.extend(self.assumptions.iter().cloned());
+        premises.extend(extra);
+
+        let mut deduction = Deduction::from_premises(premises);
+
+        if let Some(conclusion) = &self.conclusion {
+            deduction.set_conclusion(conclusion.clone());
+        }
+
+        deduction
+    }
+
+    // Solves a copy of the premises, so more can still be added afterwards
+    fn solve(&self, output: &mut impl Write) -> io::Result<()> {
+        if self.num_premises() == 0 {
+            return writeln!(output, "There are no premises to solve");
+        }
+
+        let mut deduction = self.deduction(None);
+        deduction.solve();
+        write!(output, "{}", deduction.get_values())?;
+
+        if let Some(conclusion) = deduction.get_conclusion() {
+            match deduction.evaluate_conclusion() {
+                Some(true) => writeln!(output, "∴ {} is TRUE", conclusion)?,
+                Some(false) => writeln!(output, "∴ {} is FALSE", conclusion)?,
+                None => writeln!(output, "∴ {} could not be determined", conclusion)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    // Solves the premises with an extra one without keeping it, showing the values which would
+    // be found or changed with it, or the premises it contradicts
+    fn what_if(&self, premise: Premise, output: &mut impl Write) -> io::Result<()> {
+        let mut with = self.deduction(Some(premise));
+
+        if let Consistency::Inconsistent { core } = with.check_consistency() {
+            writeln!(
+                output,
+                "The premises would be inconsistent, as these cannot all be TRUE:"
+            )?;
+
+            for premise in core {
+                writeln!(output, "{}: {}", premise.label, premise.premise)?;
+            }
+
+            return Ok(());
+        }
+
+        let mut without = self.deduction(None);
+        without.solve();
+        with.solve();
+
+        let mut found: Vec<(char, bool, Option<bool>)> = with
+            .get_values()
+            .iter()
+            .filter_map(|(proposition, value)| {
+                let before = without
+                    .get_values()
+                    .iter()
+                    .find(|(known, _)| *known == proposition)
+                    .and_then(|(_, value)| value);
+
+                match value {
+                    Some(value) if before != Some(value) => Some((proposition, value, before)),
+                    _ => None,
+                }
+            })
+            .collect();
+        found.sort();
+
+        if found.is_empty() {
+            return writeln!(output, "Nothing else would be found");
+        }
+
+        for (proposition, value, before) in found {
+            let value = if value { "TRUE" } else { "FALSE" };
+
+            match before {
+                Some(before) => writeln!(
+                    output,
+                    "{} = {} (was {})",
+                    proposition,
+                    value,
+                    if before { "TRUE" } else { "FALSE" }
+                )?,
+                None => writeln!(output, "{} = {}", proposition, value)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    // Solves the premises and explains how the value of the given proposition was found
+    fn why(&self, proposition: &str, output: &mut impl Write) -> io::Result<()> {
+        let mut chars = proposition.chars();
+
+        let proposition = match (chars.next(), chars.next()) {
+            (Some(proposition), None) if proposition.is_ascii_lowercase() => proposition,
+            _ => {
+                return writeln!(
+                    output,
+                    "Error: Expected a single proposition, such as 'why p'"
+                )
+            }
+        };
+
+        let mut deduction = self.deduction(None);
+        let history = deduction.solve();
+
+        match history.explain(proposition) {
+            Some(explanation) => write!(output, "{}", explanation),
+            None if deduction
+                .get_values()
+                .iter()
+                .any(|(known, _)| known == proposition) =>
+            {
+                writeln!(
+                    output,
+                    "The value of {} could not be determined",
+                    proposition
+                )
+            }
+            None => writeln!(output, "There is no proposition '{}'", proposition),
+        }
+    }
+}
+
+impl Balance {
+    // Counts the parentheses opened and closed by the next line
+    // Extra closing parentheses are left for the parser to report
+    fn feed(&mut self, line: &str) {
+        for character in line.chars() {
+            match character {
+                '"' => self.in_quotes = !self.in_quotes,
+                '(' if !self.in_quotes => self.depth += 1,
+                ')' if !self.in_quotes => self.depth = self.depth.saturating_sub(1),
+                _ => (),
+            }
+        }
+    }
+
+    // Checks whether any parentheses are still open
+    fn is_open(&self) -> bool {
+        self.depth > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Runs the REPL on the given input, returning everything it printed
+    fn run_script(script: &str) -> String {
+        let mut output = Vec::new();
+        run(script.as_bytes(), &mut output).unwrap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_repl() {
+        let output = run_script("assert p > q\nassert p\nconclude q\nsolve\nquit\nlist\n");
+
+        assert_eq!(
+            output,
+            ">> P1: p → q\n>> P2: p\n>> ∴ q\n>> p = TRUE\nq = TRUE\n∴ q is TRUE\n>> \n"
+        );
+    }
+
+    #[test]
+    fn test_repl_assumptions() {
+        let output = run_script(
+            "assert p > q\nassert q > r\nassume p\nassume s\nlist\ndischarge\nsolve\n\
+             discharge\ndischarge\nsolve\n",
+        );
+
+        assert_eq!(
+            output,
+            ">> P1: p → q\n\
+             >> P2: q → r\n\
+             >> P3: p (assumed)\n\
+             assume(1)>> P4: s (assumed)\n\
+             assume(2)>> P1: p → q\nP2: q → r\nP3: p (assumed)\nP4: s (assumed)\n\
+             assume(2)>> Discharged s\n\
+             assume(1)>> p = TRUE\nq = TRUE\nr = TRUE\n\
+             assume(1)>> Discharged p\n\
+             >> There is no assumption to discharge\n\
+             >> p = UNKNOWN\nq = UNKNOWN\nr = UNKNOWN\n\
+             >> \n"
+        );
+    }
+
+    #[test]
+    fn test_repl_what_if_and_why() {
+        let output = run_script("assert p > q\nassert q > r\nwhatif p\nsolve\nwhatif !r\nwhy q\n");
+
+        // Nothing found with the extra premise is kept afterwards
+        assert_eq!(
+            output,
+            ">> P1: p → q\n\
+             >> P2: q → r\n\
+             >> p = TRUE\nq = TRUE\nr = TRUE\n\
+             >> p = UNKNOWN\nq = UNKNOWN\nr = UNKNOWN\n\
+             >> p = FALSE\nq = FALSE\nr = FALSE\n\
+             >> The value of q could not be determined\n\
+             >> \n"
+        );
+
+        let output = run_script("assert p > q\nassert p\nwhatif !q\nwhatif !p\nwhatif r\n");
+
+        // An extra premise which contradicts the others is reported instead of what it would find
+        assert_eq!(
+            output,
+            ">> P1: p → q\n\
+             >> P2: p\n\
+             >> The premises would be inconsistent, as these cannot all be TRUE:\n\
+             P1: p → q\nP2: p\nP3: ¬q\n\
+             >> The premises would be inconsistent, as these cannot all be TRUE:\n\
+             P2: p\nP3: ¬p\n\
+             >> r = TRUE\n\
+             >> \n"
+        );
+
+        let output = run_script("assert p > q\nassert q > r\nassume p\nwhy r\nwhy x\nwhy pq\n");
+
+        assert_eq!(
+            output,
+            ">> P1: p → q\n\
+             >> P2: q → r\n\
+             >> P3: p (assumed)\n\
+             assume(1)>> r = TRUE from P2: q → r\n  q = TRUE from P1: p → q\n    p = TRUE from P3: p\n\
+             assume(1)>> There is no proposition 'x'\n\
+             assume(1)>> Error: Expected a single proposition, such as 'why p'\n\
+             assume(1)>> \n"
+        );
+    }
+
+    #[test]
+    fn test_repl_continuation() {
+        let output = run_script("assert (m &\n!b) > (j |\n(k\n& l))\nlist\nassert (p |\n\nlist\n");
+
+        // The premise is only asserted once every parenthesis is closed
+        assert_eq!(
+            output,
+            ">> ..> ..> ..> P1: (m ∧ ¬b) → (j ∨ (k ∧ l))\n\
+             >> P1: (m ∧ ¬b) → (j ∨ (k ∧ l))\n\
+             >> ..> Cancelled\n\
+             >> P1: (m ∧ ¬b) → (j ∨ (k ∧ l))\n\
+             >> \n"
+        );
+
+        // Parentheses inside quotes are not counted, and extra closing ones are left to the parser,
+        // which rejects them
+        let output = run_script("assert \"(\" p\nassert p)\n");
+
+        assert_eq!(
+            output,
+            ">> Error: Invalid character in premise: '\"' at position 0\n\
+             >> Error: Unbalanced parenthesis in premise at position 1\n\
+             >> \n"
+        );
+    }
+
+    #[test]
+    fn test_repl_errors() {
+        let output = run_script("assert p &\nfoo\nsolve");
+
+        assert_eq!(
+            output,
+            ">> Error: Invalid premise structure\n\
+             >> Unknown command 'foo', type 'help' for a list of commands\n\
+             >> There are no premises to solve\n\
+             >> \n"
+        );
+    }
+}
@@ -0,0 +1,330 @@
+use std::sync::OnceLock;
+
+use crate::arena::PremiseArena;
+use crate::premises::{EvaluationRule, Operator};
+use crate::PremiseNode;
+
+// Describes an operand by what the rewrite rules need to know about it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OperandKind {
+    True,
+    False,
+    // A subpremise which only holds a single operand, such as "(¬p)"
+    Wrapped,
+    // Any other operand, or several operands joined by operators
+    Other,
+}
+
+// Matches the kinds of operand a rule applies to
+#[derive(Debug, Clone, Copy)]
+enum Pattern {
+    Is(OperandKind),
+    // Either truth value
+    Known,
+    Any,
+}
+
+// Rewrites two operands joined by an operator, returning the indices which replace them
+type BinaryRewrite = fn(&mut PremiseArena, &[usize], Operator, &[usize]) -> Vec<usize>;
+
+// Rewrites a single operand after the given negations, returning the indices which replace them
+type NegatedRewrite = fn(&mut PremiseArena, &[usize], usize) -> Vec<usize>;
+
+// A rule for two operands joined by an operator, such as "TRUE ∧ p" becoming p
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BinaryRule {
+    pub(crate) rule: EvaluationRule,
+    pub(crate) rewrite: BinaryRewrite,
+}
+
+// A rule for a single operand after at least some number of negations, such as "¬¬p" becoming p
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct NegatedRule {
+    pub(crate) rule: EvaluationRule,
+    pub(crate) rewrite: NegatedRewrite,
+}
+
+use EvaluationRule::*;
+use Operator::*;
+use Pattern::*;
+
+// Every rule for two operands joined by an operator, where the first one which matches is used
+// These follow Operator::apply_partial, dropping a known operand which does not decide the value
+const BINARY_RULES: &[(Pattern, Operator, Pattern, EvaluationRule, BinaryRewrite)] = &[
+    (Known, And, Known, Conjunction, apply_to_values),
+    (Is(OperandKind::False), And, Any, Conjunction, to_false),
+    (Any, And, Is(OperandKind::False), Conjunction, to_false),
+    (Is(OperandKind::True), And, Any, Conjunction, keep_right),
+    (Any, And, Is(OperandKind::True), Conjunction, keep_left),
+    (Known, Or, Known, Disjunction, apply_to_values),
+    (Is(OperandKind::True), Or, Any, Disjunction, to_true),
+    (Any, Or, Is(OperandKind::True), Disjunction, to_true),
+    (Is(OperandKind::False), Or, Any, Disjunction, keep_right),
+    (Any, Or, Is(OperandKind::False), Disjunction, keep_left),
+    (Known, Implies, Known, Implication, apply_to_values),
+    (Is(OperandKind::False), Implies, Any, Implication, to_true),
+    (Any, Implies, Is(OperandKind::True), Implication, to_true),
+    (Is(OperandKind::True), Implies, Any, Implication, keep_right),
+    // "p → FALSE" leaves ¬p
+    (
+        Any,
+        Implies,
+        Is(OperandKind::False),
+        Implication,
+        negate_left,
+    ),
+    (Known, Iff, Known, Biconditional, apply_to_values),
+    (Is(OperandKind::True), Iff, Any, Biconditional, keep_right),
+    (Any, Iff, Is(OperandKind::True), Biconditional, keep_left),
+    // "FALSE ↔ p" and "p ↔ FALSE" leave ¬p
+    (
+        Is(OperandKind::False),
+        Iff,
+        Any,
+        Biconditional,
+        negate_right,
+    ),
+    (Any, Iff, Is(OperandKind::False), Biconditional, negate_left),
+    (Known, Xor, Known, ExclusiveDisjunction, apply_to_values),
+    // "TRUE ⊕ p" and "p ⊕ TRUE" leave ¬p
+    (
+        Is(OperandKind::True),
+        Xor,
+        Any,
+        ExclusiveDisjunction,
+        negate_right,
+    ),
+    (
+        Any,
+        Xor,
+        Is(OperandKind::True),
+        ExclusiveDisjunction,
+        negate_left,
+    ),
+    (
+        Is(OperandKind::False),
+        Xor,
+        Any,
+        ExclusiveDisjunction,
+        keep_right,
+    ),
+    (
+        Any,
+        Xor,
+        Is(OperandKind::False),
+        ExclusiveDisjunction,
+        keep_left,
+    ),
+];
+
+// Every rule for a single operand after at least the given number of negations, where the first
+// one which matches is used
+// A subpremise is unwrapped before its negations are looked at, and the innermost negation of a
+// truth value is evaluated before any double negation is removed
+const NEGATED_RULES: &[(usize, Pattern, EvaluationRule, NegatedRewrite)] = &[
+    (0, Is(OperandKind::Wrapped), Parentheses, remove_parentheses),
+    (1, Known, Negation, negate_value),
+    (2, Any, DoubleNegation, remove_double_negation),
+];
+
+const KINDS: [OperandKind; 4] = [
+    OperandKind::True,
+    OperandKind::False,
+    OperandKind::Wrapped,
+    OperandKind::Other,
+];
+const OPERATORS: [Operator; 5] = [And, Or, Implies, Iff, Xor];
+
+// Finds the rule for each shape of nodes in a single lookup, built once from the lists of rules
+#[derive(Debug)]
+pub(crate) struct RewriteTable {
+    // Indexed by the kind of the left operand, the operator and the kind of the right operand
+    binary: [[[Option<BinaryRule>; 4]; 5]; 4],
+    // Indexed by the number of negations, where any more than 2 count as 2, and the operand's kind
+    negated: [[Option<NegatedRule>; 4]; 3],
+}
+
+impl RewriteTable {
+    // Returns the table, building it the first time it is used
+    pub(crate) fn get() -> &'static Self {
+        static TABLE: OnceLock<RewriteTable> = OnceLock::new();
+
+        TABLE.get_or_init(Self::build)
+    }
+
+    // Returns the rule for two operands of the given kinds joined by the operator, if there is one
+    pub(crate) fn binary(
+        &self,
+        left: OperandKind,
+        operator: Operator,
+        right: OperandKind,
+    ) -> Option<BinaryRule> {
+        self.binary[left as usize][operator_index(operator)][right as usize]
+    }
+
+    // Returns the rule for an operand of the given kind after the negations, if there is one
+    pub(crate) fn negated(&self, negations: usize, kind: OperandKind) -> Option<NegatedRule> {
+        self.negated[negations.min(2)][kind as usize]
+    }
+
+    // Fills in every shape with the first rule which matches it
+    fn build() -> Self {
+        let mut table = Self {
+            binary: [[[None; 4]; 5]; 4],
+            negated: [[None; 4]; 3],
+        };
+
+        for left in KINDS {
+            for operator in OPERATORS {
+                for right in KINDS {
+                    table.binary[left as usize][operator_index(operator)][right as usize] =
+                        BINARY_RULES
+                            .iter()
+                            .find(|entry| {
+                                entry.1 == operator
+                                    && entry.0.matches(left)
+                                    && entry.2.matches(right)
+                            })
+                            .map(|&(_, _, _, rule, rewrite)| BinaryRule { rule, rewrite });
+                }
+            }
+        }
+
+        for negations in 0..=2 {
+            for kind in KINDS {
+                table.negated[negations][kind as usize] = NEGATED_RULES
+                    .iter()
+                    .find(|entry| negations >= entry.0 && entry.1.matches(kind))
+                    .map(|&(_, _, rule, rewrite)| NegatedRule { rule, rewrite });
+            }
+        }
+
+        table
+    }
+}
+
+impl Pattern {
+    // Checks whether an operand of the kind matches the pattern
+    fn matches(self, kind: OperandKind) -> bool {
+        match self {
+            Is(expected) => kind == expected,
+            Known => matches!(kind, OperandKind::True | OperandKind::False),
+            Any => true,
+        }
+    }
+}
+
+// Returns the position of the operator in the table
+fn operator_index(operator: Operator) -> usize {
+    match operator {
+        And => 0,
+        Or => 1,
+        Implies => 2,
+        Iff => 3,
+        Xor => 4,
+    }
+}
+
+// Replaces an operator between two truth values with its value
+fn apply_to_values(
+    arena: &mut PremiseArena,
+    left: &[usize],
+    operator: Operator,
+    right: &[usize],
+) -> Vec<usize> {
+    let (Some(left), Some(right)) = (arena.get_truth_value(left), arena.get_truth_value(right))
+    else {
+        unreachable!("both operands are truth values")
+    };
+
+    vec![arena.push_leaf(PremiseNode::TruthValue(operator.apply(left, right)))]
+}
+
+fn to_true(arena: &mut PremiseArena, _: &[usize], _: Operator, _: &[usize]) -> Vec<usize> {
+    vec![arena.push_leaf(PremiseNode::TruthValue(true))]
+}
+
+fn to_false(arena: &mut PremiseArena, _: &[usize], _: Operator, _: &[usize]) -> Vec<usize> {
+    vec![arena.push_leaf(PremiseNode::TruthValue(false))]
+}
+
+fn keep_left(_: &mut PremiseArena, left: &[usize], _: Operator, _: &[usize]) -> Vec<usize> {
+    left.to_vec()
+}
+
+fn keep_right(_: &mut PremiseArena, _: &[usize], _: Operator, right: &[usize]) -> Vec<usize> {
+    right.to_vec()
+}
+
+fn negate_left(arena: &mut PremiseArena, left: &[usize], _: Operator, _: &[usize]) -> Vec<usize> {
+    let negation = arena.push_leaf(PremiseNode::Negation);
+
+    [vec![negation], arena.wrap_operand(left.to_vec())].concat()
+}
+
+fn negate_right(arena: &mut PremiseArena, _: &[usize], _: Operator, right: &[usize]) -> Vec<usize> {
+    let negation = arena.push_leaf(PremiseNode::Negation);
+
+    [vec![negation], arena.wrap_operand(right.to_vec())].concat()
+}
+
+// Replaces a subpremise which only holds a single operand with its nodes
+fn remove_parentheses(arena: &mut PremiseArena, negations: &[usize], operand: usize) -> Vec<usize> {
+    [negations, arena.get_group(operand)].concat()
+}
+
+// Evaluates the innermost negation of a truth value
+fn negate_value(arena: &mut PremiseArena, negations: &[usize], operand: usize) -> Vec<usize> {
+    let Some(value) = arena.get_truth_value(&[operand]) else {
+        unreachable!("the operand is a truth value")
+    };
+    let value = arena.push_leaf(PremiseNode::TruthValue(!value));
+
+    [&negations[1..], &[value]].concat()
+}
+
+fn remove_double_negation(_: &mut PremiseArena, negations: &[usize], operand: usize) -> Vec<usize> {
+    [&negations[2..], &[operand]].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table() {
+        let table = RewriteTable::get();
+
+        let rule =
+            |left, operator, right| table.binary(left, operator, right).map(|rule| rule.rule);
+        assert_eq!(
+            rule(OperandKind::True, And, OperandKind::False),
+            Some(Conjunction)
+        );
+        assert_eq!(
+            rule(OperandKind::Other, Implies, OperandKind::False),
+            Some(Implication)
+        );
+        assert_eq!(rule(OperandKind::Other, Or, OperandKind::Wrapped), None);
+
+        // Every shape with a known operand has a rule, as every such operator can be evaluated
+        for left in KINDS {
+            for operator in OPERATORS {
+                for right in KINDS {
+                    assert_eq!(
+                        table.binary(left, operator, right).is_some(),
+                        Known.matches(left) || Known.matches(right)
+                    );
+                }
+            }
+        }
+
+        let rule = |negations, kind| table.negated(negations, kind).map(|rule| rule.rule);
+        assert_eq!(rule(0, OperandKind::Wrapped), Some(Parentheses));
+        assert_eq!(rule(3, OperandKind::Wrapped), Some(Parentheses));
+        assert_eq!(rule(0, OperandKind::True), None);
+        assert_eq!(rule(5, OperandKind::False), Some(Negation));
+        assert_eq!(rule(1, OperandKind::Other), None);
+        assert_eq!(rule(2, OperandKind::Other), Some(DoubleNegation));
+    }
+}
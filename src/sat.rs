@@ -0,0 +1,514 @@
+// Decides whether a set of clauses in conjunctive normal form is satisfiable, either with the
+// built-in DPLL search or by handing the clauses to an external SAT solver in the DIMACS format
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::errors::SatError;
+use crate::normal_form::{render_dimacs_over, Clause, NormalForm, NormalFormError};
+use crate::Premise;
+use crate::ValueMap;
+
+// A conjunction of clauses along with the propositions they use, numbered from 1 in order when
+// written in the DIMACS format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClauseSet {
+    propositions: Vec<char>,
+    clauses: Vec<Clause>,
+}
+
+// The answer a SatBackend gives for a set of clauses
+#[derive(Debug)]
+pub enum SatResult {
+    // The model gives a value to every proposition of the clauses and makes them all true
+    Satisfiable(ValueMap),
+    Unsatisfiable,
+    // The backend could not decide either way
+    Failed(SatError),
+}
+
+// Decides whether a set of clauses is satisfiable, finding a model if it is
+pub trait SatBackend {
+    fn solve(&self, clauses: &ClauseSet) -> SatResult;
+}
+
+// Searches for a model with unit propagation, branching on the first unassigned literal of the
+// first clause which is not yet true
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Dpll;
+
+// Runs an external SAT solver, such as minisat or kissat, on a temporary DIMACS file
+// The solver is run as the command followed by its arguments and then the path of the file, and
+// its standard output is read as "SAT" or "s SATISFIABLE" followed by a model given as lines of
+// literals ending in 0, which may start with "v", or as "UNSAT" or "s UNSATISFIABLE"
+#[derive(Debug, Clone)]
+pub struct ExternalDimacs {
+    command: PathBuf,
+    args: Vec<String>,
+}
+
+// Counts the DIMACS files written by this process, so solvers run at once use different files
+static FILES_WRITTEN: AtomicUsize = AtomicUsize::new(0);
+
+impl ClauseSet {
+    // Creates a set of clauses over the propositions
+    // Fails if a clause uses a proposition which is not one of them
+    pub fn new(propositions: Vec<char>, clauses: Vec<Clause>) -> Result<Self, SatError> {
+        if let Some(literal) = clauses
+            .iter()
+            .flatten()
+            .find(|literal| !propositions.contains(&literal.proposition))
+        {
+            return Err(SatError::UnknownProposition(literal.proposition));
+        }
+
+        Ok(Self {
+            propositions,
+            clauses,
+        })
+    }
+
+    // Creates the set of clauses which is true exactly when every premise is true, with the
+    // propositions in alphabetical order
+    // Fails if the premises would need more clauses than the limit between them
+    pub fn from_premises(
+        premises: &[&Premise],
+        max_clauses: usize,
+    ) -> Result<Self, NormalFormError> {
+        let mut propositions = BTreeSet::new();
+        let mut clauses = Vec::new();
+
+        for premise in premises {
+            propositions.append(&mut premise.propositions());
+            clauses.append(&mut premise.to_clauses(
+                NormalForm::Conjunctive,
+                max_clauses.saturating_sub(clauses.len()),
+            )?);
+        }
+
+        // Every proposition of the clauses comes from one of the premises
+        Ok(Self {
+            propositions: propositions.into_iter().collect(),
+            clauses,
+        })
+    }
+
+    pub fn get_propositions(&self) -> &[char] {
+        &self.propositions
+    }
+
+    pub fn get_clauses(&self) -> &[Clause] {
+        &self.clauses
+    }
+
+    // Renders the clauses in the DIMACS format, with a comment line giving the number of each
+    // proposition
+    pub fn to_dimacs(&self) -> String {
+        render_dimacs_over(&self.propositions, &self.clauses)
+    }
+
+    // Checks whether every clause has a literal which is true under the values, given in the
+    // order of the propositions
+    fn is_satisfied_by(&self, values: &[Option<bool>]) -> bool {
+        self.numbered().iter().all(|clause| {
+            clause
+                .iter()
+                .any(|&(i, negated)| values[i] == Some(!negated))
+        })
+    }
+
+    // Returns the clauses with each literal given as the position of its proposition and whether
+    // it is negated
+    fn numbered(&self) -> Vec<Vec<(usize, bool)>> {
+        self.clauses
+            .iter()
+            .map(|clause| {
+                clause
+                    .iter()
+                    .map(|literal| {
+                        let i = self
+                            .propositions
+                            .iter()
+                            .position(|proposition| *proposition == literal.proposition)
+                            .expect("every proposition of the clauses is in the set");
+
+                        (i, literal.negated)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Creates a model from the values, given in the order of the propositions, where any
+    // proposition without a value is FALSE
+    fn to_model(&self, values: &[Option<bool>]) -> ValueMap {
+        let mut model = ValueMap::default();
+        for (proposition, value) in self.propositions.iter().zip(values) {
+            model.set_value(*proposition, Some(value.unwrap_or(false)));
+        }
+
+        model
+    }
+}
+
+impl SatBackend for Dpll {
+    fn solve(&self, clauses: &ClauseSet) -> SatResult {
+        let mut values = vec![None; clauses.propositions.len()];
+
+        match search(&clauses.numbered(), &mut values) {
+            true => SatResult::Satisfiable(clauses.to_model(&values)),
+            false => SatResult::Unsatisfiable,
+        }
+    }
+}
+
+// Finds values which make every clause true, starting from the given ones, returning whether
+// there are any
+fn search(clauses: &[Vec<(usize, bool)>], values: &mut Vec<Option<bool>>) -> bool {
+    // Sets the literal of every clause with only one literal left which could make it true
+    loop {
+        let mut propagated = false;
+
+        for clause in clauses {
+            if clause
+                .iter()
+                .any(|&(i, negated)| values[i] == Some(!negated))
+            {
+                continue;
+            }
+
+            let mut unassigned = clause.iter().filter(|(i, _)| values[*i].is_none());
+            match (unassigned.next(), unassigned.next()) {
+                (None, _) => return false,
+                (Some(&(i, negated)), None) => {
+                    values[i] = Some(!negated);
+                    propagated = true;
+                }
+                _ => (),
+            }
+        }
+
+        if !propagated {
+            break;
+        }
+    }
+
+    let branch = clauses
+        .iter()
+        .filter(|clause| {
+            !clause
+                .iter()
+                .any(|&(i, negated)| values[i] == Some(!negated))
+        })
+        .find_map(|clause| clause.iter().find(|(i, _)| values[*i].is_none()));
+
+    let Some(&(i, negated)) = branch else {
+        return true;
+    };
+
+    for value in [!negated, negated] {
+        let mut branched = values.clone();
+        branched[i] = Some(value);
+
+        if search(clauses, &mut branched) {
+            *values = branched;
+            return true;
+        }
+    }
+
+    false
+}
+
+impl ExternalDimacs {
+    // Creates a backend which runs the command with no arguments other than the DIMACS file
+    pub fn new(command: impl Into<PathBuf>) -> Self {
+        Self {
+            command: command.into(),
+            args: Vec::new(),
+        }
+    }
+
+    // Adds an argument to pass to the solver before the path of the DIMACS file
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    // Writes the clauses to a temporary file and returns what the solver prints for it, deleting
+    // the file afterwards
+    fn run(&self, clauses: &ClauseSet) -> Result<String, SatError> {
+        let path = std::env::temp_dir().join(format!(
+            "deductions-{}-{}.cnf",
+            std::process::id(),
+            FILES_WRITTEN.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        std::fs::write(&path, clauses.to_dimacs()).map_err(|error| SatError::Io {
+            path: path.clone(),
+            error,
+        })?;
+
+        // Solvers exit with a code giving their answer, such as 10 for SAT, so only the output
+        // is looked at
+        let output = Command::new(&self.command)
+            .args(&self.args)
+            .arg(&path)
+            .output();
+        let _ = std::fs::remove_file(&path);
+
+        let output = output.map_err(|error| SatError::Io {
+            path: self.command.clone(),
+            error,
+        })?;
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+impl SatBackend for ExternalDimacs {
+    fn solve(&self, clauses: &ClauseSet) -> SatResult {
+        match self.run(clauses) {
+            Ok(output) => parse_output(&output, clauses),
+            Err(error) => SatResult::Failed(error),
+        }
+    }
+}
+
+// Reads the answer of a SAT solver for the clauses from its output, checking that any model it
+// gives makes every clause true
+fn parse_output(output: &str, clauses: &ClauseSet) -> SatResult {
+    let mut answer = None;
+    let mut values = vec![None; clauses.propositions.len()];
+    let mut has_model = false;
+    let mut model_ended = false;
+
+    for (i, line) in output.lines().enumerate() {
+        let text = line.trim();
+        let malformed = || {
+            SatResult::Failed(SatError::MalformedOutput {
+                line: i + 1,
+                text: text.to_string(),
+            })
+        };
+
+        match text {
+            "" => continue,
+            _ if text == "c" || text.starts_with("c ") => continue,
+            "SAT" | "SATISFIABLE" | "s SATISFIABLE" if answer.is_none() => answer = Some(true),
+            "UNSAT" | "UNSATISFIABLE" | "s UNSATISFIABLE" if answer.is_none() => {
+                answer = Some(false)
+            }
+            _ if answer != Some(true) || model_ended => return malformed(),
+            _ => {
+                let literals = text.strip_prefix("v ").unwrap_or(text);
+
+                for literal in literals.split_whitespace() {
+                    let Ok(literal) = literal.parse::<i64>() else {
+                        return malformed();
+                    };
+
+                    if literal == 0 {
+                        model_ended = true;
+                        continue;
+                    }
+
+                    let index = literal.unsigned_abs() as usize - 1;
+                    if model_ended || index >= values.len() {
+                        return malformed();
+                    }
+
+                    let value = Some(literal > 0);
+                    if values[index].is_some_and(|known| Some(known) != value) {
+                        return malformed();
+                    }
+
+                    values[index] = value;
+                    has_model = true;
+                }
+            }
+        }
+    }
+
+    match answer {
+        None => SatResult::Failed(SatError::NoAnswer),
+        Some(false) => SatResult::Unsatisfiable,
+        // A model with no literals is only complete when there are no propositions to give
+        Some(true) if !has_model && !model_ended => SatResult::Failed(SatError::MissingModel),
+        Some(true) if !clauses.is_satisfied_by(&complete(&values)) => {
+            SatResult::Failed(SatError::InvalidModel)
+        }
+        Some(true) => SatResult::Satisfiable(clauses.to_model(&values)),
+    }
+}
+
+// Gives every proposition without a value FALSE, as in the model a solver's answer becomes
+fn complete(values: &[Option<bool>]) -> Vec<Option<bool>> {
+    values
+        .iter()
+        .map(|value| Some(value.unwrap_or(false)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normal_form::{Literal, DEFAULT_MAX_CLAUSES};
+    use crate::truth_table::Formula;
+
+    // Creates the set of clauses for the premises
+    fn clauses(premises: &[&str]) -> ClauseSet {
+        let premises: Vec<Premise> = premises
+            .iter()
+            .map(|s| Premise::parse_str(s).unwrap())
+            .collect();
+        let premises: Vec<&Premise> = premises.iter().collect();
+
+        ClauseSet::from_premises(&premises, DEFAULT_MAX_CLAUSES).unwrap()
+    }
+
+    // Creates a backend which runs the fixture script, printing the canned output in the file
+    fn fixture(output: &str) -> ExternalDimacs {
+        let directory = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sat");
+
+        ExternalDimacs::new("sh")
+            .arg(format!("{}/solver.sh", directory))
+            .arg(format!("{}/{}", directory, output))
+    }
+
+    #[test]
+    fn test_clause_set() {
+        let set = clauses(&["p > q", "!(q > p)"]);
+        assert_eq!(set.get_propositions(), ['p', 'q']);
+        assert_eq!(set.get_clauses().len(), 3);
+        assert_eq!(
+            set.to_dimacs(),
+            "c 1 = p\nc 2 = q\np cnf 2 3\n-1 2 0\n2 0\n-1 0\n"
+        );
+
+        assert!(
+            ClauseSet::from_premises(&[&Premise::parse_str("(a & b) | (c & d)").unwrap()], 2)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_dpll() {
+        match Dpll.solve(&clauses(&["p > q", "!(q > p)"])) {
+            SatResult::Satisfiable(model) => {
+                assert_eq!(model.get_value('p'), Some(false));
+                assert_eq!(model.get_value('q'), Some(true));
+            }
+            other => panic!("Expected Satisfiable, found {:?}", other),
+        }
+
+        assert!(matches!(
+            Dpll.solve(&clauses(&["p | q", "!p", "!q"])),
+            SatResult::Unsatisfiable
+        ));
+        assert!(matches!(
+            // An empty clause can never be true
+            Dpll.solve(&ClauseSet::new(Vec::new(), vec![Vec::new()]).unwrap()),
+            SatResult::Unsatisfiable
+        ));
+        assert!(matches!(
+            Dpll.solve(&ClauseSet::new(Vec::new(), Vec::new()).unwrap()),
+            SatResult::Satisfiable(_)
+        ));
+
+        // A clause over a proposition the set does not have is rejected rather than solved
+        let literal = Literal {
+            proposition: 'p',
+            negated: false,
+        };
+        assert!(matches!(
+            ClauseSet::new(Vec::new(), vec![vec![literal]]),
+            Err(SatError::UnknownProposition('p'))
+        ));
+    }
+
+    #[test]
+    fn test_dpll_agrees_with_search() {
+        for formula in [
+            "(a | b) & (!a | c) & (!b | !c) & (a | c)",
+            "(a > b) & (b > c) & a & !c",
+            "!(a & b) & (a | b) & !(a > b)",
+            "((a | b) > c) & !(c & d) & (d | a)",
+            "(a | b | c) & (!a | !b) & (!b | !c) & (!a | !c) & !(a | c)",
+        ] {
+            let premise = Premise::parse_str(formula).unwrap();
+            let set = ClauseSet::from_premises(&[&premise], DEFAULT_MAX_CLAUSES).unwrap();
+            let satisfiable = premise.is_satisfiable();
+
+            match Dpll.solve(&set) {
+                SatResult::Satisfiable(model) => {
+                    assert!(satisfiable, "{}", formula);
+                    assert_eq!(premise.evaluate(&model), Some(true));
+                }
+                SatResult::Unsatisfiable => assert!(!satisfiable, "{}", formula),
+                SatResult::Failed(error) => panic!("{}", error),
+            }
+        }
+    }
+
+    #[test]
+    fn test_external_dimacs() {
+        let set = clauses(&["p > q", "!(q > p)"]);
+
+        match fixture("satisfiable.txt").solve(&set) {
+            SatResult::Satisfiable(model) => {
+                assert_eq!(model.get_value('p'), Some(false));
+                assert_eq!(model.get_value('q'), Some(true));
+            }
+            other => panic!("Expected Satisfiable, found {:?}", other),
+        }
+
+        assert!(matches!(
+            fixture("unsatisfiable.txt").solve(&set),
+            SatResult::Unsatisfiable
+        ));
+
+        // The solver cannot be run
+        assert!(matches!(
+            ExternalDimacs::new("/nonexistent/solver").solve(&set),
+            SatResult::Failed(SatError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn test_external_dimacs_malformed_output() {
+        let set = clauses(&["p > q", "!(q > p)"]);
+        let failure = |output| match fixture(output).solve(&set) {
+            SatResult::Failed(error) => error,
+            other => panic!("Expected Failed for {}, found {:?}", output, other),
+        };
+
+        assert!(matches!(failure("empty.txt"), SatError::NoAnswer));
+        assert!(matches!(failure("no_model.txt"), SatError::MissingModel));
+        assert!(matches!(failure("wrong_model.txt"), SatError::InvalidModel));
+        assert!(matches!(
+            failure("bad_literal.txt"),
+            SatError::MalformedOutput { line: 2, .. }
+        ));
+        assert!(matches!(
+            failure("out_of_range.txt"),
+            SatError::MalformedOutput { line: 3, .. }
+        ));
+
+        // Literals which conflict, or which come after an answer of UNSAT
+        let malformed = |output| parse_output(output, &set);
+        assert!(matches!(
+            malformed("SAT\n-1 2 1 0"),
+            SatResult::Failed(SatError::MalformedOutput { line: 2, .. })
+        ));
+        assert!(matches!(
+            malformed("UNSAT\n-1 2 0"),
+            SatResult::Failed(SatError::MalformedOutput { line: 2, .. })
+        ));
+        assert!(matches!(
+            malformed("s SATISFIABLE\nv -1 0\nv 2 0"),
+            SatResult::Failed(SatError::MalformedOutput { line: 3, .. })
+        ));
+    }
+}
@@ -0,0 +1,464 @@
+use std::fmt::{Display, Formatter};
+
+use crate::premises::{find_main_operator, negate_nodes, Operator};
+use crate::smtlib;
+use crate::Premise;
+use crate::PremiseNode;
+
+// Represents a problem found while reading an s-expression, along with the byte offset it was found at
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SexprError {
+    // The input held nothing to parse
+    Empty,
+    // A parenthesis was never closed, or was closed without being opened
+    UnbalancedParentheses {
+        position: usize,
+    },
+    // A form was started with a symbol that is not an operator, or an atom is not a proposition
+    UnknownSymbol {
+        symbol: String,
+        position: usize,
+    },
+    // An operator was given the wrong number of operands
+    WrongArity {
+        symbol: String,
+        found: usize,
+        position: usize,
+    },
+    // A form was started without a head symbol, such as "()" or "((a))"
+    MissingHead {
+        position: usize,
+    },
+    // More input was found after the first complete expression
+    TrailingInput {
+        position: usize,
+    },
+}
+
+impl Display for SexprError {
+    // Displays the error as a message for the user
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SexprError::Empty => write!(f, "Empty s-expression"),
+            SexprError::UnbalancedParentheses { position } => {
+                write!(f, "Unbalanced parenthesis at position {}", position)
+            }
+            SexprError::UnknownSymbol { symbol, position } => {
+                write!(f, "Unknown symbol '{}' at position {}", symbol, position)
+            }
+            SexprError::WrongArity {
+                symbol,
+                found,
+                position,
+            } => {
+                let expected = match symbol.as_str() {
+                    "not" => "exactly 1 operand",
+                    "implies" | "iff" => "exactly 2 operands",
+                    _ => "at least 2 operands",
+                };
+
+                write!(
+                    f,
+                    "'{}' at position {} takes {}, but {} were given",
+                    symbol, position, expected, found
+                )
+            }
+            SexprError::MissingHead { position } => {
+                write!(f, "Expected an operator at position {}", position)
+            }
+            SexprError::TrailingInput { position } => {
+                write!(
+                    f,
+                    "Unexpected input after the expression at position {}",
+                    position
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SexprError {}
+
+// The kind of s-expression a Premise is written as, which differ in how implications and
+// propositions are written
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub(crate) enum Dialect {
+    // The forms read by parse_sexpr, such as "(implies a b)"
+    Plain,
+    // SMT-LIB 2 terms, such as "(=> a b)", with every proposition written as a valid symbol
+    SmtLib,
+}
+
+// Represents a token of an s-expression along with its byte offset in the input
+#[derive(Debug, Eq, PartialEq)]
+enum Token<'a> {
+    Open(usize),
+    Close(usize),
+    Atom(&'a str, usize),
+}
+
+impl Premise {
+    // Converts the Premise to an s-expression, such as "(implies (and m (not b)) j)"
+    // Operator precedence decides how the flat nodes are grouped into forms
+    pub fn to_sexpr(&self) -> String {
+        nodes_to_sexpr(self.get_nodes(), Dialect::Plain)
+    }
+
+    // Creates a Premise from an s-expression using the forms "and", "or", "xor", "implies", "iff"
+    // and "not", where "and", "or" and "xor" accept two or more operands
+    // Compound operands are parenthesized, except within a chain of the same operator
+    pub fn parse_sexpr(sexpr_string: &str) -> Result<Self, SexprError> {
+        let tokens = tokenize(sexpr_string);
+        let mut position = 0;
+
+        if tokens.is_empty() {
+            return Err(SexprError::Empty);
+        }
+
+        let nodes = parse_expression(&tokens, &mut position)?;
+
+        match tokens.get(position) {
+            Some(Token::Close(offset)) => {
+                Err(SexprError::UnbalancedParentheses { position: *offset })
+            }
+            Some(Token::Open(offset)) | Some(Token::Atom(_, offset)) => {
+                Err(SexprError::TrailingInput { position: *offset })
+            }
+            None => Ok(Premise::new(nodes)),
+        }
+    }
+}
+
+// Converts a list of nodes to an s-expression in the dialect, splitting it at its main operator
+pub(crate) fn nodes_to_sexpr(nodes: &[PremiseNode], dialect: Dialect) -> String {
+    if let Some(i) = find_main_operator(nodes) {
+        let symbol = match nodes[i] {
+            PremiseNode::Operator(operator) => operator_symbol(operator, dialect),
+            _ => unreachable!(),
+        };
+
+        return format!(
+            "({} {} {})",
+            symbol,
+            nodes_to_sexpr(&nodes[..i], dialect),
+            nodes_to_sexpr(&nodes[i + 1..], dialect)
+        );
+    }
+
+    match nodes {
+        [] => "()".to_string(),
+        [PremiseNode::Negation, operand @ ..] => {
+            format!("(not {})", nodes_to_sexpr(operand, dialect))
+        }
+        [PremiseNode::Proposition(proposition)] => match dialect {
+            Dialect::Plain => proposition.to_string(),
+            Dialect::SmtLib => smtlib::symbol(*proposition),
+        },
+        [PremiseNode::TruthValue(value)] => value.to_string(),
+        [PremiseNode::Subpremise(subpremise)] => nodes_to_sexpr(subpremise.get_nodes(), dialect),
+        // Malformed premises are written as a form of their individual nodes
+        _ => format!(
+            "({})",
+            nodes
+                .iter()
+                .map(|node| nodes_to_sexpr(std::slice::from_ref(node), dialect))
+                .collect::<Vec<String>>()
+                .join(" ")
+        ),
+    }
+}
+
+// Returns the s-expression head symbol for an operator
+fn operator_symbol(operator: Operator, dialect: Dialect) -> &'static str {
+    match (operator, dialect) {
+        (Operator::And, _) => "and",
+        (Operator::Or, _) => "or",
+        (Operator::Implies, Dialect::Plain) => "implies",
+        (Operator::Implies, Dialect::SmtLib) => "=>",
+        (Operator::Iff, Dialect::Plain) => "iff",
+        (Operator::Iff, Dialect::SmtLib) => "=",
+        (Operator::Xor, _) => "xor",
+    }
+}
+
+// Splits the input into parentheses and atoms, ignoring all whitespace
+fn tokenize(sexpr_string: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut atom_start = None;
+
+    for (i, c) in sexpr_string.char_indices() {
+        let is_delimiter = c.is_whitespace() || c == '(' || c == ')';
+
+        if is_delimiter {
+            if let Some(start) = atom_start.take() {
+                tokens.push(Token::Atom(&sexpr_string[start..i], start));
+            }
+        } else if atom_start.is_none() {
+            atom_start = Some(i);
+        }
+
+        match c {
+            '(' => tokens.push(Token::Open(i)),
+            ')' => tokens.push(Token::Close(i)),
+            _ => (),
+        }
+    }
+
+    if let Some(start) = atom_start {
+        tokens.push(Token::Atom(&sexpr_string[start..], start));
+    }
+
+    tokens
+}
+
+// Parses a single expression starting at the given token, advancing past it
+fn parse_expression(
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<Vec<PremiseNode>, SexprError> {
+    match tokens.get(*position) {
+        Some(Token::Atom(atom, offset)) => {
+            *position += 1;
+            parse_atom(atom, *offset)
+        }
+        Some(Token::Open(open_offset)) => {
+            *position += 1;
+
+            let (symbol, symbol_offset) = match tokens.get(*position) {
+                Some(Token::Atom(symbol, offset)) => (*symbol, *offset),
+                Some(Token::Open(offset)) | Some(Token::Close(offset)) => {
+                    return Err(SexprError::MissingHead { position: *offset })
+                }
+                None => {
+                    return Err(SexprError::UnbalancedParentheses {
+                        position: *open_offset,
+                    })
+                }
+            };
+
+            *position += 1;
+
+            let mut operands = Vec::new();
+
+            loop {
+                match tokens.get(*position) {
+                    Some(Token::Close(_)) => break,
+                    Some(_) => operands.push(parse_expression(tokens, position)?),
+                    None => {
+                        return Err(SexprError::UnbalancedParentheses {
+                            position: *open_offset,
+                        })
+                    }
+                }
+            }
+
+            // Skip the closing parenthesis
+            *position += 1;
+
+            build_form(symbol, symbol_offset, operands)
+        }
+        Some(Token::Close(offset)) => Err(SexprError::UnbalancedParentheses { position: *offset }),
+        None => Err(SexprError::Empty),
+    }
+}
+
+// Parses an atom, which is either a single proposition or a truth value
+fn parse_atom(atom: &str, offset: usize) -> Result<Vec<PremiseNode>, SexprError> {
+    let mut chars = atom.chars();
+
+    match (atom, chars.next(), chars.next()) {
+        ("true" | "#t", _, _) => Ok(vec![PremiseNode::TruthValue(true)]),
+        ("false" | "#f", _, _) => Ok(vec![PremiseNode::TruthValue(false)]),
+        (_, Some(proposition @ 'a'..='z'), None) => Ok(vec![PremiseNode::Proposition(proposition)]),
+        _ => Err(SexprError::UnknownSymbol {
+            symbol: atom.to_string(),
+            position: offset,
+        }),
+    }
+}
+
+// Builds the nodes for a form from its head symbol and its already-parsed operands
+fn build_form(
+    symbol: &str,
+    offset: usize,
+    operands: Vec<Vec<PremiseNode>>,
+) -> Result<Vec<PremiseNode>, SexprError> {
+    let wrong_arity = || SexprError::WrongArity {
+        symbol: symbol.to_string(),
+        found: operands.len(),
+        position: offset,
+    };
+
+    let operator = match symbol {
+        "not" if operands.len() == 1 => return Ok(negate_nodes(&operands[0])),
+        "not" => return Err(wrong_arity()),
+        "implies" | "iff" if operands.len() != 2 => return Err(wrong_arity()),
+        "and" | "or" | "xor" if operands.len() < 2 => return Err(wrong_arity()),
+        "and" => Operator::And,
+        "or" => Operator::Or,
+        "implies" => Operator::Implies,
+        "iff" => Operator::Iff,
+        "xor" => Operator::Xor,
+        _ => {
+            return Err(SexprError::UnknownSymbol {
+                symbol: symbol.to_string(),
+                position: offset,
+            })
+        }
+    };
+
+    // Chains of "and", "or" and "xor" group from the left like their infix forms
+    let mut operands = operands.into_iter();
+    let first = operands.next().unwrap();
+
+    Ok(operands.fold(first, |left, right| combine(operator, left, right)))
+}
+
+// Joins two operands with a binary operator, wrapping either side in a subpremise if it is
+// compound, unless it continues a chain of the same operator in the direction it groups
+fn combine(
+    operator: Operator,
+    left: Vec<PremiseNode>,
+    right: Vec<PremiseNode>,
+) -> Vec<PremiseNode> {
+    let needs_parentheses = |nodes: &[PremiseNode], is_left: bool| {
+        let Some(i) = find_main_operator(nodes) else {
+            return false;
+        };
+
+        nodes[i] != PremiseNode::Operator(operator) || is_left == operator.is_right_associative()
+    };
+
+    let left = match needs_parentheses(&left, true) {
+        true => vec![PremiseNode::Subpremise(Premise::new(left))],
+        false => left,
+    };
+
+    let right = match needs_parentheses(&right, false) {
+        true => vec![PremiseNode::Subpremise(Premise::new(right))],
+        false => right,
+    };
+
+    [left, vec![PremiseNode::Operator(operator)], right].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_sexpr() {
+        let premise = Premise::parse_str("(m & !b) > j").unwrap();
+        assert_eq!(premise.to_sexpr(), "(implies (and m (not b)) j)");
+
+        let premise = Premise::parse_str("a ∧ b ∨ (c → d)").unwrap();
+        assert_eq!(premise.to_sexpr(), "(or (and a b) (implies c d))");
+
+        let premise = Premise::parse_str("a > b > c").unwrap();
+        assert_eq!(premise.to_sexpr(), "(implies a (implies b c))");
+
+        let premise = Premise::parse_str("!!(a | b)").unwrap();
+        assert_eq!(premise.to_sexpr(), "(not (not (or a b)))");
+    }
+
+    #[test]
+    fn test_parse_sexpr() {
+        let premise = Premise::parse_sexpr("  ( implies\n\t(and m (not b))   j )").unwrap();
+        assert_eq!(premise, Premise::parse_str("(m & !b) > j").unwrap());
+
+        let premise = Premise::parse_sexpr("(and a b c)").unwrap();
+        assert_eq!(premise, Premise::parse_str("a & b & c").unwrap());
+
+        let premise = Premise::parse_sexpr("(and a (and b c))").unwrap();
+        assert_eq!(premise, Premise::parse_str("a & (b & c)").unwrap());
+
+        let premise = Premise::parse_sexpr("(or #t false)").unwrap();
+        assert_eq!(premise.to_string(), "TRUE ∨ FALSE");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let formulas = [
+            "a",
+            "!a",
+            "(m & !b) > j",
+            "(a ∧ b) ∨ (c → d)",
+            "a > b > c",
+            "(a > b) > c",
+            "!(a | b) & c",
+            "a & (b | c) & !!d",
+            "(a ^ b ^ c) <> !d",
+        ];
+
+        for formula in formulas {
+            let premise = Premise::parse_str(formula).unwrap();
+            let sexpr = premise.to_sexpr();
+            let parsed = Premise::parse_sexpr(&sexpr).unwrap();
+
+            assert_eq!(parsed, premise, "{} => {}", formula, sexpr);
+            assert_eq!(parsed.to_sexpr(), sexpr);
+        }
+
+        // Grouping left implicit by precedence is made explicit, but the s-expression stays the same
+        let premise = Premise::parse_str("a & b | c").unwrap();
+        let parsed = Premise::parse_sexpr(&premise.to_sexpr()).unwrap();
+        assert_eq!(parsed.to_string(), "(a ∧ b) ∨ c");
+        assert_eq!(parsed.to_sexpr(), premise.to_sexpr());
+    }
+
+    #[test]
+    fn test_parse_sexpr_errors() {
+        assert_eq!(Premise::parse_sexpr("  "), Err(SexprError::Empty));
+        assert_eq!(
+            Premise::parse_sexpr("(and a b"),
+            Err(SexprError::UnbalancedParentheses { position: 0 })
+        );
+        assert_eq!(
+            Premise::parse_sexpr("(and a b))"),
+            Err(SexprError::UnbalancedParentheses { position: 9 })
+        );
+        assert_eq!(
+            Premise::parse_sexpr("(or a (nand b c))"),
+            Err(SexprError::UnknownSymbol {
+                symbol: "nand".to_string(),
+                position: 7
+            })
+        );
+        assert_eq!(
+            Premise::parse_sexpr("(and a Q)"),
+            Err(SexprError::UnknownSymbol {
+                symbol: "Q".to_string(),
+                position: 7
+            })
+        );
+        assert_eq!(
+            Premise::parse_sexpr("(not a b)"),
+            Err(SexprError::WrongArity {
+                symbol: "not".to_string(),
+                found: 2,
+                position: 1
+            })
+        );
+        assert_eq!(
+            Premise::parse_sexpr("(implies a)"),
+            Err(SexprError::WrongArity {
+                symbol: "implies".to_string(),
+                found: 1,
+                position: 1
+            })
+        );
+        assert_eq!(
+            Premise::parse_sexpr("(() a)"),
+            Err(SexprError::MissingHead { position: 1 })
+        );
+        assert_eq!(
+            Premise::parse_sexpr("a b"),
+            Err(SexprError::TrailingInput { position: 2 })
+        );
+
+        assert_eq!(
+            Premise::parse_sexpr("(not a b)").unwrap_err().to_string(),
+            "'not' at position 1 takes exactly 1 operand, but 2 were given"
+        );
+    }
+}
@@ -0,0 +1,177 @@
+// Writes a Deduction as an SMT-LIB 2 script, so its verdict can be checked independently by a
+// solver such as z3, which answers unsat exactly when the premises entail the conclusion
+
+use crate::sexpr::{nodes_to_sexpr, Dialect};
+use crate::Deduction;
+use crate::Premise;
+
+impl Deduction {
+    // Converts the Deduction to an SMT-LIB 2 script which declares every proposition, asserts
+    // every premise left on the stack, every value known so far and the negation of the
+    // conclusion, then checks satisfiability
+    // Without a conclusion the script checks whether the premises are consistent instead
+    pub fn to_smtlib(&self) -> String {
+        self.render_smtlib(false)
+    }
+
+    // Converts the Deduction to an SMT-LIB 2 script like to_smtlib, which also asks for the model
+    // so a counterexample can be compared with the one found by find_counterexample
+    pub fn to_smtlib_with_model(&self) -> String {
+        self.render_smtlib(true)
+    }
+
+    fn render_smtlib(&self, get_model: bool) -> String {
+        let mut script = String::new();
+
+        if get_model {
+            script.push_str("(set-option :produce-models true)\n");
+        }
+        script.push_str("(set-logic QF_UF)\n");
+
+        for proposition in self.get_propositions() {
+            script.push_str(&format!("(declare-const {} Bool)\n", symbol(proposition)));
+        }
+
+        for (_, premise) in self.get_labeled_premises() {
+            script.push_str(&format!("(assert {})\n", premise.to_smtlib()));
+        }
+
+        for (proposition, value) in self.get_values().iter() {
+            match value {
+                Some(true) => script.push_str(&format!("(assert {})\n", symbol(proposition))),
+                Some(false) => {
+                    script.push_str(&format!("(assert (not {}))\n", symbol(proposition)))
+                }
+                None => (),
+            }
+        }
+
+        if let Some(conclusion) = self.get_conclusion() {
+            script.push_str(&format!("(assert (not {}))\n", conclusion.to_smtlib()));
+        }
+
+        script.push_str("(check-sat)\n");
+        if get_model {
+            script.push_str("(get-model)\n");
+        }
+
+        script
+    }
+}
+
+impl Premise {
+    // Converts the Premise to an SMT-LIB 2 term, such as "(=> (and m (not b)) j)"
+    pub fn to_smtlib(&self) -> String {
+        nodes_to_sexpr(self.get_nodes(), Dialect::SmtLib)
+    }
+}
+
+// Returns the SMT-LIB symbol for the proposition
+// Letters are simple symbols, and anything else is quoted between bars, which cannot hold a bar
+// or a backslash, so those are written by their code point instead
+pub(crate) fn symbol(proposition: char) -> String {
+    match proposition {
+        _ if proposition.is_ascii_alphabetic() => proposition.to_string(),
+        '|' | '\\' => format!("|u{:04x}|", proposition as u32),
+        _ => format!("|{}|", proposition),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Checks that every parenthesis in the script is closed, and that each line is one command
+    fn assert_balanced(script: &str) {
+        for line in script.lines() {
+            let mut depth = 0;
+
+            for c in line.chars() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => (),
+                }
+                assert!(depth >= 0, "{} closes more than it opens", line);
+            }
+
+            assert_eq!(depth, 0, "{} is not closed", line);
+            assert!(line.starts_with('(') && line.ends_with(')'));
+        }
+    }
+
+    #[test]
+    fn test_to_smtlib() {
+        let mut deduction = Deduction::from_strs(vec!["p > q", "!(q & !r)"]).unwrap();
+        deduction.set_conclusion(Premise::parse_str("r").unwrap());
+
+        assert_eq!(
+            deduction.to_smtlib(),
+            "(set-logic QF_UF)\n\
+             (declare-const p Bool)\n\
+             (declare-const q Bool)\n\
+             (declare-const r Bool)\n\
+             (assert (=> p q))\n\
+             (assert (not (and q (not r))))\n\
+             (assert (not r))\n\
+             (check-sat)\n"
+        );
+
+        // Values found by solving are asserted in place of the premises they came from
+        let mut deduction = Deduction::from_strs(vec!["p", "p > !q"]).unwrap();
+        deduction.solve();
+        let script = deduction.to_smtlib_with_model();
+
+        assert!(script.starts_with("(set-option :produce-models true)\n"));
+        assert!(script.contains("(assert p)\n(assert (not q))\n"));
+        assert!(script.ends_with("(check-sat)\n(get-model)\n"));
+        assert_balanced(&script);
+    }
+
+    #[test]
+    fn test_example_argument() {
+        // The argument worked through in main.rs
+        let deduction =
+            Deduction::from_strs(vec!["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"])
+                .unwrap();
+        let script = deduction.to_smtlib();
+
+        assert_eq!(script.matches("(assert ").count(), 5);
+        assert!(script.contains("(assert (=> (and m (not b)) j))\n"));
+        assert!(script.ends_with("(check-sat)\n"));
+        assert_balanced(&script);
+
+        // Each operator is its own term, grouped as the premise is parsed
+        for (formula, expected) in [
+            ("(m & b) > j", "(=> (and m b) j)"),
+            ("m & (b > j)", "(and m (=> b j))"),
+            ("a & (b & c)", "(and a (and b c))"),
+            ("a & b & c", "(and (and a b) c)"),
+            ("a > b > c", "(=> a (=> b c))"),
+        ] {
+            assert_eq!(Premise::parse_str(formula).unwrap().to_smtlib(), expected);
+        }
+    }
+
+    #[test]
+    fn test_balanced() {
+        for formula in [
+            "!!!(a | !(b > !!c))",
+            "a & (b | (c & (d > e)))",
+            "p > q > r",
+            "p | !p",
+        ] {
+            let mut deduction = Deduction::from_strs(vec![formula]).unwrap();
+            deduction.set_conclusion(Premise::parse_str(formula).unwrap());
+
+            assert_balanced(&deduction.to_smtlib_with_model());
+        }
+    }
+
+    #[test]
+    fn test_symbol() {
+        assert_eq!(symbol('p'), "p");
+        assert_eq!(symbol('ψ'), "|ψ|");
+        assert_eq!(symbol('|'), "|u007c|");
+    }
+}
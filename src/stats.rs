@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Result};
+use std::time::Duration;
+
+use crate::premises::EvaluationRule;
+
+// Summarizes a solve, used to compare solving strategies
+#[derive(Debug, Default, Clone)]
+pub struct SolveStats {
+    // The number of passes made over the premise stack, including the final one which changed nothing
+    pub passes: usize,
+    // The total number of substitution and evaluation steps
+    pub steps: usize,
+    pub substitutions: usize,
+    // The number of evaluation steps which used each rule
+    pub rewrites: BTreeMap<EvaluationRule, usize>,
+    // The number of premises removed from the stack after being resolved
+    pub premises_removed: usize,
+    pub peak_stack_size: usize,
+    // The total complexity of the premises on the stack before the solve and after it
+    pub initial_complexity: u32,
+    pub final_complexity: u32,
+    pub duration: Duration,
+}
+
+impl SolveStats {
+    // Returns the total number of evaluation steps across all rules
+    pub fn get_num_evaluations(&self) -> usize {
+        self.rewrites.values().sum()
+    }
+}
+
+impl Display for SolveStats {
+    // Displays the statistics as a small two-column table
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        writeln!(f, "{:<20}{:>8}", "Passes", self.passes)?;
+        writeln!(f, "{:<20}{:>8}", "Steps", self.steps)?;
+        writeln!(f, "{:<20}{:>8}", "  Substitutions", self.substitutions)?;
+        writeln!(
+            f,
+            "{:<20}{:>8}",
+            "  Evaluations",
+            self.get_num_evaluations()
+        )?;
+
+        for (rule, count) in &self.rewrites {
+            writeln!(f, "{:<20}{:>8}", format!("    {}", rule), count)?;
+        }
+
+        writeln!(f, "{:<20}{:>8}", "Premises removed", self.premises_removed)?;
+        writeln!(f, "{:<20}{:>8}", "Peak stack size", self.peak_stack_size)?;
+        writeln!(
+            f,
+            "{:<20}{:>8}",
+            "Complexity",
+            format!("{} -> {}", self.initial_complexity, self.final_complexity)
+        )?;
+        writeln!(f, "{:<20}{:>8}", "Duration", format!("{:?}", self.duration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Deduction;
+
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let mut deduction =
+            Deduction::from_strs(vec!["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"])
+                .unwrap();
+        let stats = deduction.solve().stats();
+
+        assert_eq!(stats.passes, 10);
+        assert_eq!(stats.steps, 12);
+        assert_eq!(stats.substitutions, 5);
+        assert_eq!(stats.get_num_evaluations(), 7);
+        assert_eq!(stats.rewrites[&EvaluationRule::Implication], 4);
+        assert_eq!(stats.rewrites[&EvaluationRule::Disjunction], 1);
+        assert_eq!(stats.rewrites[&EvaluationRule::Conjunction], 1);
+        assert_eq!(stats.rewrites[&EvaluationRule::Negation], 1);
+        assert_eq!(stats.premises_removed, 5);
+        assert_eq!(stats.peak_stack_size, 5);
+        assert_eq!(stats.initial_complexity, 31);
+        assert_eq!(stats.final_complexity, 0);
+    }
+
+    #[test]
+    fn test_display() {
+        let stats = SolveStats {
+            passes: 3,
+            steps: 2,
+            substitutions: 1,
+            rewrites: BTreeMap::from([(EvaluationRule::Implication, 1)]),
+            premises_removed: 1,
+            peak_stack_size: 2,
+            initial_complexity: 17,
+            final_complexity: 5,
+            duration: Duration::from_millis(2),
+        };
+
+        let expected = "\
+Passes                     3
+Steps                      2
+  Substitutions            1
+  Evaluations              1
+    implication            1
+Premises removed           1
+Peak stack size            2
+Complexity           17 -> 5
+Duration                 2ms
+";
+
+        assert_eq!(stats.to_string(), expected);
+    }
+}
@@ -0,0 +1,383 @@
+use std::ops::Range;
+
+use crate::premises::{find_main_operator, negate_nodes, wrap_operand, Operator};
+use crate::Premise;
+use crate::PremiseNode;
+
+// Stores the first pair of subformulas where two premises differ in structure
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct StructuralDifference {
+    pub first: Premise,
+    pub second: Premise,
+}
+
+// Identifies a part of a premise, where the path holds the index of each subpremise to go into
+// from the top, as in pretty_tree, and the range is that of the part's nodes in the last one
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Location {
+    pub path: Vec<usize>,
+    pub range: Range<usize>,
+}
+
+// Represents a premise as a tree, with redundant parentheses removed and chains of ∧ or ∨
+// flattened, so premises which only differ in how they are grouped compare as equal
+// The order of operands and double negations are still part of the structure
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum Shape {
+    Proposition(char),
+    TruthValue(bool),
+    Not(Box<Shape>),
+    Operation(Operator, Vec<Shape>),
+    // Nodes which do not form a valid premise are kept as they are
+    Malformed(Vec<PremiseNode>),
+}
+
+impl Premise {
+    // Finds the first subformulas, from the left, where the normalized trees of the premises differ,
+    // or None if they have the same structure
+    pub fn find_structural_difference(&self, other: &Premise) -> Option<StructuralDifference> {
+        Shape::from_nodes(self.get_nodes()).find_difference(&Shape::from_nodes(other.get_nodes()))
+    }
+
+    // Checks whether the premises have the same normalized tree
+    pub fn is_structurally_equal(&self, other: &Premise) -> bool {
+        self.find_structural_difference(other).is_none()
+    }
+
+    // Checks whether the premises have the same structure when the order and grouping of the
+    // operands of ∧ and ∨ are ignored, like comparing their canonicalized forms
+    pub fn equivalent_structure(&self, other: &Premise) -> bool {
+        Shape::from_nodes(self.get_nodes()).canonicalize()
+            == Shape::from_nodes(other.get_nodes()).canonicalize()
+    }
+
+    // Checks whether the proposition appears anywhere in the Premise
+    pub fn contains_proposition(&self, proposition: char) -> bool {
+        self.iter_nodes()
+            .any(|node| *node == PremiseNode::Proposition(proposition))
+    }
+
+    // Finds the first subformula with the same structure as the pattern, from the outside in and
+    // then from the left, where the subformulas are the operands of every operator and negation
+    // Compound patterns match whether or not the subformula is in parentheses
+    pub fn find(&self, pattern: &Premise) -> Option<Location> {
+        let pattern = Shape::from_nodes(pattern.get_nodes());
+
+        find_subformula(self.get_nodes(), 0, &mut Vec::new(), &pattern)
+    }
+
+    // Checks whether the Premise has a subformula with the same structure as the pattern
+    pub fn contains(&self, pattern: &Premise) -> bool {
+        self.find(pattern).is_some()
+    }
+
+    // Flattens chains of ∧ or ∨ and sorts the operands of both, so premises which only differ in
+    // the order of commutative operands become equal
+    // Operands are sorted by how they are displayed, and every compound operand is parenthesized
+    pub fn canonicalize(&mut self) {
+        self.set_nodes(
+            Shape::from_nodes(self.get_nodes())
+                .canonicalize()
+                .to_nodes(),
+        );
+    }
+
+    // Returns a canonicalized copy of the Premise, leaving the Premise itself unchanged
+    pub fn canonicalized(&self) -> Premise {
+        Premise::new(
+            Shape::from_nodes(self.get_nodes())
+                .canonicalize()
+                .to_nodes(),
+        )
+    }
+}
+
+impl Shape {
+    // Builds the tree of the given nodes, splitting them at their main operator
+    fn from_nodes(nodes: &[PremiseNode]) -> Self {
+        if let Some(i) = find_main_operator(nodes) {
+            let PremiseNode::Operator(operator) = nodes[i] else {
+                unreachable!()
+            };
+
+            let mut operands = Vec::new();
+
+            for side in [&nodes[..i], &nodes[i + 1..]] {
+                match Shape::from_nodes(side) {
+                    // → is not associative, so only ∧ and ∨ chains are flattened
+                    Shape::Operation(inner, inner_operands)
+                        if inner == operator && operator.is_associative() =>
+                    {
+                        operands.extend(inner_operands)
+                    }
+                    shape => operands.push(shape),
+                }
+            }
+
+            return Shape::Operation(operator, operands);
+        }
+
+        match nodes {
+            [PremiseNode::Negation, operand @ ..] => {
+                Shape::Not(Box::new(Shape::from_nodes(operand)))
+            }
+            [PremiseNode::Proposition(proposition)] => Shape::Proposition(*proposition),
+            [PremiseNode::TruthValue(value)] => Shape::TruthValue(*value),
+            [PremiseNode::Subpremise(subpremise)] => Shape::from_nodes(subpremise.get_nodes()),
+            _ => Shape::Malformed(nodes.to_vec()),
+        }
+    }
+
+    // Converts the tree back to nodes, parenthesizing every compound operand
+    fn to_nodes(&self) -> Vec<PremiseNode> {
+        match self {
+            Shape::Proposition(proposition) => vec![PremiseNode::Proposition(*proposition)],
+            Shape::TruthValue(value) => vec![PremiseNode::TruthValue(*value)],
+            Shape::Not(operand) => negate_nodes(&operand.to_nodes()),
+            Shape::Operation(operator, operands) => {
+                let mut nodes = Vec::new();
+
+                for (i, operand) in operands.iter().enumerate() {
+                    if i > 0 {
+                        nodes.push(PremiseNode::Operator(*operator));
+                    }
+                    nodes.extend(wrap_operand(operand.to_nodes()));
+                }
+
+                nodes
+            }
+            Shape::Malformed(nodes) => nodes.clone(),
+        }
+    }
+
+    // Sorts the operands of every ∧ and ∨ in the tree, leaving the order of → operands as it is
+    fn canonicalize(self) -> Self {
+        match self {
+            Shape::Not(operand) => Shape::Not(Box::new(operand.canonicalize())),
+            Shape::Operation(operator, operands) => {
+                let mut operands: Vec<Shape> =
+                    operands.into_iter().map(Shape::canonicalize).collect();
+
+                if operator.is_commutative() {
+                    operands
+                        .sort_by_cached_key(|operand| Premise::new(operand.to_nodes()).to_string());
+                }
+
+                Shape::Operation(operator, operands)
+            }
+            shape => shape,
+        }
+    }
+
+    // Finds the first pair of subtrees which differ, descending while both trees have the same
+    // operator and number of operands
+    fn find_difference(&self, other: &Shape) -> Option<StructuralDifference> {
+        if self == other {
+            return None;
+        }
+
+        match (self, other) {
+            (Shape::Not(operand), Shape::Not(other_operand)) => {
+                operand.find_difference(other_operand)
+            }
+            (Shape::Operation(operator, operands), Shape::Operation(other_operator, others))
+                if operator == other_operator && operands.len() == others.len() =>
+            {
+                operands
+                    .iter()
+                    .zip(others)
+                    .find_map(|(operand, other)| operand.find_difference(other))
+            }
+            _ => Some(StructuralDifference {
+                first: Premise::new(self.to_nodes()),
+                second: Premise::new(other.to_nodes()),
+            }),
+        }
+    }
+}
+
+// Finds the first subformula of the nodes matching the pattern, where the nodes start at the
+// offset in the premise at the path
+fn find_subformula(
+    nodes: &[PremiseNode],
+    offset: usize,
+    path: &mut Vec<usize>,
+    pattern: &Shape,
+) -> Option<Location> {
+    if nodes.is_empty() {
+        return None;
+    }
+
+    if Shape::from_nodes(nodes) == *pattern {
+        return Some(Location {
+            path: path.clone(),
+            range: offset..offset + nodes.len(),
+        });
+    }
+
+    if let Some(i) = find_main_operator(nodes) {
+        return find_subformula(&nodes[..i], offset, path, pattern)
+            .or_else(|| find_subformula(&nodes[i + 1..], offset + i + 1, path, pattern));
+    }
+
+    match nodes {
+        [PremiseNode::Negation, operand @ ..] => {
+            find_subformula(operand, offset + 1, path, pattern)
+        }
+        [PremiseNode::Subpremise(subpremise)] => {
+            path.push(offset);
+            let found = find_subformula(subpremise.get_nodes(), 0, path, pattern);
+            path.pop();
+
+            found
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_structural_difference() {
+        // Redundant parentheses and the grouping of a chain do not matter
+        assert!(Premise::parse_str("((a & b) & c) | !(d)")
+            .unwrap()
+            .is_structurally_equal(&Premise::parse_str("a & (b & c) | !d").unwrap()));
+
+        // Grouping does matter for →, which is not associative
+        assert!(!Premise::parse_str("(a > b) > c")
+            .unwrap()
+            .is_structurally_equal(&Premise::parse_str("a > b > c").unwrap()));
+
+        // The first difference is the innermost one, from the left
+        assert_eq!(
+            Premise::parse_str("(p & q) > (r | s)")
+                .unwrap()
+                .find_structural_difference(&Premise::parse_str("(p & !q) > (s | r)").unwrap()),
+            Some(StructuralDifference {
+                first: Premise::parse_str("q").unwrap(),
+                second: Premise::parse_str("!q").unwrap(),
+            })
+        );
+
+        // Operations with a different number of operands differ as a whole
+        assert_eq!(
+            Premise::parse_str("a | b | c")
+                .unwrap()
+                .find_structural_difference(&Premise::parse_str("a | b").unwrap()),
+            Some(StructuralDifference {
+                first: Premise::parse_str("a | b | c").unwrap(),
+                second: Premise::parse_str("a | b").unwrap(),
+            })
+        );
+
+        // Semantically equivalent premises can still differ in structure
+        assert!(!Premise::parse_str("p & q")
+            .unwrap()
+            .is_structurally_equal(&Premise::parse_str("q & p").unwrap()));
+    }
+
+    #[test]
+    fn test_equivalent_structure() {
+        let equivalent = |first: &str, second: &str| {
+            Premise::parse_str(first)
+                .unwrap()
+                .equivalent_structure(&Premise::parse_str(second).unwrap())
+        };
+
+        assert!(equivalent("q | p", "p | q"));
+        assert!(equivalent("(a & b) & c", "c & (b & a)"));
+        assert!(equivalent("!(a | b & c) > d", "!((c & b) | a) > d"));
+        assert!(equivalent("(p | q) & (r | s)", "(s | r) & (q | p)"));
+
+        // ∧ and ∨ stay distinct, as do negations and the sides of →
+        assert!(!equivalent("a & b", "a | b"));
+        assert!(!equivalent("a & b | c", "a & (b | c)"));
+        assert!(!equivalent("!p | q", "p | !q"));
+        assert!(!equivalent("!!p", "p"));
+        assert!(!equivalent("p > q", "q > p"));
+        assert!(!equivalent("a | a | b", "a | b"));
+    }
+
+    #[test]
+    fn test_find() {
+        let premise = Premise::parse_str("(m & !b) > j | !(k > (l & p))").unwrap();
+
+        assert!(premise.contains_proposition('l'));
+        assert!(!premise.contains_proposition('q'));
+
+        // The whole left side of the →, which is in parentheses
+        assert_eq!(
+            premise.find(&Premise::parse_str("m & !b").unwrap()),
+            Some(Location {
+                path: vec![],
+                range: 0..1
+            })
+        );
+        assert_eq!(
+            premise.find(&Premise::parse_str("j | !(k > (l & p))").unwrap()),
+            Some(Location {
+                path: vec![],
+                range: 2..6
+            })
+        );
+
+        // Two subpremises deep
+        assert_eq!(premise.find(&Premise::parse_str("(p & l)").unwrap()), None);
+        assert_eq!(
+            premise.find(&Premise::parse_str("((l & p))").unwrap()),
+            Some(Location {
+                path: vec![5],
+                range: 2..3
+            })
+        );
+        assert_eq!(
+            premise.find(&Premise::parse_str("!b").unwrap()),
+            Some(Location {
+                path: vec![0],
+                range: 2..4
+            })
+        );
+
+        // Only whole subformulas match, so the ∧ does not take j as its operand
+        assert!(!premise.contains(&Premise::parse_str("b > j").unwrap()));
+        assert!(!premise.contains(&Premise::parse_str("j | !k").unwrap()));
+        assert!(!Premise::parse_str("a & b | c")
+            .unwrap()
+            .contains(&Premise::parse_str("b | c").unwrap()));
+        assert!(Premise::parse_str("a & b | c")
+            .unwrap()
+            .contains(&Premise::parse_str("a & b").unwrap()));
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let mut premise = Premise::parse_str("b & a").unwrap();
+        premise.canonicalize();
+        assert_eq!(premise, Premise::parse_str("a & b").unwrap());
+
+        assert_eq!(
+            Premise::parse_str("(c | a) | b").unwrap().canonicalized(),
+            Premise::parse_str("a | b | c").unwrap()
+        );
+
+        // Operands are sorted inside out, but the sides of → stay where they are
+        assert_eq!(
+            Premise::parse_str("z > !(b | a) | (d & c)")
+                .unwrap()
+                .canonicalized(),
+            Premise::parse_str("z > ((c & d) | !(a | b))").unwrap()
+        );
+
+        assert!(Premise::parse_str("q & (s | r) & p")
+            .unwrap()
+            .canonicalized()
+            .is_structurally_equal(
+                &Premise::parse_str("p & q & (r | s)")
+                    .unwrap()
+                    .canonicalized()
+            ));
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use crate::ast::BinaryExpr;
+use crate::premises::Operator;
+use crate::Premise;
+
+// Identifies a proposition by its position in a SymbolTable, so evaluating a premise under many
+// assignments indexes a slice of values instead of hashing every proposition it reads
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub(crate) struct SymbolId(u16);
+
+// Maps each proposition to a SymbolId, numbered in the order the propositions were interned
+#[derive(Debug, Default, Clone)]
+pub(crate) struct SymbolTable {
+    ids: HashMap<char, SymbolId>,
+}
+
+// Stores a premise as a program over SymbolIds, which evaluates without cloning or simplifying
+// the premise
+// Each instruction computes one subformula from the results of earlier ones, and a subformula
+// which appears more than once is only given one instruction, so it is evaluated once
+#[derive(Debug, Clone)]
+pub(crate) struct CompiledPremise {
+    instructions: Vec<Instruction>,
+}
+
+// Represents one subformula of a compiled premise, whose operands are given by the index of the
+// instruction which computes them
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+enum Instruction {
+    Load(SymbolId),
+    Const(bool),
+    Not(usize),
+    Binary(Operator, usize, usize),
+}
+
+impl SymbolId {
+    // Returns the position of the proposition in its SymbolTable
+    pub(crate) fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl SymbolTable {
+    // Returns the SymbolId of the proposition, giving it the next one if it is new
+    // Returns None if the table already holds as many propositions as a SymbolId can number
+    pub(crate) fn intern(&mut self, name: char) -> Option<SymbolId> {
+        if let Some(id) = self.ids.get(&name) {
+            return Some(*id);
+        }
+
+        let id = SymbolId(u16::try_from(self.ids.len()).ok()?);
+        self.ids.insert(name, id);
+
+        Some(id)
+    }
+
+    // Returns how many propositions have been interned
+    pub(crate) fn len(&self) -> usize {
+        self.ids.len()
+    }
+}
+
+impl CompiledPremise {
+    // Compiles the premise, interning its propositions in the table
+    // Returns None if the premise is not valid or the table runs out of SymbolIds
+    pub(crate) fn compile(premise: &Premise, symbols: &mut SymbolTable) -> Option<Self> {
+        let mut instructions = Vec::new();
+        compile_expression(
+            &premise.to_binary().ok()?,
+            symbols,
+            &mut instructions,
+            &mut HashMap::new(),
+        )?;
+
+        Some(Self { instructions })
+    }
+
+    // Finds the truth value of the premise from the value of each proposition, or None if it
+    // depends on a proposition whose value is unknown
+    // Operators whose value is decided by one known operand do not need the other, as in simplify
+    // The premise's value is the result of the last instruction
+    pub(crate) fn evaluate(&self, value: impl Fn(SymbolId) -> Option<bool>) -> Option<bool> {
+        let mut results: Vec<Option<bool>> = Vec::with_capacity(self.instructions.len());
+
+        for instruction in &self.instructions {
+            let result = match *instruction {
+                Instruction::Load(id) => value(id),
+                Instruction::Const(constant) => Some(constant),
+                Instruction::Not(operand) => results[operand].map(|operand| !operand),
+                Instruction::Binary(operator, left, right) => {
+                    operator.apply_partial(results[left], results[right])
+                }
+            };
+
+            results.push(result);
+        }
+
+        results.pop()?
+    }
+}
+
+// Adds the instructions which evaluate the expression, operands first, returning the index of the
+// one which gives its value
+// An instruction which is already in the program is reused, found by its place in the index
+fn compile_expression(
+    expression: &BinaryExpr,
+    symbols: &mut SymbolTable,
+    instructions: &mut Vec<Instruction>,
+    index: &mut HashMap<Instruction, usize>,
+) -> Option<usize> {
+    let instruction = match expression {
+        BinaryExpr::Atom(proposition) => Instruction::Load(symbols.intern(*proposition)?),
+        BinaryExpr::Const(value) => Instruction::Const(*value),
+        BinaryExpr::Not(operand) => {
+            Instruction::Not(compile_expression(operand, symbols, instructions, index)?)
+        }
+        BinaryExpr::Binary(operator, left, right) => Instruction::Binary(
+            *operator,
+            compile_expression(left, symbols, instructions, index)?,
+            compile_expression(right, symbols, instructions, index)?,
+        ),
+    };
+
+    Some(*index.entry(instruction).or_insert_with(|| {
+        instructions.push(instruction);
+        instructions.len() - 1
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ValueMap;
+
+    #[test]
+    fn test_symbol_table() {
+        let mut symbols = SymbolTable::default();
+
+        let q = symbols.intern('q').unwrap();
+        let p = symbols.intern('p').unwrap();
+        assert_eq!(symbols.intern('q'), Some(q));
+        assert_eq!((q.index(), p.index()), (0, 1));
+        assert_eq!(symbols.len(), 2);
+    }
+
+    #[test]
+    fn test_shared_subformulas() {
+        let premise = Premise::parse_str("(a & b) | !(a & b) > (b & a)").unwrap();
+        let compiled = CompiledPremise::compile(&premise, &mut SymbolTable::default()).unwrap();
+
+        // a, b, a ∧ b, ¬(a ∧ b), the ∨, b ∧ a and the →, with a ∧ b only computed once
+        assert_eq!(compiled.instructions.len(), 7);
+        assert_eq!(compiled.evaluate(|_| Some(true)), Some(true));
+        assert_eq!(compiled.evaluate(|_| Some(false)), Some(false));
+    }
+
+    #[test]
+    fn test_compiled_matches_evaluate() {
+        for premise in [
+            "(m & !b) > j",
+            "a | b & c",
+            "a > b > c",
+            "!(p & q) | !!r",
+            "(a > (b | !c)) & (c > a)",
+        ] {
+            let premise = Premise::parse_str(premise).unwrap();
+            let names: Vec<char> = premise.propositions().into_iter().collect();
+
+            let mut symbols = SymbolTable::default();
+            for name in &names {
+                symbols.intern(*name);
+            }
+            let compiled = CompiledPremise::compile(&premise, &mut symbols).unwrap();
+
+            // Every proposition may be TRUE, FALSE or unknown, so partial values are checked too
+            for assignment in 0..3usize.pow(symbols.len() as u32) {
+                let values: Vec<Option<bool>> = (0..symbols.len())
+                    .map(|i| match assignment / 3usize.pow(i as u32) % 3 {
+                        0 => Some(true),
+                        1 => Some(false),
+                        _ => None,
+                    })
+                    .collect();
+
+                let mut proposition_values = ValueMap::default();
+                for (name, value) in names.iter().zip(&values) {
+                    proposition_values.set_value(*name, *value);
+                }
+
+                assert_eq!(
+                    compiled.evaluate(|id| values[id.index()]),
+                    premise.evaluate(&proposition_values),
+                    "{} under {:?}",
+                    premise,
+                    values
+                );
+            }
+        }
+    }
+}
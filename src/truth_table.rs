@@ -0,0 +1,876 @@
+use std::borrow::Borrow;
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Result};
+
+use crate::connective::ConnectiveExpr;
+use crate::notation::Notation;
+use crate::symbols::{CompiledPremise, SymbolTable};
+use crate::Deduction;
+use crate::Premise;
+use crate::ValueMap;
+
+// The most propositions a truth table is built for unless another limit is given,
+// as the number of rows doubles with each one
+pub const DEFAULT_MAX_VARIABLES: usize = 12;
+
+// Finds the value of a formula from the values of its propositions, given in a fixed order
+pub type Evaluator<'a> = Box<dyn FnMut(&[bool]) -> Option<bool> + 'a>;
+
+// Describes a formula a truth table can be built for
+// Premise is the usual one, while ConnectiveExpr allows formulas with custom connectives
+pub trait Formula: Clone + Display {
+    // Returns every proposition in the formula, in alphabetical order
+    fn propositions(&self) -> BTreeSet<char>;
+
+    // Finds the truth value of the formula, or None if it cannot be determined
+    fn evaluate(&self, proposition_values: &ValueMap) -> Option<bool>;
+
+    // Renders the formula in the given notation
+    fn render(&self, notation: Notation) -> String;
+
+    // Returns a function which finds the value of the formula from the values of the given
+    // propositions, in the same order, for evaluating it under many assignments
+    fn evaluator<'a>(&'a self, propositions: &[char]) -> Evaluator<'a> {
+        value_map_evaluator(self, propositions)
+    }
+
+    // Returns the rows of the formula's truth table one at a time, in the same order as a
+    // TruthTable, without building the whole table or limiting the number of propositions
+    fn truth_rows(&self) -> TruthRows<'_, Self> {
+        self.truth_rows_in(RowOrder::Standard)
+    }
+
+    // Returns the rows of the formula's truth table one at a time in the given order
+    fn truth_rows_in(&self, order: RowOrder) -> TruthRows<'_, Self> {
+        TruthRows::new(self, self.propositions().into_iter().collect(), order)
+    }
+
+    // Checks whether the formula is true under every assignment, stopping at the first row where
+    // it is not
+    fn is_tautology(&self) -> bool {
+        self.truth_rows().all(|row| row.result)
+    }
+
+    // Checks whether the formula is true under some assignment, stopping at the first row where
+    // it is
+    fn is_satisfiable(&self) -> bool {
+        self.truth_rows().any(|row| row.result)
+    }
+}
+
+// Stores the value of a formula under every assignment of its propositions
+#[derive(Debug, Clone)]
+pub struct TruthTable<F: Formula = Premise> {
+    formula: F,
+    propositions: Vec<char>,
+    rows: Vec<TruthRow>,
+}
+
+// Stores one assignment of the propositions, in the same order as the table's propositions,
+// along with the value of the formula under it
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct TruthRow {
+    values: Vec<bool>,
+    result: bool,
+}
+
+// Generates the rows of a truth table as they are needed, keeping only the current assignment
+pub struct TruthRows<'a, F: Formula = Premise> {
+    formula: &'a F,
+    propositions: Vec<char>,
+    evaluate: Evaluator<'a>,
+    order: RowOrder,
+    // The assignment of the next row, or None once every row has been returned
+    values: Option<Vec<bool>>,
+    // How many of the first propositions keep the values they started with
+    fixed: usize,
+}
+
+// Stores the value of every premise of an argument, and of its conclusion if it has one, under
+// every assignment of all their propositions, for checking the argument by the truth table method
+// The rows where every premise is TRUE are its critical rows, and the argument is valid when the
+// conclusion is TRUE in all of them
+#[derive(Debug, Clone)]
+pub struct ArgumentTable {
+    premises: Vec<Premise>,
+    conclusion: Option<Premise>,
+    propositions: Vec<char>,
+    rows: Vec<ArgumentRow>,
+}
+
+// Stores one assignment of the propositions of an argument along with the value of each premise
+// and of the conclusion under it
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ArgumentRow {
+    values: Vec<bool>,
+    premises: Vec<bool>,
+    conclusion: Option<bool>,
+}
+
+// Selects the order the rows of a truth table are generated in
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum RowOrder {
+    // Counting down from every proposition being TRUE, with the first proposition changing slowest
+    Standard,
+    // Starting from every proposition being TRUE, but only changing one proposition between rows,
+    // for callers which update what they know about the formula as each proposition changes
+    Gray,
+}
+
+// Selects which rows of a truth table are shown
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum RowFilter {
+    All,
+    Satisfying,
+    Falsifying,
+}
+
+// Represents a formula which has too many propositions to build a truth table for
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TooManyVariables {
+    pub found: usize,
+    pub limit: usize,
+}
+
+impl Display for TooManyVariables {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "The formula has {} propositions, which is more than the limit of {} for a truth table",
+            self.found, self.limit
+        )
+    }
+}
+
+impl std::error::Error for TooManyVariables {}
+
+impl Formula for Premise {
+    fn propositions(&self) -> BTreeSet<char> {
+        Premise::propositions(self)
+    }
+
+    fn evaluate(&self, proposition_values: &ValueMap) -> Option<bool> {
+        Premise::evaluate(self, proposition_values)
+    }
+
+    fn render(&self, notation: Notation) -> String {
+        Premise::render(self, notation)
+    }
+
+    // Compiles the Premise so each row reads its values by index, falling back to evaluate if it
+    // cannot be compiled
+    fn evaluator<'a>(&'a self, propositions: &[char]) -> Evaluator<'a> {
+        let mut symbols = SymbolTable::default();
+        for proposition in propositions {
+            symbols.intern(*proposition);
+        }
+
+        match CompiledPremise::compile(self, &mut symbols) {
+            Some(compiled) if symbols.len() == propositions.len() => {
+                Box::new(move |values| compiled.evaluate(|id| Some(values[id.index()])))
+            }
+            _ => value_map_evaluator(self, propositions),
+        }
+    }
+}
+
+impl Formula for ConnectiveExpr {
+    fn propositions(&self) -> BTreeSet<char> {
+        ConnectiveExpr::propositions(self)
+    }
+
+    fn evaluate(&self, proposition_values: &ValueMap) -> Option<bool> {
+        ConnectiveExpr::evaluate(self, proposition_values)
+    }
+
+    // Custom connectives only have one symbol, so the formula is displayed the same way in every
+    // notation
+    fn render(&self, _notation: Notation) -> String {
+        self.to_string()
+    }
+}
+
+impl<F: Formula> TruthTable<F> {
+    // Builds the truth table of a formula, with its propositions in alphabetical order and the
+    // rows starting from every proposition being TRUE, as is usual when writing them by hand
+    pub fn new(formula: &F, max_variables: usize) -> std::result::Result<Self, TooManyVariables> {
+        let propositions: Vec<char> = formula.propositions().into_iter().collect();
+
+        if propositions.len() > max_variables {
+            return Err(TooManyVariables {
+                found: propositions.len(),
+                limit: max_variables,
+            });
+        }
+
+        let rows = TruthRows::new(formula, propositions.clone(), RowOrder::Standard).collect();
+
+        Ok(Self {
+            formula: formula.clone(),
+            propositions,
+            rows,
+        })
+    }
+
+    // Returns the formula the table was built for
+    pub fn get_formula(&self) -> &F {
+        &self.formula
+    }
+
+    // Returns the propositions in the order of the table's columns
+    pub fn get_propositions(&self) -> &[char] {
+        &self.propositions
+    }
+
+    // Returns the rows which match the filter
+    pub fn get_rows(&self, filter: RowFilter) -> impl Iterator<Item = &TruthRow> {
+        self.rows.iter().filter(move |row| filter.matches(row))
+    }
+
+    // Renders the table as plain text with aligned columns, such as:
+    // p  q  p → q
+    // T  T  T
+    pub fn render_plain(&self, filter: RowFilter) -> String {
+        self.render_text(filter, Notation::Unicode)
+    }
+
+    // Renders the table like render_plain with the formula in the given notation
+    pub fn render_text(&self, filter: RowFilter, notation: Notation) -> String {
+        let mut table = String::new();
+
+        for proposition in &self.propositions {
+            table.push_str(&format!("{}  ", proposition));
+        }
+        table.push_str(&self.formula.render(notation));
+        table.push('\n');
+
+        for row in self.get_rows(filter) {
+            for value in &row.values {
+                table.push_str(&format!("{}  ", format_value(*value)));
+            }
+            table.push_str(format_value(row.result));
+            table.push('\n');
+        }
+
+        table
+    }
+
+    // Renders the table as a LaTeX tabular, with a rule between the propositions and the formula
+    pub fn render_latex(&self, filter: RowFilter) -> String {
+        let mut table = format!(
+            "\\begin{{tabular}}{{{}|c}}\n",
+            "c".repeat(self.propositions.len())
+        );
+
+        for proposition in &self.propositions {
+            table.push_str(&format!("${}$ & ", proposition));
+        }
+        table.push_str(&format!(
+            "${}$ \\\\\n\\hline\n",
+            self.formula.render(Notation::Latex)
+        ));
+
+        for row in self.get_rows(filter) {
+            for value in &row.values {
+                table.push_str(&format!("{} & ", format_value(*value)));
+            }
+            table.push_str(&format!("{} \\\\\n", format_value(row.result)));
+        }
+
+        table.push_str("\\end{tabular}\n");
+        table
+    }
+
+    // Renders the table as a markdown table
+    pub fn render_markdown(&self, filter: RowFilter) -> String {
+        render_markdown(&self.formula, &self.propositions, self.get_rows(filter))
+    }
+}
+
+impl<'a, F: Formula> TruthRows<'a, F> {
+    // Creates the rows of the formula's truth table over the given propositions, starting from
+    // every proposition being TRUE
+    fn new(formula: &'a F, propositions: Vec<char>, order: RowOrder) -> Self {
+        Self::with_prefix(formula, propositions, order, &[])
+    }
+
+    // Creates the rows like new, but only those where the first propositions have the values of
+    // the prefix, which are the rows of one part of the table if they are in the standard order
+    pub(crate) fn with_prefix(
+        formula: &'a F,
+        propositions: Vec<char>,
+        order: RowOrder,
+        prefix: &[bool],
+    ) -> Self {
+        let mut values = vec![true; propositions.len()];
+        values[..prefix.len()].copy_from_slice(prefix);
+
+        Self {
+            formula,
+            evaluate: formula.evaluator(&propositions),
+            values: Some(values),
+            propositions,
+            order,
+            fixed: prefix.len(),
+        }
+    }
+
+    // Returns the propositions in the order of each row's values
+    pub fn get_propositions(&self) -> &[char] {
+        &self.propositions
+    }
+
+    // Renders the remaining rows which match the filter as a markdown table, in the same way as
+    // TruthTable::render_markdown but without keeping the rows
+    pub fn render_markdown(self, filter: RowFilter) -> String {
+        let (formula, propositions) = (self.formula, self.propositions.clone());
+
+        render_markdown(
+            formula,
+            &propositions,
+            self.filter(|row| filter.matches(row)),
+        )
+    }
+}
+
+impl<F: Formula> Iterator for TruthRows<'_, F> {
+    type Item = TruthRow;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let values = self.values.as_mut()?;
+
+        let row = TruthRow {
+            values: values.clone(),
+            result: (self.evaluate)(values) == Some(true),
+        };
+
+        let advanced = match self.order {
+            RowOrder::Standard => next_standard(&mut values[self.fixed..]),
+            RowOrder::Gray => next_gray(&mut values[self.fixed..]),
+        };
+        if !advanced {
+            self.values = None;
+        }
+
+        Some(row)
+    }
+}
+
+impl RowFilter {
+    // Checks whether the row is shown by the filter
+    fn matches(self, row: &TruthRow) -> bool {
+        match self {
+            RowFilter::All => true,
+            RowFilter::Satisfying => row.result,
+            RowFilter::Falsifying => !row.result,
+        }
+    }
+}
+
+impl TruthRow {
+    // Returns the value of each proposition, in the order of the table's propositions
+    pub fn get_values(&self) -> &[bool] {
+        &self.values
+    }
+
+    // Returns the value of the formula in this row
+    pub fn get_result(&self) -> bool {
+        self.result
+    }
+}
+
+impl Deduction {
+    // Builds the truth table of the argument, with a column for each premise left on the stack and
+    // one for the conclusion, which is the given one or else the Deduction's own
+    pub fn argument_truth_table(
+        &self,
+        conclusion: Option<&Premise>,
+    ) -> std::result::Result<ArgumentTable, TooManyVariables> {
+        let premises: Vec<Premise> = self
+            .get_labeled_premises()
+            .into_iter()
+            .map(|(_, premise)| premise.clone())
+            .collect();
+        let conclusion = conclusion.or(self.get_conclusion()).cloned();
+
+        ArgumentTable::new(premises, conclusion, DEFAULT_MAX_VARIABLES)
+    }
+}
+
+impl ArgumentTable {
+    // Builds the truth table of the premises and the conclusion over all of their propositions,
+    // in alphabetical order and with the rows in the standard order
+    pub fn new(
+        premises: Vec<Premise>,
+        conclusion: Option<Premise>,
+        max_variables: usize,
+    ) -> std::result::Result<Self, TooManyVariables> {
+        let propositions: Vec<char> = premises
+            .iter()
+            .chain(&conclusion)
+            .flat_map(Premise::propositions)
+            .collect::<BTreeSet<char>>()
+            .into_iter()
+            .collect();
+
+        if propositions.len() > max_variables {
+            return Err(TooManyVariables {
+                found: propositions.len(),
+                limit: max_variables,
+            });
+        }
+
+        let rows = argument_rows(&premises, conclusion.as_ref(), &propositions);
+
+        Ok(Self {
+            premises,
+            conclusion,
+            propositions,
+            rows,
+        })
+    }
+
+    // Returns the premises, in the order of the table's columns
+    pub fn get_premises(&self) -> &[Premise] {
+        &self.premises
+    }
+
+    // Returns the conclusion, if the table has one
+    pub fn get_conclusion(&self) -> Option<&Premise> {
+        self.conclusion.as_ref()
+    }
+
+    // Returns the propositions in the order of each row's values
+    pub fn get_propositions(&self) -> &[char] {
+        &self.propositions
+    }
+
+    // Returns every row of the table
+    pub fn get_rows(&self) -> &[ArgumentRow] {
+        &self.rows
+    }
+
+    // Returns the rows where every premise is TRUE
+    pub fn get_critical_rows(&self) -> impl Iterator<Item = &ArgumentRow> {
+        self.rows.iter().filter(|row| row.is_critical())
+    }
+
+    // Checks whether the conclusion is TRUE in every critical row, or returns None if there is no
+    // conclusion to check
+    pub fn is_valid(&self) -> Option<bool> {
+        self.conclusion.as_ref()?;
+
+        Some(
+            self.get_critical_rows()
+                .all(|row| row.conclusion == Some(true)),
+        )
+    }
+}
+
+impl Display for ArgumentTable {
+    // Displays the table with its columns aligned and a '*' after each critical row, such as:
+    // p  q  p → q  p  ∴ q
+    // T  T  T      T  T    *
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut headers: Vec<String> = self.propositions.iter().map(char::to_string).collect();
+        headers.extend(self.premises.iter().map(Premise::to_string));
+        if let Some(conclusion) = &self.conclusion {
+            headers.push(format!("∴ {}", conclusion));
+        }
+
+        let widths: Vec<usize> = headers
+            .iter()
+            .map(|header| header.chars().count())
+            .collect();
+        let write_line = |f: &mut Formatter<'_>, cells: &[&str], marker: &str| {
+            let mut line = String::new();
+
+            for (cell, width) in cells.iter().zip(&widths) {
+                line.push_str(&format!("{:<width$}  ", cell, width = width));
+            }
+            line.push_str(marker);
+
+            writeln!(f, "{}", line.trim_end())
+        };
+
+        let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+        write_line(f, &headers, "")?;
+
+        for row in &self.rows {
+            let cells: Vec<&str> = row
+                .values
+                .iter()
+                .chain(&row.premises)
+                .chain(&row.conclusion)
+                .map(|value| format_value(*value))
+                .collect();
+
+            write_line(f, &cells, if row.is_critical() { "*" } else { "" })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ArgumentRow {
+    // Returns the value of each proposition, in the order of the table's propositions
+    pub fn get_values(&self) -> &[bool] {
+        &self.values
+    }
+
+    // Returns the value of each premise in this row, in the order of the table's premises
+    pub fn get_premise_values(&self) -> &[bool] {
+        &self.premises
+    }
+
+    // Returns the value of the conclusion in this row, if the table has one
+    pub fn get_conclusion_value(&self) -> Option<bool> {
+        self.conclusion
+    }
+
+    // Checks whether every premise is TRUE in this row
+    pub fn is_critical(&self) -> bool {
+        self.premises.iter().all(|value| *value)
+    }
+}
+
+// Finds the value of every premise and the conclusion under each assignment of the propositions,
+// in the standard order
+fn argument_rows(
+    premises: &[Premise],
+    conclusion: Option<&Premise>,
+    propositions: &[char],
+) -> Vec<ArgumentRow> {
+    let mut premise_evaluators: Vec<Evaluator> = premises
+        .iter()
+        .map(|premise| premise.evaluator(propositions))
+        .collect();
+    let mut conclusion_evaluator = conclusion.map(|conclusion| conclusion.evaluator(propositions));
+
+    let mut rows = Vec::new();
+    let mut values = vec![true; propositions.len()];
+
+    loop {
+        rows.push(ArgumentRow {
+            values: values.clone(),
+            premises: premise_evaluators
+                .iter_mut()
+                .map(|evaluate| evaluate(&values) == Some(true))
+                .collect(),
+            conclusion: conclusion_evaluator
+                .as_mut()
+                .map(|evaluate| evaluate(&values) == Some(true)),
+        });
+
+        if !next_standard(&mut values) {
+            return rows;
+        }
+    }
+}
+
+// Returns a function which finds the value of the formula by setting the values of the given
+// propositions in a ValueMap and evaluating it
+fn value_map_evaluator<'a, F: Formula>(formula: &'a F, propositions: &[char]) -> Evaluator<'a> {
+    let propositions = propositions.to_vec();
+    let mut proposition_values = ValueMap::default();
+
+    Box::new(move |values| {
+        for (proposition, value) in propositions.iter().zip(values) {
+            proposition_values.set_value(*proposition, Some(*value));
+        }
+
+        formula.evaluate(&proposition_values)
+    })
+}
+
+// Changes the values to the next row in the standard order, counting with TRUE as 0 and FALSE as
+// 1 so the first proposition changes slowest
+// Returns false if the values were the last row
+fn next_standard(values: &mut [bool]) -> bool {
+    for value in values.iter_mut().rev() {
+        *value = !*value;
+
+        if !*value {
+            return true;
+        }
+    }
+
+    false
+}
+
+// Changes the values to the next row in the reflected Gray code order, counting with TRUE as 0 and
+// FALSE as 1 like next_standard
+// When an even number of values are FALSE the last one changes, and otherwise the one before the
+// last FALSE value does
+// Returns false if the values were the last row
+fn next_gray(values: &mut [bool]) -> bool {
+    let falses = values.iter().filter(|value| !**value).count();
+
+    let i = match falses % 2 {
+        0 => values.len().checked_sub(1),
+        _ => values
+            .iter()
+            .rposition(|value| !*value)
+            .and_then(|last| last.checked_sub(1)),
+    };
+
+    match i {
+        Some(i) => {
+            values[i] = !values[i];
+            true
+        }
+        None => false,
+    }
+}
+
+// Renders rows of the formula's truth table as a markdown table
+fn render_markdown<F: Formula, R: Borrow<TruthRow>>(
+    formula: &F,
+    propositions: &[char],
+    rows: impl Iterator<Item = R>,
+) -> String {
+    let mut table = String::from("|");
+
+    for proposition in propositions {
+        table.push_str(&format!(" {} |", proposition));
+    }
+    table.push_str(&format!(" {} |\n|", formula));
+
+    for _ in 0..=propositions.len() {
+        table.push_str("---|");
+    }
+    table.push('\n');
+
+    for row in rows {
+        let row = row.borrow();
+        table.push('|');
+
+        for value in &row.values {
+            table.push_str(&format!(" {} |", format_value(*value)));
+        }
+        table.push_str(&format!(" {} |\n", format_value(row.result)));
+    }
+
+    table
+}
+
+// Returns the single letter used for a truth value in a table
+fn format_value(value: bool) -> &'static str {
+    if value {
+        "T"
+    } else {
+        "F"
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::cell::Cell;
+    use std::collections::HashSet;
+    use std::rc::Rc;
+
+    use super::*;
+
+    // Wraps a Premise to count how many times it is evaluated
+    #[derive(Debug, Clone)]
+    pub(crate) struct Counting {
+        premise: Premise,
+        evaluations: Rc<Cell<usize>>,
+    }
+
+    impl Counting {
+        pub(crate) fn new(premise: &str) -> Self {
+            Self {
+                premise: Premise::parse_str(premise).unwrap(),
+                evaluations: Rc::new(Cell::new(0)),
+            }
+        }
+
+        pub(crate) fn get_evaluations(&self) -> usize {
+            self.evaluations.get()
+        }
+    }
+
+    impl Display for Counting {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            write!(f, "{}", self.premise)
+        }
+    }
+
+    impl Formula for Counting {
+        fn propositions(&self) -> BTreeSet<char> {
+            self.premise.propositions()
+        }
+
+        fn evaluate(&self, proposition_values: &ValueMap) -> Option<bool> {
+            self.evaluations.set(self.evaluations.get() + 1);
+            self.premise.evaluate(proposition_values)
+        }
+
+        fn render(&self, notation: Notation) -> String {
+            self.premise.render(notation)
+        }
+    }
+
+    #[test]
+    fn test_truth_table() {
+        let table = TruthTable::new(&Premise::parse_str("(m & !b) > j").unwrap(), 3).unwrap();
+
+        assert_eq!(table.get_propositions(), &['b', 'j', 'm']);
+        assert_eq!(table.get_rows(RowFilter::All).count(), 8);
+
+        // The implication is only false when its antecedent holds and j does not
+        let falsifying: Vec<&TruthRow> = table.get_rows(RowFilter::Falsifying).collect();
+        assert_eq!(falsifying.len(), 1);
+        assert_eq!(falsifying[0].get_values(), &[false, false, true]);
+
+        assert_eq!(
+            TruthTable::new(&Premise::parse_str("(m & !b) > j").unwrap(), 2).unwrap_err(),
+            TooManyVariables { found: 3, limit: 2 }
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let table =
+            TruthTable::new(&Premise::parse_str("p > q").unwrap(), DEFAULT_MAX_VARIABLES).unwrap();
+
+        assert_eq!(
+            table.render_plain(RowFilter::All),
+            "p  q  p → q\nT  T  T\nT  F  F\nF  T  T\nF  F  T\n"
+        );
+        assert_eq!(
+            table.render_markdown(RowFilter::Satisfying),
+            "| p | q | p → q |\n|---|---|---|\n| T | T | T |\n| F | T | T |\n| F | F | T |\n"
+        );
+        assert_eq!(
+            table.render_latex(RowFilter::Falsifying),
+            "\\begin{tabular}{cc|c}\n$p$ & $q$ & $p \\to q$ \\\\\n\\hline\nT & F & F \\\\\n\\end{tabular}\n"
+        );
+    }
+
+    #[test]
+    fn test_argument_truth_table() {
+        // The argument worked through in main.rs
+        let mut premises = vec!["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"];
+        let conclusion = Premise::parse_str("j").unwrap();
+
+        let deduction = Deduction::from_strs(premises.clone()).unwrap();
+        let table = deduction.argument_truth_table(Some(&conclusion)).unwrap();
+
+        assert_eq!(table.get_propositions(), &['b', 'f', 'j', 'm', 's', 't']);
+        assert_eq!(table.get_rows().len(), 64);
+        assert_eq!(table.get_premises().len(), 5);
+        assert_eq!(table.is_valid(), Some(true));
+
+        // f holds, so t cannot, so b cannot, so m and j must
+        let critical: Vec<&ArgumentRow> = table.get_critical_rows().collect();
+        assert_eq!(critical.len(), 2);
+        for row in critical {
+            assert_eq!(&row.get_values()[..4], &[false, true, true, true]);
+            assert_eq!(row.get_premise_values(), &[true; 5]);
+            assert_eq!(row.get_conclusion_value(), Some(true));
+        }
+
+        // Without f → ¬t, b may hold, so j no longer follows
+        premises[3] = "f > t";
+        let table = Deduction::from_strs(premises)
+            .unwrap()
+            .argument_truth_table(Some(&conclusion))
+            .unwrap();
+        assert_eq!(table.is_valid(), Some(false));
+        assert!(table
+            .get_critical_rows()
+            .any(|row| row.get_conclusion_value() == Some(false)));
+
+        // The Deduction's own conclusion is used when none is given
+        let mut deduction = Deduction::from_strs(vec!["p > q", "p"]).unwrap();
+        assert_eq!(
+            deduction.argument_truth_table(None).unwrap().is_valid(),
+            None
+        );
+        deduction.set_conclusion(Premise::parse_str("q").unwrap());
+        assert_eq!(
+            deduction.argument_truth_table(None).unwrap().is_valid(),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_render_argument_table() {
+        let mut deduction = Deduction::from_strs(vec!["p > q", "p"]).unwrap();
+        deduction.set_conclusion(Premise::parse_str("q").unwrap());
+
+        assert_eq!(
+            deduction.argument_truth_table(None).unwrap().to_string(),
+            "p  q  p → q  p  ∴ q\n\
+             T  T  T      T  T    *\n\
+             T  F  F      T  F\n\
+             F  T  T      F  T\n\
+             F  F  T      F  F\n"
+        );
+    }
+
+    #[test]
+    fn test_truth_rows() {
+        for premise in ["(m & !b) > j", "a | b & c > !d", "p", "!!p | q"] {
+            let premise = Premise::parse_str(premise).unwrap();
+            let table = TruthTable::new(&premise, DEFAULT_MAX_VARIABLES).unwrap();
+
+            let streamed: Vec<TruthRow> = premise.truth_rows().collect();
+            let materialized: Vec<TruthRow> = table.get_rows(RowFilter::All).cloned().collect();
+            assert_eq!(streamed, materialized);
+
+            // The Gray code order has the same rows, but only one value changes between them
+            let gray: Vec<TruthRow> = premise.truth_rows_in(RowOrder::Gray).collect();
+            assert_eq!(gray[0], materialized[0]);
+            assert_eq!(
+                gray.iter().cloned().collect::<HashSet<_>>(),
+                materialized.iter().cloned().collect::<HashSet<_>>()
+            );
+
+            for pair in gray.windows(2) {
+                let changed = pair[0]
+                    .get_values()
+                    .iter()
+                    .zip(pair[1].get_values())
+                    .filter(|(before, after)| before != after)
+                    .count();
+                assert_eq!(changed, 1);
+            }
+
+            assert_eq!(
+                premise.truth_rows().render_markdown(RowFilter::Satisfying),
+                table.render_markdown(RowFilter::Satisfying)
+            );
+        }
+    }
+
+    #[test]
+    fn test_early_exit() {
+        let counting = Counting::new;
+
+        // The first row already satisfies the formula, so no other row is generated
+        let formula = counting("a | b | c | d");
+        assert!(formula.is_satisfiable());
+        assert_eq!(formula.evaluations.get(), 1);
+
+        // The first row where d is FALSE is the second
+        let formula = counting("a | b | c | d > d");
+        assert!(!formula.is_tautology());
+        assert_eq!(formula.evaluations.get(), 2);
+
+        let formula = counting("a | !a");
+        assert!(formula.is_tautology());
+        assert_eq!(formula.evaluations.get(), 2);
+
+        let formula = counting("a & !a");
+        assert!(!formula.is_satisfiable());
+        assert_eq!(formula.evaluations.get(), 2);
+
+        // Rows are only generated as they are asked for, however many propositions there are
+        let formula = counting("a & b & c & d & e & f & g & h & i & j & k & l & m & n & o & p & q");
+        assert_eq!(formula.truth_rows().take(3).count(), 3);
+        assert_eq!(formula.evaluations.get(), 3);
+    }
+}
@@ -0,0 +1,303 @@
+// Exports the solver to JavaScript through wasm-bindgen, for embedding it in a webpage
+// Apart from the JsDeduction a page keeps between calls, only strings cross the boundary, with
+// results given as JSON, and every input is parsed with the fallible parts of the library so a
+// mistake in a formula becomes a JavaScript error instead of a panic, which aborts the whole
+// module in WebAssembly
+// Build with `wasm-pack build --target nodejs --out-name deductions -- --features wasm`
+
+use serde_json::{json, Value};
+use wasm_bindgen::prelude::*;
+
+use crate::history::EvaluationHistory;
+use crate::notation::Notation;
+use crate::observer::SolveOptions;
+use crate::truth_table::{RowFilter, TruthTable, DEFAULT_MAX_VARIABLES};
+use crate::Deduction;
+use crate::Premise;
+
+// The most steps a solve may take, so a large argument cannot freeze the page
+// Solves are not given a timeout, as WebAssembly in the browser has no clock to measure one with
+pub const MAX_STEPS: usize = 100_000;
+
+// Solves the argument given as a JSON array of premise strings, or as an object with "premises"
+// and an optional "conclusion", returning the JSON the solve command prints
+// With a conclusion, this also checks whether the premises entail it like the prove command
+#[wasm_bindgen]
+pub fn solve_argument(premises_json: &str) -> Result<String, JsError> {
+    solve_argument_json(premises_json).map_err(|message| JsError::new(&message))
+}
+
+// Parses the formula, returning a JSON object saying whether it is valid, with the formula as it
+// is displayed and its propositions if it is, or the reason it is not
+#[wasm_bindgen]
+pub fn check_formula(formula: &str) -> String {
+    check_formula_json(formula)
+}
+
+// Returns the truth table of the formula as a JSON object with every row, or throws if the formula
+// cannot be parsed or has too many propositions
+#[wasm_bindgen]
+pub fn truth_table(formula: &str) -> Result<String, JsError> {
+    truth_table_json(formula).map_err(|message| JsError::new(&message))
+}
+
+// Holds a Deduction for a webpage which works through it a step at a time, such as a tutor which
+// shows the values found so far
+#[wasm_bindgen]
+pub struct JsDeduction {
+    deduction: Deduction,
+    history: Option<EvaluationHistory>,
+}
+
+#[wasm_bindgen]
+impl JsDeduction {
+    // Creates a JsDeduction from an array of premise strings, throwing if any of them is not a
+    // string or cannot be parsed
+    #[wasm_bindgen(constructor)]
+    pub fn new(premises: Vec<JsValue>) -> Result<JsDeduction, JsError> {
+        let premises: Vec<Option<String>> = premises.iter().map(JsValue::as_string).collect();
+
+        Self::from_strings(&premises).map_err(|message| JsError::new(&message))
+    }
+
+    // Solves the premises, returning the JSON object the solve command prints as a JavaScript
+    // object
+    pub fn evaluate(&mut self) -> Result<JsValue, JsError> {
+        js_sys::JSON::parse(&self.evaluate_json().to_string())
+            .map_err(|_| JsError::new("The solve could not be converted to a JavaScript object"))
+    }
+
+    // Returns the value found for the proposition, or undefined if it is not known
+    #[wasm_bindgen(js_name = getValue)]
+    pub fn get_value(&self, proposition: char) -> Option<bool> {
+        self.deduction.get_values().get_value(proposition)
+    }
+
+    // Renders every step of the last solve as it is displayed, or nothing before the first solve
+    #[wasm_bindgen(js_name = renderHistory)]
+    pub fn render_history(&self) -> String {
+        self.history
+            .as_ref()
+            .map_or_else(String::new, |history| history.render(Notation::Unicode))
+    }
+}
+
+impl JsDeduction {
+    // Creates a JsDeduction like new, where a premise which was not a string is None, returning
+    // the message of any error
+    fn from_strings(premises: &[Option<String>]) -> Result<Self, String> {
+        let mut builder = Deduction::builder();
+
+        for (i, premise) in premises.iter().enumerate() {
+            let Some(premise) = premise else {
+                return Err(format!("Premise {} is not a string", i + 1));
+            };
+
+            builder = builder.premise_str(premise);
+        }
+
+        Ok(Self {
+            deduction: builder.build().map_err(|error| error.to_string())?,
+            history: None,
+        })
+    }
+
+    // Solves the premises like evaluate, keeping the history for render_history
+    fn evaluate_json(&mut self) -> Value {
+        let options = SolveOptions {
+            max_steps: Some(MAX_STEPS),
+            timeout: None,
+            history: None,
+        };
+        let mut history = EvaluationHistory::new();
+        let result = self.deduction.solve_with_options(&options, &mut history);
+
+        let json = history.to_solve_json(
+            &self.deduction,
+            result,
+            !self.deduction.is_contradictory(),
+            None,
+        );
+        self.history = Some(history);
+
+        json
+    }
+}
+
+// Solves the argument like solve_argument, returning the message of any error
+fn solve_argument_json(premises_json: &str) -> Result<String, String> {
+    let input: Value = serde_json::from_str(premises_json)
+        .map_err(|error| format!("The argument is not valid JSON: {}", error))?;
+
+    let (premises, conclusion) = match &input {
+        Value::Array(premises) => (premises, None),
+        Value::Object(object) => match (object.get("premises"), object.get("conclusion")) {
+            (Some(Value::Array(premises)), None | Some(Value::Null)) => (premises, None),
+            (Some(Value::Array(premises)), Some(Value::String(conclusion))) => {
+                (premises, Some(conclusion.as_str()))
+            }
+            (Some(Value::Array(_)), Some(_)) => {
+                return Err(String::from("The conclusion must be a string"))
+            }
+            _ => {
+                return Err(String::from(
+                    "The argument must have an array of \"premises\"",
+                ))
+            }
+        },
+        _ => {
+            return Err(String::from(
+                "The argument must be an array of premises or an object with \"premises\"",
+            ))
+        }
+    };
+
+    let mut builder = Deduction::builder();
+    for premise in premises {
+        let Value::String(premise) = premise else {
+            return Err(String::from("Every premise must be a string"));
+        };
+
+        builder = builder.premise_str(premise);
+    }
+
+    if let Some(conclusion) = conclusion {
+        builder = builder.conclusion_str(conclusion);
+    }
+
+    let mut deduction = builder.build().map_err(|error| error.to_string())?;
+
+    // As in the prove command, these are checked while every premise is still on the stack
+    let (entailment, consistent) = match deduction.get_conclusion() {
+        Some(conclusion) => (
+            Some(deduction.check_entailment(conclusion)),
+            deduction.find_model().is_some(),
+        ),
+        None => (None, true),
+    };
+
+    let options = SolveOptions {
+        max_steps: Some(MAX_STEPS),
+        timeout: None,
+        history: None,
+    };
+    let mut history = EvaluationHistory::new();
+    let result = deduction.solve_with_options(&options, &mut history);
+
+    let json = history.to_solve_json(
+        &deduction,
+        result,
+        consistent && !deduction.is_contradictory(),
+        entailment.as_ref(),
+    );
+
+    Ok(json.to_string())
+}
+
+// Checks the formula like check_formula
+fn check_formula_json(formula: &str) -> String {
+    let json = match Premise::parse_str(formula) {
+        Ok(premise) => json!({
+            "valid": true,
+            "formula": premise.to_string(),
+            "propositions": premise
+                .propositions()
+                .iter()
+                .map(char::to_string)
+                .collect::<Vec<String>>(),
+        }),
+        Err(error) => json!({ "valid": false, "error": error.to_string() }),
+    };
+
+    json.to_string()
+}
+
+// Builds the truth table like truth_table, returning the message of any error
+fn truth_table_json(formula: &str) -> Result<String, String> {
+    let premise = Premise::parse_str(formula).map_err(|error| error.to_string())?;
+    let table =
+        TruthTable::new(&premise, DEFAULT_MAX_VARIABLES).map_err(|error| error.to_string())?;
+
+    Ok(table.to_json(RowFilter::All).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Parses the JSON returned by one of the functions
+    fn parse(json: &str) -> Value {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_solve_argument() {
+        let solved = parse(
+            &solve_argument_json(r#"["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"]"#)
+                .unwrap(),
+        );
+        assert_eq!(solved["values"]["j"], json!(true));
+        assert_eq!(solved["completed"], json!(true));
+        assert!(!solved["steps"].as_array().unwrap().is_empty());
+
+        let proved = parse(
+            &solve_argument_json(r#"{"premises": ["p > q", "p"], "conclusion": "q"}"#).unwrap(),
+        );
+        assert_eq!(proved["verdict"], json!(true));
+        assert_eq!(proved["entailment"]["result"], json!("proved"));
+
+        // Mistakes in the input are reported instead of panicking
+        assert!(solve_argument_json("[\"p >\"]").unwrap_err().contains("P1"));
+        assert!(solve_argument_json("[1]").is_err());
+        assert!(solve_argument_json("{\"premises\": \"p\"}").is_err());
+        assert!(solve_argument_json("not json").is_err());
+        assert!(solve_argument_json(r#"{"premises": ["p"], "conclusion": "?"}"#).is_err());
+    }
+
+    #[test]
+    fn test_js_deduction() {
+        let mut deduction =
+            JsDeduction::from_strings(&[Some(String::from("p > q")), Some(String::from("p"))])
+                .unwrap();
+        assert_eq!(deduction.render_history(), "");
+        assert_eq!(deduction.get_value('q'), None);
+
+        let solved = deduction.evaluate_json();
+        assert_eq!(solved["values"], json!({ "p": true, "q": true }));
+        assert_eq!(solved["completed"], json!(true));
+        assert_eq!(deduction.get_value('q'), Some(true));
+        assert!(deduction.render_history().contains("TRUE → q"));
+
+        assert_eq!(
+            JsDeduction::from_strings(&[Some(String::from("p")), None])
+                .err()
+                .as_deref(),
+            Some("Premise 2 is not a string")
+        );
+        assert!(JsDeduction::from_strings(&[Some(String::from("p & #"))])
+            .err()
+            .unwrap()
+            .contains("'#'"));
+    }
+
+    #[test]
+    fn test_check_formula() {
+        let valid = parse(&check_formula_json("(m & !b) > j"));
+        assert_eq!(valid["valid"], json!(true));
+        assert_eq!(valid["formula"], json!("(m ∧ ¬b) → j"));
+        assert_eq!(valid["propositions"], json!(["b", "j", "m"]));
+
+        let invalid = parse(&check_formula_json("p & $"));
+        assert_eq!(invalid["valid"], json!(false));
+        assert!(invalid["error"].as_str().unwrap().contains("'$'"));
+    }
+
+    #[test]
+    fn test_truth_table() {
+        let table = parse(&truth_table_json("p > q").unwrap());
+        assert_eq!(table["rows"].as_array().unwrap().len(), 4);
+
+        assert!(truth_table_json("p &").is_err());
+        assert!(truth_table_json("a & b & c & d & e & f & g & h & i & j & k & l & m").is_err());
+    }
+}
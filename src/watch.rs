@@ -0,0 +1,174 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::Premise;
+use crate::ValueMap;
+
+// Maps each proposition to the premises which mention it, identified by their numbers, so the
+// premises a newly known value has to be substituted into are found without walking every premise
+// This is the idea behind watched literals in SAT solvers, and like them it has to be kept up to
+// date as the premises are rewritten and removed
+// Evaluating a premise can only remove propositions from it, so a premise may still be watched for
+// a proposition it no longer mentions, but every proposition it does mention is watched
+// A proposition is only in the map while some premise is watched for it, so once a known value has
+// been substituted everywhere it is no longer watched
+// Premises with no evaluation step left are settled, and are skipped until a substitution
+// rewrites them, since evaluating a premise depends on nothing but the premise itself
+#[derive(Debug, Default, Clone)]
+pub(crate) struct WatchLists {
+    premises: HashMap<char, BTreeSet<usize>>,
+    settled: BTreeSet<usize>,
+}
+
+impl WatchLists {
+    // Watches every proposition of the premise
+    pub(crate) fn add(&mut self, number: usize, premise: &Premise) {
+        for proposition in premise.propositions() {
+            self.premises.entry(proposition).or_default().insert(number);
+        }
+    }
+
+    // Stops watching any proposition for the premise, such as when it is removed from the stack
+    pub(crate) fn remove(&mut self, number: usize) {
+        self.settled.remove(&number);
+        self.premises.retain(|_, numbers| {
+            numbers.remove(&number);
+            !numbers.is_empty()
+        });
+    }
+
+    // Stops watching the propositions with known values for the premise, once it has been found
+    // to no longer mention them
+    pub(crate) fn remove_known(&mut self, number: usize, proposition_values: &ValueMap) {
+        self.premises.retain(|proposition, numbers| {
+            if proposition_values.get_value(*proposition).is_some() {
+                numbers.remove(&number);
+            }
+
+            !numbers.is_empty()
+        });
+    }
+
+    // Updates the watches of a premise which was rewritten, which may have removed propositions
+    // from it or, when renaming, added new ones
+    pub(crate) fn update(&mut self, number: usize, before: &Premise, after: &Premise) {
+        self.settled.remove(&number);
+        let (before, after) = (before.propositions(), after.propositions());
+
+        for proposition in before.difference(&after) {
+            self.unwatch(*proposition, number);
+        }
+
+        for proposition in after.difference(&before) {
+            self.premises
+                .entry(*proposition)
+                .or_default()
+                .insert(number);
+        }
+    }
+
+    // Returns the numbers of the premises watched for any of the propositions whose values changed
+    // since the last pass, which include every premise substitution would change
+    // Known values are substituted as soon as they are found, so only the changed ones can still
+    // be watched
+    pub(crate) fn affected(&self, changed: &BTreeSet<char>) -> BTreeSet<usize> {
+        changed
+            .iter()
+            .filter_map(|proposition| self.premises.get(proposition))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    // Marks the premise as having no evaluation step left
+    pub(crate) fn settle(&mut self, number: usize) {
+        self.settled.insert(number);
+    }
+
+    // Returns whether the premise is known to have no evaluation step left
+    pub(crate) fn is_settled(&self, number: usize) -> bool {
+        self.settled.contains(&number)
+    }
+
+    // Stops watching the proposition for the premise, forgetting the proposition once no premise
+    // mentions it
+    fn unwatch(&mut self, proposition: char, number: usize) {
+        if let Some(numbers) = self.premises.get_mut(&proposition) {
+            numbers.remove(&number);
+
+            if numbers.is_empty() {
+                self.premises.remove(&proposition);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watch_lists() {
+        let first = Premise::parse_str("(m & !b) > j").unwrap();
+        let second = Premise::parse_str("b > t").unwrap();
+
+        let mut watches = WatchLists::default();
+        watches.add(1, &first);
+        watches.add(2, &second);
+        assert!(watches.affected(&BTreeSet::new()).is_empty());
+
+        let mut values = ValueMap::default();
+        values.set_value('b', Some(true));
+        values.set_value('x', Some(false));
+        assert_eq!(
+            watches.affected(&BTreeSet::from(['b', 'x'])),
+            BTreeSet::from([1, 2])
+        );
+
+        // Once b is substituted neither premise mentions it
+        watches.update(1, &first, &first.substituted(&values));
+        watches.update(2, &second, &second.substituted(&values));
+        assert!(watches.affected(&BTreeSet::from(['b'])).is_empty());
+        assert!(!watches.premises.contains_key(&'b'));
+
+        values.set_value('t', Some(true));
+        assert_eq!(
+            watches.affected(&BTreeSet::from(['t'])),
+            BTreeSet::from([2])
+        );
+
+        watches.remove(2);
+        assert!(watches.affected(&BTreeSet::from(['t'])).is_empty());
+
+        // A premise evaluated to TRUE no longer mentions j, but is still watched for it
+        values.set_value('j', Some(true));
+        assert_eq!(
+            watches.affected(&BTreeSet::from(['j'])),
+            BTreeSet::from([1])
+        );
+        watches.remove_known(1, &values);
+        assert!(watches.affected(&BTreeSet::from(['j'])).is_empty());
+        assert_eq!(watches.premises[&'m'], BTreeSet::from([1]));
+    }
+
+    #[test]
+    fn test_settled_premises() {
+        let premise = Premise::parse_str("m > j").unwrap();
+
+        let mut watches = WatchLists::default();
+        watches.add(1, &premise);
+        assert!(!watches.is_settled(1));
+
+        watches.settle(1);
+        assert!(watches.is_settled(1));
+
+        // Substituting into a settled premise may give it new evaluation steps
+        let mut values = ValueMap::default();
+        values.set_value('m', Some(true));
+        watches.update(1, &premise, &premise.substituted(&values));
+        assert!(!watches.is_settled(1));
+
+        watches.settle(1);
+        watches.remove(1);
+        assert!(!watches.is_settled(1));
+    }
+}
@@ -0,0 +1,35 @@
+// Counts the allocations made by common operations, so a change which makes premises allocate
+// more often is noticed
+// This is its own test binary with a single test, as the allocator counts every thread
+
+#[path = "../benches/inputs/allocations.rs"]
+mod allocations;
+
+use allocations::{count, held, CountingAllocator};
+use deductions::{Deduction, Premise};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn test_allocations() {
+    // Cloning a premise copies each list of nodes, which here is the premise and its subpremise,
+    // but shares the string it was parsed from
+    let premise = Premise::parse_str("(m & !b) > j").unwrap();
+    let (allocations, copy) = count(|| premise.clone());
+    assert_eq!(allocations, 2);
+    assert_eq!(copy.origin(), Some("(m & !b) > j"));
+
+    // Solving the example argument the binary works through without arguments
+    let mut deduction =
+        Deduction::from_strs(vec!["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"]).unwrap();
+    let (allocations, history) = count(|| deduction.solve());
+    println!("solving the example made {} allocations", allocations);
+
+    assert_eq!(deduction.get_values().get_value('j'), Some(true));
+    assert!(allocations <= 300, "{} allocations", allocations);
+
+    // The history holds a Deduction for every pass, which is freed along with it
+    let (freed, ()) = held(|| drop(history));
+    assert!(freed < 0, "{} bytes", freed);
+}
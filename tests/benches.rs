@@ -0,0 +1,107 @@
+// Runs the body of every benchmark in benches/suite.rs once, on smaller inputs where the suite's
+// would be slow in a debug build, so they keep compiling and working without running the
+// benchmarks themselves
+
+#[path = "../benches/inputs/mod.rs"]
+mod inputs;
+
+use std::time::{Duration, Instant};
+
+use deductions::generator::SeededRng;
+use deductions::truth_table::RowFilter;
+
+use inputs::Inputs;
+
+#[test]
+fn test_benchmarks() {
+    let inputs = Inputs::new();
+
+    // The inputs are the same every time they are generated
+    let mut rng = SeededRng::new(inputs::SEED);
+    assert_eq!(
+        inputs.large_premise,
+        inputs::generate_conjunction(&mut rng, 10_000, 8, 26)
+    );
+
+    let premise = inputs::parse_large(&inputs.large_premise);
+    assert!(premise.validate());
+    assert!(premise.node_count() > 10_000);
+
+    let premises = inputs::parse_file(&inputs.premise_file);
+    assert_eq!(premises.len(), 10_000);
+    assert!(premises.iter().any(|premise| premise.depth() >= 5));
+    assert_eq!(
+        inputs::parse_file_borrowed(&inputs.premise_file).len(),
+        10_000
+    );
+
+    let nested = inputs::parse_large(&inputs::generate_nested(250));
+    assert_eq!(nested.depth(), 251);
+
+    let history = inputs::solve_example();
+    assert!(!history.get_steps().is_empty());
+
+    // Simplifying by steps and in one call reach the same premise
+    let (substituted, premises) = inputs::generate_clauses(50);
+    assert_eq!(
+        inputs::simplify_by_steps(&substituted),
+        inputs::simplify(&substituted)
+    );
+    let (deduction, _) = inputs::solve(&premises);
+    assert_eq!(deduction.get_values().get_value('a'), Some(false));
+
+    for formula in [&inputs.table_formula_15, &inputs.table_formula_16] {
+        let table = inputs::truth_table(formula);
+        assert_eq!(table.get_propositions().len(), formula.propositions().len());
+    }
+    assert_eq!(inputs.table_formula_15.propositions().len(), 15);
+    assert_eq!(inputs.table_formula_16.propositions().len(), 16);
+    inputs::check_entailment(&inputs.table_formula_15);
+
+    let repeated = inputs::generate_repeated(10);
+    let satisfying = inputs::evaluate_every_row(&repeated);
+    assert_eq!(
+        inputs::truth_table(&repeated)
+            .get_rows(RowFilter::Satisfying)
+            .count(),
+        satisfying
+    );
+
+    let tautology = inputs::generate_tautology(10);
+    assert_eq!(inputs::check_rows(&tautology, 2), (true, 1 << 10));
+
+    let cnf = inputs::cnf(&inputs.nested_formula);
+    assert!(cnf.validate());
+
+    inputs::satisfy(&inputs.random_3cnf);
+
+    let observer = inputs::solve_copying(&inputs.copied_premises);
+    assert!(!observer.copies.is_empty());
+
+    let premises = inputs::generate_history(100, 100);
+    let (_, history) = inputs::solve(&premises);
+    assert!(history.get_deductions().len() > 100);
+}
+
+#[test]
+fn test_chain_solves_quickly() {
+    let inputs = Inputs::new();
+
+    for (name, chain) in &inputs.chains {
+        let start = Instant::now();
+        let (deduction, _) = inputs::solve(chain);
+        let elapsed = start.elapsed();
+
+        // Every proposition of the chain is found to be TRUE, while those of the premises which
+        // never change stay unknown
+        assert!(deduction
+            .get_values()
+            .iter()
+            .all(|(_, value)| value == Some(true) || value.is_none()));
+
+        // The bound is far above how long this takes even in a debug build, so it is only
+        // exceeded by something like an accidental loop over every premise for every premise
+        assert!(elapsed < Duration::from_secs(10), "{:?}", elapsed);
+        println!("{} implications: {:?}", name, elapsed);
+    }
+}
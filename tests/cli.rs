@@ -0,0 +1,762 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Runs the binary with the given arguments, returning its exit status, stdout and stderr
+fn run(arguments: &[&str]) -> (Option<i32>, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_deduction_machine"))
+        .args(arguments)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("Failed to run the binary");
+
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn test_solve_file() {
+    let (status, stdout, _) = run(&["tests/fixtures/example.txt", "--steps"]);
+
+    assert_eq!(status, Some(0));
+    assert!(stdout.contains("j = TRUE [EVALUATE]"));
+    assert!(stdout.lines().any(|line| line == "j = TRUE"));
+}
+
+#[test]
+fn test_builtin_example() {
+    let (status, stdout, _) = run(&[]);
+
+    assert_eq!(status, Some(0));
+    assert!(stdout.lines().any(|line| line == "j = TRUE"));
+}
+
+#[test]
+fn test_missing_file() {
+    let (status, stdout, stderr) = run(&["tests/fixtures/missing.txt"]);
+
+    assert_eq!(status, Some(2));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("tests/fixtures/missing.txt"));
+}
+
+#[test]
+fn test_parse_error() {
+    let (status, _, stderr) = run(&["tests/fixtures/invalid.txt"]);
+
+    assert_eq!(status, Some(2));
+    assert!(stderr.contains("tests/fixtures/invalid.txt', line 2"));
+    assert!(stderr.contains("'#'"));
+    assert!(!stderr.contains("panicked"));
+}
+
+// Runs the binary with the given arguments, writing the input to its stdin
+fn run_with_stdin(arguments: &[&str], input: &str) -> (Option<i32>, String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_deduction_machine"))
+        .args(arguments)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run the binary");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn test_stdin() {
+    let (status, stdout, stderr) = run_with_stdin(
+        &["-", "--format", "unicode"],
+        "# Modus ponens\np > q\np\n∴ q\n",
+    );
+
+    assert_eq!(status, Some(0));
+    assert!(stderr.is_empty());
+    assert!(stdout.lines().any(|line| line == "q = TRUE"));
+    assert!(stdout.lines().any(|line| line == "∴ q is TRUE"));
+}
+
+#[test]
+fn test_stdin_empty() {
+    let (status, stdout, stderr) = run_with_stdin(&["-"], "# Nothing but a comment\n");
+
+    assert_eq!(status, Some(2));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("'<stdin>' contains no premises"));
+}
+
+#[test]
+fn test_unknown_flag() {
+    let (status, stdout, stderr) = run(&["solve", "--bogus"]);
+
+    assert_eq!(status, Some(2));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("Unknown flag '--bogus'"));
+    assert!(stderr.contains("Usage:"));
+}
+
+#[test]
+fn test_prove() {
+    let (status, stdout, _) = run(&[
+        "tests/fixtures/example.txt",
+        "--prove",
+        "j & m",
+        "--format",
+        "unicode",
+        "--steps",
+    ]);
+    assert_eq!(status, Some(0));
+    assert!(stdout.contains("j = TRUE [EVALUATE]"));
+    assert!(stdout.ends_with("∴ j ∧ m is proved\n"));
+
+    // s may be either true or false
+    let (status, stdout, _) = run(&[
+        "tests/fixtures/example.txt",
+        "--prove",
+        "s",
+        "--format",
+        "unicode",
+    ]);
+    assert_eq!(status, Some(1));
+    assert!(stdout.contains("∴ s is not provable\n"));
+    assert!(stdout.contains("Counterexample: b = FALSE, f = TRUE, j = TRUE, m = TRUE, s = FALSE"));
+
+    let (status, stdout, _) = run(&[
+        "tests/fixtures/example.txt",
+        "--prove",
+        "b | t",
+        "--format",
+        "unicode",
+    ]);
+    assert_eq!(status, Some(1));
+    assert!(stdout.contains("∴ b ∨ t is not provable, the premises contradict it\n"));
+
+    let (status, stdout, stderr) = run(&["tests/fixtures/example.txt", "--prove", "j &"]);
+    assert_eq!(status, Some(2));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("Could not parse the goal"));
+}
+
+#[test]
+fn test_truth_table() {
+    let (status, stdout, _) = run(&["table", "p -> (q | !p)", "--format", "unicode"]);
+
+    assert_eq!(status, Some(0));
+    assert_eq!(
+        stdout,
+        "p  q  p → (q ∨ ¬p)\n\
+         T  T  T\n\
+         T  F  F\n\
+         F  T  T\n\
+         F  F  T\n"
+    );
+
+    let (status, _, stderr) = run(&["table", "(a & b) | (c & d)", "--max-variables", "3"]);
+    assert_eq!(status, Some(4));
+    assert!(stderr.contains("more than the limit of 3"));
+}
+
+#[test]
+fn test_formats() {
+    let (status, stdout, _) = run(&["tests/fixtures/example.txt", "--format", "json"]);
+    assert_eq!(status, Some(0));
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["premises"].as_array().unwrap().len(), 5);
+    assert_eq!(json["steps"][0]["action"], "SUBSTITUTE");
+    assert_eq!(json["values"]["j"], true);
+    assert_eq!(json["values"]["s"], serde_json::Value::Null);
+
+    let (status, stdout, _) = run(&["tests/fixtures/example.txt", "--format", "ascii", "--steps"]);
+    assert_eq!(status, Some(0));
+    assert!(stdout.starts_with("(m & !b) > j\n(f | s) > m\n"));
+    assert!(stdout.is_ascii());
+    assert!(stdout.lines().any(|line| line == "j = TRUE"));
+
+    // Output is ASCII by default when it is not going to a terminal
+    let (_, default_stdout, _) = run(&["tests/fixtures/example.txt", "--steps"]);
+    assert_eq!(default_stdout, stdout);
+
+    // Plain is the default text
+    let (_, plain_stdout, _) = run(&["tests/fixtures/example.txt", "--steps", "--format", "plain"]);
+    assert_eq!(plain_stdout, stdout);
+
+    let (status, stdout, _) = run(&["--format", "markdown", "--conclude", "j"]);
+    assert_eq!(status, Some(0));
+    assert!(stdout.starts_with("**Premises**\n\n- P1: `(m ∧ ¬b) → j`\n"));
+    assert!(stdout.contains("| P2 | `(f ∨ s) → m` | `(TRUE ∨ s) → m` | SUBSTITUTE |\n"));
+    assert!(stdout.contains("- j = TRUE\n"));
+    assert!(stdout.ends_with("∴ j is TRUE\n"));
+}
+
+#[test]
+fn test_json_output() {
+    let (status, stdout, stderr) = run(&["--format", "json", "--conclude", "j"]);
+
+    // Nothing but the JSON object is written
+    assert_eq!(status, Some(0));
+    assert!(stderr.is_empty());
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["outcome"], "true");
+    assert_eq!(json["values"]["j"], true);
+
+    // The stats are the same as -v prints
+    let stats = &json["stats"];
+    assert_eq!(stats["steps"], 12);
+    assert_eq!(stats["substitutions"], 5);
+    assert_eq!(stats["evaluations"], 7);
+    assert_eq!(stats["rewrites"]["implication"], 4);
+    assert_eq!(stats["rewrites"]["negation"], 1);
+    assert_eq!(stats["premises_removed"], 5);
+    assert_eq!(stats["peak_stack_size"], 5);
+    assert!(stats["duration"].as_f64().unwrap() > 0.0);
+
+    let (status, stdout, _) = run(&["--format", "json", "--prove", "s"]);
+    assert_eq!(status, Some(1));
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["outcome"], "not_proved");
+    assert_eq!(json["values"]["j"], true);
+
+    // Errors only go to stderr, leaving stdout empty
+    let (status, stdout, stderr) = run(&["tests/fixtures/invalid.txt", "--format", "json"]);
+    assert_eq!(status, Some(2));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("line 2"));
+}
+
+#[test]
+fn test_batch() {
+    let (status, stdout, _) = run(&["batch", "tests/fixtures/batch.txt", "--format", "unicode"]);
+
+    // The malformed argument decides the exit code
+    assert_eq!(status, Some(2));
+    assert_eq!(
+        stdout,
+        "#1: VALID (q=TRUE)\n\
+         #2: INVALID (p=FALSE, q=TRUE)\n\
+         #3: PARSE ERROR line 14\n    \
+         'tests/fixtures/batch.txt', line 14: Invalid character in premise: '#' at position 4\n\
+         \n\
+         1 valid, 1 invalid, 0 inconsistent, 1 malformed\n"
+    );
+
+    let (_, stdout, _) = run(&["batch", "tests/fixtures/batch.txt", "--format", "json"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+    assert_eq!(json.as_array().unwrap().len(), 3);
+    assert_eq!(json[0]["verdict"], "valid");
+    assert_eq!(json[1]["counterexample"]["p"], false);
+    assert_eq!(json[2]["verdict"], "malformed");
+}
+
+#[test]
+fn test_batch_flags() {
+    // Checking continues past the malformed problem, which decides the exit code
+    let (status, stdout, stderr) = run(&["--batch", "tests/fixtures/problems.txt"]);
+
+    assert_eq!(status, Some(2));
+    assert!(stdout.starts_with("#1: VALID (j=TRUE)\n#2: PARSE ERROR line 11\n"));
+    assert!(stdout.ends_with("1 valid, 0 invalid, 0 inconsistent, 1 malformed\n"));
+    assert!(stderr.is_empty());
+
+    let input = std::fs::read_to_string("tests/fixtures/problems.txt").unwrap();
+    let (status, stdout, _) = run_with_stdin(&["--batch", "-"], &input);
+    assert_eq!(status, Some(2));
+    assert!(stdout.starts_with("#1: VALID (j=TRUE)\n"));
+
+    let (status, stdout, _) = run_with_stdin(&["--stdin", "-q"], "p > q\np\n∴ q\n");
+    assert_eq!(status, Some(0));
+    assert_eq!(stdout, "TRUE\n");
+}
+
+#[test]
+fn test_grade() {
+    let (status, stdout, _) = run(&["grade", "tests/fixtures/grades.csv", "-q"]);
+
+    // The malformed rows decide the exit code
+    assert_eq!(status, Some(2));
+    assert_eq!(
+        stdout,
+        "2 correct\n3 mismatch\n4 correct\n5 mismatch\n6 malformed\n7 malformed\n"
+    );
+
+    let (_, stdout, _) = run(&["grade", "tests/fixtures/grades.csv"]);
+    assert!(stdout.starts_with("Row  Student  Expected      Verdict       Grade      Time"));
+    assert!(stdout.contains("Premise 1: Invalid character in premise: '#'"));
+    assert!(stdout.contains("Expected 4 fields but found 3"));
+    assert!(stdout.ends_with("\n2 correct, 2 mismatched, 2 malformed\n"));
+
+    let path = std::env::temp_dir().join(format!("deductions-grades-{}.csv", std::process::id()));
+    let (_, stdout, _) = run(&[
+        "grade",
+        "tests/fixtures/grades.csv",
+        "--format",
+        "json",
+        "--results",
+        path.to_str().unwrap(),
+    ]);
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 6);
+    assert_eq!(json[1]["student"], "s1002");
+    assert_eq!(json[1]["verdict"], "invalid");
+
+    let lines: Vec<&str> = written.lines().collect();
+    assert_eq!(lines.len(), 7);
+    assert_eq!(lines[0], "row,student,expected,verdict,grade,time_ms,error");
+    assert!(lines[2].starts_with("3,s1002,valid,invalid,mismatch,"));
+}
+
+#[test]
+fn test_exit_codes() {
+    let example = "tests/fixtures/example.txt";
+
+    assert_eq!(
+        run(&["prove", "tests/fixtures/modus_ponens.txt"]).0,
+        Some(0)
+    );
+    assert_eq!(run(&[example, "--prove", "s"]).0, Some(1));
+    assert_eq!(run(&[example, "--max-steps"]).0, Some(2));
+    assert_eq!(
+        run(&["prove", "tests/fixtures/inconsistent.txt"]).0,
+        Some(3)
+    );
+    assert_eq!(run(&[example, "--max-steps", "1"]).0, Some(4));
+}
+
+#[test]
+fn test_steps() {
+    let values = "p = TRUE\nq = TRUE\ntherefore q is TRUE\n";
+
+    let (status, default_stdout, _) = run(&["tests/fixtures/modus_ponens.txt"]);
+    assert_eq!(status, Some(0));
+    assert_eq!(
+        default_stdout,
+        format!("p > q\np\ntherefore q\n\n{}", values)
+    );
+
+    // The full history repeats the premise stack after every pass
+    let (status, stdout, _) = run(&["tests/fixtures/modus_ponens.txt", "--steps"]);
+    assert_eq!(status, Some(0));
+    assert!(stdout.starts_with("p > q\np = TRUE\ntherefore q\n\n=>\n"));
+    assert!(stdout.ends_with(values));
+    assert_eq!(stdout.lines().count(), values.lines().count() + 10);
+
+    // The diff only has a line for each step
+    let (status, stdout, _) = run(&["tests/fixtures/modus_ponens.txt", "--steps=diff"]);
+    assert_eq!(status, Some(0));
+    assert_eq!(
+        stdout,
+        format!(
+            "P1: p > q => TRUE > q [SUBSTITUTE]\n\nP1: TRUE > q => q [EVALUATE]\n\n{}",
+            values
+        )
+    );
+
+    let (_, stdout, _) = run(&[
+        "tests/fixtures/modus_ponens.txt",
+        "--steps",
+        "--format",
+        "latex",
+    ]);
+    assert!(stdout.starts_with("\\[\n\\begin{array}{ll}\np \\to q & \\\\\n"));
+}
+
+#[test]
+fn test_quiet() {
+    let example = "tests/fixtures/example.txt";
+
+    for (arguments, expected) in [
+        (vec![example, "-q"], "b=F, f=T, j=T, m=T, s=?, t=F\n"),
+        (vec![example, "-q", "--prove", "j & m"], "PROVED\n"),
+        (vec![example, "--quiet", "--prove", "s"], "NOT_PROVED\n"),
+        (vec!["tests/fixtures/modus_ponens.txt", "-q"], "TRUE\n"),
+        (
+            vec!["prove", "-q", "tests/fixtures/inconsistent.txt"],
+            "INCONSISTENT\n",
+        ),
+        (
+            vec![example, "-q", "--max-steps", "1"],
+            "b=?, f=T, j=?, m=?, s=?, t=?\n",
+        ),
+    ] {
+        let (_, stdout, stderr) = run(&arguments);
+
+        assert_eq!(stdout, expected, "{:?}", arguments);
+        assert_eq!(stdout.lines().count(), 1);
+        assert!(stderr.is_empty());
+    }
+
+    // Batch mode prints one line per argument
+    let (status, stdout, _) = run(&["batch", "-q", "tests/fixtures/batch.txt"]);
+    assert_eq!(status, Some(2));
+    assert_eq!(stdout, "#1: VALID\n#2: INVALID\n#3: MALFORMED\n");
+
+    let (status, stdout, stderr) = run(&[example, "-q", "--steps"]);
+    assert_eq!(status, Some(2));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("--quiet cannot be used with --steps"));
+}
+
+#[test]
+fn test_check() {
+    let (status, stdout, _) = run(&["check", "tests/fixtures/modus_ponens.txt"]);
+    assert_eq!(status, Some(0));
+    assert_eq!(
+        stdout,
+        "The premises are consistent, as every premise is TRUE when:\np = TRUE\nq = TRUE\n"
+    );
+
+    let (status, stdout, _) = run(&["check", "tests/fixtures/conflict.txt"]);
+    assert_eq!(status, Some(3));
+    assert_eq!(
+        stdout,
+        "The premises are inconsistent, as these cannot all be TRUE:\n\
+         P1 (line 2): p & q\n\
+         P3 (line 4): !q\n"
+    );
+
+    let (status, stdout, _) = run(&["check", "tests/fixtures/conflict.txt", "--format", "json"]);
+    assert_eq!(status, Some(3));
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["consistent"], false);
+    assert_eq!(json["core"].as_array().unwrap().len(), 2);
+    assert_eq!(json["core"][1]["label"], "P3");
+    assert_eq!(json["core"][1]["line"], 4);
+    assert_eq!(json["core"][1]["premise"], "¬q");
+
+    let (_, stdout, _) = run(&["check", "tests/fixtures/example.txt", "--format=json"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["consistent"], true);
+    assert_eq!(json["model"]["j"], true);
+}
+
+#[test]
+fn test_refute() {
+    let (status, stdout, _) = run(&["refute", "tests/fixtures/denying_antecedent.txt"]);
+    assert_eq!(status, Some(0));
+    assert_eq!(
+        stdout,
+        "Counterexample: p=F, q=T\n\
+         With p FALSE and q TRUE, every premise is TRUE but the conclusion !q is FALSE\n"
+    );
+
+    // The main.rs argument is valid, so refuting it fails
+    let (status, stdout, _) = run(&["refute", "tests/fixtures/example.txt", "--prove", "j"]);
+    assert_eq!(status, Some(1));
+    assert_eq!(
+        stdout,
+        "therefore j follows from the premises, so there is no counterexample\n"
+    );
+
+    let (status, stdout, _) = run(&["refute", "tests/fixtures/inconsistent.txt"]);
+    assert_eq!(status, Some(3));
+    assert!(stdout.starts_with("The premises are inconsistent"));
+
+    let (_, stdout, _) = run(&[
+        "refute",
+        "tests/fixtures/denying_antecedent.txt",
+        "--format",
+        "json",
+    ]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["valid"], false);
+    assert_eq!(json["counterexample"]["q"], true);
+}
+
+#[test]
+fn test_normal_forms() {
+    let formula = "!(p > q) | r";
+
+    for (form, expected) in [
+        ("--nnf", "(p & !q) | r\n"),
+        ("--cnf", "(p | r) & (!q | r)\n"),
+        ("--dnf", "(p & !q) | r\n"),
+    ] {
+        let (status, stdout, _) = run(&["nf", form, formula]);
+
+        assert_eq!(status, Some(0), "{}", form);
+        assert_eq!(stdout, expected, "{}", form);
+    }
+
+    let (_, stdout, _) = run(&["nf", "--cnf", formula, "--clauses"]);
+    assert_eq!(stdout, "{p, r}\n{!q, r}\n");
+
+    let (status, stdout, _) = run(&["nf", "--cnf", formula, "--dimacs", "--check"]);
+    assert_eq!(status, Some(0));
+    assert_eq!(
+        stdout,
+        "c 1 = p\nc 2 = q\nc 3 = r\np cnf 3 2\n1 3 0\n-2 3 0\n\
+         c Checked: the conjunctive normal form is equivalent to !(p > q) | r\n"
+    );
+
+    let (status, _, stderr) = run(&["nf", formula]);
+    assert_eq!(status, Some(2));
+    assert!(stderr.contains("needs one of --cnf, --dnf or --nnf"));
+}
+
+#[test]
+fn test_verbosity() {
+    let example = "tests/fixtures/example.txt";
+
+    // Quiet only gives the values, and the default adds the premises without the work
+    let (_, quiet, _) = run(&[example, "-q"]);
+    assert_eq!(quiet, "b=F, f=T, j=T, m=T, s=?, t=F\n");
+
+    let (status, stdout, _) = run(&[example]);
+    assert_eq!(status, Some(0));
+    assert!(stdout.starts_with("(m & !b) > j\n(f | s) > m\nb > t\nf > !t\nf\n"));
+    assert!(!stdout.contains("[SUBSTITUTE]"));
+    assert!(stdout.lines().any(|line| line == "j = TRUE"));
+
+    // -v adds every pass, and -vv the rules and the values found in each pass
+    let (_, verbose, _) = run(&[example, "-v"]);
+    assert!(verbose.contains("(TRUE | s) > m [SUBSTITUTE]\n"));
+    assert!(verbose.contains("t = FALSE [EVALUATE]\n"));
+    assert!(!verbose.contains("values:"));
+
+    // The stats end with how long the solve took, which is recorded however it was started
+    let duration = verbose
+        .lines()
+        .find_map(|line| line.strip_prefix("Duration"))
+        .unwrap()
+        .trim();
+    assert_ne!(duration, "0ns");
+
+    let (_, very_verbose, _) = run(&[example, "-vv"]);
+    assert!(very_verbose.contains("(TRUE | s) > m [SUBSTITUTE]\n"));
+    assert!(very_verbose.contains("t = FALSE [EVALUATE: implication]\nvalues: t = FALSE\n"));
+
+    // Only the duration differs between runs
+    let without_duration = |stdout: &str| -> Vec<String> {
+        stdout
+            .lines()
+            .filter(|line| !line.starts_with("Duration"))
+            .map(String::from)
+            .collect()
+    };
+    assert_eq!(
+        without_duration(&run(&[example, "-v", "-v"]).1),
+        without_duration(&very_verbose)
+    );
+}
+
+#[test]
+fn test_solve_limits() {
+    let (status, stdout, _) = run(&["tests/fixtures/example.txt", "--max-steps", "1"]);
+
+    assert_eq!(status, Some(4));
+    assert!(stdout.contains(
+        "\nPARTIAL RESULT: stopped by --max-steps after 1 step, with 1 of 6 values found\n"
+    ));
+    assert!(stdout.lines().any(|line| line == "f = TRUE"));
+    assert!(stdout.lines().any(|line| line == "j = UNKNOWN"));
+
+    let (status, stdout, _) = run(&["tests/fixtures/example.txt", "--timeout", "0s"]);
+    assert_eq!(status, Some(4));
+    assert!(stdout.contains("\nPARTIAL RESULT: stopped by --timeout after 0 steps"));
+
+    // A limit which is not reached leaves the result as it was
+    let (status, stdout, _) = run(&["tests/fixtures/example.txt", "--timeout=5s"]);
+    assert_eq!(status, Some(0));
+    assert!(!stdout.contains("PARTIAL RESULT"));
+}
+
+#[test]
+fn test_json_input() {
+    // The argument asks for the diff of the history, which names premises by their labels
+    let (status, stdout, _) = run(&["tests/fixtures/argument.json"]);
+    assert_eq!(status, Some(0));
+    assert!(stdout.starts_with("P2: (f | s) > m => (TRUE | s) > m [SUBSTITUTE]\n"));
+    assert!(stdout.contains("\njump: TRUE > j => j [EVALUATE]\n"));
+    assert!(stdout.ends_with("j = TRUE\nm = TRUE\ns = UNKNOWN\nt = FALSE\ntherefore j is TRUE\n"));
+
+    // Options on the command line replace those in the argument
+    let (status, stdout, _) = run(&["tests/fixtures/argument.json", "--max-steps", "1"]);
+    assert_eq!(status, Some(4));
+    assert!(stdout.contains("stopped by --max-steps after 1 step"));
+
+    let (status, stdout, _) = run(&["tests/fixtures/argument.json", "--trace"]);
+    assert_eq!(status, Some(0));
+    assert!(stdout.starts_with("step=1 action=SUBSTITUTE premise=P2 "));
+    assert!(!stdout.contains("[SUBSTITUTE]"));
+
+    let (status, _, stderr) = run(&["tests/fixtures/invalid_argument.json"]);
+    assert_eq!(status, Some(2));
+    assert!(stderr.contains("tests/fixtures/invalid_argument.json', premises[1].formula: "));
+    assert!(stderr.contains("'#'"));
+}
+
+#[test]
+fn test_events() {
+    // Every line of stdout is an event, with the result printed to stderr instead
+    let (status, streamed, stderr) = run(&[
+        "prove",
+        "tests/fixtures/argument.json",
+        "--events",
+        "-",
+        "-q",
+    ]);
+    assert_eq!(status, Some(0));
+
+    assert_eq!(stderr, "PROVED\n");
+    let events: Vec<serde_json::Value> = streamed
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert!(events.iter().any(|event| event["event"] == "step"
+        && event["premise"] == "jump"
+        && event["action"] == "evaluate"));
+    assert!(events
+        .iter()
+        .any(|event| event["event"] == "value" && event["prop"] == "j" && event["value"] == true));
+    assert_eq!(events[events.len() - 2]["event"], "done");
+    assert_eq!(events[events.len() - 1]["event"], "verdict");
+    assert_eq!(events[events.len() - 1]["result"], "proved");
+
+    // The JSON result also goes to stderr, leaving only events on stdout
+    let (_, stdout, stderr) = run(&[
+        "prove",
+        "tests/fixtures/argument.json",
+        "--events",
+        "-",
+        "--format",
+        "json",
+    ]);
+    assert_eq!(stdout, streamed);
+    assert!(serde_json::from_str::<serde_json::Value>(&stderr).is_ok());
+
+    // The same events can be written to a file instead
+    let path =
+        std::env::temp_dir().join(format!("deductions-events-{}.ndjson", std::process::id()));
+    let (status, stdout, _) = run(&[
+        "prove",
+        "tests/fixtures/argument.json",
+        "--events",
+        path.to_str().unwrap(),
+        "-q",
+    ]);
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(status, Some(0));
+    assert_eq!(stdout, "PROVED\n");
+    assert_eq!(written, streamed);
+}
+
+#[test]
+fn test_equiv() {
+    // De Morgan's law
+    let (status, stdout, _) = run(&["equiv", "!(p & q)", "!p | !q"]);
+    assert_eq!(status, Some(0));
+    assert_eq!(stdout, "equivalent\n");
+
+    let (status, stdout, _) = run(&["equiv", "p > q", "q > p"]);
+    assert_eq!(status, Some(1));
+
+    // The printed assignment has to make the implications differ
+    let witness = stdout
+        .lines()
+        .nth(1)
+        .and_then(|line| line.strip_prefix("With "))
+        .and_then(|line| line.split_once(", p > q"))
+        .map(|(values, _)| values)
+        .unwrap();
+    let value = |proposition: &str| witness.contains(&format!("{}=T", proposition));
+    assert_ne!(!value("p") || value("q"), !value("q") || value("p"));
+
+    let (status, stdout, _) = run(&["equiv", "--structural", "a & (b & c)", "(a & b) & !c"]);
+    assert_eq!(status, Some(1));
+    assert_eq!(
+        stdout,
+        "structurally different\nThe first formula has c where the second has !c\n"
+    );
+
+    let (status, _, stderr) = run(&["equiv", "p &", "p"]);
+    assert_eq!(status, Some(2));
+    assert!(stderr.contains("Could not parse the formula"));
+}
+
+#[test]
+fn test_assist() {
+    let (status, stdout, _) = run_with_stdin(
+        &["assist", "tests/fixtures/modus_ponens.txt"],
+        "mp 1 1\nhint\nmp 2 1\nexport\n",
+    );
+
+    assert_eq!(status, Some(0));
+    assert!(stdout.starts_with("1. p → q  Premise\n2. p      Premise\nGoal: q\n"));
+    assert!(stdout.contains(">> Error: Modus ponens does not apply, as line 1 is p → q, which is not the antecedent p of line 1\n"));
+    assert!(stdout.contains(">> Try 'mp 1 2'\n"));
+    assert!(stdout.contains("Congratulations, line 3 proves the goal q!"));
+    assert!(stdout
+        .ends_with(">> 1. p → q  Premise\n2. p      Premise\n3. q      Modus ponens 2, 1\n>> \n"));
+
+    // A goal has to be given when the argument has no conclusion
+    let (status, _, stderr) = run_with_stdin(&["assist"], "");
+    assert_eq!(status, Some(2));
+    assert!(stderr.contains("The argument has no conclusion"));
+}
+
+#[test]
+fn test_premises_on_command_line() {
+    let premises = ["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"];
+
+    // The example argument proves j, printing every pass with --steps
+    let mut arguments = premises.to_vec();
+    arguments.extend(["--conclude", "j", "--steps", "--format", "ascii"]);
+    let (status, stdout, stderr) = run(&arguments);
+
+    assert_eq!(status, Some(0));
+    assert!(stdout.contains("(TRUE | s) > m [SUBSTITUTE]"));
+    assert!(stdout.contains("j = TRUE [EVALUATE]"));
+    assert!(stdout.lines().any(|line| line == "t = FALSE"));
+    assert!(stderr.is_empty());
+
+    // Without f, j is refuted by b being TRUE
+    let (status, stdout, _) = run(&["b", "(m & !b) > j", "b > !j", "--conclude", "j", "-q"]);
+    assert_eq!(status, Some(1));
+    assert_eq!(stdout, "FALSE\n");
+
+    // The same argument solves the same way as the built-in example
+    let (_, demo, _) = run(&["--demo", "-q"]);
+    let (status, stdout, _) = run(&premises);
+    assert_eq!(status, Some(0));
+    assert!(stdout.contains("j = TRUE"));
+    assert_eq!(run(&[&premises[..], &["-q"]].concat()).1, demo);
+}
+
+#[test]
+fn test_premises_on_command_line_errors() {
+    let (status, stdout, stderr) = run(&["p > q", "p &", "--conclude", "q"]);
+    assert_eq!(status, Some(2));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("Could not parse premise 2"));
+
+    let (status, _, stderr) = run(&["p > q", "p", "--conclude", "q)"]);
+    assert_eq!(status, Some(2));
+    assert!(stderr.contains("Could not parse the conclusion"));
+
+    let (status, _, stderr) = run(&["--demo", "p", "q"]);
+    assert_eq!(status, Some(2));
+    assert!(stderr.contains("Unexpected argument 'p'"));
+}
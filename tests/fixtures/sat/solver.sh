@@ -0,0 +1,6 @@
+#!/bin/sh
+# Stands in for a SAT solver in the tests, printing the canned output in the file given first
+# after checking that the DIMACS file given last was written
+eval "dimacs=\${$#}"
+grep -q '^p cnf ' "$dimacs" || exit 1
+cat "$1"
@@ -0,0 +1,14 @@
+(set-logic QF_UF)
+(declare-const b Bool)
+(declare-const f Bool)
+(declare-const j Bool)
+(declare-const m Bool)
+(declare-const s Bool)
+(declare-const t Bool)
+(assert (=> (and m (not b)) j))
+(assert (=> (or f s) m))
+(assert (=> b t))
+(assert (=> f (not t)))
+(assert f)
+(assert (not j))
+(check-sat)
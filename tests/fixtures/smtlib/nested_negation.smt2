@@ -0,0 +1,10 @@
+(set-option :produce-models true)
+(set-logic QF_UF)
+(declare-const p Bool)
+(declare-const q Bool)
+(declare-const r Bool)
+(assert (not (not (=> p (not q)))))
+(assert (not (or (not q) (not (not (not r))))))
+(assert (not (not (not (not p)))))
+(check-sat)
+(get-model)
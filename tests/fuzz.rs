@@ -0,0 +1,45 @@
+// Runs the body of each fuzz target over its seed inputs, so a regression on any of them is
+// caught without running the fuzzer
+
+#[path = "../fuzz/bodies.rs"]
+mod bodies;
+
+use std::fs;
+use std::path::PathBuf;
+
+// Returns the contents of every seed input of the fuzz target
+fn seeds(target: &str) -> Vec<(PathBuf, Vec<u8>)> {
+    let directory = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("fuzz/seeds")
+        .join(target);
+
+    let mut seeds: Vec<(PathBuf, Vec<u8>)> = fs::read_dir(directory)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let data = fs::read(&path).unwrap();
+
+            (path, data)
+        })
+        .collect();
+    seeds.sort();
+
+    assert!(!seeds.is_empty());
+    seeds
+}
+
+#[test]
+fn test_parse_seeds() {
+    for (path, data) in seeds("parse") {
+        println!("{}", path.display());
+        bodies::parse(&data);
+    }
+}
+
+#[test]
+fn test_solve_seeds() {
+    for (path, data) in seeds("solve") {
+        println!("{}", path.display());
+        bodies::solve(&data);
+    }
+}
@@ -0,0 +1,347 @@
+use deductions::entailment::Entailment;
+use deductions::errors::{RenameError, SatError};
+use deductions::history::StepAction;
+use deductions::normal_form::{NormalForm, DEFAULT_MAX_CLAUSES};
+use deductions::notation::Notation;
+use deductions::pattern::Pattern;
+use deductions::premises::Operator;
+use deductions::proof::{InferenceRule, Proof, ProofStep};
+use deductions::sat::{Dpll, ExternalDimacs};
+use deductions::truth_table::TruthTable;
+use deductions::{Deduction, EvaluationHistory, ParseError, Premise, PremiseNode, ValueMap};
+
+#[test]
+fn test_solve() {
+    let mut deduction =
+        Deduction::from_strs(vec!["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"]).unwrap();
+    let history = deduction.solve();
+
+    assert_eq!(deduction.get_values().get_value('j'), Some(true));
+    assert_eq!(deduction.get_values().get_value('s'), None);
+    assert!(!history.get_steps().is_empty());
+
+    // The conclusion can add propositions no premise uses
+    deduction.set_conclusion(Premise::parse_str("j | z").unwrap());
+    let propositions: Vec<char> = deduction.get_propositions().into_iter().collect();
+    assert_eq!(propositions, ['b', 'f', 'j', 'm', 's', 't', 'z']);
+}
+
+#[test]
+fn test_substitute_and_update_values() {
+    let mut deduction = Deduction::from_strs(vec!["p > q", "p"]).unwrap();
+
+    // Nothing is known until the values are read from the premises
+    deduction.substitute_all();
+    assert_eq!(deduction.get_values().get_value('p'), None);
+
+    deduction.update_actual_values();
+    assert_eq!(deduction.get_values().get_value('p'), Some(true));
+    assert_eq!(deduction.get_values().get_value('q'), None);
+
+    deduction.substitute_all();
+    let premises: Vec<String> = deduction
+        .get_labeled_premises()
+        .into_iter()
+        .map(|(_, premise)| premise.to_string())
+        .collect();
+    assert_eq!(premises, ["TRUE → q", "TRUE"]);
+}
+
+#[test]
+fn test_substituted_all() {
+    let mut deduction = Deduction::from_strs(vec!["p > q", "p"]).unwrap();
+    deduction.update_actual_values();
+
+    let substituted = deduction.substituted_all();
+    let premise_strings = |deduction: &Deduction| -> Vec<String> {
+        deduction
+            .get_labeled_premises()
+            .into_iter()
+            .map(|(_, premise)| premise.to_string())
+            .collect()
+    };
+    assert_eq!(premise_strings(&deduction), ["p → q", "p"]);
+
+    deduction.substitute_all();
+    assert_eq!(premise_strings(&substituted), premise_strings(&deduction));
+}
+
+#[test]
+fn test_check_entailment() {
+    let deduction = Deduction::from_strs(vec!["p > q", "!q"]).unwrap();
+
+    assert!(matches!(
+        deduction.check_entailment(&Premise::parse_str("!p").unwrap()),
+        Entailment::Proved
+    ));
+    assert_eq!(
+        Premise::parse_str("p & & q"),
+        Err(ParseError::InvalidStructure)
+    );
+}
+
+#[test]
+fn test_rename_proposition() {
+    let mut deduction = Deduction::from_strs(vec!["p > q", "q > r", "p"]).unwrap();
+    deduction.set_conclusion(Premise::parse_str("r").unwrap());
+
+    // Merging r into an existing proposition keeps the ValueMap to one entry for both
+    let mut history = EvaluationHistory::new();
+    deduction
+        .rename_proposition_with_observer('r', 'p', &mut history)
+        .unwrap();
+    assert_eq!(deduction.to_string(), "p → q\nq → p\np\n∴ p\n");
+    assert_eq!(
+        deduction.get_values().to_string(),
+        "p = UNKNOWN\nq = UNKNOWN\n"
+    );
+
+    let steps = history.get_steps();
+    assert_eq!(steps.len(), 1);
+    assert_eq!(steps[0].get_label(), "P2");
+    assert_eq!(
+        steps[0].get_action(),
+        StepAction::Rename { from: 'r', to: 'p' }
+    );
+    assert_eq!(steps[0].get_before().to_string(), "q → r");
+
+    // A new name does not need to be in the Deduction already
+    deduction.rename_proposition('q', 's').unwrap();
+    assert_eq!(deduction.to_string(), "p → s\ns → p\np\n∴ p\n");
+
+    deduction.solve();
+    assert_eq!(deduction.get_values().get_value('s'), Some(true));
+    assert_eq!(
+        deduction.rename_proposition('q', 'p'),
+        Err(RenameError::MissingProposition('q'))
+    );
+}
+
+#[test]
+fn test_rename_proposition_conflict() {
+    let mut deduction = Deduction::from_strs(vec!["p", "!q", "p > r"]).unwrap();
+    deduction.solve();
+
+    let error = deduction.rename_proposition('p', 'q').unwrap_err();
+    assert_eq!(error, RenameError::ConflictingValues { from: 'p', to: 'q' });
+    assert_eq!(
+        error.to_string(),
+        "'p' cannot be renamed to 'q', as their values differ"
+    );
+
+    // Nothing is changed by a rename which fails
+    assert_eq!(deduction.get_values().get_value('p'), Some(true));
+    assert_eq!(deduction.get_values().get_value('q'), Some(false));
+
+    // A known value is kept when the other is unknown
+    let mut deduction = Deduction::from_strs(vec!["p", "q > r"]).unwrap();
+    deduction.solve();
+    deduction.rename_proposition('p', 'q').unwrap();
+    assert_eq!(deduction.get_values().get_value('q'), Some(true));
+}
+
+// Builds premises which do not form valid formulas by replacing each node of some valid ones,
+// which visit_mut allows
+fn malformed_premises() -> Vec<Premise> {
+    let replacements = [
+        PremiseNode::Negation,
+        PremiseNode::Operator(Operator::And),
+        PremiseNode::TruthValue(true),
+        PremiseNode::Subpremise(Premise::parse_str("p & q").unwrap()),
+    ];
+    let mut premises = Vec::new();
+
+    for premise in ["p & q", "!p", "p > (q | r)", "(p)", "a | b > c"] {
+        for replacement in &replacements {
+            for target in 0..6 {
+                let mut premise = Premise::parse_str(premise).unwrap();
+                let mut i = 0;
+
+                premise.visit_mut(&mut |node| {
+                    if i == target {
+                        *node = replacement.clone();
+                    }
+                    i += 1;
+                });
+
+                premises.push(premise);
+            }
+        }
+    }
+
+    premises
+}
+
+#[test]
+fn test_bad_input_does_not_panic() {
+    for input in [
+        "",
+        "(",
+        ")",
+        "()",
+        "p &",
+        "& p",
+        "p q",
+        "!!",
+        "p > > q",
+        "((p)",
+        "p))",
+        "->",
+        "ü",
+        "(and)",
+        "(not p q)",
+        "(implies p)",
+        "((p q))",
+        "_",
+        "_A &",
+    ] {
+        let _ = Premise::parse_str(input);
+        let _ = Premise::parse_sexpr(input);
+        let _ = Pattern::parse(input);
+        let _ = Deduction::from_reader(input.as_bytes(), "input".as_ref());
+        let _ = Deduction::batch_from_reader(input.as_bytes(), "input".as_ref());
+    }
+
+    let valid = Premise::parse_str("p > q").unwrap();
+    let mut values = ValueMap::default();
+    values.set_value('p', Some(true));
+
+    for premise in malformed_premises() {
+        // Propositions missing from the values are unknown
+        let _ = premise.evaluate(&ValueMap::default());
+        let _ = premise.try_evaluate(&values);
+        let _ = premise.substituted(&values);
+        premise.clone().simplify();
+
+        for notation in [Notation::Ascii, Notation::Unicode, Notation::Latex] {
+            let _ = premise.render_as_written(notation);
+        }
+        let _ = premise.pretty_tree();
+        let _ = premise.to_sexpr();
+        let _ = premise.to_binary();
+
+        let _ = premise.to_nnf();
+        let _ = premise.to_cnf(DEFAULT_MAX_CLAUSES);
+        let _ = premise.to_clauses(NormalForm::Negation, DEFAULT_MAX_CLAUSES);
+        let _ = TruthTable::new(&premise, 10);
+
+        let _ = premise.canonicalized();
+        let _ = premise.equivalent_structure(&valid);
+        let _ = premise.find(&valid);
+        let _ = valid.find(&premise);
+        let _ = premise.is_equivalent(&valid);
+
+        let mut deduction = Deduction::from_premises(vec![
+            premise.clone(),
+            valid.clone(),
+            Premise::parse_str("p").unwrap(),
+        ]);
+        deduction.set_conclusion(premise.clone());
+        let _ = deduction.check_entailment(&premise);
+        let _ = deduction.check_consistency();
+
+        let history = deduction.solve();
+        let _ = history.render(Notation::Latex);
+        let _ = history.render_side_by_side(10);
+        let _ = history.explain('q');
+        let _ = deduction.evaluate_conclusion();
+
+        let mut proof = Proof::new(vec![premise.clone(), valid.clone()]);
+        for rule in InferenceRule::ALL {
+            for lines in [vec![1], vec![1, 2]] {
+                let _ = proof.apply(&ProofStep {
+                    rule,
+                    lines,
+                    formula: Some(premise.clone()),
+                });
+            }
+        }
+        let _ = proof.find_next_step(&premise);
+    }
+
+    let history = Deduction::from_strs(vec!["p", "p > q"]).unwrap().solve();
+    let _ = history.render_pass_side_by_side(0, 0);
+    let _ = history.render_pass_side_by_side(99, 0);
+    let _ = history.values_at(99);
+}
+
+#[test]
+fn test_complexity_never_increases() {
+    let mut deduction = Deduction::from_strs(vec![
+        "(m & !b) > j",
+        "(f | s) > m",
+        "b > t",
+        "f > !t",
+        "f",
+        "!!(j > (k & !(l | !m))) > ((p))",
+        "k",
+    ])
+    .unwrap();
+    let history = deduction.solve();
+
+    for step in history.get_steps() {
+        assert!(
+            step.get_after().complexity() <= step.get_before().complexity(),
+            "{} became {}",
+            step.get_before(),
+            step.get_after()
+        );
+    }
+
+    let stats = history.stats();
+    assert!(stats.final_complexity < stats.initial_complexity);
+}
+
+#[test]
+fn test_external_sat_solver() {
+    let directory = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sat");
+    let solver = |output: &str| {
+        ExternalDimacs::new("sh")
+            .arg(format!("{}/solver.sh", directory))
+            .arg(format!("{}/{}", directory, output))
+    };
+
+    // The canned model makes p FALSE and q TRUE, the only counterexample here
+    let deduction = Deduction::from_strs(vec!["p > q"]).unwrap();
+    let goal = Premise::parse_str("q > p").unwrap();
+    let counterexample = deduction
+        .find_counterexample(&goal, Some(&solver("satisfiable.txt")))
+        .unwrap()
+        .unwrap();
+    assert_eq!(counterexample.get_value('p'), Some(false));
+    assert_eq!(counterexample.get_value('q'), Some(true));
+
+    // The solver's answer is trusted when it finds no model
+    assert!(deduction
+        .is_valid(&goal, Some(&solver("unsatisfiable.txt")))
+        .unwrap());
+    assert!(!deduction.is_valid(&goal, Some(&Dpll)).unwrap());
+
+    let error = deduction
+        .is_valid(&goal, Some(&solver("bad_literal.txt")))
+        .unwrap_err();
+    assert!(matches!(error, SatError::MalformedOutput { line: 2, .. }));
+    assert_eq!(
+        error.to_string(),
+        "Could not read line 2 of the SAT solver's output: '-1 two 0'"
+    );
+}
+
+#[test]
+fn test_smtlib_golden_files() {
+    // The argument worked through in main.rs
+    let mut deduction =
+        Deduction::from_strs(vec!["(m & !b) > j", "(f | s) > m", "b > t", "f > !t", "f"]).unwrap();
+    deduction.set_conclusion(Premise::parse_str("j").unwrap());
+    assert_eq!(
+        deduction.to_smtlib(),
+        include_str!("fixtures/smtlib/example.smt2")
+    );
+
+    let mut deduction = Deduction::from_strs(vec!["!!(p > !q)", "!(!q | !!!r)"]).unwrap();
+    deduction.set_conclusion(Premise::parse_str("!!!p").unwrap());
+    assert_eq!(
+        deduction.to_smtlib_with_model(),
+        include_str!("fixtures/smtlib/nested_negation.smt2")
+    );
+}
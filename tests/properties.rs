@@ -0,0 +1,127 @@
+// Checks properties which should hold for every premise against premises from the generator,
+// which shrinks any premise a property fails for so the message shows a small one
+
+use deductions::generator::{check_property, PropertyConfig};
+use deductions::normal_form::DEFAULT_MAX_CLAUSES;
+use deductions::{Premise, ValueMap};
+
+// Checks the property with the config, panicking with the shrunk premises if it fails
+fn assert_property(config: PropertyConfig, property: impl FnMut(&[Premise]) -> bool) {
+    if let Err(failure) = check_property(&config, property) {
+        panic!("{}", failure);
+    }
+}
+
+// Returns every assignment of the propositions, as a brute-force check needs
+fn assignments(propositions: &[char]) -> Vec<ValueMap> {
+    (0..1u32 << propositions.len())
+        .map(|assignment| {
+            let mut values = ValueMap::default();
+            for (i, proposition) in propositions.iter().enumerate() {
+                values.set_value(*proposition, Some(assignment & (1 << i) != 0));
+            }
+
+            values
+        })
+        .collect()
+}
+
+#[test]
+fn test_parse_display_round_trip() {
+    assert_property(
+        PropertyConfig {
+            depth: 6,
+            ..PropertyConfig::default()
+        },
+        |premises| {
+            let displayed = premises[0].to_string();
+
+            Premise::parse_str(&displayed).is_ok_and(|parsed| parsed == premises[0])
+        },
+    );
+}
+
+#[test]
+fn test_simplify_preserves_semantics() {
+    assert_property(PropertyConfig::default(), |premises| {
+        let premise = &premises[0];
+        let propositions: Vec<char> = premise.propositions().into_iter().collect();
+
+        assignments(&propositions).iter().all(|values| {
+            // Simplifying with every value known leaves the value of the premise
+            let mut substituted = premise.substituted(values);
+            substituted.simplify();
+
+            substituted.evaluate(&ValueMap::default()) == premise.evaluate(values)
+        })
+    });
+
+    // With only some values known, the simplified premise agrees with the original under every
+    // assignment of the rest
+    assert_property(PropertyConfig::default(), |premises| {
+        let premise = &premises[0];
+        let mut known = ValueMap::default();
+        known.set_value('a', Some(true));
+        known.set_value('b', Some(false));
+
+        let mut simplified = premise.substituted(&known);
+        simplified.simplify();
+
+        assignments(&['c', 'd']).into_iter().all(|mut values| {
+            for (proposition, value) in known.iter() {
+                values.set_value(proposition, value);
+            }
+
+            simplified.evaluate(&values) == premise.evaluate(&values)
+        })
+    });
+}
+
+#[test]
+fn test_normal_forms_are_equivalent() {
+    assert_property(PropertyConfig::default(), |premises| {
+        let premise = &premises[0];
+
+        premise.to_nnf().is_ok_and(|nnf| nnf.is_equivalent(premise))
+            && premise
+                .to_cnf(DEFAULT_MAX_CLAUSES)
+                .is_ok_and(|cnf| cnf.is_equivalent(premise))
+            && premise
+                .to_dnf(DEFAULT_MAX_CLAUSES)
+                .is_ok_and(|dnf| dnf.is_equivalent(premise))
+    });
+}
+
+#[test]
+fn test_canonicalize_is_idempotent() {
+    assert_property(
+        PropertyConfig {
+            depth: 5,
+            ..PropertyConfig::default()
+        },
+        |premises| {
+            let canonical = premises[0].canonicalized();
+
+            canonical.canonicalized() == canonical
+                && canonical.is_equivalent(&premises[0])
+                && canonical.equivalent_structure(&premises[0])
+        },
+    );
+}
+
+#[test]
+fn test_de_morgan() {
+    assert_property(
+        PropertyConfig {
+            premises: 2,
+            depth: 3,
+            ..PropertyConfig::default()
+        },
+        |premises| {
+            let (p, q) = (premises[0].clone(), premises[1].clone());
+
+            (!(p.clone() & q.clone())).is_equivalent(&(!p.clone() | !q.clone()))
+                && (!(p.clone() | q.clone())).is_equivalent(&(!p & !q))
+        },
+    );
+}
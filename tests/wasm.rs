@@ -0,0 +1,50 @@
+// Calls each export of the wasm feature the way JavaScript would, inside a WebAssembly runtime
+// Run with `wasm-pack test --node -- --features wasm`, as these only build for wasm32
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use wasm_bindgen::JsValue;
+
+use deductions::wasm::{check_formula, solve_argument, truth_table, JsDeduction};
+
+#[wasm_bindgen_test]
+fn test_solve_argument() {
+    let solved = solve_argument(r#"{"premises": ["p > q", "p"], "conclusion": "q"}"#).unwrap();
+    assert!(solved.contains(r#""verdict":true"#));
+
+    // Errors are thrown to JavaScript instead of aborting the module
+    assert!(solve_argument(r#"["p >"]"#).is_err());
+    assert!(solve_argument("not json").is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_check_formula() {
+    assert!(check_formula("(m & !b) > j").contains(r#""valid":true"#));
+    assert!(check_formula("p & $").contains(r#""valid":false"#));
+}
+
+#[wasm_bindgen_test]
+fn test_truth_table() {
+    assert!(truth_table("p > q").unwrap().contains(r#""rows""#));
+    assert!(truth_table("p &").is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_js_deduction() {
+    let mut deduction =
+        JsDeduction::new(vec![JsValue::from_str("p > q"), JsValue::from_str("p")]).unwrap();
+
+    let solved = deduction.evaluate().unwrap();
+    assert!(solved.is_object());
+    assert_eq!(deduction.get_value('q'), Some(true));
+    assert!(deduction.render_history().contains("TRUE → q"));
+}
+
+#[wasm_bindgen_test]
+fn test_js_deduction_errors() {
+    // A premise which cannot be parsed, or is not a string, is thrown as an exception
+    assert!(JsDeduction::new(vec![JsValue::from_str("p & #")]).is_err());
+    assert!(JsDeduction::new(vec![JsValue::from_f64(1.0)]).is_err());
+}